@@ -0,0 +1,445 @@
+// Generated from the ABI of stake_pool 0.0.1. Do not edit.
+
+export interface AccountBalance {
+    base_account_balance?: BaseAccountBalance | null;
+    investment_account_balance?: InvestmentAccountBalance | null;
+}
+
+/** Account identifier. This is the human readable utf8 string which is used internally to index accounts on the network and their respective state. */
+export type AccountId = string;
+
+export interface Aggregated {
+    /** Fee charged by the pool when receiving rewards from validators. */
+    reward_fee?: Fee | null;
+    /** Near amount already distributed on validators. */
+    staked_balance: string;
+    /** Stakers quantity. */
+    token_accounts_quantity: number;
+    /** Minted amount of token. */
+    token_total_supply: string;
+    /** Near amount of rewards from validators. */
+    total_rewards_from_validators_near_amount: string;
+    /** Near amount required for distribution on validators. */
+    unstaked_balance: string;
+}
+
+/** Annual percentage yield, annualized linearly from the growth of the token price between two epoch reports. */
+export interface Apy {
+    denominator: number;
+    from_epoch_height: number;
+    numerator: number;
+    to_epoch_height: number;
+}
+
+export interface BaseAccountBalance {
+    classic_near_balance: string;
+    classic_near_balance_token_coverage: string;
+    common_near_balance: string;
+    token_balance: string;
+}
+
+export interface CallbackResult {
+    is_success: boolean;
+    network_epoch_height: number;
+}
+
+export interface DelayedWithdrawPreview {
+    /** Near amount available in the pool for delayed unstake process. */
+    available_near_amount: string;
+    epoch_quantity_to_take_delayed_withdrawal: number;
+    /** Near amount that will be received after passing the delayed unstake process. */
+    near_amount: string;
+    /** Token amount that will be sent. */
+    token_amount: string;
+}
+
+export interface DelayedWithdrawalDetails {
+    epoch_quantity_to_take_delayed_withdrawal: number;
+    is_auto_claim: boolean;
+    near_amount: string;
+}
+
+/** Near amount that still can be deposited. None means, that there is no limit. */
+export interface DepositCapacity {
+    account_near_amount?: string | null;
+    pool_near_amount?: string | null;
+    validator_near_amount?: string | null;
+}
+
+export interface DepositCaps {
+    account_near_amount?: string | null;
+    pool_near_amount?: string | null;
+    validator_share?: Fee | null;
+}
+
+export interface DepositPreview {
+    /** Near amount that will be exchanged. */
+    near_amount: string;
+    /** Near amount that remained as a result of the conversion at the exchange rate. */
+    remainder_near_amount: string;
+    /** Token amount that will be received. */
+    token_amount: string;
+}
+
+export interface EpochHeightRegistry {
+    network_epoch_height: number;
+    pool_epoch_height: number;
+}
+
+export interface EpochReport {
+    common_balance: string;
+    deposited_near_amount: string;
+    epoch_height: number;
+    /** Sequence number of the report since pool initialization. */
+    index: number;
+    reward_fee_token_amount: string;
+    rewards_from_validators_near_amount: string;
+    total_supply: string;
+    withdrawn_near_amount: string;
+}
+
+export interface Fee {
+    denominator: number;
+    numerator: number;
+}
+
+export interface FeeBeneficiary {
+    account_id: string;
+    /** Share of the fee is 'numerator' divided by 'denominator' of the fee split. */
+    numerator: number;
+}
+
+export interface FeeRegistry {
+    /** Fee that is taken from the Near amount on instant unstake process. */
+    instant_withdraw_fee?: Fee | null;
+    /** Fee curve that replaces the fixed instant withdraw fee if it is set. */
+    instant_withdraw_fee_curve?: InstantWithdrawFeeCurve | null;
+    instant_withdraw_fee_split: FeeSplit;
+    /** Fee that is taken from the matured delayed withdrawal in favor of the keeper, that sent it to the user. */
+    keeper_bounty?: Fee | null;
+    /** Fee that is taken from the reward fee of the pool on the referred stake in favor of the referrer. */
+    referral_fee?: Fee | null;
+    /** Fee that is taken from the rewards received on the validators. */
+    reward_fee?: Fee | null;
+    reward_fee_split: FeeSplit;
+    /** In bytes. */
+    storage_usage_per_fee_beneficiary: number;
+}
+
+export interface FeeRegistryLight {
+    instant_withdraw_fee?: Fee | null;
+    reward_fee?: Fee | null;
+}
+
+/** Distribution of the fee between beneficiaries. The first beneficiary receives the remainder of the rounding, so the whole fee is distributed. */
+export interface FeeSplit {
+    beneficiaries: FeeBeneficiary[];
+    denominator: number;
+}
+
+export type FeeType = "Reward" | "InstantWithdraw";
+
+export interface Full {
+    fee_registry_light: FeeRegistryLight;
+    fund: Fund;
+    minimum_deposit_amount: string;
+    requested_to_withdrawal_fund: RequestedToWithdrawalFund;
+    storage_staking_price: StorageStakingPrice;
+    total_token_supply: string;
+}
+
+export interface FullForAccount {
+    account_balance: AccountBalance;
+    delayed_withdrawal_details?: DelayedWithdrawalDetails | null;
+    full: Full;
+    investor_investment?: InvestorInvestment | null;
+    storage_staking_requested_coverage: StorageStakingRequestedCoverage;
+}
+
+export interface Fund {
+    /** Near remainders of the deposits, that belong to the accounts. */
+    classic_remainder_balance: string;
+    /** Near amount already distributed on validators by pool. */
+    classic_staked_balance: string;
+    /** Near amount required for distribution on validators by pool. */
+    classic_unstaked_balance: string;
+    /** Common management near amount. */
+    common_balance: string;
+    /** Near amount already distributed on validators by pool and investors. */
+    common_staked_balance: string;
+    /** Near amount already distributed on validators by investors. */
+    investment_staked_balance: string;
+    /** Near amount of failed outgoing transfers, that is not claimed yet. */
+    pending_refund_balance: string;
+}
+
+export interface FungibleTokenMetadata {
+    decimals: number;
+    icon?: string | null;
+    name: string;
+    reference?: string | null;
+    reference_hash?: string | null;
+    symbol: string;
+}
+
+/** Fee that rises linearly from the minimum to the maximum as the withdrawal depletes the available unstaked balance. */
+export interface InstantWithdrawFeeCurve {
+    /** Fee applied to a withdrawal, that takes all available unstaked balance. */
+    maximum_fee: Fee;
+    /** Fee applied to a withdrawal, that does not take any part of the available unstaked balance. */
+    minimum_fee: Fee;
+}
+
+export interface InstantWithdrawPreview {
+    /** Near amount available in the pool for instant unstake process. */
+    available_near_amount: string;
+    /** Fee charged by the pool on instant unstake process. It is the rate calculated by the fee curve if the curve is set. */
+    fee?: Fee | null;
+    /** Token amount that will be distributed between fee beneficiaries. */
+    fee_token_amount: string;
+    /** Near amount that will be received. */
+    near_amount: string;
+    /** Token amount that will be sent. */
+    token_amount: string;
+}
+
+export interface InvariantReport {
+    is_valid: boolean;
+    violations: InvariantViolation[];
+}
+
+export type InvariantViolation = {
+    ValidatorBalanceMismatch: {
+        expected_near_amount: string;
+        validators_near_amount: string;
+    };
+} | {
+    InsufficientContractBalance: {
+        contract_near_amount: string;
+        required_near_amount: string;
+    };
+} | {
+    PriceLessThanOne: {
+        common_balance: string;
+        total_supply: string;
+    };
+} | {
+    PriceDecreased: {
+        epoch_height: number;
+        near_per_token: string;
+        previous_near_per_token: string;
+    };
+} | {
+    TotalSupplyMismatch: {
+        accounts_quantity: number;
+        sampled_accounts_quantity: number;
+        sampled_token_amount: string;
+        total_supply: string;
+    };
+};
+
+export interface InvestmentAccountBalance {
+    near_balance: string;
+    near_balance_token_coverage: string;
+}
+
+export interface InvestorInvestment {
+    distribution_registry: [string, string][];
+    staked_balance: string;
+}
+
+export interface PendingPoolConfig {
+    activation_epoch_height: number;
+    pool_config: PoolConfig;
+}
+
+export interface PoolConfig {
+    epoch_quantity_for_delayed_withdrawal: number;
+    epoch_quantity_for_validator_unstake: number;
+    minimum_deposit_amount: string;
+    minimum_number_of_tgas: number;
+}
+
+export interface PromiseOrValueCallbackResult {
+    is_success: boolean;
+    network_epoch_height: number;
+}
+
+export type PromiseOrValueNull = null;
+
+export type PromiseOrValueString = string;
+
+export interface Referrer {
+    account_id: string;
+    earned_token_amount: string;
+    referred_accounts_quantity: number;
+    referred_near_amount: string;
+    referred_token_amount: string;
+    unclaimed_token_amount: string;
+}
+
+export interface RequestedToWithdrawalFund {
+    classic_near_amount: string;
+    investment_near_amount: string;
+    investment_withdrawal_registry: [string, string][];
+}
+
+export type StakeDecreasingType = "Classic" | "Investment";
+
+/** Do not change the order of variants. The number of options must be less than or equal to 256 (1 byte). */
+export type StakingContractVersion = "Core";
+
+export interface StorageStakingPrice {
+    per_delayed_withdrawal_fund_auto_claim: string;
+    per_delayed_withdrawal_fund_delayed_withdrawal: string;
+    per_delayed_withdrawal_fund_investment_withdrawal: string;
+    per_fungible_token_account: string;
+    per_validating_node_distribution: string;
+    per_validating_node_investor: string;
+    per_validating_node_validator: string;
+}
+
+export interface StorageStakingRequestedCoverage {
+    per_method_delayed_withdraw: string;
+    per_method_delayed_withdraw_from_validator?: [string, [string, string][]] | null;
+    per_method_deposit: string;
+    per_method_deposit_on_validator?: [string, [string, string][]] | null;
+}
+
+export interface Validator {
+    account_id: string;
+    classic_staked_balance: string;
+    consecutive_inactive_epoch_quantity: number;
+    drained_balance: string;
+    investment_staked_balance: string;
+    is_active: boolean;
+    is_draining: boolean;
+    is_flagged_as_inactive: boolean;
+    is_only_for_investment: boolean;
+    last_classic_stake_increasing_epoch_height?: number | null;
+    last_update_epoch_height: number;
+    unstaked_balance: string;
+}
+
+export interface ValidatorPerformance {
+    epoch_height: number;
+    /** Sequence number of the record since validator registration. */
+    index: number;
+    is_active: boolean;
+    near_amount: string;
+    rewards_near_amount: string;
+    validator_stake: string;
+}
+
+export interface ValidatorReconciliation {
+    epoch_height: number;
+    recorded_staked_near_amount: string;
+    recorded_unstaked_near_amount: string;
+    /** Near amount registered by the pool that is absent on validator. */
+    shortfall_near_amount: string;
+    staked_near_amount: string;
+    /** Near amount on validator that is not registered by the pool. */
+    surplus_near_amount: string;
+    unstaked_near_amount: string;
+}
+
+export interface StakePoolViewMethods {
+    check_invariants(args: { account_ids: AccountId[] | null }): Promise<InvariantReport>;
+    ft_balance_of(args: { account_id: AccountId }): Promise<string>;
+    ft_total_supply(args: {}): Promise<string>;
+    get_account_balance(args: { account_id: AccountId }): Promise<AccountBalance>;
+    get_aggregated(args: {}): Promise<Aggregated>;
+    get_apy(args: { epoch_quantity: number }): Promise<Apy | null>;
+    get_auto_claim_queue_length(args: {}): Promise<number>;
+    get_current_epoch_height(args: {}): Promise<EpochHeightRegistry>;
+    get_delayed_withdrawal_details(args: { account_id: AccountId }): Promise<DelayedWithdrawalDetails | null>;
+    get_deposit_caps(args: {}): Promise<DepositCaps>;
+    get_epoch_reports(args: { from_index: number, limit: number }): Promise<EpochReport[]>;
+    get_epoch_reports_quantity(args: {}): Promise<number>;
+    get_fee_registry(args: {}): Promise<FeeRegistry>;
+    get_fee_registry_light(args: {}): Promise<FeeRegistryLight>;
+    get_full(args: {}): Promise<Full>;
+    get_full_for_account(args: { account_id: AccountId }): Promise<FullForAccount>;
+    get_fund(args: {}): Promise<Fund>;
+    get_investor_investment(args: { account_id: AccountId }): Promise<InvestorInvestment | null>;
+    get_minimum_deposit_amount(args: {}): Promise<string>;
+    get_near_per_token(args: {}): Promise<string>;
+    get_pending_pool_config(args: {}): Promise<PendingPoolConfig | null>;
+    get_pending_refund(args: { account_id: AccountId }): Promise<string>;
+    get_pool_config(args: {}): Promise<PoolConfig>;
+    get_preffered_validator(args: {}): Promise<Validator | null>;
+    get_referrer(args: { referrer_account_id: AccountId }): Promise<Referrer | null>;
+    get_referrer_registry(args: {}): Promise<Referrer[]>;
+    get_remaining_deposit_capacity(args: { account_id: AccountId | null, validator_account_id: AccountId | null }): Promise<DepositCapacity>;
+    get_requested_to_withdrawal_fund(args: {}): Promise<RequestedToWithdrawalFund>;
+    get_storage_staking_price(args: {}): Promise<StorageStakingPrice>;
+    get_storage_staking_requested_coverage(args: { account_id: AccountId }): Promise<StorageStakingRequestedCoverage>;
+    get_total_token_supply(args: {}): Promise<string>;
+    get_validator_performance(args: { validator_account_id: AccountId, from_index: number, limit: number }): Promise<ValidatorPerformance[]>;
+    get_validator_reconciliation(args: { validator_account_id: AccountId }): Promise<ValidatorReconciliation | null>;
+    get_validator_registry(args: {}): Promise<Validator[]>;
+    is_stake_distributed(args: {}): Promise<boolean>;
+    preview_delayed_withdraw(args: { token_amount: string }): Promise<DelayedWithdrawPreview>;
+    preview_deposit(args: { near_amount: string }): Promise<DepositPreview>;
+    preview_instant_withdraw(args: { token_amount: string }): Promise<InstantWithdrawPreview>;
+}
+
+export interface StakePoolCallMethods {
+    add_fee_beneficiary(args: { fee_type: FeeType, beneficiary_account_id: AccountId, numerator: number }): Promise<PromiseOrValueNull>;
+    add_investor(args: { investor_account_id: AccountId }): Promise<PromiseOrValueNull>;
+    add_referrer(args: { referrer_account_id: AccountId }): Promise<PromiseOrValueNull>;
+    add_validator(args: { validator_account_id: AccountId, staking_contract_version: StakingContractVersion, is_only_for_investment: boolean, is_preferred: boolean }): Promise<PromiseOrValueNull>;
+    apply_validator_correction(args: { validator_account_id: AccountId }): Promise<void>;
+    cancel_config_update(args: {}): Promise<void>;
+    change_deposit_caps(args: { pool_near_amount: string | null, validator_share: Fee | null, account_near_amount: string | null }): Promise<void>;
+    change_instant_withdraw_fee(args: { instant_withdraw_fee: Fee | null }): Promise<void>;
+    change_instant_withdraw_fee_curve(args: { instant_withdraw_fee_curve: InstantWithdrawFeeCurve | null }): Promise<void>;
+    change_keeper_bounty(args: { keeper_bounty: Fee | null }): Promise<void>;
+    change_manager(args: { manager_id: AccountId }): Promise<void>;
+    change_preffered_validator(args: { validator_account_id: AccountId | null }): Promise<void>;
+    change_referral_fee(args: { referral_fee: Fee | null }): Promise<void>;
+    change_reward_fee(args: { reward_fee: Fee | null }): Promise<void>;
+    change_validator_investment_context(args: { validator_account_id: AccountId, is_only_for_investment: boolean }): Promise<void>;
+    claim_referral_fee(args: {}): Promise<string>;
+    claim_refund(args: { receiver_id: AccountId | null }): Promise<unknown>;
+    confirm_stake_distribution(args: {}): Promise<void>;
+    delayed_withdraw(args: { token_amount: string, min_near_amount: string | null }): Promise<PromiseOrValueNull>;
+    delayed_withdraw_from_validator(args: { near_amount: string, validator_account_id: AccountId }): Promise<PromiseOrValueNull>;
+    deposit(args: { near_amount: string, min_token_amount: string | null, referrer_id: AccountId | null }): Promise<PromiseOrValueNull>;
+    deposit_callback(args: { predecessor_account_id: AccountId, beneficiary_account_id: AccountId, validator_account_id: AccountId, attached_deposit: number, near_amount: number, refundable_near_amount: number, token_amount: number, near_remainder: number, current_epoch_height: number, storage_staking_price_per_additional_account: number, referrer_id: AccountId | null }): Promise<void>;
+    deposit_for(args: { beneficiary_id: AccountId, near_amount: string, min_token_amount: string | null, referrer_id: AccountId | null }): Promise<PromiseOrValueNull>;
+    deposit_on_validator(args: { near_amount: string, validator_account_id: AccountId, referrer_id: AccountId | null }): Promise<unknown>;
+    deposit_on_validator_callback(args: { predecessor_account_id: AccountId, validator_account_id: AccountId, near_amount: number, attached_deposit: number, refundable_near_amount: number, token_amount: number, near_remainder: number, storage_staking_price_per_additional_accounts: number, referrer_id: AccountId | null }): Promise<boolean>;
+    drain_validator(args: { validator_account_id: AccountId }): Promise<unknown>;
+    drain_validator_callback(args: { validator_account_id: AccountId, near_amount: number }): Promise<CallbackResult>;
+    enable_auto_claim(args: {}): Promise<PromiseOrValueNull>;
+    ft_transfer(args: { receiver_id: AccountId, amount: string, _memo: string | null }): Promise<void>;
+    ft_transfer_call(args: { _receiver_id: AccountId, _amount: string, _memo: string | null, _msg: string }): Promise<PromiseOrValueString>;
+    increase_validator_stake(args: { validator_account_id: AccountId, near_amount: string }): Promise<unknown>;
+    increase_validator_stake_callback(args: { validator_account_id: AccountId, near_amount: number, current_epoch_height: number }): Promise<boolean>;
+    instant_withdraw(args: { token_amount: string, min_near_amount: string | null }): Promise<unknown>;
+    instant_withdraw_to(args: { receiver_id: AccountId, token_amount: string, min_near_amount: string | null }): Promise<unknown>;
+    migrate(args: {}): Promise<void>;
+    migrate_investment_to_classic(args: { investor_account_id: AccountId, validator_account_id: AccountId }): Promise<unknown>;
+    new(args: { fungible_token_metadata: FungibleTokenMetadata, manager_id: AccountId | null, reward_fee: Fee | null, reward_fee_split: FeeSplit, instant_withdraw_fee: Fee | null, instant_withdraw_fee_split: FeeSplit, pool_config: PoolConfig | null }): Promise<void>;
+    process_matured_withdrawals(args: { limit: number }): Promise<number>;
+    reconcile_validator(args: { validator_account_id: AccountId }): Promise<unknown>;
+    reconcile_validator_callback(args: { validator_account_id: AccountId, current_epoch_height: number }): Promise<CallbackResult>;
+    remove_fee_beneficiary(args: { fee_type: FeeType, beneficiary_account_id: AccountId }): Promise<PromiseOrValueNull>;
+    remove_investor(args: { investor_account_id: AccountId }): Promise<unknown>;
+    remove_referrer(args: { referrer_account_id: AccountId }): Promise<unknown>;
+    remove_validator(args: { validator_account_id: AccountId }): Promise<unknown>;
+    requested_decrease_validator_stake(args: { validator_account_id: AccountId, near_amount: string, stake_decreasing_type: StakeDecreasingType }): Promise<unknown>;
+    requested_decrease_validator_stake_callback_1(args: { validator_account_id: AccountId, near_amount: number, stake_decreasing_type: StakeDecreasingType, refundable_near_amount: number }): Promise<PromiseOrValueCallbackResult>;
+    requested_decrease_validator_stake_callback_2(args: { validator_account_id: AccountId, near_amount: number, stake_decreasing_type: StakeDecreasingType, refundable_near_amount: number }): Promise<CallbackResult>;
+    start_draining_validator(args: { validator_account_id: AccountId }): Promise<void>;
+    take_delayed_withdrawal(args: {}): Promise<unknown>;
+    take_delayed_withdrawal_to(args: { receiver_id: AccountId }): Promise<unknown>;
+    take_unstaked_balance(args: { validator_account_id: AccountId }): Promise<unknown>;
+    take_unstaked_balance_callback(args: { validator_account_id: AccountId, requested_to_withdrawal_near_amount: number, drained_near_amount: number }): Promise<CallbackResult>;
+    transfer_near_amount_callback(args: { receiver_account_id: AccountId, refund_account_id: AccountId, near_amount: number }): Promise<void>;
+    update(args: {}): Promise<void>;
+    update_config(args: { pool_config: PoolConfig }): Promise<void>;
+    update_validator(args: { validator_account_id: AccountId }): Promise<unknown>;
+    update_validator_callback(args: { validator_account_id: AccountId, current_epoch_height: number }): Promise<CallbackResult>;
+}
@@ -0,0 +1,182 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId, Balance, BorshStorageKey, EpochHeight, PanicOnDefault, Promise};
+use stake_pool::cross_contract_call::classic_validator::ClassicValidator;
+
+/// Quantity of epochs after passing which the unstaked balance can be withdrawn.
+const EPOCH_QUANTITY_TO_UNLOCK: EpochHeight = 4;
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    Account
+}
+
+/// Model of the https://github.com/near/core-contracts/tree/master/staking-pool contract
+/// for testing the stake pool without the network. Errors are Rust panics, so the executor of the
+/// integration tests can roll them back.
+#[near_bindgen]
+#[derive(PanicOnDefault, BorshDeserialize, BorshSerialize)]
+pub struct MockValidator {
+    account_registry: LookupMap<AccountId, Account>,
+    /// Reward of each epoch is 'reward_numerator' divided by 'reward_denominator' of the staked balance.
+    reward_numerator: u64,
+    reward_denominator: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Account {
+    pub staked_balance: Balance,
+    pub unstaked_balance: Balance,
+    /// Epoch height since which the unstaked balance can be withdrawn.
+    pub unstaked_available_epoch_height: EpochHeight,
+    /// Epoch height up to which the rewards are added to the staked balance.
+    pub rewarded_epoch_height: EpochHeight
+}
+
+// The first public method of the block names the symbol of its ABI, that must differ from the symbols of the stake pool,
+// because both contracts are linked into the integration tests.
+#[near_bindgen]
+impl MockValidator {
+    /// Provides the ability to change the reward of each next epoch.
+    pub fn change_reward(&mut self, reward_numerator: u64, reward_denominator: u64) {
+        self.reward_numerator = reward_numerator;
+        self.reward_denominator = reward_denominator;
+    }
+
+    #[init]
+    pub fn new(reward_numerator: u64, reward_denominator: u64) -> Self {
+        if env::state_exists() {
+            panic!("Contract state is already initialized.");
+        }
+
+        Self {
+            account_registry: LookupMap::new(StorageKey::Account),
+            reward_numerator,
+            reward_denominator
+        }
+    }
+
+    /// Returns the account with the rewards of all passed epochs.
+    fn get_account(&self, account_id: &AccountId) -> Account {
+        let epoch_height = env::epoch_height();
+
+        let mut account = match self.account_registry.get(account_id) {
+            Some(account_) => account_,
+            None => {
+                Account {
+                    staked_balance: 0,
+                    unstaked_balance: 0,
+                    unstaked_available_epoch_height: 0,
+                    rewarded_epoch_height: epoch_height
+                }
+            }
+        };
+        while account.rewarded_epoch_height < epoch_height {
+            account.staked_balance += account.staked_balance * (self.reward_numerator as Balance) / (self.reward_denominator as Balance);
+            account.rewarded_epoch_height += 1;
+        }
+
+        account
+    }
+
+    fn internal_withdraw(&mut self, near_amount: Balance) {
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let mut account = self.get_account(&predecessor_account_id);
+        if near_amount > account.unstaked_balance {
+            panic!("Not enough unstaked balance to withdraw.");
+        }
+        if account.unstaked_available_epoch_height > env::epoch_height() {
+            panic!("The unstaked balance is not yet available due to unstaking delay.");
+        }
+        account.unstaked_balance -= near_amount;
+        self.account_registry.insert(&predecessor_account_id, &account);
+
+        Promise::new(predecessor_account_id).transfer(near_amount);
+    }
+
+    fn internal_stake(&mut self, near_amount: Balance) {
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let mut account = self.get_account(&predecessor_account_id);
+        if near_amount > account.unstaked_balance {
+            panic!("Not enough unstaked balance to stake.");
+        }
+        account.unstaked_balance -= near_amount;
+        account.staked_balance += near_amount;
+        self.account_registry.insert(&predecessor_account_id, &account);
+    }
+
+    fn internal_unstake(&mut self, near_amount: Balance) {
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let mut account = self.get_account(&predecessor_account_id);
+        if near_amount > account.staked_balance {
+            panic!("Not enough staked balance to unstake.");
+        }
+        account.staked_balance -= near_amount;
+        account.unstaked_balance += near_amount;
+        account.unstaked_available_epoch_height = env::epoch_height() + EPOCH_QUANTITY_TO_UNLOCK;
+        self.account_registry.insert(&predecessor_account_id, &account);
+    }
+}
+
+#[near_bindgen]
+impl ClassicValidator for MockValidator {
+    #[payable]
+    fn deposit(&mut self) {
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let mut account = self.get_account(&predecessor_account_id);
+        account.unstaked_balance += env::attached_deposit();
+        self.account_registry.insert(&predecessor_account_id, &account);
+    }
+
+    #[payable]
+    fn deposit_and_stake(&mut self) {
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let mut account = self.get_account(&predecessor_account_id);
+        account.staked_balance += env::attached_deposit();
+        self.account_registry.insert(&predecessor_account_id, &account);
+    }
+
+    fn withdraw(&mut self, amount: U128) {
+        self.internal_withdraw(amount.into());
+    }
+
+    fn withdraw_all(&mut self) {
+        let unstaked_balance = self.get_account(&env::predecessor_account_id()).unstaked_balance;
+
+        self.internal_withdraw(unstaked_balance);
+    }
+
+    fn stake(&mut self, amount: U128) {
+        self.internal_stake(amount.into());
+    }
+
+    fn unstake(&mut self, amount: U128) {
+        self.internal_unstake(amount.into());
+    }
+
+    fn unstake_all(&mut self) {
+        let staked_balance = self.get_account(&env::predecessor_account_id()).staked_balance;
+
+        self.internal_unstake(staked_balance);
+    }
+
+    fn get_account_staked_balance(&self, account_id: AccountId) -> U128 {
+        self.get_account(&account_id).staked_balance.into()
+    }
+
+    fn get_account_unstaked_balance(&self, account_id: AccountId) -> U128 {
+        self.get_account(&account_id).unstaked_balance.into()
+    }
+
+    fn get_account_total_balance(&self, account_id: AccountId) -> U128 {
+        let account = self.get_account(&account_id);
+
+        (account.staked_balance + account.unstaked_balance).into()
+    }
+}
@@ -0,0 +1,232 @@
+//! Machine-readable interface of the stake pool in the format of the NEAR ABI, and the TypeScript typings generated from it.
+//! The ABI is generated by the near-sdk from the contract methods themselves: with the 'abi' feature each 'near_bindgen'
+//! block exports the ABI entry of its public methods, and the entries are combined as the 'cargo near abi' combines them.
+
+use std::collections::{BTreeMap, HashMap};
+use near_abi::__private::ChunkedAbiEntry;
+use near_abi::AbiMetadata;
+use near_sdk::serde_json::{self, Map, Value};
+
+pub const ABI_FILE_NAME: &str = "stake_pool.abi.json";
+pub const TYPESCRIPT_FILE_NAME: &str = "stake_pool.d.ts";
+
+// Each symbol is exported by the 'near_bindgen' block and is named after the first public method of the block.
+// The entry is the JSON of the 'ChunkedAbiEntry', that is leaked by the block and is owned by the caller.
+#[allow(improper_ctypes)]
+extern "C" {
+    fn __near_abi_new() -> (*const u8, usize);
+    fn __near_abi_ft_transfer() -> (*const u8, usize);
+    fn __near_abi_transfer_near_amount_callback() -> (*const u8, usize);
+}
+
+/// Entries of all 'near_bindgen' blocks of the contract.
+pub const ABI_ENTRY_QUANTITY: usize = 3;
+
+#[allow(improper_ctypes_definitions)]
+fn read_entry(export: unsafe extern "C" fn() -> (*const u8, usize)) -> ChunkedAbiEntry {
+    // The entry is the vector with the equal length and capacity, that is not dropped by the block.
+    let entry = unsafe {
+        let (pointer, length) = export();
+
+        Vec::from_raw_parts(pointer as *mut u8, length, length)
+    };
+
+    serde_json::from_slice(&entry).unwrap()
+}
+
+/// Generates the ABI of the contract. The types of the arguments and of the results are in the 'definitions'
+/// of the root schema.
+pub fn generate_abi() -> Value {
+    let entries: [ChunkedAbiEntry; ABI_ENTRY_QUANTITY] = [
+        read_entry(__near_abi_new),
+        read_entry(__near_abi_ft_transfer),
+        read_entry(__near_abi_transfer_near_amount_callback)
+    ];
+
+    let abi = ChunkedAbiEntry::combine(entries).unwrap().into_abi_root(
+        AbiMetadata {
+            name: Some(env!("CARGO_PKG_NAME").to_string()),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            authors: env!("CARGO_PKG_AUTHORS").split(':').map(str::to_string).collect(),
+            build: None,
+            wasm_hash: None,
+            other: HashMap::new()
+        }
+    );
+
+    serde_json::to_value(abi).unwrap()
+}
+
+/// Generates the TypeScript typings from the ABI: the type for each definition and the interfaces
+/// of the view-methods and of the call-methods with their arguments.
+pub fn generate_typescript(abi: &Value) -> String {
+    let abi = &inline_untyped_definitions(abi);
+
+    let mut typescript = format!(
+        "// Generated from the ABI of {} {}. Do not edit.\n",
+        abi["metadata"]["name"].as_str().unwrap_or_default(),
+        abi["metadata"]["version"].as_str().unwrap_or_default()
+    );
+
+    let definitions: BTreeMap<String, Value> = match abi["body"]["root_schema"]["definitions"].as_object() {
+        Some(definitions_) => definitions_.clone().into_iter().collect(),
+        None => BTreeMap::new()
+    };
+    for (name, schema) in definitions.iter() {
+        typescript.push('\n');
+        typescript.push_str(&to_typescript_comment(schema, ""));
+        if schema.get("properties").is_some() {
+            typescript.push_str(&format!("export interface {} {}\n", name, to_typescript_object(schema, "")));
+        } else {
+            typescript.push_str(&format!("export type {} = {};\n", name, to_typescript_type(schema, "")));
+        }
+    }
+
+    let functions: Vec<Value> = abi["body"]["functions"].as_array().cloned().unwrap_or_default();
+    for (interface_name, kind) in [("StakePoolViewMethods", "view"), ("StakePoolCallMethods", "call")] {
+        typescript.push_str(&format!("\nexport interface {} {{\n", interface_name));
+
+        for function in functions.iter().filter(|function| function["kind"] == kind) {
+            let arguments: Vec<String> = function["params"]["args"].as_array().cloned().unwrap_or_default().iter()
+                .map(|argument| format!("{}: {}", argument["name"].as_str().unwrap_or_default(), to_typescript_type(&argument["type_schema"], "    ")))
+                .collect();
+            let result = match function.get("result") {
+                Some(result_) => to_typescript_type(&result_["type_schema"], "    "),
+                None => "void".to_string()
+            };
+
+            typescript.push_str(
+                &format!("    {}(args: {{ {} }}): Promise<{}>;\n", function["name"].as_str().unwrap_or_default(), arguments.join(", "), result)
+                    .replace("{  }", "{}")
+            );
+        }
+
+        typescript.push_str("}\n");
+    }
+
+    typescript
+}
+
+/// Replaces the references to the definitions, that match any value, as the 'Promise' of the near-sdk does,
+/// with the schema itself, so they are typed as 'unknown' and their names do not shadow the TypeScript types.
+fn inline_untyped_definitions(abi: &Value) -> Value {
+    fn inline(value: &mut Value, definitions: &Map<String, Value>) {
+        match value {
+            Value::Object(object) => {
+                let untyped_definition = object.get("$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|reference| definitions.get(reference.trim_start_matches("#/definitions/")))
+                    .filter(|definition| definition.is_boolean());
+                if let Some(definition) = untyped_definition {
+                    *value = definition.clone();
+
+                    return;
+                }
+
+                object.values_mut().for_each(|value_| inline(value_, definitions));
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value_| inline(value_, definitions)),
+            _ => {}
+        }
+    }
+
+    let mut abi = abi.clone();
+    let definitions = abi["body"]["root_schema"]["definitions"].as_object().cloned().unwrap_or_default();
+    inline(&mut abi, &definitions);
+    if let Some(definitions_) = abi["body"]["root_schema"]["definitions"].as_object_mut() {
+        definitions_.retain(|_, definition| !definition.is_boolean());
+    }
+
+    abi
+}
+
+/// Only the first paragraph of the description is taken, the rest of the documentation is for the Rust code.
+fn to_typescript_comment(schema: &Value, indentation: &str) -> String {
+    match schema.get("description").and_then(Value::as_str) {
+        Some(description) => format!("{}/** {} */\n", indentation, description.split("\n\n").next().unwrap_or_default().replace('\n', " ")),
+        None => String::new()
+    }
+}
+
+fn to_typescript_object(schema: &Value, indentation: &str) -> String {
+    let required: Vec<&str> = schema["required"].as_array()
+        .map(|required_| required_.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let properties = match schema["properties"].as_object() {
+        Some(properties_) => properties_,
+        None => return "{}".to_string()
+    };
+
+    let nested_indentation = format!("{}    ", indentation);
+    let mut object = "{\n".to_string();
+    for (name, property) in properties.iter() {
+        object.push_str(&to_typescript_comment(property, &nested_indentation));
+        object.push_str(
+            &format!(
+                "{}{}{}: {};\n",
+                nested_indentation,
+                name,
+                if required.contains(&name.as_str()) { "" } else { "?" },
+                to_typescript_type(property, &nested_indentation)
+            )
+        );
+    }
+    object.push_str(indentation);
+    object.push('}');
+
+    object
+}
+
+fn to_typescript_type(schema: &Value, indentation: &str) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference.trim_start_matches("#/definitions/").to_string();
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values.iter().map(Value::to_string).collect::<Vec<String>>().join(" | ");
+    }
+    for key in ["anyOf", "oneOf", "allOf"] {
+        if let Some(schemas) = schema.get(key).and_then(Value::as_array) {
+            let separator = if key == "allOf" { " & " } else { " | " };
+
+            return schemas.iter().map(|schema_| to_typescript_type(schema_, indentation)).collect::<Vec<String>>().join(separator);
+        }
+    }
+
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(type_) => vec![type_.as_str()],
+        Value::Array(types_) => types_.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".to_string()
+    };
+
+    types.into_iter()
+        .map(|type_| match type_ {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => match &schema["items"] {
+                Value::Array(items) => format!(
+                    "[{}]", items.iter().map(|item| to_typescript_type(item, indentation)).collect::<Vec<String>>().join(", ")
+                ),
+                items => {
+                    let item = to_typescript_type(items, indentation);
+
+                    // Only the union has to be parenthesized.
+                    if schema_is_union(items) { format!("({})[]", item) } else { format!("{}[]", item) }
+                }
+            },
+            "object" => to_typescript_object(schema, indentation),
+            _ => "unknown".to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+fn schema_is_union(schema: &Value) -> bool {
+    schema.get("anyOf").is_some()
+        || schema.get("oneOf").is_some()
+        || schema.get("allOf").is_some()
+        || schema.get("enum").and_then(Value::as_array).is_some_and(|values| values.len() > 1)
+        || schema["type"].as_array().is_some_and(|types| types.len() > 1)
+}
@@ -1,4 +1,4 @@
-use near_sdk::Balance;
+use near_sdk::{AccountId, Balance};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -7,5 +7,29 @@ pub struct AccountBalance {
     /// Amount of classic Near that remained as a result of the conversion at the exchange rate.
     pub classic_near_amount: Balance,
     /// Amount of investment Near that remained as a result of the conversion at the exchange rate.
+    pub investment_near_amount: Balance,
+    /// Referrer, by which the account came to the pool. It is set only once.
+    pub referrer_id: Option<AccountId>
+}
+
+/// Layout of the account balance, that was stored before the referral program.
+/// Such accounts are kept in the legacy registry and are converted on the first access.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AccountBalanceV1 {
+    pub token_amount: Balance,
+    pub classic_near_amount: Balance,
     pub investment_near_amount: Balance
-}
\ No newline at end of file
+}
+
+impl AccountBalanceV1 {
+    /// The account came to the pool before the referral program, so it has no referrer.
+    /// The remainders of this layout are the part of the common balance, so they are not carried over.
+    pub fn migrate(self) -> AccountBalance {
+        AccountBalance {
+            token_amount: self.token_amount,
+            classic_near_amount: 0,
+            investment_near_amount: self.investment_near_amount,
+            referrer_id: None
+        }
+    }
+}
@@ -3,10 +3,26 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct AccountRegistry {
+    pub owner_id: AccountId,
+    pub manager_id: AccountId
+}
+
+/// Layout of the account registry, that was stored before the fees were split between beneficiaries.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct AccountRegistryV1 {
     pub owner_id: AccountId,
     pub manager_id: AccountId,
-    /// Id of account, that will receive 'self_fee'.
+    /// Id of account, that received 'self_fee'.
     pub self_fee_receiver_account_id: AccountId,
-    /// Id of account, that will receive 'partner_fee'.
+    /// Id of account, that received 'partner_fee'.
     pub partner_fee_receiver_account_id: AccountId
-}
\ No newline at end of file
+}
+
+impl AccountRegistryV1 {
+    pub fn migrate(self) -> AccountRegistry {
+        AccountRegistry {
+            owner_id: self.owner_id,
+            manager_id: self.manager_id
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Writes the ABI of the stake pool and the TypeScript typings generated from it.
+//! Usage: abi [path to the directory, 'abi' by default]
+//! The files are 'stake_pool.abi.json' and 'stake_pool.d.ts'.
+//! Exits with the code 1 if a file can not be written.
+
+use std::path::Path;
+use std::{env, fs, process};
+use near_sdk::serde_json;
+use stake_pool::abi;
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|error| format!("File {} can not be written: {}.", path.display(), error))
+}
+
+fn run() -> Result<(), String> {
+    let directory = env::args().nth(1).unwrap_or_else(|| "abi".to_string());
+    let directory = Path::new(&directory);
+    fs::create_dir_all(directory)
+        .map_err(|error| format!("Directory {} can not be created: {}.", directory.display(), error))?;
+
+    let abi = abi::generate_abi();
+
+    write(&directory.join(abi::ABI_FILE_NAME), &format!("{}\n", serde_json::to_string_pretty(&abi).unwrap()))?;
+    write(&directory.join(abi::TYPESCRIPT_FILE_NAME), &abi::generate_typescript(&abi))
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("{}", error);
+
+        process::exit(1);
+    }
+}
@@ -0,0 +1,58 @@
+//! Replays the logs of the stake pool and prints the rebuilt state as JSON.
+//! Usage: log_replay <path to the logs> [path to the 'get_full' snapshot] [account_id=path to the 'get_full_for_account' snapshot]...
+//! The logs are a JSON array of strings in the order of execution.
+//! Exits with the code 1 if an input can not be read, and with the code 2 if the replayed state does not match the snapshots.
+
+use std::{env, fs, process};
+use near_sdk::serde_json::{self, json};
+use near_sdk::AccountId;
+use stake_pool::log_replay::{Difference, Replay};
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("File {} can not be read: {}.", path, error))
+}
+
+fn run() -> Result<(Replay, Vec<Difference>), String> {
+    let mut arguments = env::args().skip(1);
+
+    let logs_path = match arguments.next() {
+        Some(logs_path_) => logs_path_,
+        None => return Err("Path to the logs is absent.".to_string())
+    };
+    let logs: Vec<String> = serde_json::from_str(&read(&logs_path)?)
+        .map_err(|error| format!("Logs must be a JSON array of strings: {}.", error))?;
+
+    let replay = Replay::from_logs(&logs);
+
+    let mut differences: Vec<Difference> = vec![];
+    for argument in arguments {
+        match argument.split_once('=') {
+            Some((account_id, path)) => {
+                let account_id: AccountId = account_id.parse()
+                    .map_err(|_| format!("Account id {} is not valid.", account_id))?;
+
+                differences.extend(replay.diff_full_for_account(&account_id, &read(path)?)?);
+            }
+            None => differences.extend(replay.diff_full(&read(&argument)?)?)
+        }
+    }
+
+    Ok((replay, differences))
+}
+
+fn main() {
+    let (replay, differences) = match run() {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("{}", error);
+
+            process::exit(1);
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&json!({ "replay": replay, "differences": differences })).unwrap());
+
+    if !differences.is_empty() {
+        process::exit(2);
+    }
+}
@@ -0,0 +1,32 @@
+//! Decodes the export of the 'view_state' RPC method of the stake pool and prints it as JSON.
+//! Usage: state_dump [path to the export], the export is read from the standard input if the path is absent.
+//! Exits with the code 1 if the export can not be read. Orphaned and undecodable keys are listed in the 'issues'.
+
+use std::io::{self, Read};
+use std::{env, fs, process};
+use near_sdk::serde_json;
+use stake_pool::stake_pool::state_dump;
+
+fn main() {
+    let json = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(&path).map_err(|error| format!("File {} can not be read: {}.", path, error)),
+        None => {
+            let mut json = String::new();
+
+            io::stdin().read_to_string(&mut json)
+                .map(|_| json)
+                .map_err(|error| format!("Standard input can not be read: {}.", error))
+        }
+    };
+
+    let state_dump = match json.and_then(|json_| state_dump::decode_view_state(&json_)) {
+        Ok(state_dump_) => state_dump_,
+        Err(error) => {
+            eprintln!("{}", error);
+
+            process::exit(1);
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&state_dump).unwrap());
+}
@@ -0,0 +1,165 @@
+//! Accounting of the stake pool without access to the blockchain environment.
+//! The contract delegates to these functions, so off-chain tools and tests reuse exactly the same math.
+//! The price of the token is defined by the common balance of the pool and the total supply of the token.
+
+use near_sdk::{Balance, EpochHeight, ONE_NEAR};
+use super::fee::{Fee, U256};
+use super::stake_pool_error::StakePoolError;
+use super::REFERRAL_FEE_PER_TOKEN_DENOMINATOR;
+
+/// Result of exchanging the token amount on instant withdraw.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstantWithdrawal {
+    /// Part of the exchanged token amount, that is distributed between the fee beneficiaries.
+    pub fee_token_amount: Balance,
+    /// Part of the exchanged token amount, that is burned.
+    pub burned_token_amount: Balance,
+    /// Near amount received for the burned token amount.
+    pub near_amount: Balance
+}
+
+/// Returns the token amount for the Near amount and the Near remainder, that can not be exchanged because of rounding.
+/// The exchanged Near amount is rounded up, so the deposit does not decrease the price of the token.
+pub fn convert_near_amount_to_token_amount(near_amount: Balance, common_balance: Balance, total_supply: Balance) -> (Balance, Balance) {
+    if common_balance == 0 || near_amount == 0 || total_supply == 0 {
+        return (near_amount, 0);
+    }
+
+    let token_amount = (
+        U256::from(near_amount)
+        * U256::from(total_supply)
+        / U256::from(common_balance)
+    ).as_u128();
+
+    let exchanged_near_amount = (
+        (U256::from(token_amount) * U256::from(common_balance) + U256::from(total_supply) - 1)
+        / U256::from(total_supply)
+    ).as_u128();
+    let remainder_near_amount = near_amount - exchanged_near_amount;
+
+    (token_amount, remainder_near_amount)
+}
+
+pub fn convert_token_amount_to_near_amount(token_amount: Balance, common_balance: Balance, total_supply: Balance) -> Balance {
+    if total_supply == 0 || token_amount == 0 {
+        return token_amount;
+    }
+
+    (
+        U256::from(token_amount)
+        * U256::from(common_balance)
+        / U256::from(total_supply)
+    ).as_u128()
+}
+
+/// Near amount for ONE_NEAR of token amount.
+pub fn calculate_near_per_token(common_balance: Balance, total_supply: Balance) -> Balance {
+    if common_balance == 0 || total_supply == 0 {
+        return ONE_NEAR;
+    }
+
+    (
+        U256::from(ONE_NEAR)
+        * U256::from(common_balance)
+        / U256::from(total_supply)
+    ).as_u128()
+}
+
+/// Splits the token amount exchanged on instant withdraw into the fee and the burned part.
+pub fn calculate_instant_withdrawal(
+    token_amount: Balance,
+    instant_withdraw_fee: Option<&Fee>,
+    common_balance: Balance,
+    total_supply: Balance
+) -> InstantWithdrawal {
+    let fee_token_amount = match instant_withdraw_fee {
+        Some(instant_withdraw_fee_) => instant_withdraw_fee_.multiply(token_amount),
+        None => 0
+    };
+    let burned_token_amount = token_amount - fee_token_amount;
+
+    InstantWithdrawal {
+        fee_token_amount,
+        burned_token_amount,
+        near_amount: convert_token_amount_to_near_amount(burned_token_amount, common_balance, total_supply)
+    }
+}
+
+/// Returns the token amount minted as the reward fee. The common balance must already include the rewards.
+pub fn calculate_reward_fee_token_amount(
+    rewards_near_amount: Balance,
+    reward_fee: Option<&Fee>,
+    common_balance: Balance,
+    total_supply: Balance
+) -> Balance {
+    match reward_fee {
+        Some(reward_fee_) => {
+            let (rewards_token_amount, _) = convert_near_amount_to_token_amount(rewards_near_amount, common_balance, total_supply);
+
+            reward_fee_.multiply(rewards_token_amount)
+        }
+        None => 0
+    }
+}
+
+/// Returns the part of the reward fee token amount, that is accrued to one referred token, multiplied by
+/// the REFERRAL_FEE_PER_TOKEN_DENOMINATOR.
+pub fn calculate_referral_fee_per_token(
+    referral_fee: &Fee,
+    reward_fee_token_amount: Balance,
+    total_supply: Balance
+) -> Balance {
+    if total_supply == 0 {
+        return 0;
+    }
+
+    referral_fee.multiply(
+        (
+            U256::from(reward_fee_token_amount)
+            * U256::from(REFERRAL_FEE_PER_TOKEN_DENOMINATOR)
+            / U256::from(total_supply)
+        ).as_u128()
+    )
+}
+
+/// Returns the referral fee token amount of the referred token amount. The fee accrued to the referrer is rounded down,
+/// and the fee minted for all referrers is rounded up, so the minted fee covers the fees of the referrers.
+pub fn calculate_referral_fee_token_amount(
+    referred_token_amount: Balance,
+    referral_fee_per_token: Balance,
+    is_rounded_up: bool
+) -> Balance {
+    let rounding = if is_rounded_up {
+        U256::from(REFERRAL_FEE_PER_TOKEN_DENOMINATOR - 1)
+    } else {
+        U256::from(0)
+    };
+
+    (
+        (U256::from(referred_token_amount) * U256::from(referral_fee_per_token) + rounding)
+        / U256::from(REFERRAL_FEE_PER_TOKEN_DENOMINATOR)
+    ).as_u128()
+}
+
+/// Returns the quantity of epochs, that must pass before the delayed withdrawal can be taken.
+pub fn get_epoch_quantity_to_take_delayed_withdrawal(
+    started_epoch_height: EpochHeight,
+    epoch_quantity_for_delayed_withdrawal: u64,
+    current_epoch_height: EpochHeight
+) -> Result<u64, StakePoolError> {
+    if current_epoch_height < started_epoch_height {
+        return Err(
+            StakePoolError::NonexecutableCode { reason: "Current epoch height must be greater or equal to started epoch height.".to_string() }
+        );
+    }
+    let passed_epoch_height = current_epoch_height - started_epoch_height;
+
+    if epoch_quantity_for_delayed_withdrawal > passed_epoch_height {
+        Ok(epoch_quantity_for_delayed_withdrawal - passed_epoch_height)
+    } else {
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,356 @@
+use proptest::prelude::*;
+use super::*;
+
+/// Index of the fee beneficiary account in the ledger.
+const FEE_BENEFICIARY_INDEX: usize = 0;
+const ACCOUNTS_QUANTITY: usize = 4;
+const MAXIMUM_NEAR_AMOUNT: Balance = 1_000_000 * ONE_NEAR;
+const MAXIMUM_REWARDS_NEAR_AMOUNT: Balance = 1_000 * ONE_NEAR;
+/// Denominator of the epoch rewards relative to the common balance.
+const REWARDS_DENOMINATOR: u16 = 10_000;
+
+#[derive(Clone, Debug)]
+enum Operation {
+    Deposit { account_index: usize, near_amount: Balance },
+    /// Transfers the percent of the token balance.
+    Transfer { from_account_index: usize, to_account_index: usize, percent: u8 },
+    /// Rewards are proportional to the common balance, as validators reward the staked balance.
+    Reward { numerator: u16 },
+    /// Withdraws the percent of the token balance.
+    InstantWithdraw { account_index: usize, percent: u8 },
+    /// Withdraws the percent of the token balance.
+    DelayedWithdraw { account_index: usize, percent: u8 }
+}
+
+#[derive(Default)]
+struct Account {
+    token_amount: Balance,
+    /// Near remainder, that could not be exchanged on deposit.
+    near_amount: Balance,
+    deposited_near_amount: Balance,
+    /// Each exchange between Near and tokens is rounded down for the account.
+    exchanges_quantity: u128,
+    /// Value of the received tokens at the moment of the transfer.
+    received_near_amount: Balance,
+    withdrawn_near_amount: Balance,
+    has_sent_tokens: bool,
+    has_paid_instant_withdraw_fee: bool
+}
+
+impl Account {
+    /// The guarantee does not cover the tokens sent to other accounts and the fee paid by choice.
+    fn is_guaranteed(&self) -> bool {
+        !self.has_sent_tokens && !self.has_paid_instant_withdraw_fee
+    }
+}
+
+/// Model of the pool state changed by the same rules as the contract changes its fund and token accounts.
+struct Ledger {
+    common_balance: Balance,
+    /// The Near remainders of the accounts are kept outside the common balance, as the contract does.
+    remainder_balance: Balance,
+    total_supply: Balance,
+    accounts: Vec<Account>,
+    rewards_near_amount: Balance,
+    reward_fee: Fee,
+    instant_withdraw_fee: Fee
+}
+
+impl Ledger {
+    fn new() -> Self {
+        Self {
+            common_balance: 0,
+            remainder_balance: 0,
+            total_supply: 0,
+            accounts: (0..ACCOUNTS_QUANTITY).map(|_| Account::default()).collect(),
+            rewards_near_amount: 0,
+            reward_fee: Fee { numerator: 1, denominator: 10 },
+            instant_withdraw_fee: Fee { numerator: 3, denominator: 1_000 }
+        }
+    }
+
+    fn get_withdrawn_near_amount(&self) -> Balance {
+        self.accounts.iter().map(|account| account.withdrawn_near_amount).sum()
+    }
+
+    /// Near amount deposited to the pool and received from validators.
+    fn get_received_near_amount(&self) -> Balance {
+        self.accounts.iter().map(|account| account.deposited_near_amount).sum::<Balance>() + self.rewards_near_amount
+    }
+
+    fn get_near_per_token(&self) -> Balance {
+        calculate_near_per_token(self.common_balance, self.total_supply)
+    }
+
+    /// Near amount, that the account receives after withdrawing all tokens without fee.
+    fn get_withdrawable_near_amount(&self, account_index: usize) -> Balance {
+        let account = &self.accounts[account_index];
+
+        convert_token_amount_to_near_amount(account.token_amount, self.common_balance, self.total_supply) + account.near_amount
+    }
+
+    fn apply(&mut self, operation: &Operation) -> Result<(), TestCaseError> {
+        match *operation {
+            Operation::Deposit { account_index, near_amount } => {
+                let (token_amount, remainder_near_amount) = convert_near_amount_to_token_amount(near_amount, self.common_balance, self.total_supply);
+                if token_amount == 0 {
+                    return Ok(());
+                }
+
+                self.common_balance += near_amount - remainder_near_amount;
+                self.remainder_balance += remainder_near_amount;
+                self.total_supply += token_amount;
+
+                let account = &mut self.accounts[account_index];
+                account.token_amount += token_amount;
+                account.near_amount += remainder_near_amount;
+                account.deposited_near_amount += near_amount;
+                account.exchanges_quantity += 1;
+            }
+            Operation::Transfer { from_account_index, to_account_index, percent } => {
+                let token_amount = self.accounts[from_account_index].token_amount * (percent as Balance) / 100;
+                if token_amount == 0 || from_account_index == to_account_index {
+                    return Ok(());
+                }
+
+                let near_amount = convert_token_amount_to_near_amount(token_amount, self.common_balance, self.total_supply);
+
+                let from_account = &mut self.accounts[from_account_index];
+                from_account.token_amount -= token_amount;
+                from_account.has_sent_tokens = true;
+                // The remainder follows the tokens, when the sender account is removed.
+                let near_remainder = if from_account.token_amount == 0 {
+                    std::mem::take(&mut from_account.near_amount)
+                } else {
+                    0
+                };
+
+                let to_account = &mut self.accounts[to_account_index];
+                to_account.token_amount += token_amount;
+                to_account.near_amount += near_remainder;
+                to_account.received_near_amount += near_amount + near_remainder;
+            }
+            Operation::Reward { numerator } => {
+                if self.total_supply == 0 {
+                    return Ok(());
+                }
+
+                let near_amount = self.common_balance * (numerator as Balance) / (REWARDS_DENOMINATOR as Balance);
+
+                self.common_balance += near_amount;
+                self.rewards_near_amount += near_amount;
+
+                let reward_fee_token_amount = calculate_reward_fee_token_amount(near_amount, Some(&self.reward_fee), self.common_balance, self.total_supply);
+                self.total_supply += reward_fee_token_amount;
+                self.accounts[FEE_BENEFICIARY_INDEX].token_amount += reward_fee_token_amount;
+            }
+            Operation::InstantWithdraw { account_index, percent } => {
+                let token_amount = self.accounts[account_index].token_amount * (percent as Balance) / 100;
+                if token_amount == 0 {
+                    return Ok(());
+                }
+
+                let instant_withdrawal = calculate_instant_withdrawal(token_amount, Some(&self.instant_withdraw_fee), self.common_balance, self.total_supply);
+                prop_assert_eq!(instant_withdrawal.fee_token_amount + instant_withdrawal.burned_token_amount, token_amount);
+
+                let remainder_near_amount = self.accounts[account_index].near_amount;
+                let near_amount = instant_withdrawal.near_amount + remainder_near_amount;
+                if near_amount == 0 {
+                    return Ok(());
+                }
+                prop_assert!(
+                    instant_withdrawal.near_amount <= self.common_balance,
+                    "Withdrawal of {} exceeds the common balance {}.", instant_withdrawal.near_amount, self.common_balance
+                );
+                prop_assert!(remainder_near_amount <= self.remainder_balance);
+
+                self.common_balance -= instant_withdrawal.near_amount;
+                self.remainder_balance -= remainder_near_amount;
+                self.total_supply -= instant_withdrawal.burned_token_amount;
+                self.accounts[FEE_BENEFICIARY_INDEX].token_amount += instant_withdrawal.fee_token_amount;
+
+                let account = &mut self.accounts[account_index];
+                account.token_amount -= token_amount;
+                account.near_amount = 0;
+                account.withdrawn_near_amount += near_amount;
+                account.has_paid_instant_withdraw_fee |= instant_withdrawal.fee_token_amount > 0;
+            }
+            Operation::DelayedWithdraw { account_index, percent } => {
+                let token_amount = self.accounts[account_index].token_amount * (percent as Balance) / 100;
+                if token_amount == 0 {
+                    return Ok(());
+                }
+
+                self.delayed_withdraw(account_index, token_amount)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn delayed_withdraw(&mut self, account_index: usize, token_amount: Balance) -> Result<(), TestCaseError> {
+        let exchanged_near_amount = convert_token_amount_to_near_amount(token_amount, self.common_balance, self.total_supply);
+
+        let remainder_near_amount = self.accounts[account_index].near_amount;
+        let near_amount = exchanged_near_amount + remainder_near_amount;
+        prop_assert!(
+            exchanged_near_amount <= self.common_balance,
+            "Withdrawal of {} exceeds the common balance {}.", exchanged_near_amount, self.common_balance
+        );
+        prop_assert!(remainder_near_amount <= self.remainder_balance);
+
+        self.common_balance -= exchanged_near_amount;
+        self.remainder_balance -= remainder_near_amount;
+        self.total_supply -= token_amount;
+
+        let account = &mut self.accounts[account_index];
+        account.token_amount -= token_amount;
+        account.near_amount = 0;
+        account.withdrawn_near_amount += near_amount;
+        account.exchanges_quantity += 1;
+
+        Ok(())
+    }
+}
+
+fn account_index_strategy() -> impl Strategy<Value = usize> {
+    1..ACCOUNTS_QUANTITY
+}
+
+fn operation_strategy() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        3 => (account_index_strategy(), 1..=MAXIMUM_NEAR_AMOUNT)
+            .prop_map(|(account_index, near_amount)| Operation::Deposit { account_index, near_amount }),
+        1 => (0..ACCOUNTS_QUANTITY, 0..ACCOUNTS_QUANTITY, 1..=100u8)
+            .prop_map(|(from_account_index, to_account_index, percent)| Operation::Transfer { from_account_index, to_account_index, percent }),
+        2 => (1..=REWARDS_DENOMINATOR / 10)
+            .prop_map(|numerator| Operation::Reward { numerator }),
+        1 => (0..ACCOUNTS_QUANTITY, 1..=100u8)
+            .prop_map(|(account_index, percent)| Operation::InstantWithdraw { account_index, percent }),
+        1 => (0..ACCOUNTS_QUANTITY, 1..=100u8)
+            .prop_map(|(account_index, percent)| Operation::DelayedWithdraw { account_index, percent })
+    ]
+}
+
+/// Applies the operations and withdraws all tokens of all accounts at the end, checking the price and
+/// the conservation of Near after each step and the "withdraw at least deposit" guarantee for the accounts,
+/// that did not send tokens and did not pay the instant withdraw fee. The rounding of each deposit
+/// and each withdrawal can cost the account at most one yoctoNear.
+fn check_operations(operations: &[Operation]) -> Result<(), TestCaseError> {
+    let mut ledger = Ledger::new();
+
+    let mut near_per_token = ledger.get_near_per_token();
+
+    for operation in operations.iter() {
+        for account_index in 0..ACCOUNTS_QUANTITY {
+            let account = &ledger.accounts[account_index];
+            if account.is_guaranteed() {
+                prop_assert!(
+                    ledger.get_withdrawable_near_amount(account_index) + account.withdrawn_near_amount + account.exchanges_quantity
+                        >= account.deposited_near_amount + account.received_near_amount,
+                    "Account {} can not withdraw its deposit.", account_index
+                );
+            }
+        }
+
+        ledger.apply(operation)?;
+
+        let new_near_per_token = ledger.get_near_per_token();
+        prop_assert!(new_near_per_token >= ONE_NEAR, "Price {} is less than 1.", new_near_per_token);
+        prop_assert!(new_near_per_token >= near_per_token, "Price decreased from {} to {}.", near_per_token, new_near_per_token);
+        prop_assert!(ledger.common_balance >= ledger.total_supply);
+        prop_assert_eq!(ledger.common_balance + ledger.remainder_balance + ledger.get_withdrawn_near_amount(), ledger.get_received_near_amount());
+        near_per_token = new_near_per_token;
+    }
+
+    for account_index in 0..ACCOUNTS_QUANTITY {
+        let token_amount = ledger.accounts[account_index].token_amount;
+        if token_amount > 0 || ledger.accounts[account_index].near_amount > 0 {
+            ledger.delayed_withdraw(account_index, token_amount)?;
+        }
+
+        let account = &ledger.accounts[account_index];
+        if account.is_guaranteed() {
+            prop_assert!(account.withdrawn_near_amount + account.exchanges_quantity >= account.deposited_near_amount + account.received_near_amount);
+        }
+    }
+    prop_assert_eq!(ledger.total_supply, 0);
+    prop_assert_eq!(ledger.remainder_balance, 0);
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn conversion_round_trip_does_not_create_near(
+        near_amount in 0..=MAXIMUM_NEAR_AMOUNT,
+        total_supply in 1..=MAXIMUM_NEAR_AMOUNT,
+        rewards_near_amount in 0..=MAXIMUM_NEAR_AMOUNT
+    ) {
+        let common_balance = total_supply + rewards_near_amount;
+
+        let (token_amount, remainder_near_amount) = convert_near_amount_to_token_amount(near_amount, common_balance, total_supply);
+        let exchanged_near_amount = convert_token_amount_to_near_amount(token_amount, common_balance, total_supply);
+
+        prop_assert!(exchanged_near_amount + remainder_near_amount <= near_amount);
+        prop_assert!(exchanged_near_amount + remainder_near_amount + 1 >= near_amount);
+        prop_assert!(token_amount <= near_amount);
+    }
+
+    #[test]
+    fn reward_fee_does_not_decrease_price(
+        total_supply in 1..=MAXIMUM_NEAR_AMOUNT,
+        rewards_near_amount in 0..=MAXIMUM_NEAR_AMOUNT,
+        new_rewards_near_amount in 0..=MAXIMUM_REWARDS_NEAR_AMOUNT,
+        numerator in 1..100u64
+    ) {
+        let common_balance = total_supply + rewards_near_amount;
+        let new_common_balance = common_balance + new_rewards_near_amount;
+
+        let reward_fee_token_amount = calculate_reward_fee_token_amount(
+            new_rewards_near_amount,
+            Some(&Fee { numerator, denominator: 100 }),
+            new_common_balance,
+            total_supply
+        );
+
+        prop_assert!(
+            calculate_near_per_token(new_common_balance, total_supply + reward_fee_token_amount)
+                >= calculate_near_per_token(common_balance, total_supply)
+        );
+    }
+
+    #[test]
+    fn delayed_withdrawal_matures_after_epoch_quantity(
+        started_epoch_height in 0..1_000_000u64,
+        epoch_quantity_for_delayed_withdrawal in 0..100u64,
+        passed_epoch_quantity in 0..200u64
+    ) {
+        let epoch_quantity = get_epoch_quantity_to_take_delayed_withdrawal(
+            started_epoch_height,
+            epoch_quantity_for_delayed_withdrawal,
+            started_epoch_height + passed_epoch_quantity
+        ).unwrap();
+
+        prop_assert_eq!(epoch_quantity == 0, passed_epoch_quantity >= epoch_quantity_for_delayed_withdrawal);
+        prop_assert!(epoch_quantity <= epoch_quantity_for_delayed_withdrawal);
+    }
+
+    #[test]
+    fn random_operations_keep_guarantees(operations in prop::collection::vec(operation_strategy(), 1..40)) {
+        check_operations(&operations)?;
+    }
+}
+
+/// Minimal case found by the property test, when the remainder of the second deposit was counted both in the common
+/// balance and as the debt to the account, so the last withdrawal exceeded the balance of the pool.
+#[test]
+fn deposit_remainder_is_not_part_of_common_balance() {
+    let operations = vec![
+        Operation::Deposit { account_index: 1, near_amount: 26 },
+        Operation::Reward { numerator: 385 },
+        Operation::Deposit { account_index: 1, near_amount: 28958112590480911 }
+    ];
+
+    check_operations(&operations).unwrap();
+}
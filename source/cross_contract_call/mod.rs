@@ -1 +1 @@
-pub(crate) mod classic_validator;
\ No newline at end of file
+pub mod classic_validator;
\ No newline at end of file
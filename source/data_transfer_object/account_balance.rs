@@ -3,6 +3,7 @@ use super::base_account_balance::BaseAccountBalance;
 use super::investment_account_balance::InvestmentAccountBalance;
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct AccountBalance {
     pub base_account_balance: Option<BaseAccountBalance>,
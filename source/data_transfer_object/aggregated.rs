@@ -3,17 +3,22 @@ use near_sdk::serde::{Deserialize, Serialize};
 use crate::fee::Fee;
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Aggregated {
     /// Near amount required for distribution on validators.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub unstaked_balance: U128,
     /// Near amount already distributed on validators.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub staked_balance: U128,
     /// Minted amount of token.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub token_total_supply: U128,
     /// Stakers quantity.
     pub token_accounts_quantity: u64,
     /// Near amount of rewards from validators.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub total_rewards_from_validators_near_amount: U128,
     /// Fee charged by the pool when receiving rewards from validators.
     pub reward_fee: Option<Fee>
@@ -0,0 +1,14 @@
+use near_sdk::EpochHeight;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Annual percentage yield, annualized linearly from the growth of the token price
+/// between two epoch reports.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct Apy {
+    pub from_epoch_height: EpochHeight,
+    pub to_epoch_height: EpochHeight,
+    pub numerator: u64,
+    pub denominator: u64
+}
@@ -2,10 +2,15 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct BaseAccountBalance {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub token_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub common_near_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub classic_near_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub classic_near_balance_token_coverage: U128
 }
\ No newline at end of file
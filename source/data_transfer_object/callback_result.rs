@@ -2,6 +2,7 @@ use near_sdk::EpochHeight;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct CallbackResult {
     pub is_success: bool,
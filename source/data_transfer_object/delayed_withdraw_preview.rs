@@ -0,0 +1,18 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelayedWithdrawPreview {
+    /// Token amount that will be sent.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub token_amount: U128,
+    /// Near amount that will be received after passing the delayed unstake process.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub near_amount: U128,
+    pub epoch_quantity_to_take_delayed_withdrawal: u64,
+    /// Near amount available in the pool for delayed unstake process.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub available_near_amount: U128
+}
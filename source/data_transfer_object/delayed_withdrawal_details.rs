@@ -2,8 +2,11 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct DelayedWithdrawalDetails {
     pub epoch_quantity_to_take_delayed_withdrawal: u64,
-    pub near_amount: U128
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub near_amount: U128,
+    pub is_auto_claim: bool
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// Near amount that still can be deposited. None means, that there is no limit.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositCapacity {
+    #[cfg_attr(feature = "abi", schemars(with = "Option<String>"))]
+    pub pool_near_amount: Option<U128>,
+    #[cfg_attr(feature = "abi", schemars(with = "Option<String>"))]
+    pub account_near_amount: Option<U128>,
+    #[cfg_attr(feature = "abi", schemars(with = "Option<String>"))]
+    pub validator_near_amount: Option<U128>
+}
@@ -0,0 +1,14 @@
+use crate::fee::Fee;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositCaps {
+    #[cfg_attr(feature = "abi", schemars(with = "Option<String>"))]
+    pub pool_near_amount: Option<U128>,
+    pub validator_share: Option<Fee>,
+    #[cfg_attr(feature = "abi", schemars(with = "Option<String>"))]
+    pub account_near_amount: Option<U128>
+}
@@ -0,0 +1,17 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositPreview {
+    /// Near amount that will be exchanged.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub near_amount: U128,
+    /// Token amount that will be received.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub token_amount: U128,
+    /// Near amount that remained as a result of the conversion at the exchange rate.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub remainder_near_amount: U128
+}
@@ -0,0 +1,24 @@
+use near_sdk::EpochHeight;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct EpochReport {
+    /// Sequence number of the report since pool initialization.
+    pub index: u64,
+    pub epoch_height: EpochHeight,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub common_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub total_supply: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub rewards_from_validators_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub reward_fee_token_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub deposited_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub withdrawn_near_amount: U128
+}
@@ -2,6 +2,7 @@ use crate::fee::Fee;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct FeeRegistryLight {
     pub reward_fee: Option<Fee>,
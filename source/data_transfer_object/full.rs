@@ -6,12 +6,15 @@ use super::requested_to_withdrawal_fund::RequestedToWithdrawalFund;
 use super::storage_staking_price::StorageStakingPrice;
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Full {
     pub storage_staking_price: StorageStakingPrice,
     pub fund: Fund,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub total_token_supply: U128,
     pub requested_to_withdrawal_fund: RequestedToWithdrawalFund,
     pub fee_registry_light: FeeRegistryLight,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub minimum_deposit_amount: U128
 }
\ No newline at end of file
@@ -6,6 +6,7 @@ use super::investor_investment::InvestorInvestment;
 use super::storage_staking_requested_coverage::StorageStakingRequestedCoverage;
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct FullForAccount {
     pub full: Full,
@@ -2,16 +2,28 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Fund {
     /// Near amount required for distribution on validators by pool.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub classic_unstaked_balance: U128,
     /// Near amount already distributed on validators by pool.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub classic_staked_balance: U128,
     /// Near amount already distributed on validators by investors.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub investment_staked_balance: U128,
     /// Near amount already distributed on validators by pool and investors.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub common_staked_balance: U128,
+    /// Near remainders of the deposits, that belong to the accounts.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub classic_remainder_balance: U128,
     /// Common management near amount.
-    pub common_balance: U128
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub common_balance: U128,
+    /// Near amount of failed outgoing transfers, that is not claimed yet.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub pending_refund_balance: U128
 }
\ No newline at end of file
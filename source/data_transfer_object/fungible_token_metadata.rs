@@ -2,12 +2,14 @@ use near_sdk::json_types::Base64VecU8;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct FungibleTokenMetadata {
     pub name: String,
     pub symbol: String,
     pub icon: Option<String>,
     pub reference: Option<String>,
+    #[cfg_attr(feature = "abi", schemars(with = "Option<String>"))]
     pub reference_hash: Option<Base64VecU8>,
     pub decimals: u8,
 }
\ No newline at end of file
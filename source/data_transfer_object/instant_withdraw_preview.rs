@@ -0,0 +1,23 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use crate::fee::Fee;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct InstantWithdrawPreview {
+    /// Token amount that will be sent.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub token_amount: U128,
+    /// Fee charged by the pool on instant unstake process. It is the rate calculated by the fee curve if the curve is set.
+    pub fee: Option<Fee>,
+    /// Token amount that will be distributed between fee beneficiaries.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub fee_token_amount: U128,
+    /// Near amount that will be received.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub near_amount: U128,
+    /// Near amount available in the pool for instant unstake process.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub available_near_amount: U128
+}
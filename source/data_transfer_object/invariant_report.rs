@@ -0,0 +1,10 @@
+use near_sdk::serde::{Deserialize, Serialize};
+use super::invariant_violation::InvariantViolation;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct InvariantReport {
+    pub is_valid: bool,
+    pub violations: Vec<InvariantViolation>
+}
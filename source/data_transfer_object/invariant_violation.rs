@@ -0,0 +1,48 @@
+use near_sdk::EpochHeight;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub enum InvariantViolation {
+    /// Sum of Near amounts registered on validators is not equal to the Near amount
+    /// that the fund expects on validators.
+    ValidatorBalanceMismatch {
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        validators_near_amount: U128,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        expected_near_amount: U128
+    },
+    /// Near balance of the contract does not cover the Near amount that must be kept on the contract.
+    InsufficientContractBalance {
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        contract_near_amount: U128,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        required_near_amount: U128
+    },
+    /// The price of token is less than 1.
+    PriceLessThanOne {
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        common_balance: U128,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        total_supply: U128
+    },
+    /// The price of token is less than the price stored in the last epoch report.
+    PriceDecreased {
+        epoch_height: EpochHeight,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        previous_near_per_token: U128,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        near_per_token: U128
+    },
+    /// Token amount on the sampled accounts does not match the total supply.
+    TotalSupplyMismatch {
+        sampled_accounts_quantity: u64,
+        accounts_quantity: u64,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        sampled_token_amount: U128,
+        #[cfg_attr(feature = "abi", schemars(with = "String"))]
+        total_supply: U128
+    }
+}
@@ -2,8 +2,11 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct InvestmentAccountBalance {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub near_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub near_balance_token_coverage: U128
 }
\ No newline at end of file
@@ -3,8 +3,11 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct InvestorInvestment {
+    #[cfg_attr(feature = "abi", schemars(with = "Vec<(String, String)>"))]
     pub distribution_registry: Vec<(AccountId, U128)>,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub staked_balance: U128
 }
\ No newline at end of file
@@ -1,17 +1,31 @@
-pub(crate) mod account_balance;
-pub(crate) mod aggregated;
-pub(crate) mod base_account_balance;
-pub(crate) mod callback_result;
-pub(crate) mod delayed_withdrawal_details;
-pub(crate) mod epoch_height_registry;
-pub(crate) mod fee_registry_light;
-pub(crate) mod full_for_account;
-pub(crate) mod full;
-pub(crate) mod fund;
-pub(crate) mod fungible_token_metadata;
-pub(crate) mod investment_account_balance;
-pub(crate) mod investor_investment;
-pub(crate) mod requested_to_withdrawal_fund;
-pub(crate) mod storage_staking_price;
-pub(crate) mod storage_staking_requested_coverage;
-pub(crate) mod validator;
\ No newline at end of file
+pub mod account_balance;
+pub mod aggregated;
+pub mod apy;
+pub mod base_account_balance;
+pub mod callback_result;
+pub mod delayed_withdraw_preview;
+pub mod delayed_withdrawal_details;
+pub mod deposit_capacity;
+pub mod deposit_caps;
+pub mod deposit_preview;
+pub mod epoch_height_registry;
+pub mod epoch_report;
+pub mod fee_registry_light;
+pub mod full_for_account;
+pub mod full;
+pub mod fund;
+pub mod fungible_token_metadata;
+pub mod instant_withdraw_preview;
+pub mod invariant_report;
+pub mod invariant_violation;
+pub mod investment_account_balance;
+pub mod investor_investment;
+pub mod pending_pool_config;
+pub mod pool_config;
+pub mod referrer;
+pub mod requested_to_withdrawal_fund;
+pub mod storage_staking_price;
+pub mod storage_staking_requested_coverage;
+pub mod validator_performance;
+pub mod validator_reconciliation;
+pub mod validator;
\ No newline at end of file
@@ -0,0 +1,11 @@
+use near_sdk::EpochHeight;
+use near_sdk::serde::{Deserialize, Serialize};
+use super::pool_config::PoolConfig;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingPoolConfig {
+    pub pool_config: PoolConfig,
+    pub activation_epoch_height: EpochHeight
+}
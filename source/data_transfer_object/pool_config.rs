@@ -0,0 +1,13 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolConfig {
+    pub epoch_quantity_for_delayed_withdrawal: u64,
+    pub epoch_quantity_for_validator_unstake: u64,
+    pub minimum_number_of_tgas: u64,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub minimum_deposit_amount: U128
+}
@@ -0,0 +1,20 @@
+use near_sdk::AccountId;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct Referrer {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub account_id: AccountId,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub referred_token_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub referred_near_amount: U128,
+    pub referred_accounts_quantity: u64,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub earned_token_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub unclaimed_token_amount: U128
+}
@@ -3,9 +3,13 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct RequestedToWithdrawalFund {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub classic_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub investment_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "Vec<(String, String)>"))]
     pub investment_withdrawal_registry: Vec<(AccountId, U128)>
 }
\ No newline at end of file
@@ -2,12 +2,21 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct StorageStakingPrice {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_delayed_withdrawal_fund_delayed_withdrawal: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_delayed_withdrawal_fund_investment_withdrawal: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub per_delayed_withdrawal_fund_auto_claim: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_fungible_token_account: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_validating_node_validator: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_validating_node_investor: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_validating_node_distribution: U128
 }
\ No newline at end of file
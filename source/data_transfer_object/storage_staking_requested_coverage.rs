@@ -3,10 +3,15 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct StorageStakingRequestedCoverage {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_method_deposit: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "Option<(String, Vec<(String, String)>)>"))]
     pub per_method_deposit_on_validator: Option<(U128, Vec<(AccountId, U128)>)>,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub per_method_delayed_withdraw: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "Option<(String, Vec<(String, String)>)>"))]
     pub per_method_delayed_withdraw_from_validator: Option<(U128, Vec<(AccountId, U128)>)>
 }
\ No newline at end of file
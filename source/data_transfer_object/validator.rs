@@ -3,13 +3,24 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Validator {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub account_id: AccountId,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub unstaked_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub classic_staked_balance: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
     pub investment_staked_balance: U128,
     pub is_only_for_investment: bool,
     pub last_update_epoch_height: EpochHeight,
-    pub last_classic_stake_increasing_epoch_height: Option<EpochHeight>
+    pub last_classic_stake_increasing_epoch_height: Option<EpochHeight>,
+    pub is_active: bool,
+    pub consecutive_inactive_epoch_quantity: u64,
+    pub is_flagged_as_inactive: bool,
+    pub is_draining: bool,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub drained_balance: U128
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use near_sdk::EpochHeight;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct ValidatorPerformance {
+    /// Sequence number of the record since validator registration.
+    pub index: u64,
+    pub epoch_height: EpochHeight,
+    pub is_active: bool,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub validator_stake: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub rewards_near_amount: U128
+}
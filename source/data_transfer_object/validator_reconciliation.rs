@@ -0,0 +1,24 @@
+use near_sdk::EpochHeight;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct ValidatorReconciliation {
+    pub epoch_height: EpochHeight,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub staked_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub unstaked_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub recorded_staked_near_amount: U128,
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub recorded_unstaked_near_amount: U128,
+    /// Near amount on validator that is not registered by the pool.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub surplus_near_amount: U128,
+    /// Near amount registered by the pool that is absent on validator.
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub shortfall_near_amount: U128
+}
@@ -1,6 +1,7 @@
-use near_sdk::{EpochHeight, Balance, env};
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{EpochHeight, Balance, FunctionError};
+use super::core;
 use super::EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct DelayedWithdrawal {
@@ -9,24 +10,46 @@ pub struct DelayedWithdrawal {
     /// It is only needed in order to understand when it is possible to give
     /// the user his funds, because the funds can only be returned after EPOCH_QUANTITY_TO_DELAYED_WITHDRAWAL (8) epochs
     /// with delayed_withdraw method.
-    pub started_epoch_height: EpochHeight
+    pub started_epoch_height: EpochHeight,
+    /// Quantity of epochs from the pool config at the time the withdrawal was requested.
+    pub epoch_quantity_for_delayed_withdrawal: u64,
+    /// Matured withdrawal is sent to the user by keepers without the 'take_delayed_withdrawal' call.
+    pub is_auto_claim: bool
 }
 
 impl DelayedWithdrawal {
     pub fn get_epoch_quantity_to_take_delayed_withdrawal(&self, current_epoch_height: EpochHeight) -> u64 {
-        if current_epoch_height < self.started_epoch_height {
-            env::panic_str("Current epoch height must be greater or equal to started epoch height.");
-        }
-        let passed_epoch_height = current_epoch_height - self.started_epoch_height;
-
-        if EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL > passed_epoch_height {
-            EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL - passed_epoch_height
-        } else {
-            0
+        match core::get_epoch_quantity_to_take_delayed_withdrawal(
+            self.started_epoch_height,
+            self.epoch_quantity_for_delayed_withdrawal,
+            current_epoch_height
+        ) {
+            Ok(epoch_quantity) => epoch_quantity,
+            Err(error) => error.panic()
         }
     }
 
     pub fn can_take_delayed_withdrawal(&self, current_epoch_height: EpochHeight) -> bool {
         self.get_epoch_quantity_to_take_delayed_withdrawal(current_epoch_height) == 0
     }
-}
\ No newline at end of file
+}
+
+/// Layout of the delayed withdrawal, that was stored before the epoch quantity and the auto claim were added.
+/// Such withdrawals are kept in the legacy registry and are converted on the first access.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DelayedWithdrawalV1 {
+    pub near_amount: Balance,
+    pub started_epoch_height: EpochHeight
+}
+
+impl DelayedWithdrawalV1 {
+    /// The withdrawal keeps the terms it was requested on: the epoch quantity was fixed by the constant.
+    pub fn migrate(self) -> DelayedWithdrawal {
+        DelayedWithdrawal {
+            near_amount: self.near_amount,
+            started_epoch_height: self.started_epoch_height,
+            epoch_quantity_for_delayed_withdrawal: EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL,
+            is_auto_claim: false
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use near_sdk::{Balance, AccountId, env, StorageUsage};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::LookupMap;
-use super::delayed_withdrawal::DelayedWithdrawal;
+use super::delayed_withdrawal::{DelayedWithdrawal, DelayedWithdrawalV1};
 use super::get_account_id_with_maximum_length;
 use super::investment_withdrawal::InvestmentWithdrawal;
 use super::storage_key::StorageKey;
@@ -11,10 +11,22 @@ pub struct DelayedWithdrawnFund {
     /// Storage.
     /// AccountId - user account id.
     pub delayed_withdrawal_registry: LookupMap<AccountId, DelayedWithdrawal>,
+    /// Storage.
+    /// AccountId - user account id.
+    /// Delayed withdrawals in the old layout, they are moved to the delayed withdrawal registry on the first change.
+    pub legacy_delayed_withdrawal_registry: LookupMap<AccountId, DelayedWithdrawalV1>,
     /// Storage
     /// AccountId - validator account id.
     /// Balance - Near amount.
     pub investment_withdrawal_registry: LookupMap<AccountId, InvestmentWithdrawal>,
+    /// Storage.
+    /// u64 - position in the queue of delayed withdrawals, that are sent to users automatically.
+    /// AccountId - user account id.
+    pub auto_claim_queue: LookupMap<u64, AccountId>,
+    /// Position of the first element in the auto claim queue.
+    pub auto_claim_queue_head: u64,
+    /// Position after the last element in the auto claim queue.
+    pub auto_claim_queue_tail: u64,
     /// Classic Near amount needed to request from the validator.
     pub needed_to_request_classic_near_amount: Balance,
     /// Investment Near amount needed to request from the validator.
@@ -24,22 +36,72 @@ pub struct DelayedWithdrawnFund {
     /// In bytes.
     pub storage_usage_per_delayed_withdrawal: StorageUsage,
     /// In bytes.
-    pub storage_usage_per_investment_withdrawal: StorageUsage
+    pub storage_usage_per_investment_withdrawal: StorageUsage,
+    /// In bytes.
+    pub storage_usage_per_auto_claim: StorageUsage
 }
 
 impl DelayedWithdrawnFund {
     pub fn new() -> Self {
         Self {
             delayed_withdrawal_registry: Self::initialize_delayed_withdrawal_registry(),
+            legacy_delayed_withdrawal_registry: Self::initialize_legacy_delayed_withdrawal_registry(),
             investment_withdrawal_registry: Self::initialize_investment_withdrawal_registry(),
+            auto_claim_queue: Self::initialize_auto_claim_queue(),
+            auto_claim_queue_head: 0,
+            auto_claim_queue_tail: 0,
             needed_to_request_classic_near_amount: 0,
             needed_to_request_investment_near_amount: 0,
             balance: 0,
             storage_usage_per_delayed_withdrawal: Self::calculate_storage_usage_per_additional_delayed_withdrawal(),
-            storage_usage_per_investment_withdrawal: Self::calculate_storage_usage_per_additional_investment_withdrawal()
+            storage_usage_per_investment_withdrawal: Self::calculate_storage_usage_per_additional_investment_withdrawal(),
+            storage_usage_per_auto_claim: Self::calculate_storage_usage_per_additional_auto_claim()
         }
     }
 
+    pub fn get_delayed_withdrawal(&self, account_id: &AccountId) -> Option<DelayedWithdrawal> {
+        match self.delayed_withdrawal_registry.get(account_id) {
+            Some(delayed_withdrawal) => Some(delayed_withdrawal),
+            None => self.legacy_delayed_withdrawal_registry.get(account_id).map(DelayedWithdrawalV1::migrate)
+        }
+    }
+
+    pub fn insert_delayed_withdrawal(&mut self, account_id: &AccountId, delayed_withdrawal: &DelayedWithdrawal) {
+        self.legacy_delayed_withdrawal_registry.remove(account_id);
+        self.delayed_withdrawal_registry.insert(account_id, delayed_withdrawal);
+    }
+
+    pub fn remove_delayed_withdrawal(&mut self, account_id: &AccountId) -> Option<DelayedWithdrawal> {
+        match self.delayed_withdrawal_registry.remove(account_id) {
+            Some(delayed_withdrawal) => Some(delayed_withdrawal),
+            None => self.legacy_delayed_withdrawal_registry.remove(account_id).map(DelayedWithdrawalV1::migrate)
+        }
+    }
+
+    pub fn contains_delayed_withdrawal(&self, account_id: &AccountId) -> bool {
+        self.delayed_withdrawal_registry.contains_key(account_id) || self.legacy_delayed_withdrawal_registry.contains_key(account_id)
+    }
+
+    pub fn push_auto_claim(&mut self, account_id: &AccountId) {
+        self.auto_claim_queue.insert(&self.auto_claim_queue_tail, account_id);
+        self.auto_claim_queue_tail += 1;
+    }
+
+    pub fn pop_auto_claim(&mut self) -> Option<AccountId> {
+        if self.auto_claim_queue_head == self.auto_claim_queue_tail {
+            return None;
+        }
+
+        let account_id = self.auto_claim_queue.remove(&self.auto_claim_queue_head);
+        self.auto_claim_queue_head += 1;
+
+        account_id
+    }
+
+    pub fn get_auto_claim_queue_length(&self) -> u64 {
+        self.auto_claim_queue_tail - self.auto_claim_queue_head
+    }
+
     fn calculate_storage_usage_per_additional_delayed_withdrawal() -> StorageUsage {
         let mut delayed_withdrawal_registry = Self::initialize_delayed_withdrawal_registry();
 
@@ -51,7 +113,9 @@ impl DelayedWithdrawnFund {
             &account_id,
             &DelayedWithdrawal {
                 near_amount: 0,
-                started_epoch_height: env::epoch_height()
+                started_epoch_height: env::epoch_height(),
+                epoch_quantity_for_delayed_withdrawal: 0,
+                is_auto_claim: false
             }
         );
 
@@ -76,11 +140,61 @@ impl DelayedWithdrawnFund {
         env::storage_usage() - initial_storage_usage
     }
 
+    fn calculate_storage_usage_per_additional_auto_claim() -> StorageUsage {
+        let mut auto_claim_queue = Self::initialize_auto_claim_queue();
+
+        let initial_storage_usage = env::storage_usage();
+
+        auto_claim_queue.insert(&0, &get_account_id_with_maximum_length());
+
+        env::storage_usage() - initial_storage_usage
+    }
+
     fn initialize_delayed_withdrawal_registry() -> LookupMap<AccountId, DelayedWithdrawal> {
+        LookupMap::new(StorageKey::DelayedWithdrawal)
+    }
+
+    fn initialize_legacy_delayed_withdrawal_registry() -> LookupMap<AccountId, DelayedWithdrawalV1> {
         LookupMap::new(StorageKey::DelayedWithdrawnFund)
     }
 
     fn initialize_investment_withdrawal_registry() -> LookupMap<AccountId, InvestmentWithdrawal> {
         LookupMap::new(StorageKey::InvestmentWithdrawal)
     }
-}
\ No newline at end of file
+
+    fn initialize_auto_claim_queue() -> LookupMap<u64, AccountId> {
+        LookupMap::new(StorageKey::AutoClaim)
+    }
+}
+
+/// Layout of the delayed withdrawn fund, that was stored before the auto claim was added.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DelayedWithdrawnFundV1 {
+    pub delayed_withdrawal_registry: LookupMap<AccountId, DelayedWithdrawalV1>,
+    pub investment_withdrawal_registry: LookupMap<AccountId, InvestmentWithdrawal>,
+    pub needed_to_request_classic_near_amount: Balance,
+    pub needed_to_request_investment_near_amount: Balance,
+    pub balance: Balance,
+    pub storage_usage_per_delayed_withdrawal: StorageUsage,
+    pub storage_usage_per_investment_withdrawal: StorageUsage
+}
+
+impl DelayedWithdrawnFundV1 {
+    /// The delayed withdrawals stay in the legacy registry and are converted on the first access.
+    pub fn migrate(self) -> DelayedWithdrawnFund {
+        DelayedWithdrawnFund {
+            delayed_withdrawal_registry: DelayedWithdrawnFund::initialize_delayed_withdrawal_registry(),
+            legacy_delayed_withdrawal_registry: self.delayed_withdrawal_registry,
+            investment_withdrawal_registry: self.investment_withdrawal_registry,
+            auto_claim_queue: DelayedWithdrawnFund::initialize_auto_claim_queue(),
+            auto_claim_queue_head: 0,
+            auto_claim_queue_tail: 0,
+            needed_to_request_classic_near_amount: self.needed_to_request_classic_near_amount,
+            needed_to_request_investment_near_amount: self.needed_to_request_investment_near_amount,
+            balance: self.balance,
+            storage_usage_per_delayed_withdrawal: DelayedWithdrawnFund::calculate_storage_usage_per_additional_delayed_withdrawal(),
+            storage_usage_per_investment_withdrawal: self.storage_usage_per_investment_withdrawal,
+            storage_usage_per_auto_claim: DelayedWithdrawnFund::calculate_storage_usage_per_additional_auto_claim()
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use near_sdk::Balance;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use super::fee::Fee;
+
+/// No limit is applied if the cap is not set.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DepositCaps {
+    /// Maximum common balance of the pool.
+    pub pool_near_amount: Option<Balance>,
+    /// Maximum part of the common balance of the pool staked on a single validator.
+    pub validator_share: Option<Fee>,
+    /// Maximum Near amount owned by a single account in the pool.
+    pub account_near_amount: Option<Balance>
+}
+
+impl DepositCaps {
+    pub fn new() -> Self {
+        Self {
+            pool_near_amount: None,
+            validator_share: None,
+            account_near_amount: None
+        }
+    }
+
+    pub fn get_pool_remaining_near_amount(&self, common_balance: Balance) -> Option<Balance> {
+        self.pool_near_amount.map(|pool_near_amount_| pool_near_amount_.saturating_sub(common_balance))
+    }
+
+    pub fn get_validator_remaining_near_amount(&self, validator_staked_balance: Balance, common_balance: Balance) -> Option<Balance> {
+        self.validator_share.as_ref().map(|validator_share_| validator_share_.multiply(common_balance).saturating_sub(validator_staked_balance))
+    }
+
+    pub fn get_account_remaining_near_amount(&self, account_near_amount: Balance) -> Option<Balance> {
+        self.account_near_amount.map(|account_near_amount_| account_near_amount_.saturating_sub(account_near_amount))
+    }
+}
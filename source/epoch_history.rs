@@ -0,0 +1,92 @@
+use near_sdk::{env, Balance, StorageUsage};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use super::epoch_report::EpochReport;
+use super::MAXIMUM_NUMBER_OF_EPOCH_REPORTS;
+use super::storage_key::StorageKey;
+
+/// Ring buffer of reports created on each pool update. Only the last
+/// MAXIMUM_NUMBER_OF_EPOCH_REPORTS reports are kept.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct EpochHistory {
+    /// Storage.
+    /// u64 - position of the report in the ring buffer.
+    pub epoch_report_registry: LookupMap<u64, EpochReport>,
+    /// Quantity of reports created since pool initialization.
+    pub epoch_reports_quantity: u64,
+    /// Near amount deposited since previous report.
+    pub deposited_near_amount: Balance,
+    /// Near amount withdrawn since previous report.
+    pub withdrawn_near_amount: Balance,
+    /// In bytes.
+    pub storage_usage_per_epoch_report: StorageUsage
+}
+
+impl EpochHistory {
+    pub fn new() -> Self {
+        Self {
+            epoch_report_registry: Self::initialize_epoch_report_registry(),
+            epoch_reports_quantity: 0,
+            deposited_near_amount: 0,
+            withdrawn_near_amount: 0,
+            storage_usage_per_epoch_report: Self::calculate_storage_usage_per_additional_epoch_report()
+        }
+    }
+
+    /// The reports have the same size, so the storage of the whole ring buffer is known in advance
+    /// and is paid on initialization.
+    pub fn get_storage_usage(&self) -> StorageUsage {
+        MAXIMUM_NUMBER_OF_EPOCH_REPORTS * self.storage_usage_per_epoch_report
+    }
+
+    pub fn add_epoch_report(&mut self, epoch_report: &EpochReport) {
+        self.epoch_report_registry.insert(&(self.epoch_reports_quantity % MAXIMUM_NUMBER_OF_EPOCH_REPORTS), epoch_report);
+        self.epoch_reports_quantity += 1;
+        self.deposited_near_amount = 0;
+        self.withdrawn_near_amount = 0;
+    }
+
+    /// Index of the oldest report still stored in the ring buffer.
+    pub fn get_first_available_index(&self) -> u64 {
+        self.epoch_reports_quantity.saturating_sub(MAXIMUM_NUMBER_OF_EPOCH_REPORTS)
+    }
+
+    /// Index is the sequence number of the report since pool initialization.
+    pub fn get_epoch_report(&self, index: u64) -> Option<EpochReport> {
+        if index < self.get_first_available_index() || index >= self.epoch_reports_quantity {
+            return None;
+        }
+
+        self.epoch_report_registry.get(&(index % MAXIMUM_NUMBER_OF_EPOCH_REPORTS))
+    }
+
+    /// The report is removed after the measurement, because its position is used by the first report.
+    fn calculate_storage_usage_per_additional_epoch_report() -> StorageUsage {
+        let mut epoch_report_registry = Self::initialize_epoch_report_registry();
+
+        let initial_storage_usage = env::storage_usage();
+
+        epoch_report_registry.insert(
+            &0,
+            &EpochReport {
+                epoch_height: 0,
+                common_balance: 0,
+                total_supply: 0,
+                rewards_from_validators_near_amount: 0,
+                reward_fee_token_amount: 0,
+                deposited_near_amount: 0,
+                withdrawn_near_amount: 0
+            }
+        );
+
+        let storage_usage = env::storage_usage() - initial_storage_usage;
+
+        epoch_report_registry.remove(&0);
+
+        storage_usage
+    }
+
+    fn initialize_epoch_report_registry() -> LookupMap<u64, EpochReport> {
+        LookupMap::new(StorageKey::EpochReport)
+    }
+}
@@ -0,0 +1,20 @@
+use near_sdk::{Balance, EpochHeight};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct EpochReport {
+    /// Epoch to which the pool was updated.
+    pub epoch_height: EpochHeight,
+    /// Common management Near amount after updating.
+    pub common_balance: Balance,
+    /// Token total supply after updating.
+    pub total_supply: Balance,
+    /// Near amount received from validators as rewards.
+    pub rewards_from_validators_near_amount: Balance,
+    /// Token amount minted as reward fee.
+    pub reward_fee_token_amount: Balance,
+    /// Near amount deposited since previous report.
+    pub deposited_near_amount: Balance,
+    /// Near amount withdrawn since previous report.
+    pub withdrawn_near_amount: Balance
+}
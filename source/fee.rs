@@ -1,15 +1,24 @@
-use near_sdk::Balance;
+use near_sdk::{Balance, FunctionError};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::env;
 use near_sdk::serde::{Deserialize, Serialize};
 use std::clone::Clone;
-use uint::construct_uint;
+use super::stake_pool_error::StakePoolError;
 
-construct_uint! {
-    pub struct U256(4);
+pub use self::u256::U256;
+
+mod u256 {
+    // The lints are triggered by the code generated by the macro of the external crate.
+    #![allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+
+    use uint::construct_uint;
+
+    construct_uint! {
+        pub struct U256(4);
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct Fee {
     pub numerator: u64,
@@ -19,7 +28,7 @@ pub struct Fee {
 impl Fee {
     pub fn assert_valid(&self) {
         if self.denominator == 0 || self.numerator == 0 || self.numerator >= self.denominator {
-            env::panic_str("Fee is not valid.");
+            StakePoolError::FeeNotValid.panic();
         }
     }
 
@@ -0,0 +1,18 @@
+use near_sdk::AccountId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::clone::Clone;
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeBeneficiary {
+    #[cfg_attr(feature = "abi", schemars(with = "String"))]
+    pub account_id: AccountId,
+    /// Share of the fee is 'numerator' divided by 'denominator' of the fee split.
+    pub numerator: u64,
+    /// Account, that paid the storage staking price of the beneficiary added after the initialization.
+    /// The price is returned to it when the beneficiary is removed.
+    #[serde(skip)]
+    pub payer_account_id: Option<AccountId>
+}
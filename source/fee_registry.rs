@@ -1,13 +1,145 @@
+use near_sdk::{env, AccountId, StorageUsage};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
 use near_sdk::serde::{Deserialize, Serialize};
 use std::clone::Clone;
-use super::shared_fee::SharedFee;
+use super::fee::Fee;
+use super::fee_beneficiary::FeeBeneficiary;
+use super::fee_split::FeeSplit;
+use super::get_account_id_with_maximum_length;
+use super::instant_withdraw_fee_curve::InstantWithdrawFeeCurve;
+use super::storage_key::StorageKey;
 
 #[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub struct FeeRegistry {
     /// Fee that is taken from the rewards received on the validators.
-    pub reward_fee: Option<SharedFee>,
+    pub reward_fee: Option<Fee>,
+    pub reward_fee_split: FeeSplit,
     /// Fee that is taken from the Near amount on instant unstake process.
-    pub instant_withdraw_fee: Option<SharedFee>
-}
\ No newline at end of file
+    pub instant_withdraw_fee: Option<Fee>,
+    /// Fee curve that replaces the fixed instant withdraw fee if it is set.
+    pub instant_withdraw_fee_curve: Option<InstantWithdrawFeeCurve>,
+    pub instant_withdraw_fee_split: FeeSplit,
+    /// Fee that is taken from the reward fee of the pool on the referred stake in favor of the referrer.
+    pub referral_fee: Option<Fee>,
+    /// Fee that is taken from the matured delayed withdrawal in favor of the keeper, that sent it to the user.
+    pub keeper_bounty: Option<Fee>,
+    /// In bytes.
+    pub storage_usage_per_fee_beneficiary: StorageUsage
+}
+
+impl FeeRegistry {
+    /// The fee splits are stored in the contract state, so the storage usage of the additional beneficiary is the growth
+    /// of the fee split, that is measured on the separate key.
+    pub fn calculate_storage_usage_per_additional_fee_beneficiary() -> StorageUsage {
+        let mut fee_beneficiary_registry: LookupMap<u8, Vec<FeeBeneficiary>> = LookupMap::new(StorageKey::StorageUsageMeasurement);
+
+        fee_beneficiary_registry.insert(&0, &vec![]);
+
+        let initial_storage_usage = env::storage_usage();
+
+        fee_beneficiary_registry.insert(
+            &0,
+            &vec![
+                FeeBeneficiary {
+                    account_id: get_account_id_with_maximum_length(),
+                    numerator: 0,
+                    payer_account_id: Some(get_account_id_with_maximum_length())
+                }
+            ]
+        );
+
+        let storage_usage = env::storage_usage() - initial_storage_usage;
+
+        fee_beneficiary_registry.remove(&0);
+
+        storage_usage
+    }
+}
+
+/// Fee in the layout, that was stored before the fees were split between beneficiaries.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SharedFeeV1 {
+    /// Fee taken from object.
+    pub self_fee: Fee,
+    /// Fee taken from 'self_fee'.
+    pub partner_fee: Option<Fee>
+}
+
+impl SharedFeeV1 {
+    /// The partner receives its share of the fee and the self fee receiver receives the remainder of the rounding,
+    /// as it was before the split.
+    fn migrate(self, self_fee_receiver_account_id: &AccountId, partner_fee_receiver_account_id: &AccountId) -> (Fee, FeeSplit) {
+        let fee_split = match self.partner_fee {
+            Some(partner_fee) => FeeSplit {
+                beneficiaries: vec![
+                    FeeBeneficiary {
+                        account_id: self_fee_receiver_account_id.clone(),
+                        numerator: partner_fee.denominator - partner_fee.numerator,
+                        payer_account_id: None
+                    },
+                    FeeBeneficiary {
+                        account_id: partner_fee_receiver_account_id.clone(),
+                        numerator: partner_fee.numerator,
+                        payer_account_id: None
+                    }
+                ],
+                denominator: partner_fee.denominator
+            },
+            None => Self::migrate_without_partner(self_fee_receiver_account_id)
+        };
+
+        (self.self_fee, fee_split)
+    }
+
+    fn migrate_without_partner(self_fee_receiver_account_id: &AccountId) -> FeeSplit {
+        FeeSplit {
+            beneficiaries: vec![
+                FeeBeneficiary {
+                    account_id: self_fee_receiver_account_id.clone(),
+                    numerator: 1,
+                    payer_account_id: None
+                }
+            ],
+            denominator: 1
+        }
+    }
+}
+
+/// Layout of the fee registry, that was stored before the fees were split between beneficiaries.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FeeRegistryV1 {
+    pub reward_fee: Option<SharedFeeV1>,
+    pub instant_withdraw_fee: Option<SharedFeeV1>
+}
+
+impl FeeRegistryV1 {
+    pub fn migrate(self, self_fee_receiver_account_id: &AccountId, partner_fee_receiver_account_id: &AccountId) -> FeeRegistry {
+        let migrate_shared_fee = |shared_fee: Option<SharedFeeV1>| -> (Option<Fee>, FeeSplit) {
+            match shared_fee {
+                Some(shared_fee_) => {
+                    let (fee, fee_split) = shared_fee_.migrate(self_fee_receiver_account_id, partner_fee_receiver_account_id);
+
+                    (Some(fee), fee_split)
+                }
+                None => (None, SharedFeeV1::migrate_without_partner(self_fee_receiver_account_id))
+            }
+        };
+
+        let (reward_fee, reward_fee_split) = migrate_shared_fee(self.reward_fee);
+        let (instant_withdraw_fee, instant_withdraw_fee_split) = migrate_shared_fee(self.instant_withdraw_fee);
+
+        FeeRegistry {
+            reward_fee,
+            reward_fee_split,
+            instant_withdraw_fee,
+            instant_withdraw_fee_curve: None,
+            instant_withdraw_fee_split,
+            referral_fee: None,
+            keeper_bounty: None,
+            storage_usage_per_fee_beneficiary: FeeRegistry::calculate_storage_usage_per_additional_fee_beneficiary()
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use near_sdk::{AccountId, Balance, FunctionError};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::clone::Clone;
+use super::fee::Fee;
+use super::fee_beneficiary::FeeBeneficiary;
+use super::MAXIMUM_NUMBER_OF_FEE_BENEFICIARIES;
+use super::stake_pool_error::StakePoolError;
+
+/// Distribution of the fee between beneficiaries. The first beneficiary receives the remainder
+/// of the rounding, so the whole fee is distributed.
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeSplit {
+    pub beneficiaries: Vec<FeeBeneficiary>,
+    pub denominator: u64
+}
+
+impl FeeSplit {
+    pub fn assert_valid(&self) {
+        if self.denominator == 0
+            || self.beneficiaries.is_empty()
+            || self.beneficiaries.len() as u64 > MAXIMUM_NUMBER_OF_FEE_BENEFICIARIES {
+            StakePoolError::FeeSplitNotValid.panic();
+        }
+
+        let mut numerator_sum: u128 = 0;
+
+        for (index, fee_beneficiary) in self.beneficiaries.iter().enumerate() {
+            if fee_beneficiary.numerator == 0 {
+                StakePoolError::FeeSplitNotValid.panic();
+            }
+            if self.beneficiaries[..index].iter().any(|fee_beneficiary_| fee_beneficiary_.account_id == fee_beneficiary.account_id) {
+                StakePoolError::FeeBeneficiaryDuplicated.panic();
+            }
+
+            numerator_sum += fee_beneficiary.numerator as u128;
+        }
+
+        if numerator_sum != self.denominator as u128 {
+            StakePoolError::FeeBeneficiarySharesNotSumToOne.panic();
+        }
+    }
+
+    pub fn split(&self, token_amount: Balance) -> Vec<(AccountId, Balance)> {
+        let mut token_amount_registry: Vec<(AccountId, Balance)> = vec![];
+
+        let mut remainder_token_amount = token_amount;
+
+        for fee_beneficiary in self.beneficiaries.iter().skip(1) {
+            let beneficiary_token_amount = Fee {
+                numerator: fee_beneficiary.numerator,
+                denominator: self.denominator
+            }.multiply(token_amount);
+
+            remainder_token_amount -= beneficiary_token_amount;
+
+            token_amount_registry.push((fee_beneficiary.account_id.clone(), beneficiary_token_amount));
+        }
+
+        if let Some(fee_beneficiary) = self.beneficiaries.first() {
+            token_amount_registry.insert(0, (fee_beneficiary.account_id.clone(), remainder_token_amount));
+        }
+
+        token_amount_registry
+    }
+
+    pub fn contains(&self, account_id: &AccountId) -> bool {
+        self.beneficiaries.iter().any(|fee_beneficiary| fee_beneficiary.account_id == *account_id)
+    }
+}
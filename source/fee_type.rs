@@ -0,0 +1,12 @@
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub enum FeeType {
+    /// Fee that is taken from the rewards received on the validators.
+    Reward,
+    /// Fee that is taken from the Near amount on instant unstake process.
+    InstantWithdraw
+}
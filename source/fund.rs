@@ -1,6 +1,7 @@
 use near_sdk::Balance;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use super::delayed_withdrawn_fund::DelayedWithdrawnFund;
+use super::delayed_withdrawn_fund::{DelayedWithdrawnFund, DelayedWithdrawnFundV1};
+use super::pending_refund_fund::PendingRefundFund;
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Fund {
@@ -12,8 +13,13 @@ pub struct Fund {
     pub classic_liquidity_balance: Balance,
     /// Near amount already staked on validators in investment context.
     pub investment_staked_balance: Balance,
+    /// Near remainders of the deposits, that can not be exchanged for tokens because of rounding. The remainders
+    /// are kept in the classic balances, but belong to the accounts, so they are not part of the common balance.
+    pub classic_remainder_balance: Balance,
     /// Fund that should be returned to users.
     pub delayed_withdrawn_fund: DelayedWithdrawnFund,
+    /// Fund of failed outgoing transfers that should be claimed by their receivers.
+    pub pending_refund_fund: PendingRefundFund,
     pub is_distributed_on_validators_in_current_epoch: bool
 }
 
@@ -24,7 +30,9 @@ impl Fund {
             classic_staked_balance: 0,
             classic_liquidity_balance: 0,
             investment_staked_balance: 0,
+            classic_remainder_balance: 0,
             delayed_withdrawn_fund: DelayedWithdrawnFund::new(),
+            pending_refund_fund: PendingRefundFund::new(),
             is_distributed_on_validators_in_current_epoch: false
         }
     }
@@ -34,6 +42,33 @@ impl Fund {
     }
 
     pub fn get_common_balance(&self) -> Balance {
-        self.classic_unstaked_balance + self.classic_staked_balance + self.investment_staked_balance
+        self.classic_unstaked_balance + self.classic_staked_balance + self.investment_staked_balance - self.classic_remainder_balance
     }
-}
\ No newline at end of file
+}
+
+/// Layout of the fund, that was stored before the pending refunds were added.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FundV1 {
+    pub classic_unstaked_balance: Balance,
+    pub classic_staked_balance: Balance,
+    pub classic_liquidity_balance: Balance,
+    pub investment_staked_balance: Balance,
+    pub delayed_withdrawn_fund: DelayedWithdrawnFundV1,
+    pub is_distributed_on_validators_in_current_epoch: bool
+}
+
+impl FundV1 {
+    pub fn migrate(self) -> Fund {
+        Fund {
+            classic_unstaked_balance: self.classic_unstaked_balance,
+            classic_staked_balance: self.classic_staked_balance,
+            classic_liquidity_balance: self.classic_liquidity_balance,
+            investment_staked_balance: self.investment_staked_balance,
+            // The remainders of the accounts of this layout are the part of the common balance.
+            classic_remainder_balance: 0,
+            delayed_withdrawn_fund: self.delayed_withdrawn_fund.migrate(),
+            pending_refund_fund: PendingRefundFund::new(),
+            is_distributed_on_validators_in_current_epoch: self.is_distributed_on_validators_in_current_epoch
+        }
+    }
+}
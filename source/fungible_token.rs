@@ -2,7 +2,7 @@ use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::{env, AccountId, Balance, StorageUsage};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LazyOption, LookupMap};
-use super::account_balance::AccountBalance;
+use super::account_balance::{AccountBalance, AccountBalanceV1};
 use super::get_account_id_with_maximum_length;
 use super::storage_key::StorageKey;
 
@@ -12,6 +12,10 @@ pub struct FungibleToken {
     /// Storage.
     /// AccountId - user account id.
     pub account_registry: LookupMap<AccountId, AccountBalance>,
+    /// Storage.
+    /// AccountId - user account id.
+    /// Accounts in the old layout, they are moved to the account registry on the first change.
+    pub legacy_account_registry: LookupMap<AccountId, AccountBalanceV1>,
     pub accounts_quantity: u64,
     pub metadata: LazyOption<FungibleTokenMetadata>,
     /// In bytes.
@@ -23,12 +27,38 @@ impl FungibleToken {
         Self {
             total_supply: 0,
             account_registry: Self::initialize_account_registry(),
+            legacy_account_registry: Self::initialize_legacy_account_registry(),
             accounts_quantity: 0,
             metadata: Self::initialize_metadata(&fungible_token_metadata),
             storage_usage_per_account: Self::calculate_storage_usage_per_additional_account()
         }
     }
 
+    pub fn get_account_balance(&self, account_id: &AccountId) -> Option<AccountBalance> {
+        match self.account_registry.get(account_id) {
+            Some(account_balance) => Some(account_balance),
+            None => self.legacy_account_registry.get(account_id).map(AccountBalanceV1::migrate)
+        }
+    }
+
+    pub fn insert_account_balance(&mut self, account_id: &AccountId, account_balance: &AccountBalance) -> Option<AccountBalance> {
+        match self.account_registry.insert(account_id, account_balance) {
+            Some(previous_account_balance) => Some(previous_account_balance),
+            None => self.legacy_account_registry.remove(account_id).map(AccountBalanceV1::migrate)
+        }
+    }
+
+    pub fn remove_account_balance(&mut self, account_id: &AccountId) -> Option<AccountBalance> {
+        match self.account_registry.remove(account_id) {
+            Some(account_balance) => Some(account_balance),
+            None => self.legacy_account_registry.remove(account_id).map(AccountBalanceV1::migrate)
+        }
+    }
+
+    pub fn contains_account_balance(&self, account_id: &AccountId) -> bool {
+        self.account_registry.contains_key(account_id) || self.legacy_account_registry.contains_key(account_id)
+    }
+
     fn calculate_storage_usage_per_additional_account() -> StorageUsage {
         let mut account_registry = Self::initialize_account_registry();
 
@@ -41,7 +71,8 @@ impl FungibleToken {
             &AccountBalance {
                 token_amount: 0,
                 classic_near_amount: 0,
-                investment_near_amount: 0
+                investment_near_amount: 0,
+                referrer_id: Some(account_id.clone())
             }
         );
 
@@ -49,10 +80,38 @@ impl FungibleToken {
     }
 
     fn initialize_account_registry() -> LookupMap<AccountId, AccountBalance> {
+        LookupMap::new(StorageKey::AccountBalance)
+    }
+
+    fn initialize_legacy_account_registry() -> LookupMap<AccountId, AccountBalanceV1> {
         LookupMap::new(StorageKey::FungibleToken)
     }
 
     fn initialize_metadata(fungible_token_metadata: &FungibleTokenMetadata) -> LazyOption<FungibleTokenMetadata> {
         LazyOption::new(StorageKey::FungibleTokenMetadata, Some(fungible_token_metadata))
     }
-}
\ No newline at end of file
+}
+
+/// Layout of the fungible token, that was stored before the referral program.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FungibleTokenV1 {
+    pub total_supply: Balance,
+    pub account_registry: LookupMap<AccountId, AccountBalanceV1>,
+    pub accounts_quantity: u64,
+    pub metadata: LazyOption<FungibleTokenMetadata>,
+    pub storage_usage_per_account: StorageUsage
+}
+
+impl FungibleTokenV1 {
+    /// The accounts stay in the legacy registry and are converted on the first access.
+    pub fn migrate(self) -> FungibleToken {
+        FungibleToken {
+            total_supply: self.total_supply,
+            account_registry: FungibleToken::initialize_account_registry(),
+            legacy_account_registry: self.account_registry,
+            accounts_quantity: self.accounts_quantity,
+            metadata: self.metadata,
+            storage_usage_per_account: FungibleToken::calculate_storage_usage_per_additional_account()
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Gas budgets of the stake pool. The static Gas of a promise covers the execution of the called method and the static
+//! Gas of the promises created by it, so the budget of a method is the Gas of its own execution and the static Gas
+//! of the whole promise chain. Each call-method requires its budget to be prepaid.
+//!
+//! The constants are derived from the Gas measured by the `gas` test on the mocked blockchain, that asserts each of them
+//! against its measurement. The mocked blockchain counts the host functions and the receipt fees, but does not execute
+//! the Wasm instructions, so the own execution reserves the unmeasured Gas for them.
+
+use near_sdk::Gas;
+
+/// Gas of the host functions of the own execution of a method, that is not a view-method.
+/// It is the maximum measured on the single validator rounded up to TGas.
+pub const EXECUTION_HOST_FUNCTIONS: Gas = tgas(2);
+/// Gas of the Wasm instructions of the own execution of a method, it is the reserve, that is not measured.
+pub const EXECUTION_WASM_INSTRUCTIONS: Gas = tgas(5);
+/// Gas of the own execution of a method, that is not a view-method.
+pub const EXECUTION: Gas = sum(&[EXECUTION_HOST_FUNCTIONS, EXECUTION_WASM_INSTRUCTIONS]);
+
+/// Gas of the host functions of the check of one validator in the invariants on the update.
+pub const VALIDATOR_INVARIANT_CHECK_HOST_FUNCTIONS: Gas = ggas(100);
+/// Gas of the host functions of the processing of one element of the auto claim queue without the transfer.
+pub const AUTO_CLAIM_PROCESSING_HOST_FUNCTIONS: Gas = ggas(300);
+/// Gas of the check of one validator in the invariants on the update.
+pub const VALIDATOR_INVARIANT_CHECK: Gas = with_wasm_instructions(VALIDATOR_INVARIANT_CHECK_HOST_FUNCTIONS);
+/// Gas of the processing of one element of the auto claim queue without the transfer.
+pub const AUTO_CLAIM_PROCESSING: Gas = with_wasm_instructions(AUTO_CLAIM_PROCESSING_HOST_FUNCTIONS);
+
+/// Gas of the creation of the function call receipt, that is the sending and the execution fees of the receipt
+/// and of its action.
+pub const FUNCTION_CALL_RECEIPT_CREATION: Gas = tgas(5);
+/// Gas of the creation of the data receipt, that passes the result of the receipt to the callback.
+pub const DATA_RECEIPT_CREATION: Gas = tgas(10);
+/// Gas of the creation of the callback receipt, that depends on one receipt.
+pub const CALLBACK_RECEIPT_CREATION: Gas = sum(&[FUNCTION_CALL_RECEIPT_CREATION, DATA_RECEIPT_CREATION]);
+/// Gas of the creation of the transfer receipt, that is the sending and the execution fees of the receipt
+/// and of its action.
+pub const TRANSFER_RECEIPT_CREATION: Gas = tgas(1);
+
+/// Static Gas of the 'deposit_and_stake' of the validator.
+pub const VALIDATOR_DEPOSIT_AND_STAKE: Gas = tgas(25);
+/// Static Gas of the 'unstake' of the validator.
+pub const VALIDATOR_UNSTAKE: Gas = tgas(25);
+/// Static Gas of the 'unstake_all' of the validator.
+pub const VALIDATOR_UNSTAKE_ALL: Gas = tgas(25);
+/// Static Gas of the 'withdraw' of the validator.
+pub const VALIDATOR_WITHDRAW: Gas = tgas(25);
+/// Static Gas of the view-method of the validator.
+pub const VALIDATOR_VIEW: Gas = tgas(5);
+
+/// Static Gas of the 'transfer_near_amount_callback', that may store the failed transfer as the pending refund.
+pub const TRANSFER_NEAR_AMOUNT_CALLBACK: Gas = EXECUTION;
+/// Gas of the transfer of the Near amount with the callback.
+pub const TRANSFER_NEAR_AMOUNT: Gas = sum(&[
+    TRANSFER_RECEIPT_CREATION,
+    CALLBACK_RECEIPT_CREATION,
+    TRANSFER_NEAR_AMOUNT_CALLBACK
+]);
+/// Static Gas of the 'deposit_callback', that may refund the attached deposit.
+pub const DEPOSIT_CALLBACK: Gas = sum(&[EXECUTION, TRANSFER_NEAR_AMOUNT]);
+/// Static Gas of the 'deposit_on_validator_callback', that may refund the attached deposit in two transfers.
+pub const DEPOSIT_ON_VALIDATOR_CALLBACK: Gas = sum(&[EXECUTION, TRANSFER_NEAR_AMOUNT, TRANSFER_NEAR_AMOUNT]);
+/// Static Gas of the 'increase_validator_stake_callback'.
+pub const INCREASE_VALIDATOR_STAKE_CALLBACK: Gas = EXECUTION;
+/// Static Gas of the 'requested_decrease_validator_stake_callback_2', that may refund the storage staking.
+pub const REQUESTED_DECREASE_VALIDATOR_STAKE_CALLBACK_2: Gas = sum(&[EXECUTION, TRANSFER_NEAR_AMOUNT]);
+/// Static Gas of the 'requested_decrease_validator_stake_callback_1', that requests the unstake.
+pub const REQUESTED_DECREASE_VALIDATOR_STAKE_CALLBACK_1: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_UNSTAKE,
+    CALLBACK_RECEIPT_CREATION,
+    REQUESTED_DECREASE_VALIDATOR_STAKE_CALLBACK_2
+]);
+/// Static Gas of the 'take_unstaked_balance_callback', that may remove the drained validator.
+pub const TAKE_UNSTAKED_BALANCE_CALLBACK: Gas = sum(&[EXECUTION, TRANSFER_NEAR_AMOUNT]);
+/// Static Gas of the 'drain_validator_callback'.
+pub const DRAIN_VALIDATOR_CALLBACK: Gas = EXECUTION;
+/// Static Gas of the 'update_validator_callback'.
+pub const UPDATE_VALIDATOR_CALLBACK: Gas = EXECUTION;
+/// Static Gas of the 'reconcile_validator_callback'.
+pub const RECONCILE_VALIDATOR_CALLBACK: Gas = EXECUTION;
+
+/// Budget of the method, that creates no promises.
+pub const COMMON: Gas = EXECUTION;
+/// Budget of the method, that transfers the Near amount.
+pub const TRANSFER: Gas = sum(&[EXECUTION, TRANSFER_NEAR_AMOUNT]);
+/// Budget of the 'deposit' and the 'deposit_for'.
+pub const DEPOSIT: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_DEPOSIT_AND_STAKE,
+    CALLBACK_RECEIPT_CREATION,
+    DEPOSIT_CALLBACK
+]);
+/// Budget of the 'deposit_on_validator'.
+pub const DEPOSIT_ON_VALIDATOR: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_DEPOSIT_AND_STAKE,
+    CALLBACK_RECEIPT_CREATION,
+    DEPOSIT_ON_VALIDATOR_CALLBACK
+]);
+/// Budget of the 'increase_validator_stake'.
+pub const INCREASE_VALIDATOR_STAKE: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_DEPOSIT_AND_STAKE,
+    CALLBACK_RECEIPT_CREATION,
+    INCREASE_VALIDATOR_STAKE_CALLBACK
+]);
+/// Budget of the 'requested_decrease_validator_stake'.
+pub const REQUESTED_DECREASE_VALIDATOR_STAKE: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_VIEW,
+    CALLBACK_RECEIPT_CREATION,
+    REQUESTED_DECREASE_VALIDATOR_STAKE_CALLBACK_1
+]);
+/// Budget of the 'take_unstaked_balance'.
+pub const TAKE_UNSTAKED_BALANCE: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_WITHDRAW,
+    CALLBACK_RECEIPT_CREATION,
+    TAKE_UNSTAKED_BALANCE_CALLBACK
+]);
+/// Budget of the 'update_validator'.
+pub const UPDATE_VALIDATOR: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_VIEW,
+    CALLBACK_RECEIPT_CREATION,
+    UPDATE_VALIDATOR_CALLBACK
+]);
+/// Budget of the 'reconcile_validator', the callback of which depends on two receipts.
+pub const RECONCILE_VALIDATOR: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_VIEW,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_VIEW,
+    CALLBACK_RECEIPT_CREATION,
+    DATA_RECEIPT_CREATION,
+    RECONCILE_VALIDATOR_CALLBACK
+]);
+/// Budget of the 'drain_validator'.
+pub const DRAIN_VALIDATOR: Gas = sum(&[
+    EXECUTION,
+    FUNCTION_CALL_RECEIPT_CREATION,
+    VALIDATOR_UNSTAKE_ALL,
+    CALLBACK_RECEIPT_CREATION,
+    DRAIN_VALIDATOR_CALLBACK
+]);
+/// Budget of the 'update', that iterates over the registered validators. The referral fee is accrued to the referrers
+/// lazily, so the update does not iterate over them.
+pub const fn update(validators_quantity: u64) -> Gas {
+    Gas(EXECUTION.0 + validators_quantity * VALIDATOR_INVARIANT_CHECK.0)
+}
+
+/// Budget of the 'process_matured_withdrawals', that examines the quantity of the auto claim queue elements.
+/// Each element may be transferred, and the keeper bounty is transferred once.
+pub const fn process_matured_withdrawals(examined_withdrawals_quantity: u64) -> Gas {
+    Gas(
+        EXECUTION.0
+            + examined_withdrawals_quantity * (AUTO_CLAIM_PROCESSING.0 + TRANSFER_NEAR_AMOUNT.0)
+            + TRANSFER_NEAR_AMOUNT.0
+    )
+}
+
+/// The iteration decodes and encodes the same data, that it reads and writes by the host functions, so the Wasm
+/// instructions of the iteration are reserved as much Gas, as its host functions.
+const fn with_wasm_instructions(host_functions_gas: Gas) -> Gas {
+    Gas(2 * host_functions_gas.0)
+}
+
+const fn tgas(quantity: u64) -> Gas {
+    Gas(quantity * Gas::ONE_TERA.0)
+}
+
+const fn ggas(quantity: u64) -> Gas {
+    Gas(quantity * Gas::ONE_TERA.0 / 1_000)
+}
+
+const fn sum(gases: &[Gas]) -> Gas {
+    let mut gas: u64 = 0;
+
+    let mut index = 0;
+    while index < gases.len() {
+        gas += gases[index].0;
+        index += 1;
+    }
+
+    Gas(gas)
+}
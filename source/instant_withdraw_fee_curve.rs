@@ -0,0 +1,51 @@
+use near_sdk::{Balance, FunctionError};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::clone::Clone;
+use super::fee::{Fee, U256};
+use super::INSTANT_WITHDRAW_FEE_CURVE_DENOMINATOR;
+use super::stake_pool_error::StakePoolError;
+
+/// Fee that rises linearly from the minimum to the maximum as the withdrawal depletes the available unstaked balance.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+#[serde(crate = "near_sdk::serde")]
+pub struct InstantWithdrawFeeCurve {
+    /// Fee applied to a withdrawal, that does not take any part of the available unstaked balance.
+    pub minimum_fee: Fee,
+    /// Fee applied to a withdrawal, that takes all available unstaked balance.
+    pub maximum_fee: Fee
+}
+
+impl InstantWithdrawFeeCurve {
+    pub fn assert_valid(&self) {
+        self.minimum_fee.assert_valid();
+        self.maximum_fee.assert_valid();
+
+        if U256::from(self.minimum_fee.numerator) * U256::from(self.maximum_fee.denominator)
+            > U256::from(self.maximum_fee.numerator) * U256::from(self.minimum_fee.denominator) {
+            StakePoolError::InstantWithdrawFeeCurveNotValid.panic();
+        }
+    }
+
+    /// Returns the fee applied to the withdrawal of the Near amount from the available unstaked balance.
+    pub fn calculate_fee(&self, near_amount: Balance, available_near_amount: Balance) -> Fee {
+        let minimum_numerator = self.minimum_fee.multiply(INSTANT_WITHDRAW_FEE_CURVE_DENOMINATOR as Balance);
+        let maximum_numerator = self.maximum_fee.multiply(INSTANT_WITHDRAW_FEE_CURVE_DENOMINATOR as Balance);
+
+        let numerator = if available_near_amount == 0 || near_amount >= available_near_amount {
+            maximum_numerator
+        } else {
+            minimum_numerator + (
+                U256::from(maximum_numerator - minimum_numerator)
+                * U256::from(near_amount)
+                / U256::from(available_near_amount)
+            ).as_u128()
+        };
+
+        Fee {
+            numerator: numerator as u64,
+            denominator: INSTANT_WITHDRAW_FEE_CURVE_DENOMINATOR
+        }
+    }
+}
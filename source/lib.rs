@@ -1,36 +1,85 @@
 use near_sdk::ONE_NEAR;
-use near_sdk::{AccountId, Balance};
+use near_sdk::{AccountId, Balance, Gas};
+use fee::Fee;
 
 pub mod stake_pool;
+pub mod cross_contract_call;
+pub mod core;
+#[cfg(feature = "log_replay")]
+pub mod log_replay;
+#[cfg(feature = "abi")]
+pub mod abi;
+pub mod data_transfer_object;
+pub mod fee_beneficiary;
+pub mod fee_registry;
+pub mod fee_split;
+pub mod fee_type;
+pub mod fee;
+pub mod gas;
+pub mod instant_withdraw_fee_curve;
+pub mod stake_decreasing_kind;
+pub mod staking_contract_version;
 mod account_balance;
 mod account_registry;
-mod cross_contract_call;
-mod data_transfer_object;
 mod delayed_withdrawal;
 mod delayed_withdrawn_fund;
-mod fee_registry;
-mod fee;
+mod deposit_caps;
+mod epoch_history;
+mod epoch_report;
 mod fund;
 mod fungible_token;
 mod investment_withdrawal;
 mod investor_investment;
+mod pending_pool_config;
+mod pending_refund_fund;
+mod pool_config;
+mod referral;
+mod referrer;
 mod reward;
-mod shared_fee;
-mod stake_decreasing_kind;
-mod staking_contract_version;
+mod stake_pool_error;
 mod storage_key;
 mod validating;
 mod validator_balance;
+mod validator_performance;
+mod validator_reconciliation;
 mod validator;
 
-/// Quantity of epochs after passing which it is possible to withdraw fund.
+/// Default quantity of epochs after passing which it is possible to withdraw fund.
 const EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL: u64 = 8;
-/// Quantity of epochs after passing which it is possible to request unstake from validator.
+/// Default quantity of epochs after passing which it is possible to request unstake from validator.
 const EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE: u64 = 4;
-/// Minimum Gas amount for common transaction.
-const MINIMUM_NUMBER_OF_TGAS: u64 = 300;
-/// The minimum Near amount that must be attached to a transaction.
+/// Minimum quantity of epochs between requests of unstake from validator, it is the unbonding period of the Near protocol.
+const MINIMUM_EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE: u64 = 4;
+/// Default minimum Gas amount for any transaction, the method requires its own Gas budget if it is greater.
+/// Every method checks its own budget, so the default is the budget of the method, that creates no promises,
+/// and the owner can raise it as a safety margin.
+const MINIMUM_NUMBER_OF_TGAS: u64 = gas::COMMON.0.div_ceil(Gas::ONE_TERA.0);
+/// Default minimum Near amount that must be attached to a transaction.
 const MINIMUN_DEPOSIT_AMOUNT: Balance = ONE_NEAR;
+/// Maximum Gas amount, that can be attached to a transaction.
+const MAXIMUM_NUMBER_OF_TGAS: u64 = 300;
+/// Quantity of epochs after passing which the updated pool config is applied.
+const EPOCH_QUANTITY_FOR_POOL_CONFIG_UPDATE: u64 = 8;
+/// Maximum number of reports stored in the epoch history.
+const MAXIMUM_NUMBER_OF_EPOCH_REPORTS: u64 = 730;
+/// Approximate quantity of epochs in one year, it is needed for APY calculating.
+const EPOCH_QUANTITY_PER_YEAR: u64 = 730;
+/// Denominator of the APY fraction.
+const APY_DENOMINATOR: u64 = 1_000_000;
+/// Maximum number of performance records stored for each validator.
+const MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS: u64 = 30;
+/// Quantity of consecutive epochs out of the active validator set after passing which the validator is flagged as inactive.
+const MAXIMUM_CONSECUTIVE_INACTIVE_EPOCH_QUANTITY: u64 = 3;
+/// Maximum part of the validator balance that can be corrected after reconciliation.
+const MAXIMUM_VALIDATOR_BALANCE_CORRECTION: Fee = Fee { numerator: 1, denominator: 100 };
+/// Maximum number of beneficiaries in the split of each fee.
+const MAXIMUM_NUMBER_OF_FEE_BENEFICIARIES: u64 = 10;
+/// Maximum number of validators. The update iterates over the validators, so the quantity is limited by its Gas budget.
+const MAXIMUM_NUMBER_OF_VALIDATORS: u64 = 50;
+/// Denominator of the referral fee token amount accrued to one referred token.
+const REFERRAL_FEE_PER_TOKEN_DENOMINATOR: Balance = 1_000_000_000_000_000_000_000_000;
+/// Denominator of the instant withdraw fee calculated by the fee curve.
+const INSTANT_WITHDRAW_FEE_CURVE_DENOMINATOR: u64 = 1_000_000_000;
 /// Maximum number of characters in account name, it is needed for storage staking calculating.
 const MAXIMUM_NUMBER_OF_CHARACTERS_IN_ACCOUNT_NAME: usize = 64;
 
@@ -0,0 +1,855 @@
+//! Off-chain replay of the contract logs.
+//! The logs are applied in the order of execution to rebuild the fund, the token balances, the delayed withdrawals
+//! and the balances on validators without access to the contract storage. Every logged old value is checked against
+//! the replayed one, so a change of the state, that is not reflected in the logs, is reported as a discrepancy.
+//! The replayed state can be compared with the 'get_full' and 'get_full_for_account' snapshots.
+
+use std::collections::BTreeMap;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::{AccountId, Balance};
+use crate::data_transfer_object::full::Full;
+use crate::data_transfer_object::full_for_account::FullForAccount;
+
+const DEPOSIT: &[&str] = &[
+    "Deposited to @{} in {} epoch.",
+    "Attached deposit is {} yoctoNear.",
+    "Exchangeable deposit is {} yoctoNear.",
+    "Reserved storage staking price is {} yoctoNear.",
+    "Refundable deposit is {} yoctoNear.",
+    "Old @{} total supply is {} yoctoStNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "Old @{} balance is {} yoctoStNear.",
+    "@{} received {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear.",
+    "New @{} balance is {} yoctoNear.",
+    "New @{} total supply is {} yoctoStNear."
+];
+const DEPOSIT_VIA_VALIDATOR: &[&str] = &[
+    "Deposited to @{} via @{} in {} epoch.",
+    "Attached deposit is {} yoctoNear.",
+    "Exchangeable deposit is {} yoctoNear.",
+    "Reserved storage staking price is {} yoctoNear.",
+    "Refundable deposit is {} yoctoNear.",
+    "Old @{} total supply is {} yoctoStNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "Old @{} balance is {} yoctoStNear.",
+    "@{} received {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear.",
+    "New @{} balance is {} yoctoNear.",
+    "New @{} total supply is {} yoctoStNear."
+];
+const DEPOSIT_NOT_STAKED: &[&str] = &[
+    "Deposit of {} yoctoNear is not staked on validator @{} in {} epoch."
+];
+const DEPOSIT_INVESTED: &[&str] = &[
+    "Deposit of {} yoctoNear is invested by @{} on validator @{} in {} epoch."
+];
+const INSTANT_WITHDRAW: &[&str] = &[
+    "Instant withdrawing from @{} in {} epoch.",
+    "Attached deposit is {} yoctoNear.",
+    "Exchangeable deposit is {} yoctoStNear.",
+    "Fee is {}.",
+    "Released storage staking price is {} yoctoNear.",
+    "Received amount is {} yoctoNear.",
+    "Old @{} total supply is {} yoctoStNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "Old @{} balance is {} yoctoStNear.",
+    "@{} sent {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear.",
+    "New @{} balance is {} yoctoNear.",
+    "New @{} total supply is {} yoctoStNear."
+];
+const DELAYED_WITHDRAW: &[&str] = &[
+    "Delayed withdrawing from @{} in {} epoch.",
+    "Attached deposit is {} yoctoNear.",
+    "Exchangeable deposit is {} yoctoStNear.",
+    "Refundable deposit is {} yoctoNear.",
+    "Reserved storage staking price is {} yoctoNear.",
+    "Released storage staking price is {} yoctoNear.",
+    "Old expected for receiving amount is {} yoctoNear.",
+    "Old epoch quantity to take delayed withdrawal is {}.",
+    "Additional expected for receiving amount is {} yoctoNear.",
+    "New expected for receiving amount is {} yoctoNear.",
+    "New epoch quantity to take delayed withdrawal is {}.",
+    "Old @{} total supply is {} yoctoStNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "Old @{} balance is {} yoctoStNear.",
+    "@{} sent {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear.",
+    "New @{} balance is {} yoctoNear.",
+    "New @{} total supply is {} yoctoStNear."
+];
+const DELAYED_WITHDRAW_FROM_VALIDATOR: &[&str] = &[
+    "Delayed withdrawing from @{} via @{} in {} epoch.",
+    "Attached deposit is {} yoctoNear.",
+    "Exchangeable deposit is {} yoctoNear.",
+    "Refundable deposit is {} yoctoNear.",
+    "Reserved storage staking price is {} yoctoNear.",
+    "Released storage staking price is {} yoctoNear.",
+    "Old expected for receiving amount is {} yoctoNear.",
+    "Old epoch quantity to take delayed withdrawal is {}.",
+    "Additional expected for receiving amount is {} yoctoNear.",
+    "New expected for receiving amount is {} yoctoNear.",
+    "New epoch quantity to take delayed withdrawal is {}.",
+    "Old @{} total supply is {} yoctoStNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "Old @{} balance is {} yoctoStNear.",
+    "@{} sent {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear.",
+    "New @{} balance is {} yoctoNear.",
+    "New @{} total supply is {} yoctoStNear."
+];
+const DELAYED_WITHDRAWAL_TAKEN: &[&str] = &[
+    "Delayed withdrawal of {} yoctoNear is taken by @{} from @{} in {} epoch."
+];
+const FEE_RECEIVED: &[&str] = &[
+    "@{} received {} yoctoStNear as fee in {} epoch."
+];
+const TRANSFER: &[&str] = &[
+    "Transferring from @{} to @{} in {} epoch.",
+    "@{} sent {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear.",
+    "New @{} balance is {} yoctoStNear."
+];
+const TRANSFER_FAILED: &[&str] = &[
+    "Transfer of {} yoctoNear from @{} to @{} failed.",
+    "Reserved storage staking price is {} yoctoNear.",
+    "Pending refund of @{} is {} yoctoNear."
+];
+const REFUND_CLAIMED: &[&str] = &[
+    "Claiming refund on @{} in {} epoch.",
+    "@{} claimed {} yoctoNear to @{}."
+];
+const UPDATE: &[&str] = &[
+    "Updating @{} pool from {} epoch to {} epoch.",
+    "Old @{} total supply is {} yoctoStNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "Received rewards from validators is {} yoctoNear.",
+    "Fee is {}.",
+    "Received token amount as fee is {}.",
+    "New @{} balance is {} yoctoNear.",
+    "New @{} total supply is {} yoctoStNear."
+];
+const VALIDATOR_STAKE_INCREASED: &[&str] = &[
+    "Increasing validator stake on validator @{} in {} epoch.",
+    "Old @{} classic Near amount on validator is {} yoctoNear.",
+    "Old @{} investment Near amount on validator is {} yoctoNear.",
+    "Old @{} unstaked Near amount on validator is {} yoctoNear.",
+    "Staking on validator with {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear.",
+    "New @{} investment Near amount on validator is {} yoctoNear.",
+    "New @{} unstaked Near amount on validator is {} yoctoNear."
+];
+const VALIDATOR_STAKE_DECREASE_REQUESTED: &[&str] = &[
+    "Requested decreasing validator stake from validator @{} in {} epoch.",
+    "Old @{} classic Near amount on validator is {} yoctoNear.",
+    "Old @{} investment Near amount on validator is {} yoctoNear.",
+    "Old @{} unstaked Near amount on validator is {} yoctoNear.",
+    "Requested to unstake from validator is {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear.",
+    "New @{} investment Near amount on validator is {} yoctoNear.",
+    "New @{} unstaked Near amount on validator is {} yoctoNear."
+];
+const UNSTAKED_BALANCE_TAKEN: &[&str] = &[
+    "Taking unstaked balance from validator @{} in {} epoch.",
+    "Old @{} classic Near amount on validator is {} yoctoNear.",
+    "Old @{} investment Near amount on validator is {} yoctoNear.",
+    "Old @{} unstaked Near amount on validator is {} yoctoNear.",
+    "Received Near amount from validator is {} yoctoNear.",
+    "Received drained Near amount from validator is {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear.",
+    "New @{} investment Near amount on validator is {} yoctoNear.",
+    "New @{} unstaked Near amount on validator is {} yoctoNear."
+];
+const VALIDATOR_UPDATED: &[&str] = &[
+    "Updating validator @{} from {} epoch to {} epoch.",
+    "Old @{} classic Near amount on validator is {} yoctoNear.",
+    "Old @{} investment Near amount on validator is {} yoctoNear.",
+    "Old @{} unstaked near amount from validator is {} yoctoNear.",
+    "Received on validator Near amount is {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear.",
+    "New @{} investment Near amount on validator is {} yoctoNear.",
+    "New @{} unstaked near amount from validator is {} yoctoNear."
+];
+const VALIDATOR_DRAINED: &[&str] = &[
+    "Draining validator @{} in {} epoch.",
+    "Unstaked from validator is {} yoctoNear.",
+    "Requested to withdrawal from unstaked is {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear.",
+    "New @{} unstaked Near amount on validator is {} yoctoNear.",
+    "New @{} drained Near amount on validator is {} yoctoNear."
+];
+const VALIDATOR_CORRECTED: &[&str] = &[
+    "Correcting validator @{} balance by @{} in {} epoch.",
+    "Recorded staked Near amount on validator is {} yoctoNear.",
+    "Received staked Near amount from validator is {} yoctoNear.",
+    "Recorded unstaked Near amount on validator is {} yoctoNear.",
+    "Received unstaked Near amount from validator is {} yoctoNear.",
+    "Old @{} classic Near amount on validator is {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear.",
+    "Old @{} balance is {} yoctoNear.",
+    "New @{} balance is {} yoctoNear."
+];
+const INVESTMENT_MIGRATED: &[&str] = &[
+    "Migrating investment of @{} on draining validator @{} to the classic context in {} epoch.",
+    "Migrated Near amount is {} yoctoNear.",
+    "New @{} investment Near amount on validator is {} yoctoNear.",
+    "New @{} classic Near amount on validator is {} yoctoNear."
+];
+const DRAINED_VALIDATOR_REMOVED: &[&str] = &[
+    "Removing drained validator @{} in {} epoch."
+];
+/// First lines of the logs, that do not change the replayed state.
+const INFORMATIONAL: &[&str] = &[
+    "Processing of matured withdrawals on @{} in {} epoch by @{}.",
+    "Reconciling validator @{} in {} epoch.",
+    "Starting draining validator @{} in {} epoch.",
+    "Updating @{} pool config in {} epoch.",
+    "Applying @{} pool config in {} epoch.",
+    "Validator @{} is out of the active validator set for {} epochs."
+];
+
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReplayedFund {
+    pub classic_unstaked_balance: Balance,
+    pub classic_staked_balance: Balance,
+    pub investment_staked_balance: Balance,
+    pub common_balance: Balance,
+    pub pending_refund_balance: Balance,
+    pub total_supply: Balance
+}
+
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReplayedDelayedWithdrawnFund {
+    pub balance: Balance,
+    pub needed_to_request_classic_near_amount: Balance,
+    pub needed_to_request_investment_near_amount: Balance,
+    pub delayed_withdrawal_registry: BTreeMap<AccountId, Balance>
+}
+
+#[derive(Serialize, Default, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReplayedValidatorBalance {
+    pub classic_near_amount: Balance,
+    pub investment_near_amount: Balance,
+    pub requested_to_withdrawal_near_amount: Balance,
+    pub drained_near_amount: Balance
+}
+
+/// Logged value, that does not match the replayed one. The logged value is taken for the further replay.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Discrepancy {
+    pub log_index: usize,
+    pub field: String,
+    pub replayed: Balance,
+    pub logged: Balance
+}
+
+/// Value of the snapshot, that does not match the replayed one.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Difference {
+    pub field: String,
+    pub replayed: Balance,
+    pub snapshot: Balance
+}
+
+/// Deposit via validator is logged in the same way for the classic and the investment contexts,
+/// so the preceding log defines where the deposited Near amount is.
+enum DepositContext {
+    NotStaked,
+    Invested
+}
+
+#[derive(Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Replay {
+    pub fund: ReplayedFund,
+    pub delayed_withdrawn_fund: ReplayedDelayedWithdrawnFund,
+    pub token_balances: BTreeMap<AccountId, Balance>,
+    pub pending_refunds: BTreeMap<AccountId, Balance>,
+    pub validator_balances: BTreeMap<AccountId, ReplayedValidatorBalance>,
+    pub discrepancies: Vec<Discrepancy>,
+    /// Indexes of the logs, that are not emitted by the contract or have unknown format.
+    pub skipped_log_indexes: Vec<usize>,
+    #[serde(skip)]
+    log_index: usize,
+    #[serde(skip)]
+    deposit_context: Option<DepositContext>
+}
+
+/// Values of the placeholders of the matched log.
+struct Values {
+    values: Vec<String>
+}
+
+impl Values {
+    fn balance(&self, index: usize) -> Option<Balance> {
+        self.values.get(index)?.parse().ok()
+    }
+
+    fn account_id(&self, index: usize) -> Option<AccountId> {
+        self.values.get(index)?.parse().ok()
+    }
+}
+
+/// Part of the deposit and withdrawal logs, that describes the exchange of the Near amount and the token amount.
+struct Exchange {
+    old_total_supply: Balance,
+    old_common_balance: Balance,
+    account_id: AccountId,
+    old_token_balance: Balance,
+    token_amount: Balance,
+    new_token_balance: Balance,
+    new_common_balance: Balance,
+    new_total_supply: Balance
+}
+
+impl Exchange {
+    /// Reads the exchange from the 7 lines starting from the line with the old total supply.
+    fn read(values: &Values, first_index: usize) -> Option<Self> {
+        Some(
+            Self {
+                old_total_supply: values.balance(first_index + 1)?,
+                old_common_balance: values.balance(first_index + 3)?,
+                account_id: values.account_id(first_index + 4)?,
+                old_token_balance: values.balance(first_index + 5)?,
+                token_amount: values.balance(first_index + 7)?,
+                new_token_balance: values.balance(first_index + 9)?,
+                new_common_balance: values.balance(first_index + 11)?,
+                new_total_supply: values.balance(first_index + 13)?
+            }
+        )
+    }
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replays the logs of the contract in the order of execution.
+    pub fn from_logs(logs: &[String]) -> Self {
+        let mut replay = Self::new();
+
+        for log in logs.iter() {
+            replay.apply(log);
+        }
+
+        replay
+    }
+
+    pub fn apply(&mut self, log: &str) {
+        let lines: Vec<&str> = log.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        if self.apply_lines(&lines).is_none() {
+            self.skipped_log_indexes.push(self.log_index);
+        }
+
+        self.log_index += 1;
+    }
+
+    /// Compares the replayed state with the result of the 'get_full' method.
+    pub fn diff_full(&self, full_json: &str) -> Result<Vec<Difference>, String> {
+        let full: Full = match serde_json::from_str(full_json) {
+            Ok(full_) => full_,
+            Err(error) => return Err(format!("Snapshot of the 'get_full' is not valid: {}.", error))
+        };
+
+        let mut differences: Vec<Difference> = vec![];
+
+        for (field, replayed, snapshot) in [
+            ("fund.classic_unstaked_balance", self.fund.classic_unstaked_balance, full.fund.classic_unstaked_balance.0),
+            ("fund.classic_staked_balance", self.fund.classic_staked_balance, full.fund.classic_staked_balance.0),
+            ("fund.investment_staked_balance", self.fund.investment_staked_balance, full.fund.investment_staked_balance.0),
+            (
+                "fund.common_staked_balance",
+                self.fund.classic_staked_balance + self.fund.investment_staked_balance,
+                full.fund.common_staked_balance.0
+            ),
+            ("fund.common_balance", self.fund.common_balance, full.fund.common_balance.0),
+            ("fund.pending_refund_balance", self.fund.pending_refund_balance, full.fund.pending_refund_balance.0),
+            ("total_token_supply", self.fund.total_supply, full.total_token_supply.0),
+            (
+                "requested_to_withdrawal_fund.classic_near_amount",
+                self.delayed_withdrawn_fund.needed_to_request_classic_near_amount,
+                full.requested_to_withdrawal_fund.classic_near_amount.0
+            ),
+            (
+                "requested_to_withdrawal_fund.investment_near_amount",
+                self.delayed_withdrawn_fund.needed_to_request_investment_near_amount,
+                full.requested_to_withdrawal_fund.investment_near_amount.0
+            )
+        ] {
+            Self::push_difference(&mut differences, field.to_string(), replayed, snapshot);
+        }
+
+        Ok(differences)
+    }
+
+    /// Compares the replayed state of the account with the result of the 'get_full_for_account' method.
+    pub fn diff_full_for_account(&self, account_id: &AccountId, full_for_account_json: &str) -> Result<Vec<Difference>, String> {
+        let full_for_account: FullForAccount = match serde_json::from_str(full_for_account_json) {
+            Ok(full_for_account_) => full_for_account_,
+            Err(error) => return Err(format!("Snapshot of the 'get_full_for_account' for @{} is not valid: {}.", account_id, error))
+        };
+
+        let mut differences: Vec<Difference> = vec![];
+
+        Self::push_difference(
+            &mut differences,
+            format!("@{}.token_balance", account_id),
+            self.token_balances.get(account_id).copied().unwrap_or(0),
+            full_for_account.account_balance.base_account_balance.map(|base_account_balance| base_account_balance.token_balance.0).unwrap_or(0)
+        );
+        Self::push_difference(
+            &mut differences,
+            format!("@{}.delayed_withdrawal.near_amount", account_id),
+            self.delayed_withdrawn_fund.delayed_withdrawal_registry.get(account_id).copied().unwrap_or(0),
+            full_for_account.delayed_withdrawal_details.map(|delayed_withdrawal_details| delayed_withdrawal_details.near_amount.0).unwrap_or(0)
+        );
+
+        Ok(differences)
+    }
+
+    fn push_difference(differences: &mut Vec<Difference>, field: String, replayed: Balance, snapshot: Balance) {
+        if replayed != snapshot {
+            differences.push(Difference { field, replayed, snapshot });
+        }
+    }
+
+    /// Returns None if the log has unknown format.
+    fn apply_lines(&mut self, lines: &[&str]) -> Option<()> {
+        if let Some(values) = match_log(lines, DEPOSIT_VIA_VALIDATOR) {
+            let validator_account_id = values.account_id(0)?;
+            let near_amount = values.balance(4)?;
+            let exchange = Exchange::read(&values, 7)?;
+
+            match self.deposit_context.take() {
+                Some(DepositContext::NotStaked) => {
+                    self.fund.classic_unstaked_balance += near_amount;
+                }
+                Some(DepositContext::Invested) => {
+                    self.fund.investment_staked_balance += near_amount;
+                    self.get_validator_balance(&validator_account_id).investment_near_amount += near_amount;
+                }
+                None => {
+                    self.fund.classic_staked_balance += near_amount;
+                    self.get_validator_balance(&validator_account_id).classic_near_amount += near_amount;
+                }
+            }
+            self.apply_exchange(&exchange, true);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, DEPOSIT) {
+            let near_amount = values.balance(3)?;
+            let exchange = Exchange::read(&values, 6)?;
+
+            self.fund.classic_unstaked_balance += near_amount;
+            self.apply_exchange(&exchange, true);
+
+            return Some(());
+        }
+        if match_log(lines, DEPOSIT_NOT_STAKED).is_some() {
+            self.deposit_context = Some(DepositContext::NotStaked);
+
+            return Some(());
+        }
+        if match_log(lines, DEPOSIT_INVESTED).is_some() {
+            self.deposit_context = Some(DepositContext::Invested);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, INSTANT_WITHDRAW) {
+            let attached_deposit = values.balance(2)?;
+            let released_near_amount = values.balance(5)?;
+            let received_near_amount = values.balance(6)?;
+            let exchange = Exchange::read(&values, 7)?;
+
+            let near_amount = received_near_amount.saturating_sub(released_near_amount + attached_deposit);
+            self.fund.classic_unstaked_balance = self.subtract("fund.classic_unstaked_balance", self.fund.classic_unstaked_balance, near_amount);
+            self.apply_exchange(&exchange, false);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, DELAYED_WITHDRAW_FROM_VALIDATOR) {
+            let exchange = Exchange::read(&values, 13)?;
+            let near_amount = values.balance(10)?;
+
+            self.fund.investment_staked_balance = self.subtract("fund.investment_staked_balance", self.fund.investment_staked_balance, near_amount);
+            self.delayed_withdrawn_fund.needed_to_request_investment_near_amount += near_amount;
+            self.apply_delayed_withdrawal(&exchange.account_id, values.balance(8)?, values.balance(11)?);
+            self.apply_exchange(&exchange, false);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, DELAYED_WITHDRAW) {
+            let exchange = Exchange::read(&values, 12)?;
+            let near_amount = values.balance(9)?;
+
+            self.fund.classic_staked_balance = self.subtract("fund.classic_staked_balance", self.fund.classic_staked_balance, near_amount);
+            self.delayed_withdrawn_fund.needed_to_request_classic_near_amount += near_amount;
+            self.apply_delayed_withdrawal(&exchange.account_id, values.balance(7)?, values.balance(10)?);
+            self.apply_exchange(&exchange, false);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, DELAYED_WITHDRAWAL_TAKEN) {
+            let near_amount = values.balance(0)?;
+            let account_id = values.account_id(1)?;
+
+            let replayed_near_amount = self.delayed_withdrawn_fund.delayed_withdrawal_registry.remove(&account_id).unwrap_or(0);
+            self.check(format!("@{}.delayed_withdrawal.near_amount", account_id), replayed_near_amount, near_amount);
+            self.delayed_withdrawn_fund.balance = self.subtract("delayed_withdrawn_fund.balance", self.delayed_withdrawn_fund.balance, near_amount);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, FEE_RECEIVED) {
+            let account_id = values.account_id(0)?;
+
+            *self.token_balances.entry(account_id).or_insert(0) += values.balance(1)?;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, TRANSFER) {
+            let sender_account_id = values.account_id(0)?;
+            let receiver_account_id = values.account_id(1)?;
+            let token_amount = values.balance(4)?;
+            let new_sender_token_balance = values.balance(6)?;
+            let new_receiver_token_balance = values.balance(8)?;
+
+            let sender_token_balance = self.token_balances.get(&sender_account_id).copied().unwrap_or(0);
+            self.check(format!("@{}.token_balance", sender_account_id), sender_token_balance, new_sender_token_balance + token_amount);
+            let receiver_token_balance = self.token_balances.get(&receiver_account_id).copied().unwrap_or(0);
+            self.check(format!("@{}.token_balance", receiver_account_id), receiver_token_balance + token_amount, new_receiver_token_balance);
+
+            self.set_token_balance(sender_account_id, new_sender_token_balance);
+            self.set_token_balance(receiver_account_id, new_receiver_token_balance);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, TRANSFER_FAILED) {
+            let near_amount = values.balance(0)?;
+            let reserved_storage_staking_price = values.balance(3)?;
+            let account_id = values.account_id(4)?;
+            let pending_refund = values.balance(5)?;
+            let refundable_near_amount = self.subtract("refundable_near_amount", near_amount, reserved_storage_staking_price);
+
+            let replayed_pending_refund = self.pending_refunds.get(&account_id).copied().unwrap_or(0);
+            self.check(format!("@{}.pending_refund", account_id), replayed_pending_refund + refundable_near_amount, pending_refund);
+            if pending_refund > 0 {
+                self.pending_refunds.insert(account_id, pending_refund);
+            }
+            self.fund.pending_refund_balance += refundable_near_amount;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, REFUND_CLAIMED) {
+            let account_id = values.account_id(2)?;
+            let near_amount = values.balance(3)?;
+
+            let replayed_pending_refund = self.pending_refunds.remove(&account_id).unwrap_or(0);
+            self.check(format!("@{}.pending_refund", account_id), replayed_pending_refund, near_amount);
+            self.fund.pending_refund_balance = self.subtract("fund.pending_refund_balance", self.fund.pending_refund_balance, near_amount);
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, UPDATE) {
+            let rewards_near_amount = values.balance(7)?;
+
+            self.check("fund.total_supply".to_string(), self.fund.total_supply, values.balance(4)?);
+            self.check("fund.common_balance".to_string(), self.fund.common_balance, values.balance(6)?);
+            self.fund.classic_staked_balance += rewards_near_amount;
+            self.fund.common_balance = values.balance(11)?;
+            self.fund.total_supply = values.balance(13)?;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, VALIDATOR_STAKE_INCREASED) {
+            let validator_account_id = values.account_id(0)?;
+            let near_amount = values.balance(8)?;
+
+            self.check_validator_balance(&validator_account_id, &values, 3, false)?;
+            self.set_validator_balance(&validator_account_id, &values, 10)?;
+            self.fund.classic_unstaked_balance = self.subtract("fund.classic_unstaked_balance", self.fund.classic_unstaked_balance, near_amount);
+            self.fund.classic_staked_balance += near_amount;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, VALIDATOR_STAKE_DECREASE_REQUESTED) {
+            let validator_account_id = values.account_id(0)?;
+
+            self.check_validator_balance(&validator_account_id, &values, 3, false)?;
+            let classic_near_amount = values.balance(3)?.saturating_sub(values.balance(10)?);
+            let investment_near_amount = values.balance(5)?.saturating_sub(values.balance(12)?);
+            self.set_validator_balance(&validator_account_id, &values, 10)?;
+            self.delayed_withdrawn_fund.needed_to_request_classic_near_amount = self.subtract(
+                "delayed_withdrawn_fund.needed_to_request_classic_near_amount",
+                self.delayed_withdrawn_fund.needed_to_request_classic_near_amount,
+                classic_near_amount
+            );
+            self.delayed_withdrawn_fund.needed_to_request_investment_near_amount = self.subtract(
+                "delayed_withdrawn_fund.needed_to_request_investment_near_amount",
+                self.delayed_withdrawn_fund.needed_to_request_investment_near_amount,
+                investment_near_amount
+            );
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, UNSTAKED_BALANCE_TAKEN) {
+            let validator_account_id = values.account_id(0)?;
+            let requested_to_withdrawal_near_amount = values.balance(8)?;
+            let drained_near_amount = values.balance(9)?;
+
+            self.check_validator_balance(&validator_account_id, &values, 3, true)?;
+
+            let mut validator_balance = self.get_validator_balance(&validator_account_id).clone();
+            validator_balance.requested_to_withdrawal_near_amount = self.subtract(
+                &format!("@{}.requested_to_withdrawal_near_amount", validator_account_id),
+                validator_balance.requested_to_withdrawal_near_amount,
+                requested_to_withdrawal_near_amount
+            );
+            validator_balance.drained_near_amount = self.subtract(
+                &format!("@{}.drained_near_amount", validator_account_id),
+                validator_balance.drained_near_amount,
+                drained_near_amount
+            );
+            self.validator_balances.insert(validator_account_id.clone(), validator_balance);
+            self.check_validator_balance(&validator_account_id, &values, 11, true)?;
+
+            let drained_to_delayed_withdrawn_fund_near_amount =
+                drained_near_amount.min(self.delayed_withdrawn_fund.needed_to_request_classic_near_amount);
+            self.delayed_withdrawn_fund.needed_to_request_classic_near_amount -= drained_to_delayed_withdrawn_fund_near_amount;
+            self.delayed_withdrawn_fund.balance += requested_to_withdrawal_near_amount + drained_to_delayed_withdrawn_fund_near_amount;
+            self.fund.classic_staked_balance = self.subtract(
+                "fund.classic_staked_balance",
+                self.fund.classic_staked_balance,
+                drained_near_amount - drained_to_delayed_withdrawn_fund_near_amount
+            );
+            self.fund.classic_unstaked_balance += drained_near_amount - drained_to_delayed_withdrawn_fund_near_amount;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, VALIDATOR_UPDATED) {
+            let validator_account_id = values.account_id(0)?;
+
+            self.check_validator_balance(&validator_account_id, &values, 4, false)?;
+            self.set_validator_balance(&validator_account_id, &values, 11)?;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, VALIDATOR_DRAINED) {
+            let validator_account_id = values.account_id(0)?;
+            let requested_to_withdrawal_near_amount = values.balance(3)?;
+
+            let validator_balance = self.get_validator_balance(&validator_account_id);
+            validator_balance.classic_near_amount = values.balance(5)?;
+            validator_balance.requested_to_withdrawal_near_amount = values.balance(7)?;
+            validator_balance.drained_near_amount = values.balance(9)?;
+            self.delayed_withdrawn_fund.needed_to_request_classic_near_amount = self.subtract(
+                "delayed_withdrawn_fund.needed_to_request_classic_near_amount",
+                self.delayed_withdrawn_fund.needed_to_request_classic_near_amount,
+                requested_to_withdrawal_near_amount
+            );
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, VALIDATOR_CORRECTED) {
+            let validator_account_id = values.account_id(0)?;
+            let old_classic_near_amount = values.balance(8)?;
+            let new_classic_near_amount = values.balance(10)?;
+
+            let replayed_classic_near_amount = self.get_validator_balance(&validator_account_id).classic_near_amount;
+            self.check(format!("@{}.classic_near_amount", validator_account_id), replayed_classic_near_amount, old_classic_near_amount);
+            self.check("fund.common_balance".to_string(), self.fund.common_balance, values.balance(12)?);
+            self.get_validator_balance(&validator_account_id).classic_near_amount = new_classic_near_amount;
+            if new_classic_near_amount > old_classic_near_amount {
+                self.fund.classic_staked_balance += new_classic_near_amount - old_classic_near_amount;
+            } else {
+                self.fund.classic_staked_balance = self.subtract(
+                    "fund.classic_staked_balance",
+                    self.fund.classic_staked_balance,
+                    old_classic_near_amount - new_classic_near_amount
+                );
+            }
+            self.fund.common_balance = values.balance(14)?;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, INVESTMENT_MIGRATED) {
+            let validator_account_id = values.account_id(1)?;
+            let near_amount = values.balance(3)?;
+
+            let validator_balance = self.get_validator_balance(&validator_account_id);
+            validator_balance.investment_near_amount = values.balance(5)?;
+            validator_balance.classic_near_amount = values.balance(7)?;
+            self.fund.investment_staked_balance = self.subtract("fund.investment_staked_balance", self.fund.investment_staked_balance, near_amount);
+            self.fund.classic_staked_balance += near_amount;
+
+            return Some(());
+        }
+        if let Some(values) = match_log(lines, DRAINED_VALIDATOR_REMOVED) {
+            self.validator_balances.remove(&values.account_id(0)?);
+
+            return Some(());
+        }
+        if let Some(first_line) = lines.first() {
+            if INFORMATIONAL.iter().any(|pattern| match_line(first_line, pattern).is_some()) {
+                return Some(());
+            }
+        }
+
+        None
+    }
+
+    fn apply_exchange(&mut self, exchange: &Exchange, is_deposit: bool) {
+        let token_balance = self.token_balances.get(&exchange.account_id).copied().unwrap_or(0);
+
+        self.check("fund.total_supply".to_string(), self.fund.total_supply, exchange.old_total_supply);
+        self.check("fund.common_balance".to_string(), self.fund.common_balance, exchange.old_common_balance);
+        self.check(format!("@{}.token_balance", exchange.account_id), token_balance, exchange.old_token_balance);
+        let expected_token_balance = if is_deposit {
+            exchange.old_token_balance + exchange.token_amount
+        } else {
+            exchange.old_token_balance.saturating_sub(exchange.token_amount)
+        };
+        self.check(format!("@{}.token_balance", exchange.account_id), expected_token_balance, exchange.new_token_balance);
+
+        self.set_token_balance(exchange.account_id.clone(), exchange.new_token_balance);
+        self.fund.common_balance = exchange.new_common_balance;
+        self.fund.total_supply = exchange.new_total_supply;
+    }
+
+    fn apply_delayed_withdrawal(&mut self, account_id: &AccountId, old_near_amount: Balance, new_near_amount: Balance) {
+        let near_amount = self.delayed_withdrawn_fund.delayed_withdrawal_registry.get(account_id).copied().unwrap_or(0);
+
+        self.check(format!("@{}.delayed_withdrawal.near_amount", account_id), near_amount, old_near_amount);
+        self.delayed_withdrawn_fund.delayed_withdrawal_registry.insert(account_id.clone(), new_near_amount);
+    }
+
+    /// The account without tokens is removed from the registry of the contract.
+    fn set_token_balance(&mut self, account_id: AccountId, token_balance: Balance) {
+        if token_balance == 0 {
+            self.token_balances.remove(&account_id);
+        } else {
+            self.token_balances.insert(account_id, token_balance);
+        }
+    }
+
+    fn get_validator_balance(&mut self, validator_account_id: &AccountId) -> &mut ReplayedValidatorBalance {
+        self.validator_balances.entry(validator_account_id.clone()).or_default()
+    }
+
+    /// Checks the classic, the investment and the unstaked Near amounts on validator, the index is the one of the classic Near amount.
+    /// The unstaked Near amount of the unstaked balance taking includes the drained Near amount.
+    fn check_validator_balance(&mut self, validator_account_id: &AccountId, values: &Values, first_index: usize, is_drained_included: bool) -> Option<()> {
+        let validator_balance = self.get_validator_balance(validator_account_id).clone();
+
+        let unstaked_near_amount = if is_drained_included {
+            validator_balance.requested_to_withdrawal_near_amount + validator_balance.drained_near_amount
+        } else {
+            validator_balance.requested_to_withdrawal_near_amount
+        };
+
+        for (field, replayed, logged) in [
+            ("classic_near_amount", validator_balance.classic_near_amount, values.balance(first_index)?),
+            ("investment_near_amount", validator_balance.investment_near_amount, values.balance(first_index + 2)?),
+            ("unstaked_near_amount", unstaked_near_amount, values.balance(first_index + 4)?)
+        ] {
+            self.check(format!("@{}.{}", validator_account_id, field), replayed, logged);
+        }
+
+        Some(())
+    }
+
+    /// Sets the classic, the investment and the requested to withdrawal Near amounts on validator, the index is the one of the classic Near amount.
+    fn set_validator_balance(&mut self, validator_account_id: &AccountId, values: &Values, first_index: usize) -> Option<()> {
+        let classic_near_amount = values.balance(first_index)?;
+        let investment_near_amount = values.balance(first_index + 2)?;
+        let requested_to_withdrawal_near_amount = values.balance(first_index + 4)?;
+
+        let validator_balance = self.get_validator_balance(validator_account_id);
+        validator_balance.classic_near_amount = classic_near_amount;
+        validator_balance.investment_near_amount = investment_near_amount;
+        validator_balance.requested_to_withdrawal_near_amount = requested_to_withdrawal_near_amount;
+
+        Some(())
+    }
+
+    fn check(&mut self, field: String, replayed: Balance, logged: Balance) {
+        if replayed != logged {
+            self.discrepancies.push(
+                Discrepancy {
+                    log_index: self.log_index,
+                    field,
+                    replayed,
+                    logged
+                }
+            );
+        }
+    }
+
+    fn subtract(&mut self, field: &str, minuend: Balance, subtrahend: Balance) -> Balance {
+        match minuend.checked_sub(subtrahend) {
+            Some(difference) => difference,
+            None => {
+                self.check(field.to_string(), minuend, subtrahend);
+
+                0
+            }
+        }
+    }
+}
+
+/// Matches the lines of the log with the patterns line by line and returns the values of the placeholders.
+fn match_log(lines: &[&str], patterns: &[&str]) -> Option<Values> {
+    if lines.len() != patterns.len() {
+        return None;
+    }
+
+    let mut values: Vec<String> = vec![];
+    for (line, pattern) in lines.iter().zip(patterns.iter()) {
+        values.extend(match_line(line, pattern)?);
+    }
+
+    Some(Values { values })
+}
+
+/// Matches the line with the pattern, where each placeholder '{}' is a value without spaces,
+/// except the last placeholder, that takes the rest of the line up to the trailing literal.
+fn match_line(line: &str, pattern: &str) -> Option<Vec<String>> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+
+    let mut rest = line.strip_prefix(literals[0])?;
+    let mut values: Vec<String> = vec![];
+
+    for (index, literal) in literals.iter().enumerate().skip(1) {
+        let value = if index == literals.len() - 1 {
+            let value = rest.strip_suffix(literal)?;
+            rest = "";
+
+            value
+        } else {
+            let position = rest.find(literal)?;
+            let value = &rest[..position];
+            if value.contains(' ') {
+                return None;
+            }
+            rest = &rest[position + literal.len()..];
+
+            value
+        };
+
+        values.push(value.to_string());
+    }
+
+    Some(values)
+}
@@ -0,0 +1,10 @@
+use near_sdk::EpochHeight;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use super::pool_config::PoolConfig;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingPoolConfig {
+    pub pool_config: PoolConfig,
+    /// Epoch, since which the config is applied on the pool update.
+    pub activation_epoch_height: EpochHeight
+}
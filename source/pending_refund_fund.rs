@@ -0,0 +1,55 @@
+use near_sdk::{AccountId, Balance, env, StorageUsage};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use super::get_account_id_with_maximum_length;
+use super::storage_key::StorageKey;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingRefundFund {
+    /// Storage.
+    /// AccountId - account id, that has paid for the failed transfer.
+    /// Balance - Near amount.
+    pub pending_refunds: LookupMap<AccountId, Balance>,
+    /// Near amount of all failed transfers, that is not claimed yet.
+    pub balance: Balance,
+    /// In bytes.
+    pub storage_usage_per_pending_refund: StorageUsage
+}
+
+impl PendingRefundFund {
+    pub fn new() -> Self {
+        Self {
+            pending_refunds: Self::initialize_pending_refunds(),
+            balance: 0,
+            storage_usage_per_pending_refund: Self::calculate_storage_usage_per_additional_pending_refund()
+        }
+    }
+
+    pub fn increase(&mut self, account_id: &AccountId, near_amount: Balance) {
+        let pending_refund = self.pending_refunds.get(account_id).unwrap_or(0);
+
+        self.pending_refunds.insert(account_id, &(pending_refund + near_amount));
+        self.balance += near_amount;
+    }
+
+    pub fn take(&mut self, account_id: &AccountId) -> Option<Balance> {
+        let near_amount = self.pending_refunds.remove(account_id)?;
+        self.balance -= near_amount;
+
+        Some(near_amount)
+    }
+
+    fn calculate_storage_usage_per_additional_pending_refund() -> StorageUsage {
+        let mut pending_refunds = Self::initialize_pending_refunds();
+
+        let initial_storage_usage = env::storage_usage();
+
+        pending_refunds.insert(&get_account_id_with_maximum_length(), &0);
+
+        env::storage_usage() - initial_storage_usage
+    }
+
+    fn initialize_pending_refunds() -> LookupMap<AccountId, Balance> {
+        LookupMap::new(StorageKey::PendingRefund)
+    }
+}
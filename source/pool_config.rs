@@ -0,0 +1,30 @@
+use near_sdk::Balance;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use super::EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL;
+use super::EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE;
+use super::MINIMUM_NUMBER_OF_TGAS;
+use super::MINIMUN_DEPOSIT_AMOUNT;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PoolConfig {
+    /// Quantity of epochs after passing which it is possible to withdraw fund.
+    pub epoch_quantity_for_delayed_withdrawal: u64,
+    /// Quantity of epochs after passing which it is possible to request unstake from validator.
+    pub epoch_quantity_for_validator_unstake: u64,
+    /// Minimum Gas amount for any transaction, the method requires its own Gas budget if it is greater.
+    /// Every method checks its own budget, so the floor only has to cover the methods, that create no promises.
+    pub minimum_number_of_tgas: u64,
+    /// The minimum Near amount that must be attached to a transaction.
+    pub minimum_deposit_amount: Balance
+}
+
+impl PoolConfig {
+    pub fn new() -> Self {
+        Self {
+            epoch_quantity_for_delayed_withdrawal: EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL,
+            epoch_quantity_for_validator_unstake: EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE,
+            minimum_number_of_tgas: MINIMUM_NUMBER_OF_TGAS,
+            minimum_deposit_amount: MINIMUN_DEPOSIT_AMOUNT
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use near_sdk::{env, AccountId, Balance, StorageUsage};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use super::core;
+use super::fee::Fee;
+use super::get_account_id_with_maximum_length;
+use super::referrer::Referrer;
+use super::storage_key::StorageKey;
+
+/// The referral fee is accrued lazily: the update only increases the referral fee per token, and the fee of the referrer
+/// is accrued on the change of its referred token amount, on the claim or on the removal.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Referral {
+    /// Storage.
+    /// AccountId - referrer account id.
+    pub referrer_registry: UnorderedMap<AccountId, Referrer>,
+    /// Token amount referred by all registered referrers.
+    pub referred_token_amount: Balance,
+    /// Referral fee token amount accrued to one referred token since the start of the referral program,
+    /// multiplied by the REFERRAL_FEE_PER_TOKEN_DENOMINATOR.
+    pub referral_fee_per_token: Balance,
+    /// Minted referral fee token amount, that is not claimed by the referrers yet. It is the part of the total supply,
+    /// that is not owned by the token accounts.
+    pub unclaimed_token_amount: Balance,
+    /// In bytes.
+    pub storage_usage_per_referrer: StorageUsage
+}
+
+impl Referral {
+    pub fn new() -> Self {
+        Self {
+            referrer_registry: Self::initialize_referrer_registry(),
+            referred_token_amount: 0,
+            referral_fee_per_token: 0,
+            unclaimed_token_amount: 0,
+            storage_usage_per_referrer: Self::calculate_storage_usage_per_additional_referrer()
+        }
+    }
+
+    /// Returns false if the referrer is already registered.
+    pub fn add_referrer(&mut self, referrer_account_id: &AccountId) -> bool {
+        self.referrer_registry.insert(referrer_account_id, &Referrer::new(self.referral_fee_per_token)).is_none()
+    }
+
+    /// Returns the removed referrer with the accrued fee. Its referred token amount does not receive the fee anymore.
+    pub fn remove_referrer(&mut self, referrer_account_id: &AccountId) -> Option<Referrer> {
+        let mut referrer = self.referrer_registry.remove(referrer_account_id)?;
+        self.accrue_referral_fee(&mut referrer);
+
+        self.referred_token_amount -= referrer.referred_token_amount;
+
+        Some(referrer)
+    }
+
+    /// The referrer removed from the registry does not receive the referred token amount anymore.
+    pub fn increase_referred_token_amount(&mut self, referrer_account_id: &AccountId, token_amount: Balance, is_new_account: bool) {
+        if let Some(mut referrer) = self.referrer_registry.get(referrer_account_id) {
+            self.accrue_referral_fee(&mut referrer);
+
+            referrer.referred_token_amount += token_amount;
+            if is_new_account {
+                referrer.referred_accounts_quantity += 1;
+            }
+            self.referred_token_amount += token_amount;
+
+            self.referrer_registry.insert(referrer_account_id, &referrer);
+        }
+    }
+
+    pub fn decrease_referred_token_amount(&mut self, referrer_account_id: &AccountId, token_amount: Balance, is_removed_account: bool) {
+        if let Some(mut referrer) = self.referrer_registry.get(referrer_account_id) {
+            self.accrue_referral_fee(&mut referrer);
+
+            let decreased_token_amount = token_amount.min(referrer.referred_token_amount);
+            referrer.referred_token_amount -= decreased_token_amount;
+            if is_removed_account {
+                referrer.referred_accounts_quantity = referrer.referred_accounts_quantity.saturating_sub(1);
+            }
+            self.referred_token_amount -= decreased_token_amount;
+
+            self.referrer_registry.insert(referrer_account_id, &referrer);
+        }
+    }
+
+    /// Accrues the part of the reward fee to the referred token amount and returns the minted referral fee token amount.
+    pub fn distribute_referral_fee(&mut self, referral_fee: &Fee, reward_fee_token_amount: Balance, total_supply: Balance) -> Balance {
+        let referral_fee_per_token = core::calculate_referral_fee_per_token(referral_fee, reward_fee_token_amount, total_supply);
+        self.referral_fee_per_token += referral_fee_per_token;
+
+        let referral_fee_token_amount = core::calculate_referral_fee_token_amount(self.referred_token_amount, referral_fee_per_token, true)
+            .min(reward_fee_token_amount);
+        self.unclaimed_token_amount += referral_fee_token_amount;
+
+        referral_fee_token_amount
+    }
+
+    /// Returns the unclaimed token amount of the referrer, that must be added to its token account.
+    pub fn claim_referral_fee(&mut self, referrer_account_id: &AccountId) -> Option<Balance> {
+        let mut referrer = self.referrer_registry.get(referrer_account_id)?;
+        self.accrue_referral_fee(&mut referrer);
+
+        let token_amount = referrer.unclaimed_token_amount;
+        referrer.unclaimed_token_amount = 0;
+        self.referrer_registry.insert(referrer_account_id, &referrer);
+
+        self.take_unclaimed_token_amount(token_amount);
+
+        Some(token_amount)
+    }
+
+    /// Takes the claimed token amount from the minted referral fee.
+    pub fn take_unclaimed_token_amount(&mut self, token_amount: Balance) {
+        self.unclaimed_token_amount -= token_amount;
+    }
+
+    /// Accrues the fee of the referred token amount since the previous accrual.
+    pub fn accrue_referral_fee(&self, referrer: &mut Referrer) {
+        let token_amount = core::calculate_referral_fee_token_amount(
+            referrer.referred_token_amount,
+            self.referral_fee_per_token - referrer.referral_fee_per_token,
+            false
+        );
+
+        referrer.earned_token_amount += token_amount;
+        referrer.unclaimed_token_amount += token_amount;
+        referrer.referral_fee_per_token = self.referral_fee_per_token;
+    }
+
+    fn calculate_storage_usage_per_additional_referrer() -> StorageUsage {
+        let mut referrer_registry = Self::initialize_referrer_registry();
+
+        let initial_storage_usage = env::storage_usage();
+
+        let account_id = get_account_id_with_maximum_length();
+
+        referrer_registry.insert(&account_id, &Referrer::new(0));
+
+        env::storage_usage() - initial_storage_usage
+    }
+
+    fn initialize_referrer_registry() -> UnorderedMap<AccountId, Referrer> {
+        UnorderedMap::new(StorageKey::Referrer)
+    }
+}
@@ -0,0 +1,27 @@
+use near_sdk::Balance;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Referrer {
+    /// Token amount owned by the accounts that came to the pool by the referrer.
+    pub referred_token_amount: Balance,
+    pub referred_accounts_quantity: u64,
+    /// Token amount accrued from the reward fee.
+    pub earned_token_amount: Balance,
+    /// Part of the earned token amount, that is not claimed to the token account of the referrer yet.
+    pub unclaimed_token_amount: Balance,
+    /// Referral fee per token of the referral program, up to which the fee is accrued to the referrer.
+    pub referral_fee_per_token: Balance
+}
+
+impl Referrer {
+    pub fn new(referral_fee_per_token: Balance) -> Self {
+        Self {
+            referred_token_amount: 0,
+            referred_accounts_quantity: 0,
+            earned_token_amount: 0,
+            unclaimed_token_amount: 0,
+            referral_fee_per_token
+        }
+    }
+}
@@ -1,50 +1,83 @@
-use core::convert::Into;
+// The 'ext' builders are generated for the contract methods outside of their declarations, they take the builder
+// itself in addition to the arguments.
+#![allow(clippy::too_many_arguments)]
+
+use std::convert::Into;
 use near_contract_standards::fungible_token::core::FungibleTokenCore;
 use near_contract_standards::fungible_token::metadata::{FungibleTokenMetadata, FT_METADATA_SPEC};
-use near_sdk::{env, near_bindgen, PanicOnDefault, AccountId, Balance, EpochHeight, Promise, PromiseResult, StorageUsage, Gas, PromiseOrValue};
+use near_sdk::{env, near_bindgen, FunctionError, PanicOnDefault, AccountId, Balance, EpochHeight, Promise, PromiseResult, StorageUsage, Gas, PromiseOrValue};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::U128;
 use super::account_balance::AccountBalance;
-use super::account_registry::AccountRegistry;
+use super::account_registry::{AccountRegistry, AccountRegistryV1};
 use super::cross_contract_call::classic_validator::classic_validator;
+use super::core;
 use super::data_transfer_object::account_balance::AccountBalance as AccountBalanceDto;
 use super::data_transfer_object::aggregated::Aggregated;
+use super::data_transfer_object::apy::Apy;
 use super::data_transfer_object::base_account_balance::BaseAccountBalance;
 use super::data_transfer_object::callback_result::CallbackResult;
+use super::data_transfer_object::delayed_withdraw_preview::DelayedWithdrawPreview;
 use super::data_transfer_object::delayed_withdrawal_details::DelayedWithdrawalDetails;
+use super::data_transfer_object::deposit_capacity::DepositCapacity;
+use super::data_transfer_object::deposit_caps::DepositCaps as DepositCapsDto;
+use super::data_transfer_object::deposit_preview::DepositPreview;
 use super::data_transfer_object::epoch_height_registry::EpochHeightRegistry;
+use super::data_transfer_object::epoch_report::EpochReport as EpochReportDto;
 use super::data_transfer_object::fee_registry_light::FeeRegistryLight;
 use super::data_transfer_object::full_for_account::FullForAccount;
 use super::data_transfer_object::full::Full;
 use super::data_transfer_object::fund::Fund as FundDto;
 use super::data_transfer_object::fungible_token_metadata::FungibleTokenMetadata as FungibleTokenMetadataDto;
+use super::data_transfer_object::instant_withdraw_preview::InstantWithdrawPreview;
+use super::data_transfer_object::invariant_report::InvariantReport;
+use super::data_transfer_object::invariant_violation::InvariantViolation;
 use super::data_transfer_object::investment_account_balance::InvestmentAccountBalance;
 use super::data_transfer_object::investor_investment::InvestorInvestment as InvestorInvestmentDto;
+use super::data_transfer_object::pending_pool_config::PendingPoolConfig as PendingPoolConfigDto;
+use super::data_transfer_object::pool_config::PoolConfig as PoolConfigDto;
+use super::data_transfer_object::referrer::Referrer as ReferrerDto;
 use super::data_transfer_object::requested_to_withdrawal_fund::RequestedToWithdrawalFund;
 use super::data_transfer_object::storage_staking_price::StorageStakingPrice;
 use super::data_transfer_object::storage_staking_requested_coverage::StorageStakingRequestedCoverage;
 use super::data_transfer_object::validator::Validator as ValidatorDto;
+use super::data_transfer_object::validator_performance::ValidatorPerformance as ValidatorPerformanceDto;
+use super::data_transfer_object::validator_reconciliation::ValidatorReconciliation as ValidatorReconciliationDto;
 use super::delayed_withdrawal::DelayedWithdrawal;
-use super::EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE;
-use super::fee_registry::FeeRegistry;
-use super::fee::Fee;
-use super::fund::Fund;
-use super::fungible_token::FungibleToken;
+use super::deposit_caps::DepositCaps;
+use super::APY_DENOMINATOR;
+use super::epoch_history::EpochHistory;
+use super::epoch_report::EpochReport;
+use super::EPOCH_QUANTITY_PER_YEAR;
+use super::EPOCH_QUANTITY_FOR_POOL_CONFIG_UPDATE;
+use super::fee_beneficiary::FeeBeneficiary;
+use super::fee_registry::{FeeRegistry, FeeRegistryV1};
+use super::fee_split::FeeSplit;
+use super::fee_type::FeeType;
+use super::fee::{Fee, U256};
+use super::fund::{Fund, FundV1};
+use super::fungible_token::{FungibleToken, FungibleTokenV1};
+use super::gas;
+use super::instant_withdraw_fee_curve::InstantWithdrawFeeCurve;
 use super::investment_withdrawal::InvestmentWithdrawal;
 use super::investor_investment::InvestorInvestment;
-use super::MINIMUM_NUMBER_OF_TGAS;
-use super::MINIMUN_DEPOSIT_AMOUNT;
+use super::MAXIMUM_NUMBER_OF_FEE_BENEFICIARIES;
+use super::MAXIMUM_NUMBER_OF_TGAS;
+use super::MAXIMUM_NUMBER_OF_VALIDATORS;
+use super::MAXIMUM_VALIDATOR_BALANCE_CORRECTION;
+use super::MINIMUM_EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE;
+use super::pending_pool_config::PendingPoolConfig;
+use super::pool_config::PoolConfig;
+use super::referral::Referral;
+use super::referrer::Referrer;
 use super::reward::Reward;
-use super::shared_fee::SharedFee;
+use super::stake_pool_error::StakePoolError;
 use super::stake_decreasing_kind::StakeDecreasingType;
 use super::staking_contract_version::StakingContractVersion;
-use super::validating::Validating;
+use super::validating::{Validating, ValidatingV1};
 use super::validator::Validator;
-use uint::construct_uint;
-
-construct_uint! {
-    pub struct U256(4);
-}
+use super::validator_performance::ValidatorPerformance;
+use super::validator_reconciliation::ValidatorReconciliation;
 
 /// Classic context - flow for all users. Investment context - flow for investors.
 /// Investor has classic and investment flow. Random user has only classic flow.
@@ -57,65 +90,107 @@ pub struct StakePool {
     fee_registry: FeeRegistry,
     validating: Validating,
     current_epoch_height: EpochHeight,
+    reward: Reward,
+    epoch_history: EpochHistory,
+    deposit_caps: DepositCaps,
+    pool_config: PoolConfig,
+    pending_pool_config: Option<PendingPoolConfig>,
+    referral: Referral
+}
+
+/// Layout of the pool, that was stored before the fee splits, the epoch history, the deposit caps, the pool config
+/// and the referral program.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StakePoolV1 {
+    account_registry: AccountRegistryV1,
+    fungible_token: FungibleTokenV1,
+    fund: FundV1,
+    fee_registry: FeeRegistryV1,
+    validating: ValidatingV1,
+    current_epoch_height: EpochHeight,
     reward: Reward
 }
 
 #[near_bindgen]
 impl StakePool {
-    /// Call-methods:
+    // Call-methods:
 
     /// Provides the ability to pool initialization.
+    /// The attached deposit covers the storage staking price of all epoch reports of the epoch history.
     /// Available for pool owner.
     #[init]
     #[payable]
     pub fn new(
         fungible_token_metadata: FungibleTokenMetadataDto,
         manager_id: Option<AccountId>,
-        self_fee_receiver_account_id: AccountId,
-        partner_fee_receiver_account_id: AccountId,
-        reward_fee_self: Option<Fee>,
-        reward_fee_partner: Option<Fee>,
-        instant_withdraw_fee_self: Option<Fee>,
-        instant_withdraw_fee_partner: Option<Fee>
+        reward_fee: Option<Fee>,
+        reward_fee_split: FeeSplit,
+        instant_withdraw_fee: Option<Fee>,
+        instant_withdraw_fee_split: FeeSplit,
+        pool_config: Option<PoolConfigDto>
     ) -> Self {
         Self::internal_new(
             fungible_token_metadata,
             manager_id,
-            self_fee_receiver_account_id,
-            partner_fee_receiver_account_id,
-            reward_fee_self,
-            reward_fee_partner,
-            instant_withdraw_fee_self,
-            instant_withdraw_fee_partner
+            reward_fee,
+            reward_fee_split,
+            instant_withdraw_fee,
+            instant_withdraw_fee_split,
+            pool_config
         )
     }
 
+    /// Provides the ability to migrate the state of the pool deployed with the first version of the contract.
+    /// The migration covers all layout changes since that version in one step.
+    /// The attached deposit covers the storage staking price of all epoch reports of the epoch history.
+    /// Available for the pool account after the deployment of the new code.
+    #[private]
+    #[init(ignore_state)]
+    #[payable]
+    pub fn migrate() -> Self {
+        Self::internal_migrate()
+    }
+
     /// Provides the ability to stake into pool.
     /// Available for all users.
     #[payable]
-    pub fn deposit(&mut self, near_amount: U128) -> PromiseOrValue<()> {
-        self.internal_deposit(near_amount.into())
+    pub fn deposit(&mut self, near_amount: U128, min_token_amount: Option<U128>, referrer_id: Option<AccountId>) -> PromiseOrValue<()> {
+        self.internal_deposit(env::predecessor_account_id(), near_amount.into(), min_token_amount.map(|min_token_amount_| min_token_amount_.into()), referrer_id)
+    }
+
+    /// Provides the ability to stake into pool on behalf of the beneficiary account.
+    /// Available for all users.
+    #[payable]
+    pub fn deposit_for(&mut self, beneficiary_id: AccountId, near_amount: U128, min_token_amount: Option<U128>, referrer_id: Option<AccountId>) -> PromiseOrValue<()> {
+        self.internal_deposit(beneficiary_id, near_amount.into(), min_token_amount.map(|min_token_amount_| min_token_amount_.into()), referrer_id)
     }
 
     /// Provides the ability to stake via pool directly to the validator.
     /// Available only for investor.
     #[payable]
-    pub fn deposit_on_validator(&mut self, near_amount: U128, validator_account_id: AccountId) -> Promise {
-        self.internal_deposit_on_validator(near_amount.into(), validator_account_id)
+    pub fn deposit_on_validator(&mut self, near_amount: U128, validator_account_id: AccountId, referrer_id: Option<AccountId>) -> Promise {
+        self.internal_deposit_on_validator(near_amount.into(), validator_account_id, referrer_id)
     }
 
     /// Provides the ability to instant unstake.
     /// Available for all users.
     #[payable]
-    pub fn instant_withdraw(&mut self, token_amount: U128) -> Promise {
-        self.internal_instant_withdraw(token_amount.into())
+    pub fn instant_withdraw(&mut self, token_amount: U128, min_near_amount: Option<U128>) -> Promise {
+        self.internal_instant_withdraw(token_amount.into(), min_near_amount.map(|min_near_amount_| min_near_amount_.into()), env::predecessor_account_id())
+    }
+
+    /// Provides the ability to instant unstake with sending of the Near amount to the receiver account.
+    /// Available for all users.
+    #[payable]
+    pub fn instant_withdraw_to(&mut self, receiver_id: AccountId, token_amount: U128, min_near_amount: Option<U128>) -> Promise {
+        self.internal_instant_withdraw(token_amount.into(), min_near_amount.map(|min_near_amount_| min_near_amount_.into()), receiver_id)
     }
 
     /// Provides the ability to delayed unstake.
     /// Available for all users.
     #[payable]
-    pub fn delayed_withdraw(&mut self, token_amount: U128) -> PromiseOrValue<()> {
-        self.internal_delayed_withdraw(token_amount.into())
+    pub fn delayed_withdraw(&mut self, token_amount: U128, min_near_amount: Option<U128>) -> PromiseOrValue<()> {
+        self.internal_delayed_withdraw(token_amount.into(), min_near_amount.map(|min_near_amount_| min_near_amount_.into()))
     }
 
     /// Delayed unstake process directly from validator.
@@ -129,7 +204,35 @@ impl StakePool {
     /// Available for all users.
     #[payable]
     pub fn take_delayed_withdrawal(&mut self) -> Promise {
-        self.internal_take_delayed_withdrawal()
+        self.internal_take_delayed_withdrawal(env::predecessor_account_id())
+    }
+
+    /// Provides the ability to take unstaked balance after passing the delayed unstake process
+    /// with sending of the Near amount to the receiver account.
+    /// Available for all users.
+    #[payable]
+    pub fn take_delayed_withdrawal_to(&mut self, receiver_id: AccountId) -> Promise {
+        self.internal_take_delayed_withdrawal(receiver_id)
+    }
+
+    /// Provides the ability to receive the delayed withdrawal automatically after passing the delayed unstake process.
+    /// Available for all users.
+    #[payable]
+    pub fn enable_auto_claim(&mut self) -> PromiseOrValue<()> {
+        self.internal_enable_auto_claim()
+    }
+
+    /// Provides the ability to send matured delayed withdrawals with enabled auto claim to their owners.
+    /// Available for all users.
+    pub fn process_matured_withdrawals(&mut self, limit: u64) -> u64 {
+        self.internal_process_matured_withdrawals(limit)
+    }
+
+    /// Provides the ability to take the Near amount of outgoing transfers, that failed.
+    /// Available for all users.
+    #[payable]
+    pub fn claim_refund(&mut self, receiver_id: Option<AccountId>) -> Promise {
+        self.internal_claim_refund(receiver_id)
     }
 
     /// Provides the ability to stake via pool directly to the validator.
@@ -161,6 +264,20 @@ impl StakePool {
         self.internal_update_validator(validator_account_id)
     }
 
+    /// Provides the ability to compare the balance registered on the validator with the real balance
+    /// of the pool on the validator contract.
+    /// Available only for pool manager.
+    pub fn reconcile_validator(&mut self, validator_account_id: AccountId) -> Promise {
+        self.internal_reconcile_validator(validator_account_id)
+    }
+
+    /// Provides the ability to correct the balance registered on the validator by the drift
+    /// found during reconciliation in current epoch.
+    /// Available only for pool owner.
+    pub fn apply_validator_correction(&mut self, validator_account_id: AccountId) {
+        self.internal_apply_validator_correction(validator_account_id);
+    }
+
     /// Provides the ability to update pool state. Must be used after 'updated_validator'
     /// for each validator.
     /// Available only for pool manager.
@@ -199,6 +316,24 @@ impl StakePool {
         self.internal_remove_validator(validator_account_id)
     }
 
+    /// Provides the ability to start removing the validator with an available balance.
+    /// Available only for pool manager.
+    pub fn start_draining_validator(&mut self, validator_account_id: AccountId) {
+        self.internal_start_draining_validator(validator_account_id);
+    }
+
+    /// Unstakes all classic Near amount from the draining validator updated in the current epoch.
+    /// Available only for pool manager.
+    pub fn drain_validator(&mut self, validator_account_id: AccountId) -> Promise {
+        self.internal_drain_validator(validator_account_id)
+    }
+
+    /// Moves the investor stake on the draining validator to the classic context.
+    /// Available only for pool manager.
+    pub fn migrate_investment_to_classic(&mut self, investor_account_id: AccountId, validator_account_id: AccountId) -> Promise {
+        self.internal_migrate_investment_to_classic(investor_account_id, validator_account_id)
+    }
+
     /// Provides the ability to add investor.
     /// Available only for pool manager.
     #[payable]
@@ -220,14 +355,91 @@ impl StakePool {
 
     /// Provides the ability to change reward fee.
     /// Available only for pool manager.
-    pub fn change_reward_fee(&mut self, reward_fee_self: Option<Fee>, reward_fee_partner: Option<Fee>) {
-        self.internal_change_reward_fee(reward_fee_self, reward_fee_partner);
+    pub fn change_reward_fee(&mut self, reward_fee: Option<Fee>) {
+        self.internal_change_reward_fee(reward_fee);
     }
 
     /// Provides the ability to change fee for instant unstake process.
     /// Available only for pool manager.
-    pub fn change_instant_withdraw_fee(&mut self, instant_withdraw_fee_self: Option<Fee>, instant_withdraw_fee_partner: Option<Fee>) {
-        self.internal_change_instant_withdraw_fee(instant_withdraw_fee_self, instant_withdraw_fee_partner);
+    pub fn change_instant_withdraw_fee(&mut self, instant_withdraw_fee: Option<Fee>) {
+        self.internal_change_instant_withdraw_fee(instant_withdraw_fee);
+    }
+
+    /// Provides the ability to change fee curve for instant unstake process.
+    /// Available only for pool manager.
+    pub fn change_instant_withdraw_fee_curve(&mut self, instant_withdraw_fee_curve: Option<InstantWithdrawFeeCurve>) {
+        self.internal_change_instant_withdraw_fee_curve(instant_withdraw_fee_curve);
+    }
+
+    /// Provides the ability to add beneficiary to the fee split.
+    /// The share of the new beneficiary is taken only from the share of the first beneficiary.
+    /// Available only for pool manager.
+    #[payable]
+    pub fn add_fee_beneficiary(&mut self, fee_type: FeeType, beneficiary_account_id: AccountId, numerator: u64) -> PromiseOrValue<()> {
+        self.internal_add_fee_beneficiary(fee_type, beneficiary_account_id, numerator)
+    }
+
+    /// Provides the ability to remove beneficiary from the fee split.
+    /// The share of the removed beneficiary is returned to the first beneficiary.
+    /// The storage staking price is returned to the account, that paid it.
+    /// Available only for pool manager.
+    pub fn remove_fee_beneficiary(&mut self, fee_type: FeeType, beneficiary_account_id: AccountId) -> PromiseOrValue<()> {
+        self.internal_remove_fee_beneficiary(fee_type, beneficiary_account_id)
+    }
+
+    /// Provides the ability to change the part of the reward fee, that referrers receive for the referred stake.
+    /// Available only for pool manager.
+    pub fn change_referral_fee(&mut self, referral_fee: Option<Fee>) {
+        self.internal_change_referral_fee(referral_fee);
+    }
+
+    /// Provides the ability to change the part of the matured delayed withdrawal, that is paid to the keeper.
+    /// Available only for pool manager.
+    pub fn change_keeper_bounty(&mut self, keeper_bounty: Option<Fee>) {
+        self.internal_change_keeper_bounty(keeper_bounty);
+    }
+
+    /// Provides the ability to add referrer.
+    /// Available only for pool manager.
+    #[payable]
+    pub fn add_referrer(&mut self, referrer_account_id: AccountId) -> PromiseOrValue<()> {
+        self.internal_add_referrer(referrer_account_id)
+    }
+
+    /// Provides the ability to remove referrer.
+    /// Available only for pool manager.
+    pub fn remove_referrer(&mut self, referrer_account_id: AccountId) -> Promise {
+        self.internal_remove_referrer(referrer_account_id)
+    }
+
+    /// Provides the ability to receive the referral fee accrued to the referrer on its token account.
+    /// Available only for referrer.
+    #[payable]
+    pub fn claim_referral_fee(&mut self) -> U128 {
+        self.internal_claim_referral_fee().into()
+    }
+
+    /// Provides the ability to change limits of deposits.
+    /// Available only for pool manager.
+    pub fn change_deposit_caps(&mut self, pool_near_amount: Option<U128>, validator_share: Option<Fee>, account_near_amount: Option<U128>) {
+        self.internal_change_deposit_caps(
+            pool_near_amount.map(|pool_near_amount_| pool_near_amount_.into()),
+            validator_share,
+            account_near_amount.map(|account_near_amount_| account_near_amount_.into())
+        );
+    }
+
+    /// Provides the ability to change pool parameters. The config is applied on the pool update
+    /// after passing the timelock, so users can leave the pool under the current terms.
+    /// Available only for pool owner.
+    pub fn update_config(&mut self, pool_config: PoolConfigDto) {
+        self.internal_update_config(pool_config);
+    }
+
+    /// Provides the ability to cancel the pending pool config.
+    /// Available only for pool owner.
+    pub fn cancel_config_update(&mut self) {
+        self.internal_cancel_config_update();
     }
 
     /// Provides the ability to change state of fund.
@@ -236,12 +448,20 @@ impl StakePool {
         self.internal_confirm_stake_distribution();
     }
 
-    /// View-methods:
+    // View-methods:
 
     pub fn get_delayed_withdrawal_details(&self, account_id: AccountId) -> Option<DelayedWithdrawalDetails> {
         self.internal_get_delayed_withdrawal_details(account_id)
     }
 
+    pub fn get_auto_claim_queue_length(&self) -> u64 {
+        self.internal_get_auto_claim_queue_length()
+    }
+
+    pub fn get_pending_refund(&self, account_id: AccountId) -> U128 {
+        self.internal_get_pending_refund(account_id).into()
+    }
+
     pub fn get_account_balance(&self, account_id: AccountId) -> AccountBalanceDto {
         self.internal_get_account_balance(account_id)
     }
@@ -309,6 +529,77 @@ impl StakePool {
     pub fn get_full_for_account(&self, account_id: AccountId) -> FullForAccount {
         self.internal_get_full_for_account(account_id)
     }
+
+    pub fn get_near_per_token(&self) -> U128 {
+        self.internal_get_near_per_token().into()
+    }
+
+    #[handle_result]
+    pub fn preview_deposit(&self, near_amount: U128) -> Result<DepositPreview, StakePoolError> {
+        self.internal_preview_deposit(near_amount.into())
+    }
+
+    #[handle_result]
+    pub fn preview_instant_withdraw(&self, token_amount: U128) -> Result<InstantWithdrawPreview, StakePoolError> {
+        self.internal_preview_instant_withdraw(token_amount.into())
+    }
+
+    #[handle_result]
+    pub fn preview_delayed_withdraw(&self, token_amount: U128) -> Result<DelayedWithdrawPreview, StakePoolError> {
+        self.internal_preview_delayed_withdraw(token_amount.into())
+    }
+
+    pub fn get_epoch_reports_quantity(&self) -> u64 {
+        self.internal_get_epoch_reports_quantity()
+    }
+
+    pub fn get_epoch_reports(&self, from_index: u64, limit: u64) -> Vec<EpochReportDto> {
+        self.internal_get_epoch_reports(from_index, limit)
+    }
+
+    pub fn get_apy(&self, epoch_quantity: u64) -> Option<Apy> {
+        self.internal_get_apy(epoch_quantity)
+    }
+
+    #[handle_result]
+    pub fn get_validator_reconciliation(&self, validator_account_id: AccountId) -> Result<Option<ValidatorReconciliationDto>, StakePoolError> {
+        self.internal_get_validator_reconciliation(validator_account_id)
+    }
+
+    #[handle_result]
+    pub fn get_validator_performance(&self, validator_account_id: AccountId, from_index: u64, limit: u64) -> Result<Vec<ValidatorPerformanceDto>, StakePoolError> {
+        self.internal_get_validator_performance(validator_account_id, from_index, limit)
+    }
+
+    pub fn get_pool_config(&self) -> PoolConfigDto {
+        self.internal_get_pool_config()
+    }
+
+    pub fn get_pending_pool_config(&self) -> Option<PendingPoolConfigDto> {
+        self.internal_get_pending_pool_config()
+    }
+
+    pub fn get_deposit_caps(&self) -> DepositCapsDto {
+        self.internal_get_deposit_caps()
+    }
+
+    pub fn get_referrer(&self, referrer_account_id: AccountId) -> Option<ReferrerDto> {
+        self.internal_get_referrer(referrer_account_id)
+    }
+
+    pub fn get_referrer_registry(&self) -> Vec<ReferrerDto> {
+        self.internal_get_referrer_registry()
+    }
+
+    #[handle_result]
+    pub fn get_remaining_deposit_capacity(&self, account_id: Option<AccountId>, validator_account_id: Option<AccountId>) -> Result<DepositCapacity, StakePoolError> {
+        self.internal_get_remaining_deposit_capacity(account_id, validator_account_id)
+    }
+
+    /// The total supply is checked against token balances of the sampled accounts.
+    pub fn check_invariants(&self, account_ids: Option<Vec<AccountId>>) -> InvariantReport {
+        self.internal_check_invariants(account_ids.unwrap_or_default())
+    }
 }
 
 #[near_bindgen]
@@ -342,15 +633,14 @@ impl StakePool {
     fn internal_new(
         fungible_token_metadata: FungibleTokenMetadataDto,
         manager_id: Option<AccountId>,
-        self_fee_receiver_account_id: AccountId,
-        partner_fee_receiver_account_id: AccountId,
-        reward_fee_self: Option<Fee>,
-        reward_fee_partner: Option<Fee>,
-        instant_withdraw_fee_self: Option<Fee>,
-        instant_withdraw_fee_partner: Option<Fee>
+        reward_fee: Option<Fee>,
+        reward_fee_split: FeeSplit,
+        instant_withdraw_fee: Option<Fee>,
+        instant_withdraw_fee_split: FeeSplit,
+        pool_config: Option<PoolConfigDto>
     ) -> Self {
         if env::state_exists() {
-            env::panic_str("Contract state is already initialize.");
+            StakePoolError::ContractStateAlreadyInitialized.panic();
         }
 
         let fungible_token_metadata_ = FungibleTokenMetadata {
@@ -364,49 +654,15 @@ impl StakePool {
         };
         fungible_token_metadata_.assert_valid();
 
-        if self_fee_receiver_account_id == partner_fee_receiver_account_id {
-            env::panic_str("The self fee receiver account and partner fee receiver account can not be the same.");
-        }
-
-        if reward_fee_self.is_none() && reward_fee_partner.is_some() {
-            env::panic_str("Reward fees are not valid.");
+        if let Some(ref reward_fee_) = reward_fee {
+            reward_fee_.assert_valid();
         }
-        let reward_fee = if let Some(reward_fee_self_) = reward_fee_self {
-            reward_fee_self_.assert_valid();
+        reward_fee_split.assert_valid();
 
-            if let Some(ref reward_fee_partner_) = reward_fee_partner {
-                reward_fee_partner_.assert_valid();
-            }
-
-            Some (
-                SharedFee {
-                    self_fee: reward_fee_self_,
-                    partner_fee: reward_fee_partner
-                }
-            )
-        } else {
-            None
-        };
-
-        if instant_withdraw_fee_self.is_none() && instant_withdraw_fee_partner.is_some() {
-            env::panic_str("Instant withdraw fees are not valid.");
+        if let Some(ref instant_withdraw_fee_) = instant_withdraw_fee {
+            instant_withdraw_fee_.assert_valid();
         }
-        let instant_withdraw_fee = if let Some(instant_withdraw_fee_self_) = instant_withdraw_fee_self {
-            instant_withdraw_fee_self_.assert_valid();
-
-            if let Some(ref instant_withdraw_fee_partner_) = instant_withdraw_fee_partner {
-                instant_withdraw_fee_partner_.assert_valid();
-            }
-
-            Some (
-                SharedFee {
-                    self_fee: instant_withdraw_fee_self_,
-                    partner_fee: instant_withdraw_fee_partner
-                }
-            )
-        } else {
-            None
-        };
+        instant_withdraw_fee_split.assert_valid();
 
         let predecessor_account_id = env::predecessor_account_id();
 
@@ -418,19 +674,24 @@ impl StakePool {
         let account_balance = AccountBalance {
             token_amount: 0,
             classic_near_amount: 0,
-            investment_near_amount: 0
+            investment_near_amount: 0,
+            referrer_id: None
         };
 
         let mut stake_pool = Self {
             account_registry: AccountRegistry {
                 owner_id: predecessor_account_id.clone(),
-                manager_id: manager_id_,
-                self_fee_receiver_account_id,
-                partner_fee_receiver_account_id
+                manager_id: manager_id_
             },
             fee_registry: FeeRegistry {
                 reward_fee,
-                instant_withdraw_fee
+                reward_fee_split,
+                instant_withdraw_fee,
+                instant_withdraw_fee_curve: None,
+                instant_withdraw_fee_split,
+                referral_fee: None,
+                keeper_bounty: None,
+                storage_usage_per_fee_beneficiary: FeeRegistry::calculate_storage_usage_per_additional_fee_beneficiary()
             },
             fungible_token: FungibleToken::new(fungible_token_metadata_.clone()),
             fund: Fund::new(),
@@ -439,61 +700,118 @@ impl StakePool {
             reward: Reward {
                 previous_epoch_rewards_from_validators_near_amount: 0,
                 total_rewards_from_validators_near_amount: 0
+            },
+            epoch_history: EpochHistory::new(),
+            deposit_caps: DepositCaps::new(),
+            pool_config: PoolConfig::new(),
+            pending_pool_config: None,
+            referral: Referral::new()
+        };
+        if let Some(pool_config_) = pool_config {
+            stake_pool.pool_config = Self::convert_pool_config(pool_config_);
+            stake_pool.assert_pool_config_is_valid(&stake_pool.pool_config);
+        }
+        let fee_beneficiary_registry: Vec<AccountId> = stake_pool.fee_registry.reward_fee_split.beneficiaries.iter()
+            .chain(stake_pool.fee_registry.instant_withdraw_fee_split.beneficiaries.iter())
+            .map(|fee_beneficiary| fee_beneficiary.account_id.clone())
+            .collect();
+        for fee_beneficiary_account_id in fee_beneficiary_registry.iter() {
+            if !stake_pool.fungible_token.contains_account_balance(fee_beneficiary_account_id) {
+                stake_pool.fungible_token.insert_account_balance(fee_beneficiary_account_id, &account_balance);
+                stake_pool.fungible_token.accounts_quantity += 1;
+            }
+        }
+        stake_pool.assert_epoch_history_storage_staking_is_covered();
+
+        stake_pool
+    }
+
+    fn internal_migrate() -> Self {
+        let stake_pool: StakePoolV1 = match env::state_read() {
+            Some(stake_pool_) => stake_pool_,
+            None => {
+                StakePoolError::ContractStateNotInitialized.panic();
             }
         };
-        stake_pool.fungible_token.account_registry.insert(&stake_pool.account_registry.self_fee_receiver_account_id, &account_balance);
-        stake_pool.fungible_token.account_registry.insert(&stake_pool.account_registry.partner_fee_receiver_account_id, &account_balance);
-        stake_pool.fungible_token.accounts_quantity = 2;
+
+        let stake_pool = Self {
+            fee_registry: stake_pool.fee_registry.migrate(
+                &stake_pool.account_registry.self_fee_receiver_account_id,
+                &stake_pool.account_registry.partner_fee_receiver_account_id
+            ),
+            account_registry: stake_pool.account_registry.migrate(),
+            fungible_token: stake_pool.fungible_token.migrate(),
+            fund: stake_pool.fund.migrate(),
+            validating: stake_pool.validating.migrate(),
+            current_epoch_height: stake_pool.current_epoch_height,
+            reward: stake_pool.reward,
+            epoch_history: EpochHistory::new(),
+            deposit_caps: DepositCaps::new(),
+            pool_config: PoolConfig::new(),
+            pending_pool_config: None,
+            referral: Referral::new()
+        };
+        stake_pool.assert_epoch_history_storage_staking_is_covered();
 
         stake_pool
     }
 
-    fn internal_deposit(&mut self, near_amount: Balance) -> PromiseOrValue<()> {
-        Self::assert_gas_is_enough();
-        Self::assert_minimum_deposit();
+    fn internal_deposit(&mut self, beneficiary_account_id: AccountId, near_amount: Balance, min_token_amount: Option<Balance>, referrer_id: Option<AccountId>) -> PromiseOrValue<()> {
+        self.assert_gas_is_enough(gas::DEPOSIT);
+        self.assert_minimum_deposit();
         self.assert_epoch_is_synchronized();
 
         let predecessor_account_id = env::predecessor_account_id();
 
+        // The referrer is bound to the account forever, so only the beneficiary itself can choose it.
+        if referrer_id.is_some() && predecessor_account_id != beneficiary_account_id {
+            StakePoolError::ReferrerCanBeChosenOnlyByBeneficiary.panic();
+        }
+        self.assert_referrer_is_valid(&beneficiary_account_id, &referrer_id);
+
         let attached_deposit = env::attached_deposit();
 
-        let (storage_staking_price_per_additional_account, mut account_balance) = match self.fungible_token.account_registry.get(&predecessor_account_id) {
+        let (storage_staking_price_per_additional_account, mut account_balance) = match self.fungible_token.get_account_balance(&beneficiary_account_id) {
             Some(account_balance_) => (0, account_balance_),
             None => {
                 (
                     Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account),
-                    AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0 }
+                    AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0, referrer_id: None }
                 )
             }
         };
 
-        let minimum_near_amount = if MINIMUN_DEPOSIT_AMOUNT > storage_staking_price_per_additional_account {
-            MINIMUN_DEPOSIT_AMOUNT - storage_staking_price_per_additional_account
+        let minimum_near_amount = if self.pool_config.minimum_deposit_amount > storage_staking_price_per_additional_account {
+            self.pool_config.minimum_deposit_amount - storage_staking_price_per_additional_account
         } else {
-            env::panic_str("Logic error.");
+            StakePoolError::NonexecutableCode { reason: "Logic error.".to_string() }.panic();
         };
         if near_amount < minimum_near_amount {
-            env::panic_str("Near amount less then minimum required near amount.");
+            StakePoolError::NearAmountLessThanMinimumRequiredNearAmount { required_near_amount: minimum_near_amount.into(), provided_near_amount: near_amount.into() }.panic();
         }
 
         if attached_deposit < storage_staking_price_per_additional_account {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_account.into(), provided_near_amount: attached_deposit.into() }.panic();
         }
 
         let available_for_staking_near_amount = attached_deposit - storage_staking_price_per_additional_account;
 
         if near_amount > available_for_staking_near_amount {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearDeposit { required_near_amount: (storage_staking_price_per_additional_account + near_amount).into(), provided_near_amount: attached_deposit.into() }.panic();
         }
 
         let refundable_near_amount = available_for_staking_near_amount - near_amount;
 
+        self.assert_pool_deposit_cap(near_amount);
+        self.assert_account_deposit_cap(&beneficiary_account_id, near_amount);
+
         let (token_amount, remainder_near_amount) = self.convert_near_amount_to_token_amount(near_amount);
         if token_amount == 0 {
-            env::panic_str("Insufficient near amount.");
+            StakePoolError::InsufficientNearAmount.panic();
         }
+        Self::assert_minimum_token_amount(token_amount, min_token_amount);
 
-        if self.fund.is_distributed_on_validators_in_current_epoch && self.validating.preffered_validator.is_some() {
+        if self.fund.is_distributed_on_validators_in_current_epoch && self.is_preffered_validator_able_to_stake(near_amount) {
             match self.validating.preffered_validator {
                 Some(ref preffered_validator_account_id) => {
                     match self.validating.validator_registry.get(preffered_validator_account_id) {
@@ -503,11 +821,14 @@ impl StakePool {
                                     PromiseOrValue::Promise(
                                         classic_validator::ext(preffered_validator_account_id.clone())
                                             .with_attached_deposit(near_amount)
+                                            .with_static_gas(gas::VALIDATOR_DEPOSIT_AND_STAKE)
                                             .deposit_and_stake()
                                             .then(
                                                 Self::ext(env::current_account_id())
+                                                    .with_static_gas(gas::DEPOSIT_CALLBACK)
                                                     .deposit_callback(
                                                         predecessor_account_id,
+                                                        beneficiary_account_id,
                                                         preffered_validator_account_id.clone(),
                                                         attached_deposit,
                                                         near_amount,
@@ -515,7 +836,8 @@ impl StakePool {
                                                         token_amount,
                                                         remainder_near_amount,
                                                         self.current_epoch_height,
-                                                        storage_staking_price_per_additional_account
+                                                        storage_staking_price_per_additional_account,
+                                                        referrer_id
                                                     )
                                             )
                                     )
@@ -523,27 +845,33 @@ impl StakePool {
                             }
                         }
                         None => {
-                            env::panic_str("Nonexecutable code. Object must exist.");
+                            StakePoolError::object_must_exist().panic();
                         }
                     }
                 }
                 None => {
-                    env::panic_str("Nonexecutable code. Object must exist.");
+                    StakePoolError::object_must_exist().panic();
                 }
             }
         } else {
+            let common_balance_log = self.fund.get_common_balance();
+
             self.fund.classic_unstaked_balance += near_amount;
+            self.fund.classic_remainder_balance += remainder_near_amount;
             self.fungible_token.total_supply += token_amount;
+            self.epoch_history.deposited_near_amount += near_amount;
 
             account_balance.token_amount += token_amount;
             account_balance.classic_near_amount += remainder_near_amount;
-            if let None = self.fungible_token.account_registry.insert(&predecessor_account_id, &account_balance) {
+            if account_balance.referrer_id.is_none() {
+                account_balance.referrer_id = referrer_id;
+            }
+            if self.insert_account_balance(&beneficiary_account_id, &account_balance).is_none() {
                 self.fungible_token.accounts_quantity += 1;
             }
 
             if refundable_near_amount > 0 {
-                Promise::new(predecessor_account_id.clone())
-                    .transfer(refundable_near_amount);
+                Self::transfer_near_amount(predecessor_account_id, refundable_near_amount);
             }
 
             let current_account_id_log = env::current_account_id();
@@ -572,12 +900,12 @@ impl StakePool {
                     &current_account_id_log,
                     self.fungible_token.total_supply - token_amount,
                     &current_account_id_log,
-                    self.fund.get_common_balance() - near_amount,
-                    &predecessor_account_id,
+                    common_balance_log,
+                    &beneficiary_account_id,
                     account_balance.token_amount - token_amount,
-                    &predecessor_account_id,
+                    &beneficiary_account_id,
                     token_amount,
-                    &predecessor_account_id,
+                    &beneficiary_account_id,
                     account_balance.token_amount,
                     &current_account_id_log,
                     self.fund.get_common_balance(),
@@ -590,70 +918,81 @@ impl StakePool {
         }
     }
 
-    fn internal_deposit_on_validator(&mut self, near_amount: Balance, validator_account_id: AccountId) -> Promise {
-        Self::assert_gas_is_enough();
-        Self::assert_minimum_deposit();
+    fn internal_deposit_on_validator(&mut self, near_amount: Balance, validator_account_id: AccountId, referrer_id: Option<AccountId>) -> Promise {
+        self.assert_gas_is_enough(gas::DEPOSIT_ON_VALIDATOR);
+        self.assert_minimum_deposit();
         self.assert_epoch_is_synchronized();
 
         let validator = match self.validating.validator_registry.get(&validator_account_id) {
             Some(validator_) => validator_,
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
             }
         };
+        if validator.is_draining {
+            StakePoolError::ValidatorInDrainingState.panic();
+        }
 
         let predecessor_account_id = env::predecessor_account_id();
 
+        self.assert_referrer_is_valid(&predecessor_account_id, &referrer_id);
+
         let attached_deposit = env::attached_deposit();
 
         let investor_investment = match self.validating.investor_investment_registry.get(&predecessor_account_id) {
             Some(investor_investment_) => investor_investment_,
             None => {
-                env::panic_str("Investor account is not registered yet.");
+                StakePoolError::InvestorAccountNotRegistered { investor_account_id: predecessor_account_id.clone() }.panic();
             }
         };
 
         let mut storage_staking_price_per_additional_accounts: Balance = 0;
 
-        if let None = investor_investment.distribution_registry.get(&validator_account_id) {
+        if investor_investment.distribution_registry.get(&validator_account_id).is_none() {
             storage_staking_price_per_additional_accounts += Self::calculate_storage_staking_price(self.validating.storage_usage_per_distribution);
         }
 
-        if let None = self.fungible_token.account_registry.get(&predecessor_account_id) {
+        if !self.fungible_token.contains_account_balance(&predecessor_account_id) {
             storage_staking_price_per_additional_accounts += Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
         };
 
-        let minimum_near_amount = if MINIMUN_DEPOSIT_AMOUNT > storage_staking_price_per_additional_accounts {
-            MINIMUN_DEPOSIT_AMOUNT - storage_staking_price_per_additional_accounts
+        let minimum_near_amount = if self.pool_config.minimum_deposit_amount > storage_staking_price_per_additional_accounts {
+            self.pool_config.minimum_deposit_amount - storage_staking_price_per_additional_accounts
         } else {
-            env::panic_str("Logic error.");
+            StakePoolError::NonexecutableCode { reason: "Logic error.".to_string() }.panic();
         };
         if near_amount < minimum_near_amount {
-            env::panic_str("Near amount less then minimum required near amount.");
+            StakePoolError::NearAmountLessThanMinimumRequiredNearAmount { required_near_amount: minimum_near_amount.into(), provided_near_amount: near_amount.into() }.panic();
         }
 
         if attached_deposit <= storage_staking_price_per_additional_accounts {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearDeposit { required_near_amount: (storage_staking_price_per_additional_accounts + near_amount).into(), provided_near_amount: attached_deposit.into() }.panic();
         }
         let available_for_staking_near_amount = attached_deposit - storage_staking_price_per_additional_accounts;
 
         if near_amount > available_for_staking_near_amount {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearDeposit { required_near_amount: (storage_staking_price_per_additional_accounts + near_amount).into(), provided_near_amount: attached_deposit.into() }.panic();
         }
         let refundable_near_amount = available_for_staking_near_amount - near_amount;
 
+        self.assert_pool_deposit_cap(near_amount);
+        self.assert_account_deposit_cap(&predecessor_account_id, near_amount);
+        self.assert_validator_share_cap(&validator, near_amount, self.fund.get_common_balance() + near_amount);
+
         let (token_amount, remainder_near_amount) = self.convert_near_amount_to_token_amount(near_amount);
         if token_amount == 0 {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearAmount.panic();
         }
 
         match validator.staking_contract_version {
             StakingContractVersion::Core => {
                 classic_validator::ext(validator_account_id.clone())
                     .with_attached_deposit(near_amount)
+                    .with_static_gas(gas::VALIDATOR_DEPOSIT_AND_STAKE)
                     .deposit_and_stake()
                     .then(
                         Self::ext(env::current_account_id())
+                            .with_static_gas(gas::DEPOSIT_ON_VALIDATOR_CALLBACK)
                             .deposit_on_validator_callback(
                                 predecessor_account_id,
                                 validator_account_id.clone(),
@@ -662,34 +1001,35 @@ impl StakePool {
                                 refundable_near_amount,
                                 token_amount,
                                 remainder_near_amount,
-                                storage_staking_price_per_additional_accounts
+                                storage_staking_price_per_additional_accounts,
+                                referrer_id
                             )
                     )
             }
         }
     }
 
-    fn internal_instant_withdraw(&mut self, mut token_amount: Balance) -> Promise {
-        Self::assert_gas_is_enough();
+    fn internal_instant_withdraw(&mut self, mut token_amount: Balance, min_near_amount: Option<Balance>, receiver_account_id: AccountId) -> Promise {
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
 
         if token_amount == 0 {
-            env::panic_str("Insufficient token amount.");
+            StakePoolError::InsufficientTokenAmount.panic();
         }
 
         let token_amount_log = token_amount;
 
         let predecessor_account_id = env::predecessor_account_id();
 
-        let mut account_balance = match self.fungible_token.account_registry.get(&predecessor_account_id) {
+        let mut account_balance = match self.fungible_token.get_account_balance(&predecessor_account_id) {
             Some(account_balance_) => account_balance_,
             None => {
-                env::panic_str("Token account is not registered.");
+                StakePoolError::TokenAccountNotRegistered { account_id: predecessor_account_id.clone() }.panic();
             }
         };
         if account_balance.token_amount < token_amount {
-            env::panic_str("Token amount exceeded the available token balance.");
+            StakePoolError::TokenAmountExceededAvailableTokenBalance { requested_token_amount: token_amount.into(), available_token_amount: account_balance.token_amount.into() }.panic();
         }
 
         let token_balance_log = account_balance.token_amount;
@@ -697,84 +1037,46 @@ impl StakePool {
         account_balance.token_amount -= token_amount;
         if let Some(investor_investment) = self.validating.investor_investment_registry.get(&predecessor_account_id) {
             if (self.convert_token_amount_to_near_amount(account_balance.token_amount) + account_balance.investment_near_amount) < investor_investment.staked_balance {
-                env::panic_str("Token amount exceeded the available to instant withdraw token amount.");
+                StakePoolError::TokenAmountExceededAvailableToInstantWithdrawTokenAmount.panic();
             }
         }
 
-        let mut instant_withdraw_fee_self_token_amount: u128 = 0;
-
-        let mut instant_withdraw_fee_partner_token_amount: u128 = 0;
-
-        let mut instant_withdraw_fee_self_log: Option<Fee> = None;
-
-        if let Some(ref instant_withdraw_fee) = self.fee_registry.instant_withdraw_fee {
-            instant_withdraw_fee_self_log = Some(instant_withdraw_fee.self_fee.clone());
-
-            let mut instant_withdraw_fee_self_token_amount_ = instant_withdraw_fee.self_fee.multiply(token_amount);
-            if instant_withdraw_fee_self_token_amount_ != 0 {
-                token_amount -= instant_withdraw_fee_self_token_amount_;
-
-                if let Some(ref instant_withdraw_fee_partner) = instant_withdraw_fee.partner_fee {
-                    let instant_withdraw_fee_partner_token_amount_ = instant_withdraw_fee_partner.multiply(instant_withdraw_fee_self_token_amount_);
-                    if instant_withdraw_fee_partner_token_amount_ != 0 {
-                        instant_withdraw_fee_partner_token_amount = instant_withdraw_fee_partner_token_amount_;
+        let instant_withdraw_fee = self.get_instant_withdraw_fee(token_amount);
 
-                        instant_withdraw_fee_self_token_amount_ -= instant_withdraw_fee_partner_token_amount_;
-                    }
-                }
-
-                instant_withdraw_fee_self_token_amount = instant_withdraw_fee_self_token_amount_;
-            }
-        }
+        let instant_withdrawal = core::calculate_instant_withdrawal(
+            token_amount,
+            instant_withdraw_fee.as_ref(),
+            self.fund.get_common_balance(),
+            self.fungible_token.total_supply
+        );
+        let instant_withdraw_fee_token_amount = instant_withdrawal.fee_token_amount;
+        token_amount = instant_withdrawal.burned_token_amount;
 
-        let mut near_amount = self.convert_token_amount_to_near_amount(token_amount) + account_balance.classic_near_amount;
+        let mut near_amount = instant_withdrawal.near_amount + account_balance.classic_near_amount;
 
         if near_amount == 0 {
-            env::panic_str("Insufficient token amount.");
+            StakePoolError::InsufficientTokenAmount.panic();
         }
         if near_amount > self.fund.classic_unstaked_balance {
-            env::panic_str("Token amount exceeded the available unstaked near balance.");
+            StakePoolError::TokenAmountExceededAvailableUnstakedNearBalance.panic();
         }
+        Self::assert_minimum_near_amount(near_amount, min_near_amount);
+
+        let common_balance_log = self.fund.get_common_balance();
 
+        self.fund.classic_remainder_balance -= account_balance.classic_near_amount;
         account_balance.classic_near_amount = 0;
 
         self.fund.classic_unstaked_balance -= near_amount;
-
-        if predecessor_account_id == self.account_registry.self_fee_receiver_account_id {
-            account_balance.token_amount += instant_withdraw_fee_self_token_amount;
-        } else {
-            let mut account_balance_ = match self.fungible_token.account_registry.get(&self.account_registry.self_fee_receiver_account_id) {
-                Some(account_balance__) => account_balance__,
-                None => {
-                    env::panic_str("Nonexecutable code. Object must exist.");
-                }
-            };
-            account_balance_.token_amount += instant_withdraw_fee_self_token_amount;
-
-            self.fungible_token.account_registry.insert(&self.account_registry.self_fee_receiver_account_id, &account_balance_);
-        }
-        if predecessor_account_id == self.account_registry.partner_fee_receiver_account_id {
-            account_balance.token_amount += instant_withdraw_fee_partner_token_amount
-        } else {
-            let mut account_balance_ = match self.fungible_token.account_registry.get(&self.account_registry.partner_fee_receiver_account_id) {
-                Some(account_balance__) => account_balance__,
-                None => {
-                    env::panic_str("Nonexecutable code. Object must exist.");
-                }
-            };
-            account_balance_.token_amount += instant_withdraw_fee_partner_token_amount;
-
-            self.fungible_token.account_registry.insert(&self.account_registry.partner_fee_receiver_account_id, &account_balance_);
-        }
+        self.epoch_history.withdrawn_near_amount += near_amount;
 
         let released_storage_staking_price_per_additional_account_log = if account_balance.token_amount > 0
-            || predecessor_account_id == self.account_registry.self_fee_receiver_account_id
-            || predecessor_account_id == self.account_registry.partner_fee_receiver_account_id {
-            self.fungible_token.account_registry.insert(&predecessor_account_id, &account_balance);
+            || self.is_permanent_token_account(&predecessor_account_id) {
+            self.insert_account_balance(&predecessor_account_id, &account_balance);
 
             0
         } else {
-            self.fungible_token.account_registry.remove(&predecessor_account_id);
+            self.remove_account_balance(&predecessor_account_id);
             self.fungible_token.accounts_quantity -= 1;
 
             let storage_staking_price_per_additional_account = Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
@@ -784,6 +1086,13 @@ impl StakePool {
             storage_staking_price_per_additional_account
         };
 
+        self.distribute_fee(&self.fee_registry.instant_withdraw_fee_split.clone(), instant_withdraw_fee_token_amount);
+
+        let new_token_balance_log = match self.fungible_token.get_account_balance(&predecessor_account_id) {
+            Some(account_balance_) => account_balance_.token_amount,
+            None => 0
+        };
+
         self.fungible_token.total_supply -= token_amount;
 
         let attached_deposit = env::attached_deposit();
@@ -812,19 +1121,19 @@ impl StakePool {
                 self.current_epoch_height,
                 attached_deposit,
                 token_amount_log,
-                instant_withdraw_fee_self_log,
+                instant_withdraw_fee,
                 released_storage_staking_price_per_additional_account_log,
                 near_amount,
                 &current_account_id_log,
                 self.fungible_token.total_supply + token_amount,
                 &current_account_id_log,
-                self.fund.get_common_balance() + near_amount - released_storage_staking_price_per_additional_account_log - attached_deposit,
+                common_balance_log,
                 &predecessor_account_id,
                 token_balance_log,
                 &predecessor_account_id,
-                token_amount + instant_withdraw_fee_self_token_amount + instant_withdraw_fee_partner_token_amount,
+                token_amount + instant_withdraw_fee_token_amount,
                 &predecessor_account_id,
-                account_balance.token_amount,
+                new_token_balance_log,
                 &current_account_id_log,
                 self.fund.get_common_balance(),
                 &current_account_id_log,
@@ -832,43 +1141,47 @@ impl StakePool {
             ).as_str()
         );
 
-        Promise::new(predecessor_account_id)
-            .transfer(near_amount)
+        Self::transfer_near_amount_to(receiver_account_id, predecessor_account_id, near_amount)
     }
 
-    fn internal_delayed_withdraw(&mut self, token_amount: Balance) -> PromiseOrValue<()> {
-        Self::assert_gas_is_enough();
+    fn internal_delayed_withdraw(&mut self, token_amount: Balance, min_near_amount: Option<Balance>) -> PromiseOrValue<()> {
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
 
         if token_amount == 0 {
-            env::panic_str("Insufficient token amount.");
+            StakePoolError::InsufficientTokenAmount.panic();
         }
 
         let predecessor_account_id = env::predecessor_account_id();
 
-        let mut account_balance = match self.fungible_token.account_registry.get(&predecessor_account_id) {
+        let mut account_balance = match self.fungible_token.get_account_balance(&predecessor_account_id) {
             Some(account_balance_) => account_balance_,
             None => {
-                env::panic_str("Token account is not registered.");
+                StakePoolError::TokenAccountNotRegistered { account_id: predecessor_account_id.clone() }.panic();
             }
         };
         if account_balance.token_amount < token_amount {
-            env::panic_str("Token amount exceeded the available token balance.");
+            StakePoolError::TokenAmountExceededAvailableTokenBalance { requested_token_amount: token_amount.into(), available_token_amount: account_balance.token_amount.into() }.panic();
         }
 
         let near_amount = self.convert_token_amount_to_near_amount(token_amount) + account_balance.classic_near_amount;
 
         if near_amount == 0 {
-            env::panic_str("Insufficient token amount.");
+            StakePoolError::InsufficientTokenAmount.panic();
         }
         if near_amount > self.fund.classic_staked_balance {
-            env::panic_str("Token amount exceeded the available staked near balance.");
+            StakePoolError::TokenAmountExceededAvailableStakedNearBalance.panic();
         }
+        Self::assert_minimum_near_amount(near_amount, min_near_amount);
+
+        let common_balance_log = self.fund.get_common_balance();
 
+        self.fund.classic_remainder_balance -= account_balance.classic_near_amount;
         account_balance.classic_near_amount = 0;
 
         self.fund.classic_staked_balance -= near_amount;
+        self.epoch_history.withdrawn_near_amount += near_amount;
 
         let attached_deposit = env::attached_deposit();
 
@@ -878,7 +1191,7 @@ impl StakePool {
             delayed_withdrawal_near_amount_log,
             epoch_quantity_to_take_delayed_withdrawal_log,
             mut delayed_withdrawal
-        ) = match self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.get(&predecessor_account_id) {
+        ) = match self.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&predecessor_account_id) {
             Some(delayed_withdrawal_) => {
                 (
                     attached_deposit,
@@ -892,7 +1205,7 @@ impl StakePool {
                 let storage_staking_price_per_additional_delayed_withdrawal =
                     Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal);
                 if attached_deposit < storage_staking_price_per_additional_delayed_withdrawal {
-                    env::panic_str("Insufficient near deposit.");
+                    StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_delayed_withdrawal.into(), provided_near_amount: attached_deposit.into() }.panic();
                 }
 
                 (
@@ -902,30 +1215,32 @@ impl StakePool {
                     0,
                     DelayedWithdrawal {
                         near_amount: 0,
-                        started_epoch_height: self.current_epoch_height
+                        started_epoch_height: self.current_epoch_height,
+                        epoch_quantity_for_delayed_withdrawal: self.pool_config.epoch_quantity_for_delayed_withdrawal,
+                        is_auto_claim: false
                     },
                 )
             }
         };
         delayed_withdrawal.near_amount += near_amount;
         delayed_withdrawal.started_epoch_height = self.current_epoch_height;
-        self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.insert(&predecessor_account_id, &delayed_withdrawal);
+        delayed_withdrawal.epoch_quantity_for_delayed_withdrawal = self.pool_config.epoch_quantity_for_delayed_withdrawal;
+        self.fund.delayed_withdrawn_fund.insert_delayed_withdrawal(&predecessor_account_id, &delayed_withdrawal);
         self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount += near_amount;
 
         account_balance.token_amount -= token_amount;
         if let Some(investor_investment) = self.validating.investor_investment_registry.get(&predecessor_account_id) {
             if (self.convert_token_amount_to_near_amount(account_balance.token_amount) + account_balance.investment_near_amount) < investor_investment.staked_balance {
-                env::panic_str("Token amount exceeded the available to delayed withdraw token amount.");
+                StakePoolError::TokenAmountExceededAvailableToDelayedWithdrawTokenAmount.panic();
             }
         }
         let released_storage_staking_price_per_additional_account_log = if account_balance.token_amount > 0
-            || predecessor_account_id == self.account_registry.self_fee_receiver_account_id
-            || predecessor_account_id == self.account_registry.partner_fee_receiver_account_id  {
-            self.fungible_token.account_registry.insert(&predecessor_account_id, &account_balance);
+            || self.is_permanent_token_account(&predecessor_account_id) {
+            self.insert_account_balance(&predecessor_account_id, &account_balance);
 
             0
         } else {
-            self.fungible_token.account_registry.remove(&predecessor_account_id);
+            self.remove_account_balance(&predecessor_account_id);
             self.fungible_token.accounts_quantity -= 1;
 
             let storage_staking_price_per_additional_account =
@@ -976,7 +1291,7 @@ impl StakePool {
                 &current_account_id_log,
                 self.fungible_token.total_supply + token_amount,
                 &current_account_id_log,
-                self.fund.get_common_balance() + near_amount,
+                common_balance_log,
                 &predecessor_account_id,
                 account_balance.token_amount + token_amount,
                 &predecessor_account_id,
@@ -992,8 +1307,7 @@ impl StakePool {
 
         if refundable_near_amount > 0 {
             return PromiseOrValue::Promise(
-                Promise::new(predecessor_account_id)
-                    .transfer(refundable_near_amount)
+                Self::transfer_near_amount(predecessor_account_id, refundable_near_amount)
             );
         }
 
@@ -1001,21 +1315,21 @@ impl StakePool {
     }
 
     fn internal_delayed_withdraw_from_validator(&mut self, near_amount: Balance, validator_account_id: AccountId) -> PromiseOrValue<()> {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
 
         if near_amount == 0 {
-            env::panic_str("Insufficient near amount.");
+            StakePoolError::InsufficientNearAmount.panic();
         }
         if near_amount > self.fund.investment_staked_balance {
-            env::panic_str("Token amount exceeded the available staked near balance.");
+            StakePoolError::TokenAmountExceededAvailableStakedNearBalance.panic();
         }
 
         let validator = match self.validating.validator_registry.get(&validator_account_id) {
             Some(validator_) => validator_,
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
             }
         };
 
@@ -1024,18 +1338,18 @@ impl StakePool {
         let mut investor_investment = match self.validating.investor_investment_registry.get(&predecessor_account_id) {
             Some(investor_investment_) => investor_investment_,
             None => {
-                env::panic_str("Investor account is not registered yet.");
+                StakePoolError::InvestorAccountNotRegistered { investor_account_id: predecessor_account_id.clone() }.panic();
             }
         };
 
         let mut staked_balance = match investor_investment.distribution_registry.get(&validator_account_id) {
             Some(staked_balance_) => staked_balance_,
             None => {
-                env::panic_str("There is no investor stake on this validator.");
+                StakePoolError::NoInvestorStakeOnValidator.panic();
             }
         };
         if near_amount > staked_balance {
-            env::panic_str("Near amount exceeded the available investor near balance on validator.");
+            StakePoolError::NearAmountExceededAvailableInvestorNearBalanceOnValidator.panic();
         }
 
         let attached_deposit = env::attached_deposit();
@@ -1050,7 +1364,7 @@ impl StakePool {
                 let storage_staking_price_per_additional_investment_withdrawal =
                     Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_investment_withdrawal);
                 if attached_deposit < storage_staking_price_per_additional_investment_withdrawal {
-                    env::panic_str("Insufficient near deposit.");
+                    StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_investment_withdrawal.into(), provided_near_amount: attached_deposit.into() }.panic();
                 }
 
                 (
@@ -1064,31 +1378,32 @@ impl StakePool {
             }
         };
         if near_amount > (validator.balance.investment_near_amount - investment_withdrawal.near_amount) {
-            env::panic_str("Near amount exceeded the available near balance on validator.");
+            StakePoolError::NearAmountExceededAvailableNearBalanceOnValidator.panic();
         }
 
         let (token_amount, _) = self.convert_near_amount_to_token_amount(near_amount);
         if token_amount == 0 {
-            env::panic_str("Insufficient near amount.");
+            StakePoolError::InsufficientNearAmount.panic();
         }
 
-        let mut account_balance = match self.fungible_token.account_registry.get(&predecessor_account_id) {
+        let mut account_balance = match self.fungible_token.get_account_balance(&predecessor_account_id) {
             Some(account_balance_) => account_balance_,
             None => {
-                env::panic_str("Token account is not registered.");
+                StakePoolError::TokenAccountNotRegistered { account_id: predecessor_account_id.clone() }.panic();
             }
         };
         if account_balance.token_amount < token_amount {
-            env::panic_str("Token amount exceeded the available token balance.");
+            StakePoolError::TokenAmountExceededAvailableTokenBalance { requested_token_amount: token_amount.into(), available_token_amount: account_balance.token_amount.into() }.panic();
         }
 
         self.fund.investment_staked_balance -= near_amount;
+        self.epoch_history.withdrawn_near_amount += near_amount;
 
         let (
             delayed_withdrawal_near_amount_log,
             epoch_quantity_to_take_delayed_withdrawal_log,
             mut delayed_withdrawal
-         ) = match self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.get(&predecessor_account_id) {
+         ) = match self.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&predecessor_account_id) {
             Some(delayed_withdrawal_) => {
                 (
                     delayed_withdrawal_.near_amount,
@@ -1100,7 +1415,7 @@ impl StakePool {
                 let storage_staking_price_per_additional_delayed_withdrawal =
                     Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal);
                 if refundable_near_amount < storage_staking_price_per_additional_delayed_withdrawal {
-                    env::panic_str("Insufficient near deposit.");
+                    StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_delayed_withdrawal.into(), provided_near_amount: refundable_near_amount.into() }.panic();
                 }
                 refundable_near_amount -= storage_staking_price_per_additional_delayed_withdrawal;
 
@@ -1111,14 +1426,17 @@ impl StakePool {
                     0,
                     DelayedWithdrawal {
                         near_amount: 0,
-                        started_epoch_height: self.current_epoch_height
+                        started_epoch_height: self.current_epoch_height,
+                        epoch_quantity_for_delayed_withdrawal: self.pool_config.epoch_quantity_for_delayed_withdrawal,
+                        is_auto_claim: false
                     }
                 )
             }
         };
         delayed_withdrawal.started_epoch_height = self.current_epoch_height;
+        delayed_withdrawal.epoch_quantity_for_delayed_withdrawal = self.pool_config.epoch_quantity_for_delayed_withdrawal;
         delayed_withdrawal.near_amount += near_amount;
-        self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.insert(&predecessor_account_id, &delayed_withdrawal);
+        self.fund.delayed_withdrawn_fund.insert_delayed_withdrawal(&predecessor_account_id, &delayed_withdrawal);
 
         investment_withdrawal.near_amount += near_amount;
         self.fund.delayed_withdrawn_fund.investment_withdrawal_registry.insert(&validator_account_id, &investment_withdrawal);
@@ -1146,11 +1464,10 @@ impl StakePool {
 
         account_balance.token_amount -= token_amount;
         if account_balance.token_amount > 0
-            || predecessor_account_id == self.account_registry.self_fee_receiver_account_id
-            || predecessor_account_id == self.account_registry.partner_fee_receiver_account_id  {
-            self.fungible_token.account_registry.insert(&predecessor_account_id, &account_balance);
+            || self.is_permanent_token_account(&predecessor_account_id) {
+            self.insert_account_balance(&predecessor_account_id, &account_balance);
         } else {
-            self.fungible_token.account_registry.remove(&predecessor_account_id);
+            self.remove_account_balance(&predecessor_account_id);
             self.fungible_token.accounts_quantity -= 1;
 
             let storage_staking_price_per_additional_account =
@@ -1218,124 +1535,296 @@ impl StakePool {
 
         if refundable_near_amount > 0 {
             return PromiseOrValue::Promise(
-                Promise::new(predecessor_account_id)
-                    .transfer(refundable_near_amount)
+                Self::transfer_near_amount(predecessor_account_id, refundable_near_amount)
             )
         }
 
         PromiseOrValue::Value(())
     }
 
-    fn internal_take_delayed_withdrawal(&mut self) -> Promise {
-        Self::assert_gas_is_enough();
+    fn internal_take_delayed_withdrawal(&mut self, receiver_account_id: AccountId) -> Promise {
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
 
         let predecessor_account_id = env::predecessor_account_id();
 
-        let delayed_withdrawal = match self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.remove(&predecessor_account_id) {
+        let delayed_withdrawal = match self.fund.delayed_withdrawn_fund.remove_delayed_withdrawal(&predecessor_account_id) {
             Some(delayed_withdrawal_) => delayed_withdrawal_,
             None => {
-                env::panic_str("Delayed withdrawal account is not registered.");
+                StakePoolError::DelayedWithdrawalAccountNotRegistered { account_id: predecessor_account_id.clone() }.panic();
             }
         };
         if !delayed_withdrawal.can_take_delayed_withdrawal(self.current_epoch_height) {
-            env::panic_str("Wrong epoch for withdrawal.");
+            StakePoolError::WrongEpochForWithdrawal.panic();
         }
 
         self.fund.delayed_withdrawn_fund.balance -= delayed_withdrawal.near_amount;
 
+        Self::log_taken_delayed_withdrawal(&predecessor_account_id, delayed_withdrawal.near_amount, self.current_epoch_height);
+
         let near_amount = delayed_withdrawal.near_amount
             + Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal)
             + env::attached_deposit();
 
-        Promise::new(predecessor_account_id)
-            .transfer(near_amount)
+        Self::transfer_near_amount_to(receiver_account_id, predecessor_account_id, near_amount)
     }
 
-    fn internal_increase_validator_stake(&mut self, validator_account_id: AccountId, near_amount: Balance) -> Promise {
-        Self::assert_gas_is_enough();
+    fn internal_enable_auto_claim(&mut self) -> PromiseOrValue<()> {
+        self.assert_gas_is_enough(gas::TRANSFER);
+        Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
-        self.assert_authorized_management_only_by_manager();
 
-        if near_amount == 0 {
-            env::panic_str("Insufficient near amount.");
-        }
-        if near_amount > self.fund.classic_unstaked_balance {
-            env::panic_str("Near amount exceeded the available unstaked near balance.");
-        }
+        let predecessor_account_id = env::predecessor_account_id();
 
-        let validator = match self.validating.validator_registry.get(&validator_account_id) {
-            Some(validator_) => validator_,
+        let mut delayed_withdrawal = match self.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&predecessor_account_id) {
+            Some(delayed_withdrawal_) => delayed_withdrawal_,
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::DelayedWithdrawalAccountNotRegistered { account_id: predecessor_account_id.clone() }.panic();
             }
         };
-        if validator.is_only_for_investment {
-            env::panic_str("Validator is used only for investment purpose.");
+        if delayed_withdrawal.is_auto_claim {
+            StakePoolError::AutoClaimAlreadyEnabled.panic();
         }
 
-        match validator.staking_contract_version {
-            StakingContractVersion::Core => {
-                classic_validator::ext(validator_account_id.clone())
-                    .with_attached_deposit(near_amount)
-                    .deposit_and_stake()
-                    .then(
-                        Self::ext(env::current_account_id())
-                            .increase_validator_stake_callback(validator_account_id, near_amount, env::epoch_height())
-                    )
-            }
-        }
-    }
+        let storage_staking_price_per_additional_auto_claim =
+            Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_auto_claim);
 
-    fn internal_requested_decrease_validator_stake(
-        &mut self,
-        validator_account_id: AccountId,
-        near_amount: Balance,
-        stake_decreasing_type: StakeDecreasingType
-    ) -> Promise {
-        Self::assert_gas_is_enough();
-        self.assert_epoch_is_desynchronized();
-        self.assert_authorized_management_only_by_manager();
-        if !Self::is_right_epoch(env::epoch_height()) {
-            env::panic_str("Epoch is not intended for a requested decrease validator stake request.");
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit < storage_staking_price_per_additional_auto_claim {
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_auto_claim.into(), provided_near_amount: attached_deposit.into() }.panic();
         }
 
-        if near_amount == 0 {
-            env::panic_str("Insufficient near amount.");
+        delayed_withdrawal.is_auto_claim = true;
+        self.fund.delayed_withdrawn_fund.insert_delayed_withdrawal(&predecessor_account_id, &delayed_withdrawal);
+        self.fund.delayed_withdrawn_fund.push_auto_claim(&predecessor_account_id);
+
+        let near_amount = attached_deposit - storage_staking_price_per_additional_auto_claim;
+        if near_amount > 0 {
+            return PromiseOrValue::Promise(
+                Self::transfer_near_amount(predecessor_account_id, near_amount)
+            );
         }
 
-        let validator = match self.validating.validator_registry.get(&validator_account_id) {
-            Some(validator_) => validator_,
-            None => {
-                env::panic_str("Validator account is not registered yet.");
-            }
-        };
-        match stake_decreasing_type {
-            StakeDecreasingType::Classic => {
-                if near_amount > validator.balance.classic_near_amount {
-                    env::panic_str("Near amount exceeded the available staked near balance.");
+        PromiseOrValue::Value(())
+    }
+
+    /// Walks the auto claim queue from the beginning. The matured delayed withdrawal is sent to the user,
+    /// the immature one is moved to the end of the queue, and the queue element that no longer refers to
+    /// a delayed withdrawal with enabled auto claim is dropped with the refund of its storage staking.
+    fn internal_process_matured_withdrawals(&mut self, limit: u64) -> u64 {
+        let examined_withdrawals_quantity = limit.min(self.fund.delayed_withdrawn_fund.get_auto_claim_queue_length());
+
+        self.assert_gas_is_enough(gas::process_matured_withdrawals(examined_withdrawals_quantity));
+        self.assert_epoch_is_synchronized();
+
+        let storage_staking_price_per_additional_auto_claim =
+            Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_auto_claim);
+        let storage_staking_price_per_additional_delayed_withdrawal =
+            Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal);
+
+        let mut processed_withdrawals_quantity: u64 = 0;
+        let mut keeper_bounty_near_amount: Balance = 0;
+
+        for _ in 0..examined_withdrawals_quantity {
+            let account_id = match self.fund.delayed_withdrawn_fund.pop_auto_claim() {
+                Some(account_id_) => account_id_,
+                None => {
+                    StakePoolError::object_must_exist().panic();
                 }
-                if near_amount > self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount {
-                    env::panic_str("Near amount is more than requested near amount.");
+            };
+
+            let delayed_withdrawal = match self.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&account_id) {
+                Some(delayed_withdrawal_) if delayed_withdrawal_.is_auto_claim => delayed_withdrawal_,
+                _ => {
+                    Self::transfer_near_amount(account_id, storage_staking_price_per_additional_auto_claim);
+
+                    continue;
                 }
+            };
+            if !delayed_withdrawal.can_take_delayed_withdrawal(self.current_epoch_height) {
+                self.fund.delayed_withdrawn_fund.push_auto_claim(&account_id);
+
+                continue;
             }
-            StakeDecreasingType::Investment => {
-                if near_amount > validator.balance.investment_near_amount {
-                    env::panic_str("Near amount exceeded the available unstaked near balance.");
-                }
+
+            self.fund.delayed_withdrawn_fund.remove_delayed_withdrawal(&account_id);
+            self.fund.delayed_withdrawn_fund.balance -= delayed_withdrawal.near_amount;
+
+            Self::log_taken_delayed_withdrawal(&account_id, delayed_withdrawal.near_amount, self.current_epoch_height);
+
+            let bounty_near_amount = match self.fee_registry.keeper_bounty {
+                Some(ref keeper_bounty) => keeper_bounty.multiply(delayed_withdrawal.near_amount),
+                None => 0
+            };
+            keeper_bounty_near_amount += bounty_near_amount;
+
+            Self::transfer_near_amount(
+                account_id,
+                delayed_withdrawal.near_amount - bounty_near_amount
+                    + storage_staking_price_per_additional_delayed_withdrawal
+                    + storage_staking_price_per_additional_auto_claim
+            );
+
+            processed_withdrawals_quantity += 1;
+        }
+
+        let predecessor_account_id = env::predecessor_account_id();
+
+        if keeper_bounty_near_amount > 0 {
+            Self::transfer_near_amount(predecessor_account_id.clone(), keeper_bounty_near_amount);
+        }
+
+        env::log_str(
+            format!(
+                "
+                Processing of matured withdrawals on @{} in {} epoch by @{}.
+                Examined withdrawals quantity is {}.
+                Processed withdrawals quantity is {}.
+                Keeper bounty is {} yoctoNear.
+                ",
+                env::current_account_id(),
+                self.current_epoch_height,
+                &predecessor_account_id,
+                examined_withdrawals_quantity,
+                processed_withdrawals_quantity,
+                keeper_bounty_near_amount
+            ).as_str()
+        );
+
+        processed_withdrawals_quantity
+    }
+
+    fn internal_claim_refund(&mut self, receiver_id: Option<AccountId>) -> Promise {
+        self.assert_gas_is_enough(gas::TRANSFER);
+        Self::assert_natural_deposit();
+
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let near_amount = match self.fund.pending_refund_fund.take(&predecessor_account_id) {
+            Some(near_amount_) => near_amount_,
+            None => {
+                StakePoolError::PendingRefundNotFound.panic();
+            }
+        };
+
+        let receiver_account_id = receiver_id.unwrap_or(predecessor_account_id.clone());
+
+        env::log_str(
+            format!(
+                "
+                Claiming refund on @{} in {} epoch.
+                @{} claimed {} yoctoNear to @{}.
+                ",
+                env::current_account_id(),
+                self.current_epoch_height,
+                &predecessor_account_id,
+                near_amount,
+                &receiver_account_id
+            ).as_str()
+        );
+
+        let storage_staking_price_per_pending_refund = Self::calculate_storage_staking_price(self.fund.pending_refund_fund.storage_usage_per_pending_refund);
+
+        Self::transfer_near_amount_to(receiver_account_id, predecessor_account_id, near_amount + storage_staking_price_per_pending_refund + env::attached_deposit())
+    }
+
+    fn internal_increase_validator_stake(&mut self, validator_account_id: AccountId, near_amount: Balance) -> Promise {
+        self.assert_gas_is_enough(gas::INCREASE_VALIDATOR_STAKE);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        if near_amount == 0 {
+            StakePoolError::InsufficientNearAmount.panic();
+        }
+        if near_amount > self.fund.classic_unstaked_balance {
+            StakePoolError::NearAmountExceededAvailableUnstakedNearBalance.panic();
+        }
+
+        let validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+        if validator.is_only_for_investment {
+            StakePoolError::ValidatorUsedOnlyForInvestment.panic();
+        }
+        if validator.is_flagged_as_inactive() {
+            StakePoolError::ValidatorFlaggedAsInactive.panic();
+        }
+        if validator.is_draining {
+            StakePoolError::ValidatorInDrainingState.panic();
+        }
+        self.assert_validator_share_cap(&validator, near_amount, self.fund.get_common_balance());
+
+        match validator.staking_contract_version {
+            StakingContractVersion::Core => {
+                classic_validator::ext(validator_account_id.clone())
+                    .with_attached_deposit(near_amount)
+                    .with_static_gas(gas::VALIDATOR_DEPOSIT_AND_STAKE)
+                    .deposit_and_stake()
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(gas::INCREASE_VALIDATOR_STAKE_CALLBACK)
+                            .increase_validator_stake_callback(validator_account_id, near_amount, env::epoch_height())
+                    )
+            }
+        }
+    }
+
+    fn internal_requested_decrease_validator_stake(
+        &mut self,
+        validator_account_id: AccountId,
+        near_amount: Balance,
+        stake_decreasing_type: StakeDecreasingType
+    ) -> Promise {
+        self.assert_gas_is_enough(gas::REQUESTED_DECREASE_VALIDATOR_STAKE);
+        self.assert_epoch_is_desynchronized();
+        self.assert_authorized_management_only_by_manager();
+        if !self.is_right_epoch(env::epoch_height()) {
+            StakePoolError::WrongEpochForRequestedDecreaseValidatorStake.panic();
+        }
+
+        if near_amount == 0 {
+            StakePoolError::InsufficientNearAmount.panic();
+        }
+
+        let validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+        match stake_decreasing_type {
+            StakeDecreasingType::Classic => {
+                // All classic Near amount of the draining validator is unstaked by the draining.
+                if validator.is_draining {
+                    StakePoolError::ValidatorInDrainingState.panic();
+                }
+                if near_amount > validator.balance.classic_near_amount {
+                    StakePoolError::NearAmountExceededAvailableStakedNearBalance.panic();
+                }
+                if near_amount > self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount {
+                    StakePoolError::NearAmountMoreThanRequestedNearAmount.panic();
+                }
+            }
+            StakeDecreasingType::Investment => {
+                if near_amount > validator.balance.investment_near_amount {
+                    StakePoolError::NearAmountExceededAvailableUnstakedNearBalance.panic();
+                }
                 if near_amount > self.fund.delayed_withdrawn_fund.needed_to_request_investment_near_amount {
-                    env::panic_str("Near amount is more than requested near amount.");
+                    StakePoolError::NearAmountMoreThanRequestedNearAmount.panic();
                 }
 
                 let investment_withdrawal = match self.fund.delayed_withdrawn_fund.investment_withdrawal_registry.get(&validator_account_id) {
                     Some(investment_withdrawal_) => investment_withdrawal_,
                     None => {
-                        env::panic_str("Investment withdrawal account is not registered yet.");
+                        StakePoolError::InvestmentWithdrawalAccountNotRegistered { account_id: validator_account_id.clone() }.panic();
                     }
                 };
                 if near_amount > investment_withdrawal.near_amount {
-                    env::panic_str("Near amount is more than requested near amount from validator.");
+                    StakePoolError::NearAmountMoreThanRequestedNearAmountFromValidator.panic();
                 }
             }
         }
@@ -1345,9 +1834,11 @@ impl StakePool {
         match validator.staking_contract_version {
             StakingContractVersion::Core => {
                 classic_validator::ext(validator_account_id.clone())
+                    .with_static_gas(gas::VALIDATOR_VIEW)
                     .get_account_unstaked_balance(current_account_id.clone())
                     .then(
                         Self::ext(current_account_id)
+                            .with_static_gas(gas::REQUESTED_DECREASE_VALIDATOR_STAKE_CALLBACK_1)
                             .requested_decrease_validator_stake_callback_1(
                                 validator_account_id,
                                 near_amount,
@@ -1360,46 +1851,49 @@ impl StakePool {
     }
 
     fn internal_take_unstaked_balance(&mut self, validator_account_id: AccountId) -> Promise {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::TAKE_UNSTAKED_BALANCE);
         self.assert_epoch_is_desynchronized();
         self.assert_authorized_management_only_by_manager();
 
         let current_epoch_height = env::epoch_height();
 
-        if !Self::is_right_epoch(current_epoch_height) {
-            env::panic_str("Epoch is not intended for a take unstaked balance.");
+        if !self.is_right_epoch(current_epoch_height) {
+            StakePoolError::WrongEpochForTakeUnstakedBalance.panic();
         }
         match self.validating.validator_registry.get(&validator_account_id) {
             Some(validator) => {
-                if validator.balance.requested_to_withdrawal_near_amount == 0 {
-                    env::panic_str("Insufficient unstaked balance on validator.");
+                if validator.balance.get_unstaked_balance() == 0 {
+                    StakePoolError::InsufficientUnstakedBalanceOnValidator.panic();
                 }
                 if validator.last_update_epoch_height >= current_epoch_height {
-                    env::panic_str("Validator is already updated.");
+                    StakePoolError::ValidatorAlreadyUpdated.panic();
                 }
 
                 match validator.staking_contract_version {
                     StakingContractVersion::Core => {
                         classic_validator::ext(validator_account_id.clone())
-                            .withdraw(validator.balance.requested_to_withdrawal_near_amount.into())
+                            .with_static_gas(gas::VALIDATOR_WITHDRAW)
+                            .withdraw(validator.balance.get_unstaked_balance().into())
                             .then(
                                 Self::ext(env::current_account_id())
+                                    .with_static_gas(gas::TAKE_UNSTAKED_BALANCE_CALLBACK)
                                     .take_unstaked_balance_callback(
                                         validator_account_id,
-                                        validator.balance.requested_to_withdrawal_near_amount
+                                        validator.balance.requested_to_withdrawal_near_amount,
+                                        validator.balance.drained_near_amount
                                     )
                             )
                     }
                 }
             }
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
             }
         }
     }
 
     fn internal_update_validator(&mut self, validator_account_id: AccountId) -> Promise {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::UPDATE_VALIDATOR);
         self.assert_epoch_is_desynchronized();
         self.assert_authorized_management_only_by_manager();
 
@@ -1412,25 +1906,158 @@ impl StakePool {
                     match validator.staking_contract_version {
                         StakingContractVersion::Core => {
                             classic_validator::ext(validator_account_id.clone())
+                                .with_static_gas(gas::VALIDATOR_VIEW)
                                 .get_account_total_balance(current_account_id.clone())
                                 .then(
                                     Self::ext(current_account_id)
+                                        .with_static_gas(gas::UPDATE_VALIDATOR_CALLBACK)
                                         .update_validator_callback(validator_account_id, current_epoch_height)
                                 )
                         }
                     }
                 } else {
-                    env::panic_str("Validator is already updated.");
+                    StakePoolError::ValidatorAlreadyUpdated.panic();
                 }
             }
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        }
+    }
+
+    fn internal_reconcile_validator(&mut self, validator_account_id: AccountId) -> Promise {
+        self.assert_gas_is_enough(gas::RECONCILE_VALIDATOR);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        let validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+
+        let current_account_id = env::current_account_id();
+
+        match validator.staking_contract_version {
+            StakingContractVersion::Core => {
+                classic_validator::ext(validator_account_id.clone())
+                    .with_static_gas(gas::VALIDATOR_VIEW)
+                    .get_account_staked_balance(current_account_id.clone())
+                    .and(
+                        classic_validator::ext(validator_account_id.clone())
+                            .with_static_gas(gas::VALIDATOR_VIEW)
+                            .get_account_unstaked_balance(current_account_id.clone())
+                    )
+                    .then(
+                        Self::ext(current_account_id)
+                            .with_static_gas(gas::RECONCILE_VALIDATOR_CALLBACK)
+                            .reconcile_validator_callback(validator_account_id, env::epoch_height())
+                    )
+            }
+        }
+    }
+
+    fn internal_apply_validator_correction(&mut self, validator_account_id: AccountId) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_owner();
+
+        let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+
+        let validator_reconciliation = match validator.last_reconciliation.take() {
+            Some(validator_reconciliation_) => validator_reconciliation_,
+            None => {
+                StakePoolError::ValidatorNotReconciled.panic();
+            }
+        };
+        if validator_reconciliation.epoch_height != self.current_epoch_height {
+            StakePoolError::ValidatorReconciliationOutdated.panic();
+        }
+        if validator_reconciliation.recorded_staked_near_amount != validator.balance.classic_near_amount + validator.balance.investment_near_amount
+            || validator_reconciliation.recorded_unstaked_near_amount != validator.balance.get_unstaked_balance() {
+            StakePoolError::ValidatorBalanceChangedAfterReconciliation.panic();
+        }
+
+        let near_amount = validator_reconciliation.get_near_amount();
+
+        let recorded_near_amount = validator_reconciliation.get_recorded_near_amount();
+
+        if near_amount == recorded_near_amount {
+            StakePoolError::ValidatorBalanceHasNoDrift.panic();
+        }
+
+        let maximum_correction_near_amount = MAXIMUM_VALIDATOR_BALANCE_CORRECTION.multiply(recorded_near_amount);
+
+        let common_balance_log = self.fund.get_common_balance();
+
+        let classic_near_amount_log = validator.balance.classic_near_amount;
+
+        // Only the drift of the total balance is corrected. The drift between staked and unstaked
+        // balances is absorbed on the next requested decreasing of validator stake.
+        if near_amount > recorded_near_amount {
+            let surplus_near_amount = near_amount - recorded_near_amount;
+            if surplus_near_amount > maximum_correction_near_amount {
+                StakePoolError::ValidatorBalanceDriftExceededMaximumCorrection.panic();
+            }
+
+            validator.balance.classic_near_amount += surplus_near_amount;
+            self.fund.classic_staked_balance += surplus_near_amount;
+        } else {
+            let shortfall_near_amount = recorded_near_amount - near_amount;
+            if shortfall_near_amount > maximum_correction_near_amount {
+                StakePoolError::ValidatorBalanceDriftExceededMaximumCorrection.panic();
+            }
+            if shortfall_near_amount > validator.balance.classic_near_amount
+                || shortfall_near_amount > self.fund.classic_staked_balance {
+                StakePoolError::ValidatorBalanceDriftExceededClassicStakedBalance.panic();
             }
+
+            validator.balance.classic_near_amount -= shortfall_near_amount;
+            self.fund.classic_staked_balance -= shortfall_near_amount;
         }
+        self.validating.validator_registry.insert(&validator_account_id, &validator);
+
+        let current_account_id_log = env::current_account_id();
+        env::log_str(
+            format!(
+                "
+                Correcting validator @{} balance by @{} in {} epoch.
+                Recorded staked Near amount on validator is {} yoctoNear.
+                Received staked Near amount from validator is {} yoctoNear.
+                Recorded unstaked Near amount on validator is {} yoctoNear.
+                Received unstaked Near amount from validator is {} yoctoNear.
+                Old @{} classic Near amount on validator is {} yoctoNear.
+                New @{} classic Near amount on validator is {} yoctoNear.
+                Old @{} balance is {} yoctoNear.
+                New @{} balance is {} yoctoNear.
+                ",
+                &validator_account_id,
+                env::predecessor_account_id(),
+                self.current_epoch_height,
+                validator_reconciliation.recorded_staked_near_amount,
+                validator_reconciliation.staked_near_amount,
+                validator_reconciliation.recorded_unstaked_near_amount,
+                validator_reconciliation.unstaked_near_amount,
+                &current_account_id_log,
+                classic_near_amount_log,
+                &current_account_id_log,
+                validator.balance.classic_near_amount,
+                &current_account_id_log,
+                common_balance_log,
+                &current_account_id_log,
+                self.fund.get_common_balance()
+            ).as_str()
+        );
     }
 
     fn internal_update(&mut self) {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::update(self.validating.validators_quantity));
         self.assert_epoch_is_desynchronized();
         self.assert_authorized_management_only_by_manager();
 
@@ -1440,62 +2067,40 @@ impl StakePool {
 
         let total_supply_log = self.fungible_token.total_supply;
 
+        let mut rewards_from_validators_near_amount: Balance = 0;
+
         if self.validating.validators_quantity > 0 {
-            if (self.validating.quantity_of_validators_updated_in_current_epoch / self.validating.validators_quantity == 0)
+            if self.validating.quantity_of_validators_updated_in_current_epoch < self.validating.validators_quantity
                 || (self.validating.quantity_of_validators_updated_in_current_epoch % self.validating.validators_quantity != 0) {
-                env::panic_str("Some validators are not updated.");
+                StakePoolError::SomeValidatorsNotUpdated.panic();
             }
 
-            if Self::is_right_epoch(current_epoch_height)
+            if self.is_right_epoch(current_epoch_height)
                 && (self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount > 0
                     || self.fund.delayed_withdrawn_fund.needed_to_request_investment_near_amount > 0) {
-                    env::panic_str("Some funds are not unstaked from validators.");
+                    StakePoolError::SomeFundsNotUnstakedFromValidators.panic();
             }
 
             self.fund.classic_staked_balance += self.reward.previous_epoch_rewards_from_validators_near_amount;
             self.validating.quantity_of_validators_updated_in_current_epoch = 0;
             self.reward.total_rewards_from_validators_near_amount += self.reward.previous_epoch_rewards_from_validators_near_amount;
 
-            let (previous_epoch_rewards_from_validators_token_amount, _) = self.convert_near_amount_to_token_amount(
-                self.reward.previous_epoch_rewards_from_validators_near_amount
-            );
-
-            let mut reward_fee_self_log: Option<Fee> = None;
-
-            if let Some(ref reward_fee) = self.fee_registry.reward_fee {
-                reward_fee_self_log = Some(reward_fee.self_fee.clone());
-
-                let mut reward_fee_self_token_amount = reward_fee.self_fee.multiply(previous_epoch_rewards_from_validators_token_amount);
-                if reward_fee_self_token_amount != 0 {
-                    self.fungible_token.total_supply += reward_fee_self_token_amount;
-
-                    if let Some(ref reward_fee_partner) = reward_fee.partner_fee {
-                        let reward_fee_partner_token_amount = reward_fee_partner.multiply(reward_fee_self_token_amount);
-                        if reward_fee_partner_token_amount != 0 {
-                            reward_fee_self_token_amount -= reward_fee_partner_token_amount;
+            let reward_fee_log = self.fee_registry.reward_fee.clone();
 
-                            let mut account_balance = match self.fungible_token.account_registry.get(&self.account_registry.partner_fee_receiver_account_id) {
-                                Some(account_balance_) => account_balance_,
-                                None => {
-                                    env::panic_str("Nonexecutable code. Object must exist.");
-                                }
-                            };
-                            account_balance.token_amount += reward_fee_partner_token_amount;
-
-                            self.fungible_token.account_registry.insert(&self.account_registry.partner_fee_receiver_account_id, &account_balance);
-                        }
-                    }
-
-                    let mut account_balance = match self.fungible_token.account_registry.get(&self.account_registry.self_fee_receiver_account_id) {
-                        Some(account_balance_) => account_balance_,
-                        None => {
-                            env::panic_str("Nonexecutable code. Object must exist.");
-                        }
-                    };
-                    account_balance.token_amount += reward_fee_self_token_amount;
+            let mut reward_fee_token_amount = core::calculate_reward_fee_token_amount(
+                self.reward.previous_epoch_rewards_from_validators_near_amount,
+                self.fee_registry.reward_fee.as_ref(),
+                self.fund.get_common_balance(),
+                self.fungible_token.total_supply
+            );
+            if reward_fee_token_amount != 0 {
+                self.fungible_token.total_supply += reward_fee_token_amount;
 
-                    self.fungible_token.account_registry.insert(&self.account_registry.self_fee_receiver_account_id, &account_balance);
+                if let Some(ref referral_fee) = self.fee_registry.referral_fee.clone() {
+                    reward_fee_token_amount -= self.referral.distribute_referral_fee(referral_fee, reward_fee_token_amount, total_supply_log);
                 }
+
+                self.distribute_fee(&self.fee_registry.reward_fee_split.clone(), reward_fee_token_amount);
             }
 
             let current_account_id_log = env::current_account_id();
@@ -1519,7 +2124,7 @@ impl StakePool {
                     current_account_id_log,
                     common_balance_log,
                     self.reward.previous_epoch_rewards_from_validators_near_amount,
-                    reward_fee_self_log,
+                    reward_fee_log,
                     self.fungible_token.total_supply - total_supply_log,
                     current_account_id_log,
                     self.fund.get_common_balance(),
@@ -1528,9 +2133,55 @@ impl StakePool {
                 ).as_str()
             );
 
+            rewards_from_validators_near_amount = self.reward.previous_epoch_rewards_from_validators_near_amount;
             self.reward.previous_epoch_rewards_from_validators_near_amount = 0;
         }
 
+        // The violations are only reported here, because the refusal to advance the epoch would freeze
+        // the whole pipeline. The 'check_invariants' view remains the hard check.
+        let invariant_violations = self.collect_invariant_violations();
+        if !invariant_violations.is_empty() {
+            env::log_str(
+                format!(
+                    "
+                    Invariants of @{} are broken in {} epoch.
+                    Violations are {}.
+                    ",
+                    env::current_account_id(),
+                    self.current_epoch_height,
+                    near_sdk::serde_json::to_string(&invariant_violations).unwrap_or_default()
+                ).as_str()
+            );
+        }
+
+        self.epoch_history.add_epoch_report(
+            &EpochReport {
+                epoch_height: current_epoch_height,
+                common_balance: self.fund.get_common_balance(),
+                total_supply: self.fungible_token.total_supply,
+                rewards_from_validators_near_amount,
+                reward_fee_token_amount: self.fungible_token.total_supply - total_supply_log,
+                deposited_near_amount: self.epoch_history.deposited_near_amount,
+                withdrawn_near_amount: self.epoch_history.withdrawn_near_amount
+            }
+        );
+
+        if let Some(pending_pool_config) = self.pending_pool_config.take() {
+            if pending_pool_config.activation_epoch_height <= current_epoch_height {
+                self.pool_config = pending_pool_config.pool_config;
+
+                env::log_str(
+                    format!(
+                        "Applying @{} pool config in {} epoch.",
+                        env::current_account_id(),
+                        current_epoch_height
+                    ).as_str()
+                );
+            } else {
+                self.pending_pool_config = Some(pending_pool_config);
+            }
+        }
+
         self.fund.is_distributed_on_validators_in_current_epoch = false;
         self.current_epoch_height = current_epoch_height;
     }
@@ -1542,26 +2193,29 @@ impl StakePool {
         is_only_for_investment: bool,
         is_preferred: bool
     ) -> PromiseOrValue<()> {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
         if is_preferred && is_only_for_investment {
-            env::panic_str("Prefferred validator can not be only for investment.");
+            StakePoolError::PreferredValidatorOnlyForInvestment.panic();
+        }
+        if self.validating.validators_quantity >= MAXIMUM_NUMBER_OF_VALIDATORS {
+            StakePoolError::MaximumNumberOfValidatorsReached.panic();
         }
 
         let attached_deposit = env::attached_deposit();
 
         let storage_staking_price_per_additional_validator = Self::calculate_storage_staking_price(self.validating.storage_usage_per_validator);
         if attached_deposit < storage_staking_price_per_additional_validator {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_validator.into(), provided_near_amount: attached_deposit.into() }.panic();
         }
 
-        if let Some(_) = self.validating.validator_registry.insert(
-            &validator_account_id, &Validator::new(staking_contract_version, is_only_for_investment)
-        ) {
-            env::panic_str("Validator account is already registered.");
+        if self.validating.validator_registry.insert(
+            &validator_account_id, &Validator::new(validator_account_id.clone(), staking_contract_version, is_only_for_investment)
+        ).is_some() {
+            StakePoolError::ValidatorAccountAlreadyRegistered { validator_account_id: validator_account_id.clone() }.panic();
         }
         self.validating.validators_quantity += 1;
 
@@ -1572,8 +2226,7 @@ impl StakePool {
         let near_amount = attached_deposit - storage_staking_price_per_additional_validator;
         if near_amount > 0 {
             return PromiseOrValue::Promise(
-                Promise::new(env::predecessor_account_id())
-                    .transfer(near_amount)
+                Self::transfer_near_amount(env::predecessor_account_id(), near_amount)
             );
         }
 
@@ -1581,21 +2234,20 @@ impl StakePool {
     }
 
     fn internal_remove_validator(&mut self, validator_account_id: AccountId) -> Promise {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::TRANSFER);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
-        let validator = match self.validating.validator_registry.remove(&validator_account_id) {
+        let mut validator = match self.validating.validator_registry.remove(&validator_account_id) {
             Some(validator_) => validator_,
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
             }
         };
-        if validator.balance.classic_near_amount > 0
-            || validator.balance.investment_near_amount > 0
-            || validator.balance.requested_to_withdrawal_near_amount > 0 {
-            env::panic_str("Validator has an available balance.");
+        if validator.balance.get_balance() > 0 {
+            StakePoolError::ValidatorHasAvailableBalance.panic();
         }
+        validator.clear_performance_registry();
 
         self.validating.validators_quantity -= 1;
 
@@ -1607,71 +2259,247 @@ impl StakePool {
 
         let refundable_near_amount = Self::calculate_storage_staking_price(self.validating.storage_usage_per_validator);
 
-        Promise::new(env::predecessor_account_id())
-            .transfer(refundable_near_amount)
+        Self::transfer_near_amount(env::predecessor_account_id(), refundable_near_amount)
     }
 
-    fn internal_change_validator_investment_context(&mut self, validator_account_id: AccountId, is_only_for_investment: bool) {
-        Self::assert_gas_is_enough();
+    fn internal_start_draining_validator(&mut self, validator_account_id: AccountId) {
+        self.assert_gas_is_enough(gas::COMMON);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
         let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
             Some(validator_) => validator_,
             None => {
-                env::panic_str("Validator account is not registered yet.");
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
             }
         };
-
-        if validator.is_only_for_investment == is_only_for_investment {
-            env::panic_str("Changing the state to the same state.");
+        if validator.is_draining {
+            StakePoolError::ChangingStateToSameState.panic();
         }
 
-        if is_only_for_investment {
-            if let Some(ref preffered_validator_account_id) = self.validating.preffered_validator {
-                if *preffered_validator_account_id == validator_account_id {
-                    env::panic_str("Prefferred validator can not be only for investment.");
-                }
-            }
+        validator.is_draining = true;
+        self.validating.validator_registry.insert(&validator_account_id, &validator);
 
-            if validator.balance.classic_near_amount > 0 {
-                env::panic_str("Validator classic staked balance is not equal to zero.");
+        if let Some(ref preffered_validator_account_id) = self.validating.preffered_validator {
+            if *preffered_validator_account_id == validator_account_id {
+                self.validating.preffered_validator = None;
             }
         }
 
-        validator.is_only_for_investment = is_only_for_investment;
-        self.validating.validator_registry.insert(&validator_account_id, &validator);
+        env::log_str(
+            format!(
+                "
+                Starting draining validator @{} in {} epoch.
+                Classic Near amount on validator is {} yoctoNear.
+                Investment Near amount on validator is {} yoctoNear.
+                Investment stake should be migrated to the classic context or withdrawn by investors.
+                ",
+                validator_account_id,
+                self.current_epoch_height,
+                validator.balance.classic_near_amount,
+                validator.balance.investment_near_amount
+            ).as_str()
+        );
     }
 
-    fn internal_change_preffered_validator(&mut self, validator_account_id: Option<AccountId>) {
-        Self::assert_gas_is_enough();
-        self.assert_epoch_is_synchronized();
+    fn internal_drain_validator(&mut self, validator_account_id: AccountId) -> Promise {
+        self.assert_gas_is_enough(gas::DRAIN_VALIDATOR);
+        self.assert_epoch_is_desynchronized();
         self.assert_authorized_management_only_by_manager();
+        if !self.is_right_epoch(env::epoch_height()) {
+            StakePoolError::WrongEpochForDrainValidator.panic();
+        }
+
+        let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+        if !validator.is_draining {
+            StakePoolError::ValidatorNotInDrainingState.panic();
+        }
+        if validator.is_drain_in_flight {
+            StakePoolError::ValidatorDrainInFlight.panic();
+        }
+        if validator.last_update_epoch_height < env::epoch_height() {
+            StakePoolError::ValidatorNotUpdated.panic();
+        }
+        if validator.balance.investment_near_amount > 0 {
+            StakePoolError::ValidatorHasInvestmentBalance.panic();
+        }
+        if validator.balance.classic_near_amount == 0 {
+            StakePoolError::InsufficientStakedBalanceOnValidator.panic();
+        }
+
+        validator.is_drain_in_flight = true;
+        self.validating.validator_registry.insert(&validator_account_id, &validator);
+
+        match validator.staking_contract_version {
+            StakingContractVersion::Core => {
+                classic_validator::ext(validator_account_id.clone())
+                    .with_static_gas(gas::VALIDATOR_UNSTAKE_ALL)
+                    .unstake_all()
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(gas::DRAIN_VALIDATOR_CALLBACK)
+                            .drain_validator_callback(validator_account_id, validator.balance.classic_near_amount)
+                    )
+            }
+        }
+    }
+
+    fn internal_migrate_investment_to_classic(&mut self, investor_account_id: AccountId, validator_account_id: AccountId) -> Promise {
+        self.assert_gas_is_enough(gas::TRANSFER);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+        if !validator.is_draining {
+            StakePoolError::ValidatorNotInDrainingState.panic();
+        }
+
+        let mut investor_investment = match self.validating.investor_investment_registry.get(&investor_account_id) {
+            Some(investor_investment_) => investor_investment_,
+            None => {
+                StakePoolError::InvestorAccountNotRegistered { investor_account_id: investor_account_id.clone() }.panic();
+            }
+        };
+
+        let near_amount = match investor_investment.distribution_registry.remove(&validator_account_id) {
+            Some(staked_balance_) => staked_balance_,
+            None => {
+                StakePoolError::NoInvestorStakeOnValidator.panic();
+            }
+        };
+        investor_investment.distributions_quantity -= 1;
+        investor_investment.staked_balance -= near_amount;
+        self.validating.investor_investment_registry.insert(&investor_account_id, &investor_investment);
+
+        validator.balance.investment_near_amount -= near_amount;
+        validator.balance.classic_near_amount += near_amount;
+        self.validating.validator_registry.insert(&validator_account_id, &validator);
+
+        self.fund.investment_staked_balance -= near_amount;
+        self.fund.classic_staked_balance += near_amount;
+
+        env::log_str(
+            format!(
+                "
+                Migrating investment of @{} on draining validator @{} to the classic context in {} epoch.
+                Migrated Near amount is {} yoctoNear.
+                New @{} investment Near amount on validator is {} yoctoNear.
+                New @{} classic Near amount on validator is {} yoctoNear.
+                ",
+                &investor_account_id,
+                &validator_account_id,
+                self.current_epoch_height,
+                near_amount,
+                &validator_account_id,
+                validator.balance.investment_near_amount,
+                &validator_account_id,
+                validator.balance.classic_near_amount
+            ).as_str()
+        );
+
+        Self::transfer_near_amount(investor_account_id, Self::calculate_storage_staking_price(self.validating.storage_usage_per_distribution))
+    }
+
+    /// Removes the draining validator without balance. Storage staking price is returned to the manager.
+    fn remove_drained_validator(&mut self, validator_account_id: AccountId) {
+        let mut validator = match self.validating.validator_registry.remove(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::object_must_exist().panic();
+            }
+        };
+        validator.clear_performance_registry();
+
+        self.validating.validators_quantity -= 1;
+
+        env::log_str(
+            format!(
+                "Removing drained validator @{} in {} epoch.",
+                validator_account_id,
+                env::epoch_height()
+            ).as_str()
+        );
+
+        Self::transfer_near_amount(self.account_registry.manager_id.clone(), Self::calculate_storage_staking_price(self.validating.storage_usage_per_validator));
+    }
+
+    fn internal_change_validator_investment_context(&mut self, validator_account_id: AccountId, is_only_for_investment: bool) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id.clone() }.panic();
+            }
+        };
+
+        if validator.is_only_for_investment == is_only_for_investment {
+            StakePoolError::ChangingStateToSameState.panic();
+        }
+
+        if is_only_for_investment {
+            if let Some(ref preffered_validator_account_id) = self.validating.preffered_validator {
+                if *preffered_validator_account_id == validator_account_id {
+                    StakePoolError::PreferredValidatorOnlyForInvestment.panic();
+                }
+            }
+
+            if validator.balance.classic_near_amount > 0 {
+                StakePoolError::ValidatorClassicStakedBalanceNotZero.panic();
+            }
+        }
+
+        validator.is_only_for_investment = is_only_for_investment;
+        self.validating.validator_registry.insert(&validator_account_id, &validator);
+    }
+
+    fn internal_change_preffered_validator(&mut self, validator_account_id: Option<AccountId>) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        match validator_account_id {
+            Some(validator_account_id_) => {
+                let validator = match self.validating.validator_registry.get(&validator_account_id_) {
+                    Some(validator_) => validator_,
+                    None => {
+                        StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id_.clone() }.panic();
+                    }
+                };
+
+                if let Some(ref preffered_validator_account_id) = self.validating.preffered_validator {
+                    if *preffered_validator_account_id == validator_account_id_ {
+                        StakePoolError::ChangingStateToSameState.panic();
+                    }
+                }
 
-        match validator_account_id {
-            Some(validator_account_id_) => {
-                let validator = match self.validating.validator_registry.get(&validator_account_id_) {
-                    Some(validator_) => validator_,
-                    None => {
-                        env::panic_str("Validator account is not registered yet.");
-                    }
-                };
-
-                if let Some(ref preffered_validator_account_id) = self.validating.preffered_validator {
-                    if *preffered_validator_account_id == validator_account_id_ {
-                        env::panic_str("Changing the state to the same state.");
-                    }
-                }
-
                 if validator.is_only_for_investment {
-                    env::panic_str("Prefferred validator can not be only for investment.");
+                    StakePoolError::PreferredValidatorOnlyForInvestment.panic();
+                }
+                if validator.is_flagged_as_inactive() {
+                    StakePoolError::PreferredValidatorFlaggedAsInactive.panic();
+                }
+                if validator.is_draining {
+                    StakePoolError::PreferredValidatorInDrainingState.panic();
                 }
 
                 self.validating.preffered_validator = Some(validator_account_id_);
             }
             None => {
-                if let None = self.validating.preffered_validator {
-                    env::panic_str("Changing the state to the same state.");
+                if self.validating.preffered_validator.is_none() {
+                    StakePoolError::ChangingStateToSameState.panic();
                 }
 
                 self.validating.preffered_validator = None;
@@ -1680,27 +2508,26 @@ impl StakePool {
     }
 
     fn internal_add_investor(&mut self, investor_account_id: AccountId) -> PromiseOrValue<()> {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
         let storage_staking_price_per_additional_investor_investment = Self::calculate_storage_staking_price(self.validating.storage_usage_per_investor_investment);
         if env::attached_deposit() < storage_staking_price_per_additional_investor_investment {
-            env::panic_str("Insufficient near deposit.");
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_investor_investment.into(), provided_near_amount: env::attached_deposit().into() }.panic();
         }
 
-        if let Some(_) = self.validating.investor_investment_registry.insert(
+        if self.validating.investor_investment_registry.insert(
             &investor_account_id, &InvestorInvestment::new(investor_account_id.clone())
-        ) {
-            env::panic_str("Investor account is already registered.");
+        ).is_some() {
+            StakePoolError::InvestorAccountAlreadyRegistered { investor_account_id: investor_account_id.clone() }.panic();
         }
 
         let near_amount = env::attached_deposit() - storage_staking_price_per_additional_investor_investment;
         if near_amount > 0 {
             return PromiseOrValue::Promise(
-                Promise::new(env::predecessor_account_id())
-                    .transfer(near_amount)
+                Self::transfer_near_amount(env::predecessor_account_id(), near_amount)
             );
         }
 
@@ -1708,225 +2535,496 @@ impl StakePool {
     }
 
     fn internal_remove_investor(&mut self, investor_account_id: AccountId) -> Promise {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::TRANSFER);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
         let investor_investment = match self.validating.investor_investment_registry.remove(&investor_account_id) {
             Some(investor_investment_) => investor_investment_,
             None => {
-                env::panic_str("Investor account is not registered yet.");
+                StakePoolError::InvestorAccountNotRegistered { investor_account_id: investor_account_id.clone() }.panic();
             }
         };
         if investor_investment.staked_balance > 0 || investor_investment.distributions_quantity > 0 {
-            env::panic_str("Validator has an available balance.");
+            StakePoolError::ValidatorHasAvailableBalance.panic();
         }
 
         let near_amount = Self::calculate_storage_staking_price(self.validating.storage_usage_per_investor_investment);
 
-        Promise::new(env::predecessor_account_id())
-            .transfer(near_amount)
+        Self::transfer_near_amount(env::predecessor_account_id(), near_amount)
     }
 
     fn internal_change_manager(&mut self, manager_id: AccountId) {
-        Self::assert_gas_is_enough();
+        self.assert_gas_is_enough(gas::COMMON);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management();
 
         self.account_registry.manager_id = manager_id;
     }
 
-    fn internal_change_reward_fee(&mut self, reward_fee_self: Option<Fee>, reward_fee_partner: Option<Fee>) {
-        Self::assert_gas_is_enough();
+    fn internal_change_reward_fee(&mut self, reward_fee: Option<Fee>) {
+        self.assert_gas_is_enough(gas::COMMON);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
-        if reward_fee_self.is_none() && reward_fee_partner.is_some() {
-            env::panic_str("Reward fees are not valid.");
+        if let Some(ref reward_fee_) = reward_fee {
+            reward_fee_.assert_valid();
         }
-        self.fee_registry.reward_fee = if let Some(reward_fee_self_) = reward_fee_self {
-            reward_fee_self_.assert_valid();
 
-            if let Some(ref reward_fee_partner) = reward_fee_partner {
-                reward_fee_partner.assert_valid();
-            }
-
-            Some (
-                SharedFee {
-                    self_fee: reward_fee_self_,
-                    partner_fee: reward_fee_partner
-                }
-            )
-        } else {
-            None
-        };
+        self.fee_registry.reward_fee = reward_fee;
     }
 
-    fn internal_change_instant_withdraw_fee(&mut self, instant_withdraw_fee_self: Option<Fee>, instant_withdraw_fee_partner: Option<Fee>) {
-        Self::assert_gas_is_enough();
+    fn internal_change_instant_withdraw_fee(&mut self, instant_withdraw_fee: Option<Fee>) {
+        self.assert_gas_is_enough(gas::COMMON);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
-        if instant_withdraw_fee_self.is_none() && instant_withdraw_fee_partner.is_some() {
-            env::panic_str("Instant withdraw fees are not valid.");
+        if let Some(ref instant_withdraw_fee_) = instant_withdraw_fee {
+            instant_withdraw_fee_.assert_valid();
         }
-        self.fee_registry.instant_withdraw_fee = if let Some(instant_withdraw_fee_self_) = instant_withdraw_fee_self {
-            instant_withdraw_fee_self_.assert_valid();
-
-            if let Some(ref instant_withdraw_fee_partner) = instant_withdraw_fee_partner {
-                instant_withdraw_fee_partner.assert_valid();
-            }
 
-            Some (
-                SharedFee {
-                    self_fee: instant_withdraw_fee_self_,
-                    partner_fee: instant_withdraw_fee_partner
-                }
-            )
-        } else {
-            None
-        };
+        self.fee_registry.instant_withdraw_fee = instant_withdraw_fee;
     }
 
-    fn internal_confirm_stake_distribution(&mut self) {
-        Self::assert_gas_is_enough();
+    fn internal_change_instant_withdraw_fee_curve(&mut self, instant_withdraw_fee_curve: Option<InstantWithdrawFeeCurve>) {
+        self.assert_gas_is_enough(gas::COMMON);
         self.assert_epoch_is_synchronized();
         self.assert_authorized_management_only_by_manager();
 
-        if self.fund.is_distributed_on_validators_in_current_epoch {
-            env::panic_str("Fund has already been distributed.");
+        if let Some(ref instant_withdraw_fee_curve_) = instant_withdraw_fee_curve {
+            instant_withdraw_fee_curve_.assert_valid();
         }
 
-        self.fund.is_distributed_on_validators_in_current_epoch = true;
+        self.fee_registry.instant_withdraw_fee_curve = instant_withdraw_fee_curve;
     }
 
-    fn internal_ft_transfer(&mut self, receiver_account_id: AccountId, token_amount: Balance) -> Promise {
-        Self::assert_gas_is_enough();
+    fn internal_add_fee_beneficiary(&mut self, fee_type: FeeType, beneficiary_account_id: AccountId, numerator: u64) -> PromiseOrValue<()> {
+        self.assert_gas_is_enough(gas::TRANSFER);
         Self::assert_natural_deposit();
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
 
-        if token_amount == 0 {
-            env::panic_str("Insufficient token amount.");
-        }
+        let predecessor_account_id = env::predecessor_account_id();
 
-        let mut refundable_near_amount = env::attached_deposit();
+        let mut storage_staking_price_per_additional_accounts = Self::calculate_storage_staking_price(self.fee_registry.storage_usage_per_fee_beneficiary);
 
-        let predecessor_account_id = env::predecessor_account_id();
-        let mut predecessor_account_balance = match self.fungible_token.account_registry.get(&predecessor_account_id) {
-            Some(account_balance) => account_balance,
-            None => {
-                env::panic_str("Token account is not registered yet.");
-            }
+        let fee_split = match fee_type {
+            FeeType::Reward => &mut self.fee_registry.reward_fee_split,
+            FeeType::InstantWithdraw => &mut self.fee_registry.instant_withdraw_fee_split
         };
+        if fee_split.contains(&beneficiary_account_id) {
+            StakePoolError::FeeBeneficiaryAlreadyRegistered.panic();
+        }
+        if fee_split.beneficiaries.len() as u64 >= MAXIMUM_NUMBER_OF_FEE_BENEFICIARIES {
+            StakePoolError::MaximumNumberOfFeeBeneficiariesReached.panic();
+        }
+        if numerator == 0
+            || numerator >= fee_split.beneficiaries[0].numerator {
+            StakePoolError::FeeBeneficiaryShareNotValid.panic();
+        }
 
-        let mut receiver_account_balance = match self.fungible_token.account_registry.get(&receiver_account_id) {
-            Some(account_balance) => account_balance,
-            None => {
-                env::panic_str("Token account is not registered yet.");
+        fee_split.beneficiaries[0].numerator -= numerator;
+        fee_split.beneficiaries.push(
+            FeeBeneficiary {
+                account_id: beneficiary_account_id.clone(),
+                numerator,
+                payer_account_id: Some(predecessor_account_id.clone())
             }
-        };
+        );
+        fee_split.assert_valid();
 
-        if predecessor_account_balance.token_amount < token_amount {
-            env::panic_str("Token amount exceeded the available token balance.");
+        // The beneficiary token account is needed to receive the fee.
+        if !self.fungible_token.contains_account_balance(&beneficiary_account_id) {
+            storage_staking_price_per_additional_accounts += Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
+
+            self.insert_account_balance(
+                &beneficiary_account_id,
+                &AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0, referrer_id: None }
+            );
+            self.fungible_token.accounts_quantity += 1;
         }
 
-        predecessor_account_balance.token_amount -= token_amount;
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit < storage_staking_price_per_additional_accounts {
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_accounts.into(), provided_near_amount: attached_deposit.into() }.panic();
+        }
 
-        if let Some(investor_investment) = self.validating.investor_investment_registry.get(&predecessor_account_id) {
-            if (self.convert_token_amount_to_near_amount(predecessor_account_balance.token_amount) + predecessor_account_balance.investment_near_amount)
-                < investor_investment.staked_balance {
-                env::panic_str("Token amount exceeded the available to transfer token amount.");
+        let near_amount = attached_deposit - storage_staking_price_per_additional_accounts;
+        if near_amount > 0 {
+            return PromiseOrValue::Promise(
+                Self::transfer_near_amount(predecessor_account_id, near_amount)
+            );
+        }
+
+        PromiseOrValue::Value(())
+    }
+
+    fn internal_remove_fee_beneficiary(&mut self, fee_type: FeeType, beneficiary_account_id: AccountId) -> PromiseOrValue<()> {
+        self.assert_gas_is_enough(gas::TRANSFER);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        let fee_split = match fee_type {
+            FeeType::Reward => &mut self.fee_registry.reward_fee_split,
+            FeeType::InstantWithdraw => &mut self.fee_registry.instant_withdraw_fee_split
+        };
+        let index = match fee_split.beneficiaries.iter().position(|fee_beneficiary| fee_beneficiary.account_id == beneficiary_account_id) {
+            Some(index_) => index_,
+            None => {
+                StakePoolError::FeeBeneficiaryNotRegistered.panic();
             }
+        };
+        if index == 0 {
+            StakePoolError::FirstFeeBeneficiaryCanNotBeRemoved.panic();
         }
 
-        receiver_account_balance.token_amount += token_amount;
+        let fee_beneficiary = fee_split.beneficiaries.remove(index);
+        fee_split.beneficiaries[0].numerator += fee_beneficiary.numerator;
 
-        if predecessor_account_balance.token_amount > 0
-            || predecessor_account_id == self.account_registry.self_fee_receiver_account_id
-            || predecessor_account_id == self.account_registry.partner_fee_receiver_account_id {
-            self.fungible_token.account_registry.insert(&predecessor_account_id, &predecessor_account_balance);
-        } else {
-            self.fungible_token.account_registry.remove(&predecessor_account_id);
-            self.fungible_token.accounts_quantity -= 1;
+        // The storage of the beneficiaries of the initialization is not paid separately.
+        match fee_beneficiary.payer_account_id {
+            Some(payer_account_id) => {
+                let near_amount = Self::calculate_storage_staking_price(self.fee_registry.storage_usage_per_fee_beneficiary);
 
-            receiver_account_balance.classic_near_amount += predecessor_account_balance.classic_near_amount;
+                PromiseOrValue::Promise(
+                    Self::transfer_near_amount(payer_account_id, near_amount)
+                )
+            }
+            None => PromiseOrValue::Value(())
+        }
+    }
 
-            refundable_near_amount += Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
+    fn internal_change_referral_fee(&mut self, referral_fee: Option<Fee>) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        if let Some(ref referral_fee_) = referral_fee {
+            referral_fee_.assert_valid();
         }
-        self.fungible_token.account_registry.insert(&receiver_account_id, &receiver_account_balance);
 
-        Promise::new(predecessor_account_id)
-            .transfer(refundable_near_amount)
+        self.fee_registry.referral_fee = referral_fee;
     }
 
-    pub fn internal_get_delayed_withdrawal_details(&self, account_id: AccountId) -> Option<DelayedWithdrawalDetails> {
+    fn internal_change_keeper_bounty(&mut self, keeper_bounty: Option<Fee>) {
+        self.assert_gas_is_enough(gas::COMMON);
         self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
 
-        if let Some(delayed_withdrawal) = self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.get(&account_id) {
-            return Some(
-                    DelayedWithdrawalDetails {
-                        epoch_quantity_to_take_delayed_withdrawal: delayed_withdrawal.get_epoch_quantity_to_take_delayed_withdrawal(self.current_epoch_height),
-                        near_amount: delayed_withdrawal.near_amount.into()
-                }
-            );
+        if let Some(ref keeper_bounty_) = keeper_bounty {
+            keeper_bounty_.assert_valid();
         }
 
-        None
+        self.fee_registry.keeper_bounty = keeper_bounty;
     }
 
-    fn internal_get_total_token_supply(&self) -> Balance {
+    fn internal_add_referrer(&mut self, referrer_account_id: AccountId) -> PromiseOrValue<()> {
+        self.assert_gas_is_enough(gas::TRANSFER);
+        Self::assert_natural_deposit();
         self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
 
-        self.fungible_token.total_supply
-    }
+        let mut storage_staking_price_per_additional_accounts = Self::calculate_storage_staking_price(self.referral.storage_usage_per_referrer);
 
-    fn internal_get_minimum_deposit_amount(&self) -> Balance {
-        MINIMUN_DEPOSIT_AMOUNT
-    }
+        // The referrer token account is needed to receive the referral fee.
+        if !self.fungible_token.contains_account_balance(&referrer_account_id) {
+            storage_staking_price_per_additional_accounts += Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
 
-    pub fn internal_get_storage_staking_price(&self) -> StorageStakingPrice {
-        StorageStakingPrice {
-            per_delayed_withdrawal_fund_delayed_withdrawal: Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal).into(),
-            per_delayed_withdrawal_fund_investment_withdrawal: Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_investment_withdrawal).into(),
-            per_fungible_token_account: Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account).into(),
-            per_validating_node_validator: Self::calculate_storage_staking_price(self.validating.storage_usage_per_validator).into(),
-            per_validating_node_investor: Self::calculate_storage_staking_price(self.validating.storage_usage_per_investor_investment).into(),
-            per_validating_node_distribution: Self::calculate_storage_staking_price(self.validating.storage_usage_per_distribution).into()
+            self.insert_account_balance(
+                &referrer_account_id,
+                &AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0, referrer_id: None }
+            );
+            self.fungible_token.accounts_quantity += 1;
+        }
+
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit < storage_staking_price_per_additional_accounts {
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price_per_additional_accounts.into(), provided_near_amount: attached_deposit.into() }.panic();
+        }
+
+        if !self.referral.add_referrer(&referrer_account_id) {
+            StakePoolError::ReferrerAccountAlreadyRegistered { referrer_account_id: referrer_account_id.clone() }.panic();
         }
+
+        let near_amount = attached_deposit - storage_staking_price_per_additional_accounts;
+        if near_amount > 0 {
+            return PromiseOrValue::Promise(
+                Self::transfer_near_amount(env::predecessor_account_id(), near_amount)
+            );
+        }
+
+        PromiseOrValue::Value(())
     }
 
-    pub fn internal_get_storage_staking_requested_coverage(&self, account_id: AccountId) -> StorageStakingRequestedCoverage {
+    fn internal_remove_referrer(&mut self, referrer_account_id: AccountId) -> Promise {
+        self.assert_gas_is_enough(gas::TRANSFER);
         self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
 
-        let storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal = Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal);
+        let referrer = match self.referral.remove_referrer(&referrer_account_id) {
+            Some(referrer_) => referrer_,
+            None => {
+                StakePoolError::ReferrerAccountNotRegistered { referrer_account_id: referrer_account_id.clone() }.panic();
+            }
+        };
 
-        let storage_staking_price_per_delayed_withdrawal_fund_investment_withdrawal = Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_investment_withdrawal);
+        // The fee accrued before the removal is not lost.
+        self.referral.take_unclaimed_token_amount(referrer.unclaimed_token_amount);
+        self.receive_referral_fee(&referrer_account_id, referrer.unclaimed_token_amount);
 
-        let storage_staking_price_per_fungible_token_account = Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
+        let near_amount = Self::calculate_storage_staking_price(self.referral.storage_usage_per_referrer);
 
-        let storage_staking_price_per_validating_node_distribution = Self::calculate_storage_staking_price(self.validating.storage_usage_per_distribution);
+        Self::transfer_near_amount(env::predecessor_account_id(), near_amount)
+    }
 
-        let per_method_deposit = if !self.fungible_token.account_registry.contains_key(&account_id) {
-            storage_staking_price_per_fungible_token_account
-        } else {
-            0
-        };
+    fn internal_claim_referral_fee(&mut self) -> Balance {
+        self.assert_gas_is_enough(gas::COMMON);
+        Self::assert_natural_deposit();
 
-        let per_method_delayed_withdraw = if !self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.contains_key(&account_id) {
-            storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal
-        } else {
-            0
+        let predecessor_account_id = env::predecessor_account_id();
+
+        let token_amount = match self.referral.claim_referral_fee(&predecessor_account_id) {
+            Some(token_amount_) => token_amount_,
+            None => {
+                StakePoolError::ReferrerAccountNotRegistered { referrer_account_id: predecessor_account_id.clone() }.panic();
+            }
         };
+        self.receive_referral_fee(&predecessor_account_id, token_amount);
 
-        let (per_method_deposit_on_validator, per_method_delayed_withdraw_from_validator): (Option<(U128, Vec<(AccountId, U128)>)>, Option<(U128, Vec<(AccountId, U128)>)>) =
-            match self.validating.investor_investment_registry.get(&account_id) {
-            Some(investor_investment) => {
-                let requested_storage_staking_price_per_fungible_token_account = if !self.fungible_token.account_registry.contains_key(&account_id) {
-                    storage_staking_price_per_fungible_token_account
+        token_amount
+    }
+
+    fn internal_change_deposit_caps(&mut self, pool_near_amount: Option<Balance>, validator_share: Option<Fee>, account_near_amount: Option<Balance>) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        if let Some(ref validator_share_) = validator_share {
+            validator_share_.assert_valid();
+        }
+
+        self.deposit_caps = DepositCaps {
+            pool_near_amount,
+            validator_share,
+            account_near_amount
+        };
+    }
+
+    fn internal_update_config(&mut self, pool_config: PoolConfigDto) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_owner();
+
+        let pool_config_ = Self::convert_pool_config(pool_config);
+        self.assert_pool_config_is_valid(&pool_config_);
+
+        let activation_epoch_height = self.current_epoch_height + EPOCH_QUANTITY_FOR_POOL_CONFIG_UPDATE;
+
+        env::log_str(
+            format!(
+                "
+                Updating @{} pool config in {} epoch.
+                New pool config will be applied since {} epoch.
+                Epoch quantity for delayed withdrawal is {}.
+                Epoch quantity for validator unstake is {}.
+                Minimum number of TGas is {}.
+                Minimum deposit amount is {} yoctoNear.
+                ",
+                env::current_account_id(),
+                self.current_epoch_height,
+                activation_epoch_height,
+                pool_config_.epoch_quantity_for_delayed_withdrawal,
+                pool_config_.epoch_quantity_for_validator_unstake,
+                pool_config_.minimum_number_of_tgas,
+                pool_config_.minimum_deposit_amount
+            ).as_str()
+        );
+
+        self.pending_pool_config = Some(
+            PendingPoolConfig {
+                pool_config: pool_config_,
+                activation_epoch_height
+            }
+        );
+    }
+
+    fn internal_cancel_config_update(&mut self) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_owner();
+
+        if self.pending_pool_config.take().is_none() {
+            StakePoolError::PendingPoolConfigNotFound.panic();
+        }
+    }
+
+    fn internal_confirm_stake_distribution(&mut self) {
+        self.assert_gas_is_enough(gas::COMMON);
+        self.assert_epoch_is_synchronized();
+        self.assert_authorized_management_only_by_manager();
+
+        if self.fund.is_distributed_on_validators_in_current_epoch {
+            StakePoolError::FundAlreadyDistributed.panic();
+        }
+
+        self.fund.is_distributed_on_validators_in_current_epoch = true;
+    }
+
+    fn internal_ft_transfer(&mut self, receiver_account_id: AccountId, token_amount: Balance) -> Promise {
+        self.assert_gas_is_enough(gas::TRANSFER);
+        Self::assert_natural_deposit();
+
+        if token_amount == 0 {
+            StakePoolError::InsufficientTokenAmount.panic();
+        }
+
+        let mut refundable_near_amount = env::attached_deposit();
+
+        let predecessor_account_id = env::predecessor_account_id();
+        let mut predecessor_account_balance = match self.fungible_token.get_account_balance(&predecessor_account_id) {
+            Some(account_balance) => account_balance,
+            None => {
+                StakePoolError::TokenAccountNotRegistered { account_id: predecessor_account_id.clone() }.panic();
+            }
+        };
+
+        let mut receiver_account_balance = match self.fungible_token.get_account_balance(&receiver_account_id) {
+            Some(account_balance) => account_balance,
+            None => {
+                StakePoolError::TokenAccountNotRegistered { account_id: receiver_account_id.clone() }.panic();
+            }
+        };
+
+        if predecessor_account_balance.token_amount < token_amount {
+            StakePoolError::TokenAmountExceededAvailableTokenBalance { requested_token_amount: token_amount.into(), available_token_amount: predecessor_account_balance.token_amount.into() }.panic();
+        }
+
+        predecessor_account_balance.token_amount -= token_amount;
+
+        if let Some(investor_investment) = self.validating.investor_investment_registry.get(&predecessor_account_id) {
+            if (self.convert_token_amount_to_near_amount(predecessor_account_balance.token_amount) + predecessor_account_balance.investment_near_amount)
+                < investor_investment.staked_balance {
+                StakePoolError::TokenAmountExceededAvailableToTransferTokenAmount.panic();
+            }
+        }
+
+        receiver_account_balance.token_amount += token_amount;
+
+        if predecessor_account_balance.token_amount > 0
+            || self.is_permanent_token_account(&predecessor_account_id) {
+            self.insert_account_balance(&predecessor_account_id, &predecessor_account_balance);
+        } else {
+            self.remove_account_balance(&predecessor_account_id);
+            self.fungible_token.accounts_quantity -= 1;
+
+            receiver_account_balance.classic_near_amount += predecessor_account_balance.classic_near_amount;
+
+            refundable_near_amount += Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
+        }
+        self.insert_account_balance(&receiver_account_id, &receiver_account_balance);
+
+        env::log_str(
+            format!(
+                "
+                Transferring from @{} to @{} in {} epoch.
+                @{} sent {} yoctoStNear.
+                New @{} balance is {} yoctoStNear.
+                New @{} balance is {} yoctoStNear.
+                ",
+                &predecessor_account_id,
+                &receiver_account_id,
+                self.current_epoch_height,
+                &predecessor_account_id,
+                token_amount,
+                &predecessor_account_id,
+                predecessor_account_balance.token_amount,
+                &receiver_account_id,
+                receiver_account_balance.token_amount
+            ).as_str()
+        );
+
+        Self::transfer_near_amount(predecessor_account_id, refundable_near_amount)
+    }
+
+    pub fn internal_get_delayed_withdrawal_details(&self, account_id: AccountId) -> Option<DelayedWithdrawalDetails> {
+        self.assert_epoch_is_synchronized();
+
+        if let Some(delayed_withdrawal) = self.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&account_id) {
+            return Some(
+                    DelayedWithdrawalDetails {
+                        epoch_quantity_to_take_delayed_withdrawal: delayed_withdrawal.get_epoch_quantity_to_take_delayed_withdrawal(self.current_epoch_height),
+                        near_amount: delayed_withdrawal.near_amount.into(),
+                        is_auto_claim: delayed_withdrawal.is_auto_claim
+                }
+            );
+        }
+
+        None
+    }
+
+    fn internal_get_auto_claim_queue_length(&self) -> u64 {
+        self.fund.delayed_withdrawn_fund.get_auto_claim_queue_length()
+    }
+
+    fn internal_get_pending_refund(&self, account_id: AccountId) -> Balance {
+        self.fund.pending_refund_fund.pending_refunds.get(&account_id).unwrap_or(0)
+    }
+
+    fn internal_get_total_token_supply(&self) -> Balance {
+        self.assert_epoch_is_synchronized();
+
+        self.fungible_token.total_supply
+    }
+
+    fn internal_get_minimum_deposit_amount(&self) -> Balance {
+        self.pool_config.minimum_deposit_amount
+    }
+
+    pub fn internal_get_storage_staking_price(&self) -> StorageStakingPrice {
+        StorageStakingPrice {
+            per_delayed_withdrawal_fund_delayed_withdrawal: Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal).into(),
+            per_delayed_withdrawal_fund_investment_withdrawal: Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_investment_withdrawal).into(),
+            per_delayed_withdrawal_fund_auto_claim: Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_auto_claim).into(),
+            per_fungible_token_account: Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account).into(),
+            per_validating_node_validator: Self::calculate_storage_staking_price(self.validating.storage_usage_per_validator).into(),
+            per_validating_node_investor: Self::calculate_storage_staking_price(self.validating.storage_usage_per_investor_investment).into(),
+            per_validating_node_distribution: Self::calculate_storage_staking_price(self.validating.storage_usage_per_distribution).into()
+        }
+    }
+
+    pub fn internal_get_storage_staking_requested_coverage(&self, account_id: AccountId) -> StorageStakingRequestedCoverage {
+        self.assert_epoch_is_synchronized();
+
+        let storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal = Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal);
+
+        let storage_staking_price_per_delayed_withdrawal_fund_investment_withdrawal = Self::calculate_storage_staking_price(self.fund.delayed_withdrawn_fund.storage_usage_per_investment_withdrawal);
+
+        let storage_staking_price_per_fungible_token_account = Self::calculate_storage_staking_price(self.fungible_token.storage_usage_per_account);
+
+        let storage_staking_price_per_validating_node_distribution = Self::calculate_storage_staking_price(self.validating.storage_usage_per_distribution);
+
+        let per_method_deposit = if !self.fungible_token.contains_account_balance(&account_id) {
+            storage_staking_price_per_fungible_token_account
+        } else {
+            0
+        };
+
+        let per_method_delayed_withdraw = if !self.fund.delayed_withdrawn_fund.contains_delayed_withdrawal(&account_id) {
+            storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal
+        } else {
+            0
+        };
+
+        let (per_method_deposit_on_validator, per_method_delayed_withdraw_from_validator) =
+            match self.validating.investor_investment_registry.get(&account_id) {
+            Some(investor_investment) => {
+                let requested_storage_staking_price_per_fungible_token_account = if !self.fungible_token.contains_account_balance(&account_id) {
+                    storage_staking_price_per_fungible_token_account
                 } else {
                     0
                 };
 
-                let requested_storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal = if !self.fund.delayed_withdrawn_fund.delayed_withdrawal_registry.contains_key(&account_id) {
+                let requested_storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal = if !self.fund.delayed_withdrawn_fund.contains_delayed_withdrawal(&account_id) {
                     storage_staking_price_per_delayed_withdrawal_fund_delayed_withdrawal
                 } else {
                     0
@@ -1969,7 +3067,7 @@ impl StakePool {
     }
 
     fn internal_get_account_balance(&self, account_id: AccountId) -> AccountBalanceDto {
-        match self.fungible_token.account_registry.get(&account_id) {
+        match self.fungible_token.get_account_balance(&account_id) {
             Some(account_balance) => {
                 let common_near_balance = self.convert_token_amount_to_near_amount(account_balance.token_amount)
                 + account_balance.classic_near_amount
@@ -1978,7 +3076,7 @@ impl StakePool {
                 match self.validating.investor_investment_registry.get(&account_id) {
                     Some(investor_investment) => {
                         if common_near_balance < investor_investment.staked_balance {
-                            env::panic_str("Nonexecutable code. Near balance should be greater then or equal to investment near balance.");
+                            StakePoolError::NonexecutableCode { reason: "Near balance should be greater then or equal to investment near balance.".to_string() }.panic();
                         }
 
                         let (mut investment_near_balance_token_coverage, remainder_near_amount) = self.convert_near_amount_to_token_amount(investor_investment.staked_balance);
@@ -2051,7 +3149,9 @@ impl StakePool {
             classic_staked_balance: self.fund.classic_staked_balance.into(),
             investment_staked_balance: self.fund.investment_staked_balance.into(),
             common_staked_balance: self.fund.get_staked_balance().into(),
-            common_balance: self.fund.get_common_balance().into()
+            classic_remainder_balance: self.fund.classic_remainder_balance.into(),
+            common_balance: self.fund.get_common_balance().into(),
+            pending_refund_balance: self.fund.pending_refund_fund.balance.into()
         }
     }
 
@@ -2065,19 +3165,9 @@ impl StakePool {
     fn internal_get_fee_registry_light(&self) -> FeeRegistryLight {
         self.assert_epoch_is_synchronized();
 
-        let reward_fee = match self.fee_registry.reward_fee {
-            Some(ref reward_fee_) => Some(reward_fee_.self_fee.clone()),
-            None => None
-        };
-
-        let instant_withdraw_fee = match self.fee_registry.instant_withdraw_fee {
-            Some(ref instant_withdraw_fee_) => Some(instant_withdraw_fee_.self_fee.clone()),
-            None => None
-        };
-
         FeeRegistryLight {
-            reward_fee,
-            instant_withdraw_fee
+            reward_fee: self.fee_registry.reward_fee.clone(),
+            instant_withdraw_fee: self.fee_registry.instant_withdraw_fee.clone()
         }
     }
 
@@ -2130,7 +3220,12 @@ impl StakePool {
                     investment_staked_balance: validator.balance.investment_near_amount.into(),
                     is_only_for_investment: validator.is_only_for_investment,
                     last_update_epoch_height: validator.last_update_epoch_height,
-                    last_classic_stake_increasing_epoch_height: validator.last_classic_stake_increasing_epoch_height
+                    last_classic_stake_increasing_epoch_height: validator.last_classic_stake_increasing_epoch_height,
+                    is_active: validator.is_active,
+                    consecutive_inactive_epoch_quantity: validator.consecutive_inactive_epoch_quantity,
+                    is_flagged_as_inactive: validator.is_flagged_as_inactive(),
+                    is_draining: validator.is_draining,
+                    drained_balance: validator.balance.drained_near_amount.into()
                 }
             );
         }
@@ -2143,7 +3238,7 @@ impl StakePool {
             let validator = match self.validating.validator_registry.get(preffered_validator_account_id) {
                 Some(validator_) => validator_,
                 None => {
-                    env::panic_str("Nonexecutable code. Object must exist.");
+                    StakePoolError::object_must_exist().panic();
                 }
             };
 
@@ -2155,164 +3250,865 @@ impl StakePool {
                     investment_staked_balance: validator.balance.investment_near_amount.into(),
                     is_only_for_investment: validator.is_only_for_investment,
                     last_update_epoch_height: validator.last_update_epoch_height,
-                    last_classic_stake_increasing_epoch_height: validator.last_classic_stake_increasing_epoch_height
+                    last_classic_stake_increasing_epoch_height: validator.last_classic_stake_increasing_epoch_height,
+                    is_active: validator.is_active,
+                    consecutive_inactive_epoch_quantity: validator.consecutive_inactive_epoch_quantity,
+                    is_flagged_as_inactive: validator.is_flagged_as_inactive(),
+                    is_draining: validator.is_draining,
+                    drained_balance: validator.balance.drained_near_amount.into()
                 }
             )
         }
 
-        None
+        None
+    }
+
+    fn internal_get_aggregated(&self) -> Aggregated {
+        self.assert_epoch_is_synchronized();
+
+        Aggregated {
+            unstaked_balance: self.fund.classic_unstaked_balance.into(),
+            staked_balance: self.fund.get_staked_balance().into(),
+            token_total_supply: self.fungible_token.total_supply.into(),
+            token_accounts_quantity: self.fungible_token.accounts_quantity,
+            total_rewards_from_validators_near_amount: self.reward.total_rewards_from_validators_near_amount.into(),
+            reward_fee: self.get_fee_registry_light().reward_fee
+        }
+    }
+
+    fn internal_get_requested_to_withdrawal_fund(&self) -> RequestedToWithdrawalFund {
+        let mut investment_withdrawal_registry: Vec<(AccountId, U128)> = vec![];
+
+        for validator_account_id in self.validating.validator_registry.keys() {
+            if let Some(investment_withdrawal) = self.fund.delayed_withdrawn_fund.investment_withdrawal_registry.get(&validator_account_id) {
+                investment_withdrawal_registry.push((validator_account_id, investment_withdrawal.near_amount.into()))
+            }
+        }
+
+        RequestedToWithdrawalFund {
+            classic_near_amount: self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount.into(),
+            investment_near_amount: self.fund.delayed_withdrawn_fund.needed_to_request_investment_near_amount.into(),
+            investment_withdrawal_registry
+        }
+    }
+
+    pub fn internal_get_full(&self) -> Full {
+        self.assert_epoch_is_synchronized();
+
+        Full {
+            storage_staking_price: self.internal_get_storage_staking_price(),
+            fund: self.internal_get_fund(),
+            total_token_supply: self.internal_get_total_token_supply().into(),
+            requested_to_withdrawal_fund: self.internal_get_requested_to_withdrawal_fund(),
+            fee_registry_light: self.internal_get_fee_registry_light(),
+            minimum_deposit_amount: self.get_minimum_deposit_amount()
+        }
+    }
+
+    pub fn internal_get_full_for_account(&self, account_id: AccountId) -> FullForAccount {
+        self.assert_epoch_is_synchronized();
+
+        FullForAccount {
+            full: self.internal_get_full(),
+            account_balance: self.internal_get_account_balance(account_id.clone()),
+            delayed_withdrawal_details: self.internal_get_delayed_withdrawal_details(account_id.clone()),
+            investor_investment: self.get_investor_investment(account_id.clone()),
+            storage_staking_requested_coverage: self.get_storage_staking_requested_coverage(account_id)
+        }
+    }
+
+    fn internal_get_near_per_token(&self) -> Balance {
+        self.assert_epoch_is_synchronized();
+
+        let decimals = match self.fungible_token.metadata.get() {
+            Some(fungible_token_metadata) => fungible_token_metadata.decimals,
+            None => {
+                StakePoolError::object_must_exist().panic();
+            }
+        };
+
+        self.convert_token_amount_to_near_amount(10u128.pow(decimals as u32))
+    }
+
+    fn internal_preview_deposit(&self, near_amount: Balance) -> Result<DepositPreview, StakePoolError> {
+        self.check_epoch_is_synchronized()?;
+
+        let (token_amount, remainder_near_amount) = self.convert_near_amount_to_token_amount(near_amount);
+
+        Ok(
+            DepositPreview {
+                near_amount: near_amount.into(),
+                token_amount: token_amount.into(),
+                remainder_near_amount: remainder_near_amount.into()
+            }
+        )
+    }
+
+    fn internal_preview_instant_withdraw(&self, token_amount: Balance) -> Result<InstantWithdrawPreview, StakePoolError> {
+        self.check_epoch_is_synchronized()?;
+
+        let instant_withdraw_fee = self.get_instant_withdraw_fee(token_amount);
+
+        let instant_withdrawal = core::calculate_instant_withdrawal(
+            token_amount,
+            instant_withdraw_fee.as_ref(),
+            self.fund.get_common_balance(),
+            self.fungible_token.total_supply
+        );
+
+        Ok(
+            InstantWithdrawPreview {
+                token_amount: token_amount.into(),
+                fee: instant_withdraw_fee,
+                fee_token_amount: instant_withdrawal.fee_token_amount.into(),
+                near_amount: instant_withdrawal.near_amount.into(),
+                available_near_amount: self.fund.classic_unstaked_balance.into()
+            }
+        )
+    }
+
+    fn internal_preview_delayed_withdraw(&self, token_amount: Balance) -> Result<DelayedWithdrawPreview, StakePoolError> {
+        self.check_epoch_is_synchronized()?;
+
+        Ok(
+            DelayedWithdrawPreview {
+                token_amount: token_amount.into(),
+                near_amount: self.convert_token_amount_to_near_amount(token_amount).into(),
+                epoch_quantity_to_take_delayed_withdrawal: self.pool_config.epoch_quantity_for_delayed_withdrawal,
+                available_near_amount: self.fund.classic_staked_balance.into()
+            }
+        )
+    }
+
+    fn internal_get_epoch_reports_quantity(&self) -> u64 {
+        self.epoch_history.epoch_reports_quantity
+    }
+
+    fn internal_get_epoch_reports(&self, from_index: u64, limit: u64) -> Vec<EpochReportDto> {
+        let mut epoch_report_dto_registry: Vec<EpochReportDto> = vec![];
+
+        let from_index_ = from_index.max(self.epoch_history.get_first_available_index());
+
+        let to_index = from_index_.saturating_add(limit).min(self.epoch_history.epoch_reports_quantity);
+
+        for index in from_index_..to_index {
+            let epoch_report = match self.epoch_history.get_epoch_report(index) {
+                Some(epoch_report_) => epoch_report_,
+                None => {
+                    StakePoolError::object_must_exist().panic();
+                }
+            };
+
+            epoch_report_dto_registry.push(
+                EpochReportDto {
+                    index,
+                    epoch_height: epoch_report.epoch_height,
+                    common_balance: epoch_report.common_balance.into(),
+                    total_supply: epoch_report.total_supply.into(),
+                    rewards_from_validators_near_amount: epoch_report.rewards_from_validators_near_amount.into(),
+                    reward_fee_token_amount: epoch_report.reward_fee_token_amount.into(),
+                    deposited_near_amount: epoch_report.deposited_near_amount.into(),
+                    withdrawn_near_amount: epoch_report.withdrawn_near_amount.into()
+                }
+            );
+        }
+
+        epoch_report_dto_registry
+    }
+
+    /// Calculates APY from the token price growth over the last 'epoch_quantity' stored reports.
+    /// Returns nothing, if there are not enough reports or the APY does not fit into the numerator.
+    fn internal_get_apy(&self, epoch_quantity: u64) -> Option<Apy> {
+        if epoch_quantity == 0 || self.epoch_history.epoch_reports_quantity < 2 {
+            return None;
+        }
+
+        let last_index = self.epoch_history.epoch_reports_quantity - 1;
+
+        let first_index = last_index.saturating_sub(epoch_quantity).max(self.epoch_history.get_first_available_index());
+
+        let (first_epoch_report, last_epoch_report) = match (
+            self.epoch_history.get_epoch_report(first_index),
+            self.epoch_history.get_epoch_report(last_index)
+        ) {
+            (Some(first_epoch_report_), Some(last_epoch_report_)) => (first_epoch_report_, last_epoch_report_),
+            _ => {
+                StakePoolError::object_must_exist().panic();
+            }
+        };
+        if last_epoch_report.epoch_height <= first_epoch_report.epoch_height {
+            return None;
+        }
+
+        // The price of token is equal to 1 while the pool is empty.
+        let (first_common_balance, first_total_supply) = if first_epoch_report.common_balance == 0 || first_epoch_report.total_supply == 0 {
+            (1, 1)
+        } else {
+            (first_epoch_report.common_balance, first_epoch_report.total_supply)
+        };
+        let (last_common_balance, last_total_supply) = if last_epoch_report.common_balance == 0 || last_epoch_report.total_supply == 0 {
+            (1, 1)
+        } else {
+            (last_epoch_report.common_balance, last_epoch_report.total_supply)
+        };
+
+        let last_price = U256::from(last_common_balance) * U256::from(first_total_supply);
+
+        let first_price = U256::from(first_common_balance) * U256::from(last_total_supply);
+
+        let numerator = if last_price > first_price {
+            (last_price - first_price)
+                * U256::from(EPOCH_QUANTITY_PER_YEAR)
+                * U256::from(APY_DENOMINATOR)
+                / (first_price * U256::from(last_epoch_report.epoch_height - first_epoch_report.epoch_height))
+        } else {
+            U256::zero()
+        };
+        // The growth from the price of the empty pool can be too large to be expressed.
+        if numerator > U256::from(u64::MAX) {
+            return None;
+        }
+
+        Some(
+            Apy {
+                from_epoch_height: first_epoch_report.epoch_height,
+                to_epoch_height: last_epoch_report.epoch_height,
+                numerator: numerator.low_u64(),
+                denominator: APY_DENOMINATOR
+            }
+        )
+    }
+
+    fn internal_get_pool_config(&self) -> PoolConfigDto {
+        Self::convert_pool_config_to_dto(&self.pool_config)
+    }
+
+    fn internal_get_pending_pool_config(&self) -> Option<PendingPoolConfigDto> {
+        self.pending_pool_config.as_ref().map(
+            |pending_pool_config| PendingPoolConfigDto {
+                pool_config: Self::convert_pool_config_to_dto(&pending_pool_config.pool_config),
+                activation_epoch_height: pending_pool_config.activation_epoch_height
+            }
+        )
+    }
+
+    fn internal_get_deposit_caps(&self) -> DepositCapsDto {
+        DepositCapsDto {
+            pool_near_amount: self.deposit_caps.pool_near_amount.map(|pool_near_amount_| pool_near_amount_.into()),
+            validator_share: self.deposit_caps.validator_share.clone(),
+            account_near_amount: self.deposit_caps.account_near_amount.map(|account_near_amount_| account_near_amount_.into())
+        }
+    }
+
+    fn internal_get_referrer(&self, referrer_account_id: AccountId) -> Option<ReferrerDto> {
+        self.referral.referrer_registry.get(&referrer_account_id).map(
+            |referrer| self.convert_referrer_to_dto(referrer_account_id, referrer)
+        )
+    }
+
+    fn internal_get_referrer_registry(&self) -> Vec<ReferrerDto> {
+        let mut referrer_dto_registry: Vec<ReferrerDto> = vec![];
+
+        for (referrer_account_id, referrer) in self.referral.referrer_registry.into_iter() {
+            referrer_dto_registry.push(self.convert_referrer_to_dto(referrer_account_id, referrer));
+        }
+
+        referrer_dto_registry
+    }
+
+    fn internal_get_remaining_deposit_capacity(&self, account_id: Option<AccountId>, validator_account_id: Option<AccountId>) -> Result<DepositCapacity, StakePoolError> {
+        let common_balance = self.fund.get_common_balance();
+
+        let pool_near_amount = self.deposit_caps.get_pool_remaining_near_amount(common_balance);
+
+        let account_near_amount = match account_id {
+            Some(account_id_) => self.deposit_caps.get_account_remaining_near_amount(self.get_account_near_amount(&account_id_)),
+            None => None
+        };
+
+        let validator_near_amount = match validator_account_id {
+            Some(validator_account_id_) => {
+                let validator = match self.validating.validator_registry.get(&validator_account_id_) {
+                    Some(validator_) => validator_,
+                    None => {
+                        return Err(StakePoolError::ValidatorAccountNotRegistered { validator_account_id: validator_account_id_ });
+                    }
+                };
+
+                self.deposit_caps.get_validator_remaining_near_amount(
+                    validator.balance.classic_near_amount + validator.balance.investment_near_amount,
+                    common_balance
+                )
+            }
+            None => None
+        };
+
+        Ok(
+            DepositCapacity {
+                pool_near_amount: pool_near_amount.map(|pool_near_amount_| pool_near_amount_.into()),
+                account_near_amount: account_near_amount.map(|account_near_amount_| account_near_amount_.into()),
+                validator_near_amount: validator_near_amount.map(|validator_near_amount_| validator_near_amount_.into())
+            }
+        )
+    }
+
+    fn internal_check_invariants(&self, account_ids: Vec<AccountId>) -> InvariantReport {
+        let mut violations = self.collect_invariant_violations();
+
+        if self.epoch_history.epoch_reports_quantity > 0 {
+            let epoch_report = match self.epoch_history.get_epoch_report(self.epoch_history.epoch_reports_quantity - 1) {
+                Some(epoch_report_) => epoch_report_,
+                None => {
+                    StakePoolError::object_must_exist().panic();
+                }
+            };
+
+            let previous_near_per_token = core::calculate_near_per_token(epoch_report.common_balance, epoch_report.total_supply);
+
+            let near_per_token = core::calculate_near_per_token(self.fund.get_common_balance(), self.fungible_token.total_supply);
+
+            if near_per_token < previous_near_per_token {
+                violations.push(
+                    InvariantViolation::PriceDecreased {
+                        epoch_height: epoch_report.epoch_height,
+                        previous_near_per_token: previous_near_per_token.into(),
+                        near_per_token: near_per_token.into()
+                    }
+                );
+            }
+        }
+
+        let mut sampled_account_ids: Vec<AccountId> = vec![];
+
+        let mut sampled_token_amount: Balance = 0;
+
+        for account_id in account_ids.into_iter() {
+            if sampled_account_ids.contains(&account_id) {
+                continue;
+            }
+
+            if let Some(account_balance) = self.fungible_token.get_account_balance(&account_id) {
+                sampled_token_amount += account_balance.token_amount;
+
+                sampled_account_ids.push(account_id);
+            }
+        }
+
+        let sampled_accounts_quantity = sampled_account_ids.len() as u64;
+
+        if sampled_token_amount > self.fungible_token.total_supply
+            || (sampled_accounts_quantity == self.fungible_token.accounts_quantity
+                && sampled_token_amount != self.fungible_token.total_supply) {
+            violations.push(
+                InvariantViolation::TotalSupplyMismatch {
+                    sampled_accounts_quantity,
+                    accounts_quantity: self.fungible_token.accounts_quantity,
+                    sampled_token_amount: sampled_token_amount.into(),
+                    total_supply: self.fungible_token.total_supply.into()
+                }
+            );
+        }
+
+        InvariantReport {
+            is_valid: violations.is_empty(),
+            violations
+        }
+    }
+
+    fn internal_get_validator_performance(&self, validator_account_id: AccountId, from_index: u64, limit: u64) -> Result<Vec<ValidatorPerformanceDto>, StakePoolError> {
+        let validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                return Err(StakePoolError::ValidatorAccountNotRegistered { validator_account_id });
+            }
+        };
+
+        let mut validator_performance_dto_registry: Vec<ValidatorPerformanceDto> = vec![];
+
+        let from_index_ = from_index.max(validator.get_first_available_performance_index());
+
+        let to_index = from_index_.saturating_add(limit).min(validator.performance_records_quantity);
+
+        for index in from_index_..to_index {
+            let validator_performance = match validator.get_performance(index) {
+                Some(validator_performance_) => validator_performance_,
+                None => {
+                    StakePoolError::object_must_exist().panic();
+                }
+            };
+
+            validator_performance_dto_registry.push(
+                ValidatorPerformanceDto {
+                    index,
+                    epoch_height: validator_performance.epoch_height,
+                    is_active: validator_performance.is_active,
+                    validator_stake: validator_performance.validator_stake.into(),
+                    near_amount: validator_performance.near_amount.into(),
+                    rewards_near_amount: validator_performance.rewards_near_amount.into()
+                }
+            );
+        }
+
+        Ok(validator_performance_dto_registry)
+    }
+
+    fn internal_get_validator_reconciliation(&self, validator_account_id: AccountId) -> Result<Option<ValidatorReconciliationDto>, StakePoolError> {
+        let validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                return Err(StakePoolError::ValidatorAccountNotRegistered { validator_account_id });
+            }
+        };
+
+        Ok(validator.last_reconciliation.map(
+            |validator_reconciliation| {
+                let near_amount = validator_reconciliation.get_near_amount();
+
+                let recorded_near_amount = validator_reconciliation.get_recorded_near_amount();
+
+                ValidatorReconciliationDto {
+                    epoch_height: validator_reconciliation.epoch_height,
+                    staked_near_amount: validator_reconciliation.staked_near_amount.into(),
+                    unstaked_near_amount: validator_reconciliation.unstaked_near_amount.into(),
+                    recorded_staked_near_amount: validator_reconciliation.recorded_staked_near_amount.into(),
+                    recorded_unstaked_near_amount: validator_reconciliation.recorded_unstaked_near_amount.into(),
+                    surplus_near_amount: near_amount.saturating_sub(recorded_near_amount).into(),
+                    shortfall_near_amount: recorded_near_amount.saturating_sub(near_amount).into()
+                }
+            }
+        ))
+    }
+
+    fn internal_ft_total_supply(&self) -> Balance {
+        self.fungible_token.total_supply
+    }
+
+    fn internal_ft_balance_of(&self, account_id: AccountId) -> Balance {
+        match self.fungible_token.get_account_balance(&account_id) {
+            Some(account_balance) => account_balance.token_amount,
+            None => 0
+        }
+    }
+
+    fn convert_near_amount_to_token_amount(&self, near_amount: Balance) -> (Balance, Balance) {
+        core::convert_near_amount_to_token_amount(near_amount, self.fund.get_common_balance(), self.fungible_token.total_supply)
+    }
+
+    /// Returns the fee applied to the token amount on instant unstake process.
+    /// The fee curve takes precedence over the fixed fee.
+    fn get_instant_withdraw_fee(&self, token_amount: Balance) -> Option<Fee> {
+        match self.fee_registry.instant_withdraw_fee_curve {
+            Some(ref instant_withdraw_fee_curve) => Some(
+                instant_withdraw_fee_curve.calculate_fee(
+                    self.convert_token_amount_to_near_amount(token_amount),
+                    self.fund.classic_unstaked_balance
+                )
+            ),
+            None => self.fee_registry.instant_withdraw_fee.clone()
+        }
+    }
+
+    fn convert_token_amount_to_near_amount(&self, token_amount: Balance) -> Balance {
+        core::convert_token_amount_to_near_amount(token_amount, self.fund.get_common_balance(), self.fungible_token.total_supply)
+    }
+
+    /// Checks the invariants that do not depend on the price history and on the token accounts.
+    fn collect_invariant_violations(&self) -> Vec<InvariantViolation> {
+        let mut violations: Vec<InvariantViolation> = vec![];
+
+        let mut validators_near_amount: Balance = 0;
+
+        let mut requested_to_withdrawal_near_amount: Balance = 0;
+
+        for validator in self.validating.validator_registry.values() {
+            validators_near_amount += validator.balance.get_balance();
+            requested_to_withdrawal_near_amount += validator.balance.requested_to_withdrawal_near_amount;
+        }
+
+        let expected_near_amount = self.fund.get_staked_balance()
+            + self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount
+            + self.fund.delayed_withdrawn_fund.needed_to_request_investment_near_amount
+            + requested_to_withdrawal_near_amount
+            + self.reward.previous_epoch_rewards_from_validators_near_amount;
+
+        if validators_near_amount != expected_near_amount {
+            violations.push(
+                InvariantViolation::ValidatorBalanceMismatch {
+                    validators_near_amount: validators_near_amount.into(),
+                    expected_near_amount: expected_near_amount.into()
+                }
+            );
+        }
+
+        let required_near_amount = self.fund.classic_unstaked_balance
+            + self.fund.delayed_withdrawn_fund.balance
+            + self.fund.pending_refund_fund.balance
+            + Self::calculate_storage_staking_price(env::storage_usage());
+
+        let contract_near_amount = env::account_balance();
+
+        if contract_near_amount < required_near_amount {
+            violations.push(
+                InvariantViolation::InsufficientContractBalance {
+                    contract_near_amount: contract_near_amount.into(),
+                    required_near_amount: required_near_amount.into()
+                }
+            );
+        }
+
+        if self.fund.get_common_balance() < self.fungible_token.total_supply {
+            violations.push(
+                InvariantViolation::PriceLessThanOne {
+                    common_balance: self.fund.get_common_balance().into(),
+                    total_supply: self.fungible_token.total_supply.into()
+                }
+            );
+        }
+
+        violations
+    }
+
+    fn assert_authorized_management_only_by_manager(&self) {
+        if env::predecessor_account_id() != self.account_registry.manager_id {
+            StakePoolError::UnauthorizedManagementByManager.panic();
+        }
+    }
+
+    fn assert_authorized_management_only_by_owner(&self) {
+        if env::predecessor_account_id() != self.account_registry.owner_id {
+            StakePoolError::UnauthorizedManagementByOwner.panic();
+        }
+    }
+
+    fn assert_authorized_management(&self) {
+        let predecessor_account_id = env::predecessor_account_id();
+
+        if predecessor_account_id != self.account_registry.owner_id
+            && predecessor_account_id != self.account_registry.manager_id {
+            StakePoolError::UnauthorizedManagementByOwnerOrManager.panic();
+        }
+    }
+
+    fn assert_natural_deposit() {
+        if env::attached_deposit() == 0 {
+            StakePoolError::NotNaturalAttachedDeposit.panic();
+        }
+    }
+
+    fn assert_minimum_deposit(&self) {
+        if env::attached_deposit() < self.pool_config.minimum_deposit_amount {
+            StakePoolError::AttachedDepositLessThanMinimumDeposit { required_near_amount: self.pool_config.minimum_deposit_amount.into(), provided_near_amount: env::attached_deposit().into() }.panic();
+        }
+    }
+
+    fn assert_epoch_history_storage_staking_is_covered(&self) {
+        let storage_staking_price = Self::calculate_storage_staking_price(self.epoch_history.get_storage_usage());
+
+        if env::attached_deposit() < storage_staking_price {
+            StakePoolError::InsufficientNearDeposit { required_near_amount: storage_staking_price.into(), provided_near_amount: env::attached_deposit().into() }.panic();
+        }
+    }
+
+    fn assert_minimum_token_amount(token_amount: Balance, min_token_amount: Option<Balance>) {
+        if let Some(min_token_amount_) = min_token_amount {
+            if token_amount < min_token_amount_ {
+                StakePoolError::TokenAmountLessThanMinimumExpectedTokenAmount { expected_token_amount: min_token_amount_.into(), token_amount: token_amount.into() }.panic();
+            }
+        }
+    }
+
+    fn assert_minimum_near_amount(near_amount: Balance, min_near_amount: Option<Balance>) {
+        if let Some(min_near_amount_) = min_near_amount {
+            if near_amount < min_near_amount_ {
+                StakePoolError::NearAmountLessThanMinimumExpectedNearAmount { expected_near_amount: min_near_amount_.into(), near_amount: near_amount.into() }.panic();
+            }
+        }
+    }
+
+    /// The fee accrued since the previous accrual of the referrer is included.
+    fn convert_referrer_to_dto(&self, referrer_account_id: AccountId, mut referrer: Referrer) -> ReferrerDto {
+        self.referral.accrue_referral_fee(&mut referrer);
+
+        ReferrerDto {
+            account_id: referrer_account_id,
+            referred_token_amount: referrer.referred_token_amount.into(),
+            referred_near_amount: self.convert_token_amount_to_near_amount(referrer.referred_token_amount).into(),
+            referred_accounts_quantity: referrer.referred_accounts_quantity,
+            earned_token_amount: referrer.earned_token_amount.into(),
+            unclaimed_token_amount: referrer.unclaimed_token_amount.into()
+        }
+    }
+
+    /// Transfers the Near amount to the account. If the transfer fails, the Near amount is recorded
+    /// as a pending refund of the account, that can be claimed later.
+    fn transfer_near_amount(account_id: AccountId, near_amount: Balance) -> Promise {
+        Self::transfer_near_amount_to(account_id.clone(), account_id, near_amount)
+    }
+
+    /// The Near amount of the failed transfer is recorded as the pending refund of the account, that has paid for the transfer,
+    /// because the receiver may be not able to claim it.
+    fn transfer_near_amount_to(receiver_account_id: AccountId, refund_account_id: AccountId, near_amount: Balance) -> Promise {
+        Promise::new(receiver_account_id.clone())
+            .transfer(near_amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(gas::TRANSFER_NEAR_AMOUNT_CALLBACK)
+                    .transfer_near_amount_callback(receiver_account_id, refund_account_id, near_amount)
+            )
+    }
+
+    /// Distributes the fee token amount between the fee beneficiaries according to their shares.
+    fn distribute_fee(&mut self, fee_split: &FeeSplit, token_amount: Balance) {
+        if token_amount == 0 {
+            return;
+        }
+
+        for (fee_beneficiary_account_id, fee_beneficiary_token_amount) in fee_split.split(token_amount).into_iter() {
+            if fee_beneficiary_token_amount == 0 {
+                continue;
+            }
+
+            let mut account_balance = match self.fungible_token.get_account_balance(&fee_beneficiary_account_id) {
+                Some(account_balance_) => account_balance_,
+                None => {
+                    StakePoolError::object_must_exist().panic();
+                }
+            };
+            account_balance.token_amount += fee_beneficiary_token_amount;
+
+            self.insert_account_balance(&fee_beneficiary_account_id, &account_balance);
+
+            Self::log_received_fee(&fee_beneficiary_account_id, fee_beneficiary_token_amount);
+        }
+    }
+
+    /// Adds the claimed referral fee to the token account of the referrer.
+    fn receive_referral_fee(&mut self, referrer_account_id: &AccountId, token_amount: Balance) {
+        if token_amount == 0 {
+            return;
+        }
+
+        let mut account_balance = match self.fungible_token.get_account_balance(referrer_account_id) {
+            Some(account_balance_) => account_balance_,
+            None => {
+                StakePoolError::object_must_exist().panic();
+            }
+        };
+        account_balance.token_amount += token_amount;
+        self.insert_account_balance(referrer_account_id, &account_balance);
+
+        Self::log_received_fee(referrer_account_id, token_amount);
+    }
+
+    fn log_received_fee(account_id: &AccountId, token_amount: Balance) {
+        env::log_str(
+            format!(
+                "@{} received {} yoctoStNear as fee in {} epoch.",
+                account_id,
+                token_amount,
+                env::epoch_height()
+            ).as_str()
+        );
+    }
+
+    fn log_taken_delayed_withdrawal(account_id: &AccountId, near_amount: Balance, current_epoch_height: EpochHeight) {
+        env::log_str(
+            format!(
+                "Delayed withdrawal of {} yoctoNear is taken by @{} from @{} in {} epoch.",
+                near_amount,
+                account_id,
+                env::current_account_id(),
+                current_epoch_height
+            ).as_str()
+        );
     }
 
-    fn internal_get_aggregated(&self) -> Aggregated {
-        self.assert_epoch_is_synchronized();
+    /// Saves the account balance and keeps the token amount referred by the referrer of the account up to date.
+    fn insert_account_balance(&mut self, account_id: &AccountId, account_balance: &AccountBalance) -> Option<AccountBalance> {
+        let previous_account_balance = self.fungible_token.insert_account_balance(account_id, account_balance);
 
-        Aggregated {
-            unstaked_balance: self.fund.classic_unstaked_balance.into(),
-            staked_balance: self.fund.get_staked_balance().into(),
-            token_total_supply: self.fungible_token.total_supply.into(),
-            token_accounts_quantity: self.fungible_token.accounts_quantity,
-            total_rewards_from_validators_near_amount: self.reward.total_rewards_from_validators_near_amount.into(),
-            reward_fee: self.get_fee_registry_light().reward_fee
+        let mut is_new_referred_account = true;
+        if let Some(ref previous_account_balance_) = previous_account_balance {
+            if let Some(ref referrer_id) = previous_account_balance_.referrer_id {
+                self.referral.decrease_referred_token_amount(referrer_id, previous_account_balance_.token_amount, false);
+
+                is_new_referred_account = false;
+            }
+        }
+        if let Some(ref referrer_id) = account_balance.referrer_id {
+            self.referral.increase_referred_token_amount(referrer_id, account_balance.token_amount, is_new_referred_account);
         }
+
+        previous_account_balance
     }
 
-    fn internal_get_requested_to_withdrawal_fund(&self) -> RequestedToWithdrawalFund {
-        let mut investment_withdrawal_registry: Vec<(AccountId, U128)> = vec![];
+    fn remove_account_balance(&mut self, account_id: &AccountId) -> Option<AccountBalance> {
+        let account_balance = self.fungible_token.remove_account_balance(account_id);
 
-        for validator_account_id in self.validating.validator_registry.keys() {
-            if let Some(investment_withdrawal) = self.fund.delayed_withdrawn_fund.investment_withdrawal_registry.get(&validator_account_id) {
-                investment_withdrawal_registry.push((validator_account_id, investment_withdrawal.near_amount.into()))
+        if let Some(ref account_balance_) = account_balance {
+            if let Some(ref referrer_id) = account_balance_.referrer_id {
+                self.referral.decrease_referred_token_amount(referrer_id, account_balance_.token_amount, true);
             }
         }
 
-        RequestedToWithdrawalFund {
-            classic_near_amount: self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount.into(),
-            investment_near_amount: self.fund.delayed_withdrawn_fund.needed_to_request_investment_near_amount.into(),
-            investment_withdrawal_registry
-        }
+        account_balance
     }
 
-    pub fn internal_get_full(&self) -> Full {
-        self.assert_epoch_is_synchronized();
+    /// Token account, that is not removed with zero balance.
+    fn is_permanent_token_account(&self, account_id: &AccountId) -> bool {
+        self.fee_registry.reward_fee_split.contains(account_id)
+            || self.fee_registry.instant_withdraw_fee_split.contains(account_id)
+            || self.referral.referrer_registry.get(account_id).is_some()
+    }
 
-        Full {
-            storage_staking_price: self.internal_get_storage_staking_price(),
-            fund: self.internal_get_fund(),
-            total_token_supply: self.internal_get_total_token_supply().into(),
-            requested_to_withdrawal_fund: self.internal_get_requested_to_withdrawal_fund(),
-            fee_registry_light: self.internal_get_fee_registry_light(),
-            minimum_deposit_amount: self.get_minimum_deposit_amount()
+    fn assert_referrer_is_valid(&self, account_id: &AccountId, referrer_id: &Option<AccountId>) {
+        if let Some(ref referrer_id_) = referrer_id {
+            if referrer_id_ == account_id {
+                StakePoolError::AccountCanNotBeReferrerOfItself.panic();
+            }
+            if self.referral.referrer_registry.get(referrer_id_).is_none() {
+                StakePoolError::ReferrerAccountNotRegistered { referrer_account_id: referrer_id_.clone() }.panic();
+            }
         }
     }
 
-    pub fn internal_get_full_for_account(&self, account_id: AccountId) -> FullForAccount {
-        self.assert_epoch_is_synchronized();
-
-        FullForAccount {
-            full: self.internal_get_full(),
-            account_balance: self.internal_get_account_balance(account_id.clone()),
-            delayed_withdrawal_details: self.internal_get_delayed_withdrawal_details(account_id.clone()),
-            investor_investment: self.get_investor_investment(account_id.clone()),
-            storage_staking_requested_coverage: self.get_storage_staking_requested_coverage(account_id)
+    /// Near amount for the token balance of the account.
+    fn get_account_near_amount(&self, account_id: &AccountId) -> Balance {
+        match self.fungible_token.get_account_balance(account_id) {
+            Some(account_balance) => self.convert_token_amount_to_near_amount(account_balance.token_amount),
+            None => 0
         }
     }
 
-    fn internal_ft_total_supply(&self) -> Balance {
-        self.fungible_token.total_supply
+    fn assert_pool_deposit_cap(&self, near_amount: Balance) {
+        if let Some(remaining_near_amount) = self.deposit_caps.get_pool_remaining_near_amount(self.fund.get_common_balance()) {
+            if near_amount > remaining_near_amount {
+                StakePoolError::PoolDepositCapExceeded { remaining_near_amount: remaining_near_amount.into(), near_amount: near_amount.into() }.panic();
+            }
+        }
     }
 
-    fn internal_ft_balance_of(&self, account_id: AccountId) -> Balance {
-        match self.fungible_token.account_registry.get(&account_id) {
-            Some(account_balance) => account_balance.token_amount,
-            None => 0
+    fn assert_account_deposit_cap(&self, account_id: &AccountId, near_amount: Balance) {
+        if let Some(remaining_near_amount) = self.deposit_caps.get_account_remaining_near_amount(self.get_account_near_amount(account_id)) {
+            if near_amount > remaining_near_amount {
+                StakePoolError::AccountDepositCapExceeded { remaining_near_amount: remaining_near_amount.into(), near_amount: near_amount.into() }.panic();
+            }
         }
     }
 
-    fn convert_near_amount_to_token_amount(&self, near_amount: Balance) -> (Balance, Balance) {
-        let common_balance = self.fund.get_common_balance();
-
-        if common_balance == 0 || near_amount == 0 || self.fungible_token.total_supply == 0 {
-            return (near_amount, 0);
+    /// The common balance is the balance of the pool after the stake increasing.
+    fn assert_validator_share_cap(&self, validator: &Validator, near_amount: Balance, common_balance: Balance) {
+        if let Some(remaining_near_amount) = self.get_validator_remaining_near_amount(validator, common_balance) {
+            if near_amount > remaining_near_amount {
+                StakePoolError::ValidatorShareCapExceeded { remaining_near_amount: remaining_near_amount.into(), near_amount: near_amount.into() }.panic();
+            }
         }
+    }
 
-        let token_amount = (
-            U256::from(near_amount)
-            * U256::from(self.fungible_token.total_supply)
-            / U256::from(common_balance)
-        ).as_u128();
-
-        let remainder_near_amount = near_amount - self.convert_token_amount_to_near_amount(token_amount);
-
-
-        (token_amount, remainder_near_amount)
+    fn get_validator_remaining_near_amount(&self, validator: &Validator, common_balance: Balance) -> Option<Balance> {
+        self.deposit_caps.get_validator_remaining_near_amount(
+            validator.balance.classic_near_amount + validator.balance.investment_near_amount,
+            common_balance
+        )
     }
 
-    fn convert_token_amount_to_near_amount(&self, token_amount: Balance) -> Balance {
-        if self.fungible_token.total_supply == 0 || token_amount == 0 {
-            return token_amount
+    /// The deposit is staked on the preferred validator only if it does not exceed the share cap of the validator.
+    /// Otherwise it stays unstaked on the pool until the manager distributes it.
+    fn is_preffered_validator_able_to_stake(&self, near_amount: Balance) -> bool {
+        match self.validating.preffered_validator {
+            Some(ref preffered_validator_account_id) => {
+                match self.validating.validator_registry.get(preffered_validator_account_id) {
+                    Some(validator) => {
+                        match self.get_validator_remaining_near_amount(&validator, self.fund.get_common_balance() + near_amount) {
+                            Some(remaining_near_amount) => near_amount <= remaining_near_amount,
+                            None => true
+                        }
+                    }
+                    None => {
+                        StakePoolError::object_must_exist().panic();
+                    }
+                }
+            }
+            None => false
         }
-
-        (
-            U256::from(token_amount)
-            * U256::from(self.fund.get_common_balance())
-            / U256::from(self.fungible_token.total_supply)
-        ).as_u128()
     }
 
-    fn assert_authorized_management_only_by_manager(&self) {
-        if env::predecessor_account_id() != self.account_registry.manager_id {
-            env::panic_str("Unauthorized management. Management must be carried out either by the manager of the pool.");
+    fn assert_epoch_is_synchronized(&self) {
+        if let Err(error) = self.check_epoch_is_synchronized() {
+            error.panic();
         }
     }
 
-    fn assert_authorized_management(&self) {
-        let predecessor_account_id = env::predecessor_account_id();
-
-        if predecessor_account_id != self.account_registry.owner_id
-            && predecessor_account_id != self.account_registry.manager_id {
-            env::panic_str("Unauthorized management. Management must be carried out either by the owner or manager of the pool.");
+    fn check_epoch_is_synchronized(&self) -> Result<(), StakePoolError> {
+        if self.current_epoch_height != env::epoch_height() {
+            return Err(StakePoolError::EpochShouldBeSynchronized);
         }
+
+        Ok(())
     }
 
-    fn assert_natural_deposit() {
-        if env::attached_deposit() == 0 {
-            env::panic_str("Not natural attached deposit.");
+    fn assert_epoch_is_desynchronized(&self) {
+        if self.current_epoch_height == env::epoch_height() {
+            StakePoolError::EpochShouldBeDesynchronized.panic();
         }
     }
 
-    fn assert_minimum_deposit() {
-        if env::attached_deposit() < MINIMUN_DEPOSIT_AMOUNT {
-            env::panic_str("Attached deposit less then minimum required deposit.");
+    /// Asserts, that the prepaid Gas covers the budget of the method, but not less than the minimum of the pool config.
+    fn assert_gas_is_enough(&self, budget: Gas) {
+        let required_gas = budget.max(Gas::ONE_TERA * self.pool_config.minimum_number_of_tgas);
+
+        if env::prepaid_gas() < required_gas {
+            StakePoolError::NotEnoughGas { required_gas: required_gas.0, provided_gas: env::prepaid_gas().0 }.panic();
         }
     }
 
-    fn assert_epoch_is_synchronized(&self) {
-        if self.current_epoch_height != env::epoch_height() {
-            env::panic_str("Epoch should be in synchronized state.");
-        }
+    fn is_right_epoch(&self, epoch_height: EpochHeight) -> bool {
+        (epoch_height % self.pool_config.epoch_quantity_for_validator_unstake) == 0
     }
 
-    fn assert_epoch_is_desynchronized(&self) {
-        if self.current_epoch_height == env::epoch_height() {
-            env::panic_str("Epoch should be in desynchronized state.");
+    fn assert_pool_config_is_valid(&self, pool_config: &PoolConfig) {
+        // The delayed withdrawal must cover the waiting for the next unstake request and the unbonding period after it.
+        let is_epoch_quantity_for_delayed_withdrawal_valid = match pool_config.epoch_quantity_for_validator_unstake.checked_mul(2) {
+            Some(minimum_epoch_quantity_for_delayed_withdrawal) => pool_config.epoch_quantity_for_delayed_withdrawal >= minimum_epoch_quantity_for_delayed_withdrawal,
+            None => false
+        };
+        if pool_config.epoch_quantity_for_validator_unstake < MINIMUM_EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE || !is_epoch_quantity_for_delayed_withdrawal_valid {
+            StakePoolError::PoolConfigEpochQuantitiesNotValid.panic();
+        }
+        if pool_config.minimum_number_of_tgas == 0 || pool_config.minimum_number_of_tgas > MAXIMUM_NUMBER_OF_TGAS {
+            StakePoolError::PoolConfigMinimumNumberOfTgasNotValid.panic();
+        }
+        // The minimum deposit must cover the storage staking for the investor on deposit on validator.
+        if pool_config.minimum_deposit_amount <= Self::calculate_storage_staking_price(
+            self.fungible_token.storage_usage_per_account + self.validating.storage_usage_per_distribution
+        ) {
+            StakePoolError::PoolConfigMinimumDepositAmountNotValid.panic();
         }
     }
 
-    fn assert_gas_is_enough() {
-        if env::prepaid_gas() < (Gas::ONE_TERA * MINIMUM_NUMBER_OF_TGAS) {
-            env::panic_str("Not enough Gas quantity.");
+    fn convert_pool_config(pool_config: PoolConfigDto) -> PoolConfig {
+        PoolConfig {
+            epoch_quantity_for_delayed_withdrawal: pool_config.epoch_quantity_for_delayed_withdrawal,
+            epoch_quantity_for_validator_unstake: pool_config.epoch_quantity_for_validator_unstake,
+            minimum_number_of_tgas: pool_config.minimum_number_of_tgas,
+            minimum_deposit_amount: pool_config.minimum_deposit_amount.into()
         }
     }
 
-    fn is_right_epoch(epoch_height: EpochHeight) -> bool {
-        (epoch_height % EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE) == 0
+    fn convert_pool_config_to_dto(pool_config: &PoolConfig) -> PoolConfigDto {
+        PoolConfigDto {
+            epoch_quantity_for_delayed_withdrawal: pool_config.epoch_quantity_for_delayed_withdrawal,
+            epoch_quantity_for_validator_unstake: pool_config.epoch_quantity_for_validator_unstake,
+            minimum_number_of_tgas: pool_config.minimum_number_of_tgas,
+            minimum_deposit_amount: pool_config.minimum_deposit_amount.into()
+        }
     }
 
     fn calculate_storage_staking_price(quantity_of_bytes: StorageUsage) -> Balance {
         match Balance::from(quantity_of_bytes).checked_mul(env::storage_byte_cost()) {
             Some(storage_staking_price) => storage_staking_price,
             None => {
-                env::panic_str("Calculation overflow.");
+                StakePoolError::CalculationOverflow.panic();
             }
         }
     }
@@ -2320,10 +4116,53 @@ impl StakePool {
 
 #[near_bindgen]
 impl StakePool {
+    #[private]
+    pub fn transfer_near_amount_callback(&mut self, receiver_account_id: AccountId, refund_account_id: AccountId, near_amount: Balance) {
+        if env::promise_results_count() == 0 {
+            StakePoolError::CallbackExpectedResult.panic();
+        }
+
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        // The storage staking of the new pending refund is taken from the refunded Near amount and is returned on claim,
+        // so the failed transfers are not able to consume the storage at the expense of the pool.
+        // The Near amount, that does not cover the storage staking, stays on the pool.
+        let reserved_storage_staking_price = if self.fund.pending_refund_fund.pending_refunds.contains_key(&refund_account_id) {
+            0
+        } else {
+            Self::calculate_storage_staking_price(self.fund.pending_refund_fund.storage_usage_per_pending_refund).min(near_amount)
+        };
+        let refundable_near_amount = near_amount - reserved_storage_staking_price;
+
+        if refundable_near_amount > 0 {
+            self.fund.pending_refund_fund.increase(&refund_account_id, refundable_near_amount);
+        }
+
+        env::log_str(
+            format!(
+                "
+                Transfer of {} yoctoNear from @{} to @{} failed.
+                Reserved storage staking price is {} yoctoNear.
+                Pending refund of @{} is {} yoctoNear.
+                ",
+                near_amount,
+                env::current_account_id(),
+                &receiver_account_id,
+                reserved_storage_staking_price,
+                &refund_account_id,
+                self.internal_get_pending_refund(refund_account_id.clone())
+            ).as_str()
+        );
+    }
+
+    // The callback receives the whole context of the call as arguments, because the state may change before it is executed.
     #[private]
     pub fn deposit_callback(
         &mut self,
         predecessor_account_id: AccountId,
+        beneficiary_account_id: AccountId,
         validator_account_id: AccountId,
         attached_deposit: Balance,
         near_amount: Balance,
@@ -2331,18 +4170,21 @@ impl StakePool {
         token_amount: Balance,
         near_remainder: Balance,
         current_epoch_height: EpochHeight,
-        storage_staking_price_per_additional_account: Balance
+        storage_staking_price_per_additional_account: Balance,
+        referrer_id: Option<AccountId>
     ) {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
+        let common_balance_log = self.fund.get_common_balance();
+
         match env::promise_result(0) {
             PromiseResult::Successful(_) => {
                 let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
                 validator.balance.classic_near_amount += near_amount;
@@ -2353,25 +4195,38 @@ impl StakePool {
             }
             _ => {
                 self.fund.classic_unstaked_balance += near_amount;
+
+                env::log_str(
+                    format!(
+                        "Deposit of {} yoctoNear is not staked on validator @{} in {} epoch.",
+                        near_amount,
+                        &validator_account_id,
+                        env::epoch_height()
+                    ).as_str()
+                );
             }
         }
 
-        let mut account_balance = match self.fungible_token.account_registry.get(&predecessor_account_id) {
+        let mut account_balance = match self.fungible_token.get_account_balance(&beneficiary_account_id) {
             Some(account_balance_) => account_balance_,
             None => {
                 self.fungible_token.accounts_quantity += 1;
 
-                AccountBalance {token_amount: 0, classic_near_amount: 0, investment_near_amount: 0}
+                AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0, referrer_id: None }
             }
         };
         account_balance.token_amount += token_amount;
         account_balance.classic_near_amount += near_remainder;
-        self.fungible_token.account_registry.insert(&predecessor_account_id, &account_balance);
+        if account_balance.referrer_id.is_none() {
+            account_balance.referrer_id = referrer_id;
+        }
+        self.insert_account_balance(&beneficiary_account_id, &account_balance);
+        self.fund.classic_remainder_balance += near_remainder;
         self.fungible_token.total_supply += token_amount;
+        self.epoch_history.deposited_near_amount += near_amount;
 
         if refundable_near_amount > 0 {
-            Promise::new(predecessor_account_id.clone())
-                .transfer(refundable_near_amount);
+            Self::transfer_near_amount(predecessor_account_id, refundable_near_amount);
         }
 
         let current_account_id_log = env::current_account_id();
@@ -2401,12 +4256,12 @@ impl StakePool {
                 &current_account_id_log,
                 self.fungible_token.total_supply - token_amount,
                 &current_account_id_log,
-                self.fund.get_common_balance() - near_amount,
-                &predecessor_account_id,
+                common_balance_log,
+                &beneficiary_account_id,
                 account_balance.token_amount - token_amount,
-                &predecessor_account_id,
+                &beneficiary_account_id,
                 token_amount,
-                &predecessor_account_id,
+                &beneficiary_account_id,
                 account_balance.token_amount,
                 &current_account_id_log,
                 self.fund.get_common_balance(),
@@ -2416,6 +4271,7 @@ impl StakePool {
         );
     }
 
+    // The callback receives the whole context of the call as arguments, because the state may change before it is executed.
     #[private]
     pub fn deposit_on_validator_callback(
         &mut self,
@@ -2426,10 +4282,11 @@ impl StakePool {
         refundable_near_amount: Balance,
         token_amount: Balance,
         near_remainder: Balance,
-        storage_staking_price_per_additional_accounts: Balance
+        storage_staking_price_per_additional_accounts: Balance,
+        referrer_id: Option<AccountId>
     ) -> bool {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
         match env::promise_result(0) {
@@ -2437,7 +4294,7 @@ impl StakePool {
                 let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
                 validator.balance.investment_near_amount += near_amount;
@@ -2446,7 +4303,7 @@ impl StakePool {
                 let mut investor_investment = match self.validating.investor_investment_registry.get(&predecessor_account_id) {
                     Some(investor_investment_) => investor_investment_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
                 let mut staked_balance = match investor_investment.distribution_registry.get(&validator_account_id) {
@@ -2462,26 +4319,39 @@ impl StakePool {
                 investor_investment.staked_balance += near_amount;
                 self.validating.investor_investment_registry.insert(&predecessor_account_id, &investor_investment);
 
-                let mut account_balance = match self.fungible_token.account_registry.get(&predecessor_account_id) {
+                let mut account_balance = match self.fungible_token.get_account_balance(&predecessor_account_id) {
                     Some(account_balance_) => account_balance_,
                     None => {
                         self.fungible_token.accounts_quantity += 1;
 
-                        AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0}
+                        AccountBalance { token_amount: 0, classic_near_amount: 0, investment_near_amount: 0, referrer_id: None }
                     }
                 };
                 account_balance.token_amount += token_amount;
                 account_balance.investment_near_amount += near_remainder;
-                self.fungible_token.account_registry.insert(&predecessor_account_id, &account_balance);
+                if account_balance.referrer_id.is_none() {
+                    account_balance.referrer_id = referrer_id;
+                }
+                self.insert_account_balance(&predecessor_account_id, &account_balance);
                 self.fungible_token.total_supply += token_amount;
 
                 self.fund.investment_staked_balance += near_amount;
+                self.epoch_history.deposited_near_amount += near_amount;
 
                 if refundable_near_amount > 0 {
-                    Promise::new(predecessor_account_id.clone())
-                        .transfer(refundable_near_amount);
+                    Self::transfer_near_amount(predecessor_account_id.clone(), refundable_near_amount);
                 }
 
+                env::log_str(
+                    format!(
+                        "Deposit of {} yoctoNear is invested by @{} on validator @{} in {} epoch.",
+                        near_amount,
+                        &predecessor_account_id,
+                        &validator_account_id,
+                        env::epoch_height()
+                    ).as_str()
+                );
+
                 let current_account_id_log = env::current_account_id();
                 env::log_str(
                     format!(
@@ -2526,8 +4396,7 @@ impl StakePool {
                 true
             }
             _ => {
-                Promise::new(predecessor_account_id)
-                    .transfer(attached_deposit);
+                Self::transfer_near_amount(predecessor_account_id, attached_deposit);
 
                 false
             }
@@ -2542,7 +4411,7 @@ impl StakePool {
         current_epoch_height: EpochHeight
     ) -> bool {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
         match env::promise_result(0) {
@@ -2553,7 +4422,7 @@ impl StakePool {
                 let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
                 validator.balance.classic_near_amount += near_amount;
@@ -2608,7 +4477,7 @@ impl StakePool {
         refundable_near_amount: Balance
     ) -> PromiseOrValue<CallbackResult> {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
         match env::promise_result(0) {
@@ -2616,18 +4485,18 @@ impl StakePool {
                 let unstaked_balance: Balance = match near_sdk::serde_json::from_slice::<U128>(data.as_slice()) {
                     Ok(unstaked_balance_) => unstaked_balance_.into(),
                     Err(_) => {
-                        env::panic_str("Nonexecutable code. It should be valid JSON object.");
+                        StakePoolError::NonexecutableCode { reason: "It should be valid JSON object.".to_string() }.panic();
                     }
                 };
 
                 let validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
 
-                let unstaked_remainder = unstaked_balance - validator.balance.requested_to_withdrawal_near_amount;
+                let unstaked_remainder = unstaked_balance - validator.balance.get_unstaked_balance();
 
                 let needed_to_unstake_near_amount = near_amount - unstaked_remainder;
 
@@ -2635,9 +4504,11 @@ impl StakePool {
                     StakingContractVersion::Core => {
                         PromiseOrValue::Promise(
                             classic_validator::ext(validator_account_id.clone())
+                                .with_static_gas(gas::VALIDATOR_UNSTAKE)
                                 .unstake(needed_to_unstake_near_amount.into())
                                 .then(
                                     Self::ext(env::current_account_id())
+                                        .with_static_gas(gas::REQUESTED_DECREASE_VALIDATOR_STAKE_CALLBACK_2)
                                         .requested_decrease_validator_stake_callback_2(
                                             validator_account_id,
                                             near_amount,
@@ -2669,7 +4540,7 @@ impl StakePool {
         refundable_near_amount: Balance
     ) -> CallbackResult {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
         match env::promise_result(0) {
@@ -2677,7 +4548,7 @@ impl StakePool {
                 let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
 
@@ -2694,7 +4565,7 @@ impl StakePool {
                         let mut investment_withdrawal = match self.fund.delayed_withdrawn_fund.investment_withdrawal_registry.get(&validator_account_id) {
                             Some(investment_withdrawal_) => investment_withdrawal_,
                             None => {
-                                env::panic_str("Nonexecutable code. Object must exist.");
+                                StakePoolError::object_must_exist().panic();
                             }
                         };
                         if near_amount < investment_withdrawal.near_amount {
@@ -2704,8 +4575,7 @@ impl StakePool {
                         } else {
                             self.fund.delayed_withdrawn_fund.investment_withdrawal_registry.remove(&validator_account_id);
 
-                            Promise::new(investment_withdrawal.account_id)
-                                .transfer(refundable_near_amount);
+                            Self::transfer_near_amount(investment_withdrawal.account_id, refundable_near_amount);
                         }
 
                         validator.balance.investment_near_amount -= near_amount;
@@ -2765,10 +4635,11 @@ impl StakePool {
     pub fn take_unstaked_balance_callback(
         &mut self,
         validator_account_id: AccountId,
-        requested_to_withdrawal_near_amount: Balance
+        requested_to_withdrawal_near_amount: Balance,
+        drained_near_amount: Balance
     ) -> CallbackResult {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
         match env::promise_result(0) {
@@ -2776,13 +4647,22 @@ impl StakePool {
                 let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
 
                 self.fund.delayed_withdrawn_fund.balance += requested_to_withdrawal_near_amount;
 
+                // The drained Near amount firstly covers the classic Near amount needed to request from validators.
+                let drained_to_delayed_withdrawn_fund_near_amount =
+                    drained_near_amount.min(self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount);
+                self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount -= drained_to_delayed_withdrawn_fund_near_amount;
+                self.fund.delayed_withdrawn_fund.balance += drained_to_delayed_withdrawn_fund_near_amount;
+                self.fund.classic_staked_balance -= drained_near_amount - drained_to_delayed_withdrawn_fund_near_amount;
+                self.fund.classic_unstaked_balance += drained_near_amount - drained_to_delayed_withdrawn_fund_near_amount;
+
                 validator.balance.requested_to_withdrawal_near_amount -= requested_to_withdrawal_near_amount;
+                validator.balance.drained_near_amount -= drained_near_amount;
                 self.validating.validator_registry.insert(&validator_account_id, &validator);
 
                 let current_account_id_log = env::current_account_id();
@@ -2794,6 +4674,7 @@ impl StakePool {
                         Old @{} investment Near amount on validator is {} yoctoNear.
                         Old @{} unstaked Near amount on validator is {} yoctoNear.
                         Received Near amount from validator is {} yoctoNear.
+                        Received drained Near amount from validator is {} yoctoNear.
                         New @{} classic Near amount on validator is {} yoctoNear.
                         New @{} investment Near amount on validator is {} yoctoNear.
                         New @{} unstaked Near amount on validator is {} yoctoNear.
@@ -2805,14 +4686,91 @@ impl StakePool {
                         current_account_id_log,
                         validator.balance.investment_near_amount,
                         current_account_id_log,
-                        validator.balance.requested_to_withdrawal_near_amount + requested_to_withdrawal_near_amount,
+                        validator.balance.get_unstaked_balance() + requested_to_withdrawal_near_amount + drained_near_amount,
                         requested_to_withdrawal_near_amount,
+                        drained_near_amount,
                         current_account_id_log,
                         validator.balance.classic_near_amount,
                         current_account_id_log,
                         validator.balance.investment_near_amount,
                         current_account_id_log,
-                        validator.balance.requested_to_withdrawal_near_amount
+                        validator.balance.get_unstaked_balance()
+                    ).as_str()
+                );
+
+                if validator.is_draining && validator.balance.get_balance() == 0 {
+                    self.remove_drained_validator(validator_account_id);
+                }
+
+                CallbackResult {
+                    is_success: true,
+                    network_epoch_height: env::epoch_height()
+                }
+            }
+            _ => {
+                CallbackResult {
+                    is_success: false,
+                    network_epoch_height: env::epoch_height()
+                }
+            }
+        }
+    }
+
+    #[private]
+    pub fn drain_validator_callback(
+        &mut self,
+        validator_account_id: AccountId,
+        near_amount: Balance
+    ) -> CallbackResult {
+        if env::promise_results_count() == 0 {
+            StakePoolError::CallbackExpectedResult.panic();
+        }
+
+        let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
+            Some(validator_) => validator_,
+            None => {
+                StakePoolError::object_must_exist().panic();
+            }
+        };
+        validator.is_drain_in_flight = false;
+
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                // The classic stake decreasing, that is requested before the draining, can be received earlier,
+                // so only the remaining classic Near amount is unstaked.
+                let unstaked_near_amount = near_amount.min(validator.balance.classic_near_amount);
+
+                // The unstaked Near amount firstly covers the classic Near amount needed to request from validators.
+                let requested_to_withdrawal_near_amount =
+                    unstaked_near_amount.min(self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount);
+                self.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount -= requested_to_withdrawal_near_amount;
+
+                validator.balance.classic_near_amount -= unstaked_near_amount;
+                validator.balance.requested_to_withdrawal_near_amount += requested_to_withdrawal_near_amount;
+                validator.balance.drained_near_amount += unstaked_near_amount - requested_to_withdrawal_near_amount;
+                self.validating.validator_registry.insert(&validator_account_id, &validator);
+
+                let current_account_id_log = env::current_account_id();
+                env::log_str(
+                    format!(
+                        "
+                        Draining validator @{} in {} epoch.
+                        Unstaked from validator is {} yoctoNear.
+                        Requested to withdrawal from unstaked is {} yoctoNear.
+                        New @{} classic Near amount on validator is {} yoctoNear.
+                        New @{} unstaked Near amount on validator is {} yoctoNear.
+                        New @{} drained Near amount on validator is {} yoctoNear.
+                        ",
+                        validator_account_id,
+                        env::epoch_height(),
+                        unstaked_near_amount,
+                        requested_to_withdrawal_near_amount,
+                        current_account_id_log,
+                        validator.balance.classic_near_amount,
+                        current_account_id_log,
+                        validator.balance.requested_to_withdrawal_near_amount,
+                        current_account_id_log,
+                        validator.balance.drained_near_amount
                     ).as_str()
                 );
 
@@ -2822,6 +4780,8 @@ impl StakePool {
                 }
             }
             _ => {
+                self.validating.validator_registry.insert(&validator_account_id, &validator);
+
                 CallbackResult {
                     is_success: false,
                     network_epoch_height: env::epoch_height()
@@ -2837,7 +4797,7 @@ impl StakePool {
         current_epoch_height: EpochHeight
     ) -> CallbackResult {
         if env::promise_results_count() == 0 {
-            env::panic_str("Contract expected a result on the callback.");
+            StakePoolError::CallbackExpectedResult.panic();
         }
 
         match env::promise_result(0) {
@@ -2845,23 +4805,51 @@ impl StakePool {
                 let new_balance: u128 = match near_sdk::serde_json::from_slice::<U128>(data.as_slice()) {
                     Ok(new_balance_) => new_balance_.into(),
                     Err(_) => {
-                        env::panic_str("Nonexecutable code. It should be valid JSON object.");
+                        StakePoolError::NonexecutableCode { reason: "It should be valid JSON object.".to_string() }.panic();
                     }
                 };
 
                 let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
                     Some(validator_) => validator_,
                     None => {
-                        env::panic_str("Nonexecutable code. Object must exist.");
+                        StakePoolError::object_must_exist().panic();
                     }
                 };
 
                 let staking_rewards_near_amount = new_balance - validator.balance.get_balance();
 
+                let validator_stake = env::validator_stake(&validator_account_id);
+
+                validator.add_performance(
+                    &ValidatorPerformance {
+                        epoch_height: current_epoch_height,
+                        is_active: validator_stake > 0,
+                        validator_stake,
+                        near_amount: validator.balance.get_balance(),
+                        rewards_near_amount: staking_rewards_near_amount
+                    }
+                );
+
                 validator.last_update_epoch_height = current_epoch_height;
                 validator.balance.classic_near_amount += staking_rewards_near_amount;
 
                 self.validating.validator_registry.insert(&validator_account_id, &validator);
+
+                if validator.is_flagged_as_inactive() {
+                    if let Some(ref preffered_validator_account_id) = self.validating.preffered_validator {
+                        if *preffered_validator_account_id == validator_account_id {
+                            self.validating.preffered_validator = None;
+                        }
+                    }
+
+                    env::log_str(
+                        format!(
+                            "Validator @{} is out of the active validator set for {} epochs.",
+                            validator_account_id,
+                            validator.consecutive_inactive_epoch_quantity
+                        ).as_str()
+                    );
+                }
                 self.validating.quantity_of_validators_updated_in_current_epoch += 1;
 
                 self.reward.previous_epoch_rewards_from_validators_near_amount += staking_rewards_near_amount;
@@ -2911,4 +4899,82 @@ impl StakePool {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[private]
+    pub fn reconcile_validator_callback(
+        &mut self,
+        validator_account_id: AccountId,
+        current_epoch_height: EpochHeight
+    ) -> CallbackResult {
+        if env::promise_results_count() != 2 {
+            StakePoolError::CallbackExpectedTwoResults.panic();
+        }
+
+        match (env::promise_result(0), env::promise_result(1)) {
+            (PromiseResult::Successful(staked_data), PromiseResult::Successful(unstaked_data)) => {
+                let (staked_near_amount, unstaked_near_amount): (Balance, Balance) = match (
+                    near_sdk::serde_json::from_slice::<U128>(staked_data.as_slice()),
+                    near_sdk::serde_json::from_slice::<U128>(unstaked_data.as_slice())
+                ) {
+                    (Ok(staked_near_amount_), Ok(unstaked_near_amount_)) => (staked_near_amount_.into(), unstaked_near_amount_.into()),
+                    _ => {
+                        StakePoolError::NonexecutableCode { reason: "It should be valid JSON object.".to_string() }.panic();
+                    }
+                };
+
+                let mut validator = match self.validating.validator_registry.get(&validator_account_id) {
+                    Some(validator_) => validator_,
+                    None => {
+                        StakePoolError::object_must_exist().panic();
+                    }
+                };
+
+                let validator_reconciliation = ValidatorReconciliation {
+                    epoch_height: current_epoch_height,
+                    staked_near_amount,
+                    unstaked_near_amount,
+                    recorded_staked_near_amount: validator.balance.classic_near_amount + validator.balance.investment_near_amount,
+                    recorded_unstaked_near_amount: validator.balance.get_unstaked_balance()
+                };
+
+                env::log_str(
+                    format!(
+                        "
+                        Reconciling validator @{} in {} epoch.
+                        Recorded staked Near amount on validator is {} yoctoNear.
+                        Received staked Near amount from validator is {} yoctoNear.
+                        Recorded unstaked Near amount on validator is {} yoctoNear.
+                        Received unstaked Near amount from validator is {} yoctoNear.
+                        ",
+                        validator_account_id,
+                        current_epoch_height,
+                        validator_reconciliation.recorded_staked_near_amount,
+                        validator_reconciliation.staked_near_amount,
+                        validator_reconciliation.recorded_unstaked_near_amount,
+                        validator_reconciliation.unstaked_near_amount
+                    ).as_str()
+                );
+
+                validator.last_reconciliation = Some(validator_reconciliation);
+                self.validating.validator_registry.insert(&validator_account_id, &validator);
+
+                CallbackResult {
+                    is_success: true,
+                    network_epoch_height: env::epoch_height()
+                }
+            }
+            _ => {
+                CallbackResult {
+                    is_success: false,
+                    network_epoch_height: env::epoch_height()
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "state_dump")]
+pub mod state_dump;
+
+#[cfg(test)]
+mod tests;
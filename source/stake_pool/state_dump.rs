@@ -0,0 +1,662 @@
+//! Offline decoder of the exported contract state.
+//! The export is the result of the 'view_state' RPC method: key/value pairs encoded as base64.
+//! Each key is decoded by the 'StorageKey' prefix into the types of the crate and printed as JSON.
+//! Keys, that can not be decoded, and entries, that are not reachable from the contract root, are reported as issues.
+
+use std::collections::{HashMap, HashSet};
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::{AccountId, Balance};
+use crate::account_balance::{AccountBalance, AccountBalanceV1};
+use crate::delayed_withdrawal::{DelayedWithdrawal, DelayedWithdrawalV1};
+use crate::epoch_report::EpochReport;
+use crate::fee::Fee;
+use crate::investment_withdrawal::InvestmentWithdrawal;
+use crate::investor_investment::InvestorInvestment;
+use crate::referrer::Referrer;
+use crate::storage_key::StorageKey;
+use crate::validator::Validator;
+use crate::validator_performance::ValidatorPerformance;
+use crate::validator_reconciliation::ValidatorReconciliation;
+use crate::{get_account_id_with_maximum_length, MAXIMUM_NUMBER_OF_EPOCH_REPORTS, MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS};
+use super::StakePool;
+
+/// Key of the contract root in the storage.
+const STATE_KEY: &[u8] = b"STATE";
+/// Suffixes of the prefixes of the 'UnorderedMap' parts.
+const UNORDERED_MAP_KEY_INDEX_SUFFIX: u8 = b'i';
+const UNORDERED_MAP_KEYS_SUFFIX: u8 = b'k';
+const UNORDERED_MAP_VALUES_SUFFIX: u8 = b'v';
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct StateItem {
+    key: Base64VecU8,
+    value: Base64VecU8
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct ViewStateResult {
+    values: Vec<StateItem>
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(untagged)]
+enum ViewStateResponse {
+    /// Full JSON RPC response.
+    Rpc { result: ViewStateResult },
+    Result(ViewStateResult)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StateDump {
+    pub root: Option<Value>,
+    pub entries: Vec<DecodedEntry>,
+    pub issues: Vec<Issue>
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DecodedEntry {
+    /// Base64 encoded key.
+    pub key: String,
+    /// Name of the 'StorageKey' variant or "STATE" for the contract root.
+    pub storage_key: String,
+    /// Decoded parts of the key.
+    pub key_fields: Value,
+    pub value: Value
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum IssueKind {
+    /// The entry can be decoded, but it is not reachable from the contract root.
+    Orphaned,
+    /// The key or the value can not be decoded.
+    Undecodable
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Issue {
+    /// Base64 encoded key.
+    pub key: String,
+    pub kind: IssueKind,
+    pub reason: String
+}
+
+/// Entry of the storage, that is decoded, but not yet checked for reachability.
+struct Entry {
+    key: Vec<u8>,
+    storage_key: &'static str,
+    key_fields: Value,
+    value: Value,
+    reference: Reference
+}
+
+/// Reference, that must exist in the contract state for the entry to be reachable.
+enum Reference {
+    /// Entry of the 'LookupMap', that can not be checked, because the keys are not enumerable.
+    None,
+    Investor(AccountId),
+    Validator(AccountId),
+    ValidatorPerformancePosition(AccountId, u64),
+    EpochReportPosition(u64),
+    AutoClaimPosition(u64),
+    UnorderedMapIndex(&'static str, u64)
+}
+
+/// Decodes the export of the 'view_state' RPC method, either the full JSON RPC response or its result.
+pub fn decode_view_state(json: &str) -> Result<StateDump, String> {
+    let view_state_result = match serde_json::from_str::<ViewStateResponse>(json) {
+        Ok(ViewStateResponse::Rpc { result }) => result,
+        Ok(ViewStateResponse::Result(result)) => result,
+        Err(error) => return Err(format!("State export is not valid: {}.", error))
+    };
+
+    Ok(
+        decode(
+            view_state_result.values.into_iter()
+                .map(|state_item| (state_item.key.into(), state_item.value.into()))
+                .collect()
+        )
+    )
+}
+
+/// Decodes the key/value pairs of the contract storage.
+pub fn decode(state: Vec<(Vec<u8>, Vec<u8>)>) -> StateDump {
+    let mut root: Option<StakePool> = None;
+    let mut entries: Vec<Entry> = vec![];
+    let mut issues: Vec<Issue> = vec![];
+
+    for (key, value) in state.into_iter() {
+        if key == STATE_KEY {
+            match StakePool::try_from_slice(&value) {
+                Ok(stake_pool) => root = Some(stake_pool),
+                Err(error) => issues.push(Issue::new(&key, IssueKind::Undecodable, format!("Contract root can not be decoded: {}.", error)))
+            }
+
+            continue;
+        }
+
+        match decode_entry(&key, &value) {
+            Ok(entry) => entries.push(entry),
+            Err(reason) => issues.push(Issue::new(&key, IssueKind::Undecodable, reason))
+        }
+    }
+
+    let root = match root {
+        Some(root_) => root_,
+        None => {
+            issues.push(Issue::new(STATE_KEY, IssueKind::Undecodable, "Contract root is absent.".to_string()));
+
+            return StateDump {
+                root: None,
+                entries: entries.into_iter().map(Entry::into_decoded_entry).collect(),
+                issues
+            };
+        }
+    };
+
+    let investor_account_ids: HashSet<AccountId> = entries.iter()
+        .filter(|entry| entry.storage_key == "InvestorInvestment")
+        .filter_map(|entry| serde_json::from_value(entry.key_fields["account_id"].clone()).ok())
+        .collect();
+
+    let mut validators: HashMap<AccountId, u64> = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.storage_key == "Validator" && entry.key_fields["part"] == "value") {
+        if let Some(account_id) = find_unordered_map_key(&entries, "Validator", &entry.key_fields["index"]) {
+            validators.insert(account_id, serde_json::from_value(entry.value["performance_records_quantity"].clone()).unwrap_or(0));
+        }
+    }
+
+    // The storage usage per additional entry is measured on initialization by inserting the entries for the account
+    // with the maximum length, that are never removed.
+    let measurement_account_id = Value::String(get_account_id_with_maximum_length().to_string());
+
+    for entry in entries.iter() {
+        let is_measurement_entry = entry.key_fields.as_object()
+            .map(|key_fields| key_fields.values().any(|key_field| *key_field == measurement_account_id))
+            .unwrap_or(false)
+            || entry.value.get("account_id") == Some(&measurement_account_id);
+        if is_measurement_entry {
+            issues.push(Issue::new(&entry.key, IssueKind::Orphaned, "Entry is left by the storage usage measurement on initialization.".to_string()));
+
+            continue;
+        }
+
+        let reason = match entry.reference {
+            Reference::None => None,
+            Reference::Investor(ref investor_account_id) => {
+                if investor_account_ids.contains(investor_account_id) {
+                    None
+                } else {
+                    Some(format!("Investor @{} is not registered.", investor_account_id))
+                }
+            }
+            Reference::Validator(ref validator_account_id) => {
+                if validators.contains_key(validator_account_id) {
+                    None
+                } else {
+                    Some(format!("Validator @{} is not registered.", validator_account_id))
+                }
+            }
+            Reference::ValidatorPerformancePosition(ref validator_account_id, position) => {
+                match validators.get(validator_account_id) {
+                    Some(performance_records_quantity) => {
+                        if position < (*performance_records_quantity).min(MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS) {
+                            None
+                        } else {
+                            Some(format!("Performance record {} of validator @{} is out of the ring buffer.", position, validator_account_id))
+                        }
+                    }
+                    None => Some(format!("Validator @{} is not registered.", validator_account_id))
+                }
+            }
+            Reference::EpochReportPosition(position) => {
+                if position < root.epoch_history.epoch_reports_quantity.min(MAXIMUM_NUMBER_OF_EPOCH_REPORTS) {
+                    None
+                } else {
+                    Some(format!("Epoch report {} is out of the ring buffer.", position))
+                }
+            }
+            Reference::AutoClaimPosition(position) => {
+                let delayed_withdrawn_fund = &root.fund.delayed_withdrawn_fund;
+
+                if delayed_withdrawn_fund.auto_claim_queue_head <= position && position < delayed_withdrawn_fund.auto_claim_queue_tail {
+                    None
+                } else {
+                    Some(format!("Auto claim {} is out of the queue.", position))
+                }
+            }
+            Reference::UnorderedMapIndex(storage_key, index) => {
+                let length = if storage_key == "Validator" {
+                    root.validating.validator_registry.len()
+                } else {
+                    root.referral.referrer_registry.len()
+                };
+
+                if index >= length {
+                    Some(format!("Index {} is out of the {} registry of length {}.", index, storage_key, length))
+                } else if entry.key_fields["part"] == "key_index"
+                    && find_unordered_map_key(&entries, storage_key, &json!(index)).map(|account_id| json!(account_id)) != Some(entry.key_fields["account_id"].clone()) {
+                    Some(format!("Index {} of the {} registry belongs to another key.", index, storage_key))
+                } else {
+                    None
+                }
+            }
+        };
+
+        if let Some(reason) = reason {
+            issues.push(Issue::new(&entry.key, IssueKind::Orphaned, reason));
+        }
+    }
+
+    StateDump {
+        root: Some(convert_stake_pool(&root)),
+        entries: entries.into_iter().map(Entry::into_decoded_entry).collect(),
+        issues
+    }
+}
+
+impl Entry {
+    fn into_decoded_entry(self) -> DecodedEntry {
+        DecodedEntry {
+            key: encode_base64(&self.key),
+            storage_key: self.storage_key.to_string(),
+            key_fields: self.key_fields,
+            value: self.value
+        }
+    }
+}
+
+impl Issue {
+    fn new(key: &[u8], kind: IssueKind, reason: String) -> Self {
+        Self {
+            key: encode_base64(key),
+            kind,
+            reason
+        }
+    }
+}
+
+/// Returns the first byte of the prefix of the storage key.
+fn get_tag(storage_key: StorageKey) -> u8 {
+    storage_key.try_to_vec().unwrap()[0]
+}
+
+fn get_storage_key_name(tag: u8) -> Option<&'static str> {
+    let account_id: AccountId = "aa".parse().unwrap();
+
+    [
+        (StorageKey::InvestorInvestment, "InvestorInvestment"),
+        (StorageKey::FungibleToken, "FungibleToken"),
+        (StorageKey::FungibleTokenMetadata, "FungibleTokenMetadata"),
+        (StorageKey::Validator, "Validator"),
+        (StorageKey::DelayedWithdrawnFund, "DelayedWithdrawnFund"),
+        (StorageKey::Distribution { investor_account_id: account_id.clone() }, "Distribution"),
+        (StorageKey::InvestmentWithdrawal, "InvestmentWithdrawal"),
+        (StorageKey::EpochReport, "EpochReport"),
+        (StorageKey::ValidatorPerformance { validator_account_id: account_id }, "ValidatorPerformance"),
+        (StorageKey::Referrer, "Referrer"),
+        (StorageKey::AutoClaim, "AutoClaim"),
+        (StorageKey::PendingRefund, "PendingRefund"),
+        (StorageKey::DelayedWithdrawal, "DelayedWithdrawal"),
+        (StorageKey::AccountBalance, "AccountBalance")
+    ].into_iter()
+        .find_map(|(storage_key, name)| if get_tag(storage_key) == tag { Some(name) } else { None })
+}
+
+fn decode_entry(key: &[u8], value: &[u8]) -> Result<Entry, String> {
+    let (tag, key_rest) = match key.split_first() {
+        Some(split) => split,
+        None => return Err("Key is empty.".to_string())
+    };
+
+    let storage_key = match get_storage_key_name(*tag) {
+        Some(storage_key_) => storage_key_,
+        None => return Err(format!("Prefix {} does not match any storage key.", tag))
+    };
+
+    let (key_fields, value, reference) = match storage_key {
+        "InvestorInvestment" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let investor_investment: InvestorInvestment = deserialize(value, "value")?;
+
+            (json!({ "account_id": account_id }), convert_investor_investment(&investor_investment), Reference::None)
+        }
+        "FungibleToken" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let account_balance: AccountBalanceV1 = deserialize(value, "value")?;
+
+            (json!({ "account_id": account_id }), convert_account_balance(&account_balance.migrate()), Reference::None)
+        }
+        "AccountBalance" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let account_balance: AccountBalance = deserialize(value, "value")?;
+
+            (json!({ "account_id": account_id }), convert_account_balance(&account_balance), Reference::None)
+        }
+        "FungibleTokenMetadata" => {
+            if !key_rest.is_empty() {
+                return Err("Key of the fungible token metadata has extra bytes.".to_string());
+            }
+            let fungible_token_metadata: FungibleTokenMetadata = deserialize(value, "value")?;
+
+            (json!({}), serde_json::to_value(&fungible_token_metadata).unwrap(), Reference::None)
+        }
+        "Validator" => decode_unordered_map_entry(
+            "Validator",
+            key_rest,
+            value,
+            |value_| deserialize::<Validator>(value_, "value").map(|validator| convert_validator(&validator))
+        )?,
+        "DelayedWithdrawnFund" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let delayed_withdrawal: DelayedWithdrawalV1 = deserialize(value, "value")?;
+
+            (json!({ "account_id": account_id }), convert_delayed_withdrawal(&delayed_withdrawal.migrate()), Reference::None)
+        }
+        "DelayedWithdrawal" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let delayed_withdrawal: DelayedWithdrawal = deserialize(value, "value")?;
+
+            (json!({ "account_id": account_id }), convert_delayed_withdrawal(&delayed_withdrawal), Reference::None)
+        }
+        "Distribution" => {
+            let (investor_account_id, validator_account_id): (AccountId, AccountId) = deserialize(key_rest, "key")?;
+            let near_amount: Balance = deserialize(value, "value")?;
+
+            (
+                json!({ "investor_account_id": investor_account_id, "validator_account_id": validator_account_id }),
+                json!({ "near_amount": near_amount.to_string() }),
+                Reference::Investor(investor_account_id)
+            )
+        }
+        "InvestmentWithdrawal" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let investment_withdrawal: InvestmentWithdrawal = deserialize(value, "value")?;
+
+            (
+                json!({ "validator_account_id": account_id }),
+                json!({ "near_amount": investment_withdrawal.near_amount.to_string(), "account_id": investment_withdrawal.account_id }),
+                Reference::Validator(account_id)
+            )
+        }
+        "EpochReport" => {
+            let position: u64 = deserialize(key_rest, "key")?;
+            let epoch_report: EpochReport = deserialize(value, "value")?;
+
+            (json!({ "position": position }), convert_epoch_report(&epoch_report), Reference::EpochReportPosition(position))
+        }
+        "ValidatorPerformance" => {
+            let (validator_account_id, position): (AccountId, u64) = deserialize(key_rest, "key")?;
+            let validator_performance: ValidatorPerformance = deserialize(value, "value")?;
+
+            (
+                json!({ "validator_account_id": validator_account_id, "position": position }),
+                convert_validator_performance(&validator_performance),
+                Reference::ValidatorPerformancePosition(validator_account_id, position)
+            )
+        }
+        "Referrer" => decode_unordered_map_entry(
+            "Referrer",
+            key_rest,
+            value,
+            |value_| deserialize::<Referrer>(value_, "value").map(|referrer| convert_referrer(&referrer))
+        )?,
+        "AutoClaim" => {
+            let position: u64 = deserialize(key_rest, "key")?;
+            let account_id: AccountId = deserialize(value, "value")?;
+
+            (json!({ "position": position }), json!({ "account_id": account_id }), Reference::AutoClaimPosition(position))
+        }
+        "PendingRefund" => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let near_amount: Balance = deserialize(value, "value")?;
+
+            (json!({ "account_id": account_id }), json!({ "near_amount": near_amount.to_string() }), Reference::None)
+        }
+        _ => return Err(format!("Storage key {} is not supported.", storage_key))
+    };
+
+    Ok(
+        Entry {
+            key: key.to_vec(),
+            storage_key,
+            key_fields,
+            value,
+            reference
+        }
+    )
+}
+
+/// 'UnorderedMap' consists of the index of the keys and two vectors of the keys and the values.
+fn decode_unordered_map_entry(
+    storage_key: &'static str,
+    key_rest: &[u8],
+    value: &[u8],
+    decode_value: impl FnOnce(&[u8]) -> Result<Value, String>
+) -> Result<(Value, Value, Reference), String> {
+    let (suffix, key_rest) = match key_rest.split_first() {
+        Some(split) => split,
+        None => return Err(format!("Key of the {} registry has no suffix.", storage_key))
+    };
+
+    match *suffix {
+        UNORDERED_MAP_KEY_INDEX_SUFFIX => {
+            let account_id: AccountId = deserialize(key_rest, "key")?;
+            let index: u64 = deserialize(value, "value")?;
+
+            Ok((json!({ "part": "key_index", "account_id": account_id }), json!({ "index": index }), Reference::UnorderedMapIndex(storage_key, index)))
+        }
+        UNORDERED_MAP_KEYS_SUFFIX => {
+            let index: u64 = deserialize(key_rest, "key")?;
+            let account_id: AccountId = deserialize(value, "value")?;
+
+            Ok((json!({ "part": "key", "index": index }), json!({ "account_id": account_id }), Reference::UnorderedMapIndex(storage_key, index)))
+        }
+        UNORDERED_MAP_VALUES_SUFFIX => {
+            let index: u64 = deserialize(key_rest, "key")?;
+
+            Ok((json!({ "part": "value", "index": index }), decode_value(value)?, Reference::UnorderedMapIndex(storage_key, index)))
+        }
+        _ => Err(format!("Suffix {} does not match any part of the {} registry.", suffix, storage_key))
+    }
+}
+
+fn find_unordered_map_key(entries: &[Entry], storage_key: &str, index: &Value) -> Option<AccountId> {
+    entries.iter()
+        .find(|entry| entry.storage_key == storage_key && entry.key_fields["part"] == "key" && entry.key_fields["index"] == *index)
+        .and_then(|entry| serde_json::from_value(entry.value["account_id"].clone()).ok())
+}
+
+/// Deserializes the whole slice, the remaining bytes are the error.
+fn deserialize<T: BorshDeserialize>(bytes: &[u8], part: &str) -> Result<T, String> {
+    T::try_from_slice(bytes).map_err(|error| format!("The {} can not be decoded: {}.", part, error))
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    match serde_json::to_value(Base64VecU8(bytes.to_vec())) {
+        Ok(Value::String(encoded)) => encoded,
+        _ => String::new()
+    }
+}
+
+fn convert_fee(fee: &Option<Fee>) -> Value {
+    match fee {
+        Some(fee_) => json!({ "numerator": fee_.numerator, "denominator": fee_.denominator }),
+        None => Value::Null
+    }
+}
+
+fn convert_stake_pool(stake_pool: &StakePool) -> Value {
+    let fund = &stake_pool.fund;
+    let delayed_withdrawn_fund = &fund.delayed_withdrawn_fund;
+    let validating = &stake_pool.validating;
+
+    json!({
+        "account_registry": {
+            "owner_id": stake_pool.account_registry.owner_id,
+            "manager_id": stake_pool.account_registry.manager_id
+        },
+        "fungible_token": {
+            "total_supply": stake_pool.fungible_token.total_supply.to_string(),
+            "accounts_quantity": stake_pool.fungible_token.accounts_quantity,
+            "storage_usage_per_account": stake_pool.fungible_token.storage_usage_per_account
+        },
+        "fund": {
+            "classic_unstaked_balance": fund.classic_unstaked_balance.to_string(),
+            "classic_staked_balance": fund.classic_staked_balance.to_string(),
+            "classic_liquidity_balance": fund.classic_liquidity_balance.to_string(),
+            "investment_staked_balance": fund.investment_staked_balance.to_string(),
+            "classic_remainder_balance": fund.classic_remainder_balance.to_string(),
+            "delayed_withdrawn_fund": {
+                "auto_claim_queue_head": delayed_withdrawn_fund.auto_claim_queue_head,
+                "auto_claim_queue_tail": delayed_withdrawn_fund.auto_claim_queue_tail,
+                "needed_to_request_classic_near_amount": delayed_withdrawn_fund.needed_to_request_classic_near_amount.to_string(),
+                "needed_to_request_investment_near_amount": delayed_withdrawn_fund.needed_to_request_investment_near_amount.to_string(),
+                "balance": delayed_withdrawn_fund.balance.to_string(),
+                "storage_usage_per_delayed_withdrawal": delayed_withdrawn_fund.storage_usage_per_delayed_withdrawal,
+                "storage_usage_per_investment_withdrawal": delayed_withdrawn_fund.storage_usage_per_investment_withdrawal,
+                "storage_usage_per_auto_claim": delayed_withdrawn_fund.storage_usage_per_auto_claim
+            },
+            "pending_refund_fund": {
+                "balance": fund.pending_refund_fund.balance.to_string(),
+                "storage_usage_per_pending_refund": fund.pending_refund_fund.storage_usage_per_pending_refund
+            },
+            "is_distributed_on_validators_in_current_epoch": fund.is_distributed_on_validators_in_current_epoch
+        },
+        "fee_registry": serde_json::to_value(&stake_pool.fee_registry).unwrap(),
+        "validating": {
+            "validators_quantity": validating.validators_quantity,
+            "preffered_validator": validating.preffered_validator,
+            "quantity_of_validators_updated_in_current_epoch": validating.quantity_of_validators_updated_in_current_epoch,
+            "storage_usage_per_validator": validating.storage_usage_per_validator,
+            "storage_usage_per_investor_investment": validating.storage_usage_per_investor_investment,
+            "storage_usage_per_distribution": validating.storage_usage_per_distribution
+        },
+        "current_epoch_height": stake_pool.current_epoch_height,
+        "reward": {
+            "previous_epoch_rewards_from_validators_near_amount": stake_pool.reward.previous_epoch_rewards_from_validators_near_amount.to_string(),
+            "total_rewards_from_validators_near_amount": stake_pool.reward.total_rewards_from_validators_near_amount.to_string()
+        },
+        "epoch_history": {
+            "epoch_reports_quantity": stake_pool.epoch_history.epoch_reports_quantity,
+            "deposited_near_amount": stake_pool.epoch_history.deposited_near_amount.to_string(),
+            "withdrawn_near_amount": stake_pool.epoch_history.withdrawn_near_amount.to_string()
+        },
+        "deposit_caps": {
+            "pool_near_amount": stake_pool.deposit_caps.pool_near_amount.map(|near_amount| near_amount.to_string()),
+            "validator_share": convert_fee(&stake_pool.deposit_caps.validator_share),
+            "account_near_amount": stake_pool.deposit_caps.account_near_amount.map(|near_amount| near_amount.to_string())
+        },
+        "pool_config": serde_json::to_value(StakePool::convert_pool_config_to_dto(&stake_pool.pool_config)).unwrap(),
+        "pending_pool_config": stake_pool.pending_pool_config.as_ref().map(
+            |pending_pool_config| json!({
+                "pool_config": serde_json::to_value(StakePool::convert_pool_config_to_dto(&pending_pool_config.pool_config)).unwrap(),
+                "activation_epoch_height": pending_pool_config.activation_epoch_height
+            })
+        ),
+        "referral": {
+            "referrers_quantity": stake_pool.referral.referrer_registry.len(),
+            "referred_token_amount": stake_pool.referral.referred_token_amount.to_string(),
+            "referral_fee_per_token": stake_pool.referral.referral_fee_per_token.to_string(),
+            "unclaimed_token_amount": stake_pool.referral.unclaimed_token_amount.to_string(),
+            "storage_usage_per_referrer": stake_pool.referral.storage_usage_per_referrer
+        }
+    })
+}
+
+fn convert_account_balance(account_balance: &AccountBalance) -> Value {
+    json!({
+        "token_amount": account_balance.token_amount.to_string(),
+        "classic_near_amount": account_balance.classic_near_amount.to_string(),
+        "investment_near_amount": account_balance.investment_near_amount.to_string(),
+        "referrer_id": account_balance.referrer_id
+    })
+}
+
+fn convert_investor_investment(investor_investment: &InvestorInvestment) -> Value {
+    json!({
+        "distributions_quantity": investor_investment.distributions_quantity,
+        "staked_balance": investor_investment.staked_balance.to_string()
+    })
+}
+
+fn convert_validator_reconciliation(validator_reconciliation: &ValidatorReconciliation) -> Value {
+    json!({
+        "epoch_height": validator_reconciliation.epoch_height,
+        "staked_near_amount": validator_reconciliation.staked_near_amount.to_string(),
+        "unstaked_near_amount": validator_reconciliation.unstaked_near_amount.to_string(),
+        "recorded_staked_near_amount": validator_reconciliation.recorded_staked_near_amount.to_string(),
+        "recorded_unstaked_near_amount": validator_reconciliation.recorded_unstaked_near_amount.to_string()
+    })
+}
+
+fn convert_validator(validator: &Validator) -> Value {
+    json!({
+        "balance": {
+            "classic_near_amount": validator.balance.classic_near_amount.to_string(),
+            "investment_near_amount": validator.balance.investment_near_amount.to_string(),
+            "requested_to_withdrawal_near_amount": validator.balance.requested_to_withdrawal_near_amount.to_string(),
+            "drained_near_amount": validator.balance.drained_near_amount.to_string()
+        },
+        "staking_contract_version": serde_json::to_value(&validator.staking_contract_version).unwrap(),
+        "is_only_for_investment": validator.is_only_for_investment,
+        "last_update_epoch_height": validator.last_update_epoch_height,
+        "last_classic_stake_increasing_epoch_height": validator.last_classic_stake_increasing_epoch_height,
+        "last_reconciliation": validator.last_reconciliation.as_ref().map(convert_validator_reconciliation),
+        "is_active": validator.is_active,
+        "consecutive_inactive_epoch_quantity": validator.consecutive_inactive_epoch_quantity,
+        "is_draining": validator.is_draining,
+        "performance_records_quantity": validator.performance_records_quantity
+    })
+}
+
+fn convert_delayed_withdrawal(delayed_withdrawal: &DelayedWithdrawal) -> Value {
+    json!({
+        "near_amount": delayed_withdrawal.near_amount.to_string(),
+        "started_epoch_height": delayed_withdrawal.started_epoch_height,
+        "epoch_quantity_for_delayed_withdrawal": delayed_withdrawal.epoch_quantity_for_delayed_withdrawal,
+        "is_auto_claim": delayed_withdrawal.is_auto_claim
+    })
+}
+
+fn convert_epoch_report(epoch_report: &EpochReport) -> Value {
+    json!({
+        "epoch_height": epoch_report.epoch_height,
+        "common_balance": epoch_report.common_balance.to_string(),
+        "total_supply": epoch_report.total_supply.to_string(),
+        "rewards_from_validators_near_amount": epoch_report.rewards_from_validators_near_amount.to_string(),
+        "reward_fee_token_amount": epoch_report.reward_fee_token_amount.to_string(),
+        "deposited_near_amount": epoch_report.deposited_near_amount.to_string(),
+        "withdrawn_near_amount": epoch_report.withdrawn_near_amount.to_string()
+    })
+}
+
+fn convert_validator_performance(validator_performance: &ValidatorPerformance) -> Value {
+    json!({
+        "epoch_height": validator_performance.epoch_height,
+        "is_active": validator_performance.is_active,
+        "validator_stake": validator_performance.validator_stake.to_string(),
+        "near_amount": validator_performance.near_amount.to_string(),
+        "rewards_near_amount": validator_performance.rewards_near_amount.to_string()
+    })
+}
+
+fn convert_referrer(referrer: &Referrer) -> Value {
+    json!({
+        "referred_token_amount": referrer.referred_token_amount.to_string(),
+        "referred_accounts_quantity": referrer.referred_accounts_quantity,
+        "earned_token_amount": referrer.earned_token_amount.to_string(),
+        "unclaimed_token_amount": referrer.unclaimed_token_amount.to_string(),
+        "referral_fee_per_token": referrer.referral_fee_per_token.to_string()
+    })
+}
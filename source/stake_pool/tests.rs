@@ -0,0 +1,1099 @@
+use std::collections::HashMap;
+use near_sdk::mock::{Receipt, VmAction};
+use near_sdk::serde::Deserialize;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+use near_sdk::{testing_env, RuntimeFeesConfig, VMConfig, ONE_NEAR, ONE_YOCTO};
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap};
+use crate::account_balance::AccountBalanceV1;
+use crate::account_registry::AccountRegistryV1;
+use crate::delayed_withdrawal::DelayedWithdrawalV1;
+use crate::delayed_withdrawn_fund::DelayedWithdrawnFundV1;
+use crate::fee_registry::SharedFeeV1;
+use crate::fungible_token::FungibleTokenV1;
+use crate::storage_key::StorageKey;
+use crate::validator::ValidatorV1;
+use crate::validator_balance::ValidatorBalanceV1;
+use crate::EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL;
+use crate::get_account_id_with_maximum_length;
+use crate::MAXIMUM_CONSECUTIVE_INACTIVE_EPOCH_QUANTITY;
+use super::*;
+
+const INITIAL_EPOCH_HEIGHT: EpochHeight = 101;
+const CONTRACT_BALANCE: Balance = 1_000_000 * ONE_NEAR;
+const REWARDS_NEAR_AMOUNT: Balance = ONE_NEAR / 10;
+// Covers the storage staking price of the epoch history.
+const INITIALIZATION_DEPOSIT: Balance = 2 * ONE_NEAR;
+
+fn pool_account_id() -> AccountId {
+    "pool.near".parse().unwrap()
+}
+
+fn owner_account_id() -> AccountId {
+    "owner.near".parse().unwrap()
+}
+
+fn validator_account_id() -> AccountId {
+    "validator.near".parse().unwrap()
+}
+
+fn user_account_id() -> AccountId {
+    "user.near".parse().unwrap()
+}
+
+fn fee_split() -> FeeSplit {
+    FeeSplit {
+        beneficiaries: vec![
+            FeeBeneficiary {
+                account_id: owner_account_id(),
+                numerator: 1,
+                payer_account_id: None
+            }
+        ],
+        denominator: 1
+    }
+}
+
+fn partner_account_id() -> AccountId {
+    "partner.near".parse().unwrap()
+}
+
+fn near_amount_result(near_amount: Balance) -> PromiseResult {
+    PromiseResult::Successful(near_sdk::serde_json::to_vec(&U128(near_amount)).unwrap())
+}
+
+fn empty_result() -> PromiseResult {
+    PromiseResult::Successful(vec![])
+}
+
+fn set_context(
+    epoch_height: EpochHeight,
+    validator_stake: Balance,
+    predecessor_account_id: AccountId,
+    attached_deposit: Balance,
+    promise_results: Vec<PromiseResult>
+) {
+    let mut validators: HashMap<String, Balance> = HashMap::new();
+    validators.insert(validator_account_id().to_string(), validator_stake);
+
+    testing_env!(
+        VMContextBuilder::new()
+            .current_account_id(pool_account_id())
+            .predecessor_account_id(predecessor_account_id.clone())
+            .signer_account_id(predecessor_account_id)
+            .attached_deposit(attached_deposit)
+            .account_balance(CONTRACT_BALANCE)
+            .epoch_height(epoch_height)
+            .prepaid_gas(Gas::ONE_TERA * MAXIMUM_NUMBER_OF_TGAS)
+            .build(),
+        VMConfig::test(),
+        RuntimeFeesConfig::test(),
+        validators,
+        promise_results
+    );
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct TransferNearAmountCallbackArguments {
+    receiver_account_id: AccountId,
+    refund_account_id: AccountId,
+    near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct DepositCallbackArguments {
+    predecessor_account_id: AccountId,
+    beneficiary_account_id: AccountId,
+    validator_account_id: AccountId,
+    attached_deposit: Balance,
+    near_amount: Balance,
+    refundable_near_amount: Balance,
+    token_amount: Balance,
+    near_remainder: Balance,
+    current_epoch_height: EpochHeight,
+    storage_staking_price_per_additional_account: Balance,
+    referrer_id: Option<AccountId>
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct DepositOnValidatorCallbackArguments {
+    predecessor_account_id: AccountId,
+    validator_account_id: AccountId,
+    near_amount: Balance,
+    attached_deposit: Balance,
+    refundable_near_amount: Balance,
+    token_amount: Balance,
+    near_remainder: Balance,
+    storage_staking_price_per_additional_accounts: Balance,
+    referrer_id: Option<AccountId>
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct IncreaseValidatorStakeCallbackArguments {
+    validator_account_id: AccountId,
+    near_amount: Balance,
+    current_epoch_height: EpochHeight
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct RequestedDecreaseValidatorStakeCallbackArguments {
+    validator_account_id: AccountId,
+    near_amount: Balance,
+    stake_decreasing_type: StakeDecreasingType,
+    refundable_near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct TakeUnstakedBalanceCallbackArguments {
+    validator_account_id: AccountId,
+    requested_to_withdrawal_near_amount: Balance,
+    drained_near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct DrainValidatorCallbackArguments {
+    validator_account_id: AccountId,
+    near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct ValidatorCallbackArguments {
+    validator_account_id: AccountId,
+    current_epoch_height: EpochHeight
+}
+
+/// Drives the pool through the mocked blockchain. Each step is executed in a new context, and the callbacks
+/// scheduled by the step are resolved with the scripted promise results.
+struct Harness {
+    stake_pool: StakePool,
+    epoch_height: EpochHeight,
+    validator_stake: Balance,
+    receipts: Vec<Receipt>
+}
+
+impl Harness {
+    fn new() -> Self {
+        set_context(INITIAL_EPOCH_HEIGHT, ONE_NEAR, owner_account_id(), INITIALIZATION_DEPOSIT, vec![]);
+
+        let stake_pool = StakePool::new(
+            FungibleTokenMetadataDto {
+                name: "Staked Near".to_string(),
+                symbol: "stNEAR".to_string(),
+                icon: None,
+                reference: None,
+                reference_hash: None,
+                decimals: 24
+            },
+            None,
+            None,
+            fee_split(),
+            None,
+            fee_split(),
+            None
+        );
+
+        Self {
+            stake_pool,
+            epoch_height: INITIAL_EPOCH_HEIGHT,
+            validator_stake: ONE_NEAR,
+            receipts: vec![]
+        }
+    }
+
+    /// Executes the method and keeps the receipts created by it, so the scheduled callbacks can be resolved.
+    fn call<R>(&mut self, predecessor_account_id: AccountId, attached_deposit: Balance, method: impl FnOnce(&mut StakePool) -> R) {
+        set_context(self.epoch_height, self.validator_stake, predecessor_account_id, attached_deposit, vec![]);
+        drop(method(&mut self.stake_pool));
+        self.receipts = get_created_receipts();
+    }
+
+    /// Executes the scheduled callback with the arguments serialized by the contract.
+    fn resolve(&mut self, method_name: &str, promise_results: Vec<PromiseResult>) {
+        let arguments = match self.find_callback_arguments(method_name) {
+            Some(arguments_) => arguments_,
+            None => panic!("Callback {} is not scheduled.", method_name)
+        };
+
+        set_context(self.epoch_height, self.validator_stake, pool_account_id(), 0, promise_results);
+
+        let stake_pool = &mut self.stake_pool;
+        match method_name {
+            "transfer_near_amount_callback" => {
+                let arguments: TransferNearAmountCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.transfer_near_amount_callback(arguments.receiver_account_id, arguments.refund_account_id, arguments.near_amount);
+            }
+            "deposit_callback" => {
+                let arguments: DepositCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.deposit_callback(
+                    arguments.predecessor_account_id,
+                    arguments.beneficiary_account_id,
+                    arguments.validator_account_id,
+                    arguments.attached_deposit,
+                    arguments.near_amount,
+                    arguments.refundable_near_amount,
+                    arguments.token_amount,
+                    arguments.near_remainder,
+                    arguments.current_epoch_height,
+                    arguments.storage_staking_price_per_additional_account,
+                    arguments.referrer_id
+                );
+            }
+            "deposit_on_validator_callback" => {
+                let arguments: DepositOnValidatorCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.deposit_on_validator_callback(
+                    arguments.predecessor_account_id,
+                    arguments.validator_account_id,
+                    arguments.near_amount,
+                    arguments.attached_deposit,
+                    arguments.refundable_near_amount,
+                    arguments.token_amount,
+                    arguments.near_remainder,
+                    arguments.storage_staking_price_per_additional_accounts,
+                    arguments.referrer_id
+                );
+            }
+            "increase_validator_stake_callback" => {
+                let arguments: IncreaseValidatorStakeCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.increase_validator_stake_callback(arguments.validator_account_id, arguments.near_amount, arguments.current_epoch_height);
+            }
+            "requested_decrease_validator_stake_callback_1" => {
+                let arguments: RequestedDecreaseValidatorStakeCallbackArguments = Self::deserialize(&arguments);
+
+                drop(
+                    stake_pool.requested_decrease_validator_stake_callback_1(
+                        arguments.validator_account_id,
+                        arguments.near_amount,
+                        arguments.stake_decreasing_type,
+                        arguments.refundable_near_amount
+                    )
+                );
+            }
+            "requested_decrease_validator_stake_callback_2" => {
+                let arguments: RequestedDecreaseValidatorStakeCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.requested_decrease_validator_stake_callback_2(
+                    arguments.validator_account_id,
+                    arguments.near_amount,
+                    arguments.stake_decreasing_type,
+                    arguments.refundable_near_amount
+                );
+            }
+            "take_unstaked_balance_callback" => {
+                let arguments: TakeUnstakedBalanceCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.take_unstaked_balance_callback(
+                    arguments.validator_account_id,
+                    arguments.requested_to_withdrawal_near_amount,
+                    arguments.drained_near_amount
+                );
+            }
+            "drain_validator_callback" => {
+                let arguments: DrainValidatorCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.drain_validator_callback(arguments.validator_account_id, arguments.near_amount);
+            }
+            "update_validator_callback" => {
+                let arguments: ValidatorCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.update_validator_callback(arguments.validator_account_id, arguments.current_epoch_height);
+            }
+            "reconcile_validator_callback" => {
+                let arguments: ValidatorCallbackArguments = Self::deserialize(&arguments);
+
+                stake_pool.reconcile_validator_callback(arguments.validator_account_id, arguments.current_epoch_height);
+            }
+            _ => panic!("Callback {} is not supported.", method_name)
+        }
+
+        self.receipts = get_created_receipts();
+    }
+
+    fn is_scheduled(&self, method_name: &str) -> bool {
+        self.find_callback_arguments(method_name).is_some()
+    }
+
+    fn find_callback_arguments(&self, method_name: &str) -> Option<Vec<u8>> {
+        self.receipts.iter()
+            .filter(|receipt| receipt.receiver_id == pool_account_id())
+            .flat_map(|receipt| receipt.actions.iter())
+            .find_map(
+                |action| match action {
+                    VmAction::FunctionCall { function_name, args, .. } if function_name == method_name => Some(args.clone()),
+                    _ => None
+                }
+            )
+    }
+
+    fn find_transfer(&self, account_id: &AccountId) -> Option<Balance> {
+        self.receipts.iter()
+            .filter(|receipt| receipt.receiver_id == *account_id)
+            .flat_map(|receipt| receipt.actions.iter())
+            .find_map(
+                |action| match action {
+                    VmAction::Transfer { deposit } => Some(*deposit),
+                    _ => None
+                }
+            )
+    }
+
+    fn deserialize<T: DeserializeOwned>(arguments: &[u8]) -> T {
+        near_sdk::serde_json::from_slice(arguments).unwrap()
+    }
+
+    fn advance_epoch(&mut self) {
+        self.epoch_height += 1;
+    }
+
+    /// Advances epochs until the pool is allowed to request unstake from validators.
+    fn advance_to_right_epoch(&mut self) {
+        self.advance_epoch();
+        while !self.stake_pool.is_right_epoch(self.epoch_height) {
+            self.advance_epoch();
+        }
+    }
+
+    fn add_validator(&mut self, is_only_for_investment: bool) {
+        self.call(
+            owner_account_id(),
+            ONE_NEAR,
+            |stake_pool| stake_pool.add_validator(validator_account_id(), StakingContractVersion::Core, is_only_for_investment, !is_only_for_investment)
+        );
+    }
+
+    fn deposit(&mut self, near_amount: Balance) {
+        self.call(user_account_id(), near_amount + ONE_NEAR, |stake_pool| stake_pool.deposit(near_amount.into(), None, None));
+    }
+
+    /// Deposits to the pool and stakes the Near amount on the validator.
+    fn deposit_and_stake(&mut self, near_amount: Balance) {
+        self.deposit(near_amount);
+        self.call(owner_account_id(), 0, |stake_pool| stake_pool.increase_validator_stake(validator_account_id(), near_amount.into()));
+        self.resolve("increase_validator_stake_callback", vec![empty_result()]);
+    }
+
+    fn delayed_withdraw(&mut self, token_amount: Balance) {
+        self.call(user_account_id(), ONE_NEAR, |stake_pool| stake_pool.delayed_withdraw(token_amount.into(), None));
+    }
+
+    /// Updates the validator with the rewards and the pool after that, so the epoch becomes synchronized.
+    fn update_validator(&mut self, rewards_near_amount: Balance) {
+        let validator_balance = self.validator().balance.get_balance();
+
+        self.call(owner_account_id(), 0, |stake_pool| stake_pool.update_validator(validator_account_id()));
+        self.resolve("update_validator_callback", vec![near_amount_result(validator_balance + rewards_near_amount)]);
+    }
+
+    fn synchronize_epoch(&mut self, rewards_near_amount: Balance) {
+        self.update_validator(rewards_near_amount);
+        self.call(owner_account_id(), 0, |stake_pool| stake_pool.update());
+    }
+
+    fn validator(&self) -> Validator {
+        self.stake_pool.validating.validator_registry.get(&validator_account_id()).unwrap()
+    }
+
+    fn token_amount(&self, account_id: &AccountId) -> Balance {
+        match self.stake_pool.fungible_token.get_account_balance(account_id) {
+            Some(account_balance) => account_balance.token_amount,
+            None => 0
+        }
+    }
+}
+
+#[test]
+fn transfer_near_amount_callback_ignores_successful_transfer() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    assert!(harness.find_transfer(&owner_account_id()).is_some());
+
+    harness.resolve("transfer_near_amount_callback", vec![empty_result()]);
+
+    assert_eq!(harness.stake_pool.fund.pending_refund_fund.balance, 0);
+}
+
+#[test]
+fn transfer_near_amount_callback_records_failed_transfer() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    let refund_near_amount = harness.find_transfer(&owner_account_id()).unwrap();
+
+    harness.resolve("transfer_near_amount_callback", vec![PromiseResult::Failed]);
+
+    // The storage staking of the pending refund is reserved from the refunded Near amount.
+    let pending_refund = refund_near_amount
+        - StakePool::calculate_storage_staking_price(harness.stake_pool.fund.pending_refund_fund.storage_usage_per_pending_refund);
+    assert_eq!(harness.stake_pool.fund.pending_refund_fund.balance, pending_refund);
+    assert_eq!(harness.stake_pool.fund.pending_refund_fund.pending_refunds.get(&owner_account_id()), Some(pending_refund));
+}
+
+#[test]
+fn transfer_near_amount_callback_records_failed_transfer_to_payer() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit(10 * ONE_NEAR);
+
+    harness.call(
+        user_account_id(),
+        ONE_YOCTO,
+        |stake_pool| stake_pool.instant_withdraw_to(validator_account_id(), U128(ONE_NEAR), None)
+    );
+    harness.resolve("transfer_near_amount_callback", vec![PromiseResult::Failed]);
+
+    assert!(harness.stake_pool.fund.pending_refund_fund.pending_refunds.get(&validator_account_id()).is_none());
+    assert!(harness.stake_pool.fund.pending_refund_fund.pending_refunds.get(&user_account_id()).is_some());
+}
+
+#[test]
+fn transfer_near_amount_callback_keeps_dust_on_pool() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.call(owner_account_id(), 0, |_| StakePool::transfer_near_amount_to(user_account_id(), user_account_id(), ONE_YOCTO));
+
+    harness.resolve("transfer_near_amount_callback", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.stake_pool.fund.pending_refund_fund.balance, 0);
+    assert!(harness.stake_pool.fund.pending_refund_fund.pending_refunds.get(&user_account_id()).is_none());
+}
+
+#[test]
+fn deposit_without_distribution_does_not_schedule_callback() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+
+    harness.deposit(10 * ONE_NEAR);
+
+    assert!(!harness.is_scheduled("deposit_callback"));
+    assert_eq!(harness.stake_pool.fund.classic_unstaked_balance, 10 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fungible_token.total_supply, 10 * ONE_NEAR);
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+}
+
+#[test]
+fn deposit_remainder_is_kept_outside_common_balance() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.advance_epoch();
+    harness.synchronize_epoch(REWARDS_NEAR_AMOUNT);
+
+    let near_amount = ONE_NEAR;
+    let common_balance = harness.stake_pool.fund.get_common_balance();
+    let (token_amount, remainder_near_amount) = core::convert_near_amount_to_token_amount(near_amount, common_balance, harness.stake_pool.fungible_token.total_supply);
+    assert!(remainder_near_amount > 0);
+
+    harness.deposit(near_amount);
+
+    assert_eq!(harness.stake_pool.fund.classic_remainder_balance, remainder_near_amount);
+    assert_eq!(harness.stake_pool.fund.get_common_balance(), common_balance + near_amount - remainder_near_amount);
+
+    harness.call(user_account_id(), ONE_YOCTO, |stake_pool| stake_pool.instant_withdraw(token_amount.into(), None));
+
+    assert_eq!(harness.stake_pool.fund.classic_remainder_balance, 0);
+    assert_eq!(harness.stake_pool.fungible_token.get_account_balance(&user_account_id()).unwrap().classic_near_amount, 0);
+}
+
+#[test]
+fn deposit_for_binds_referrer_chosen_by_beneficiary() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.call(owner_account_id(), ONE_NEAR, |stake_pool| stake_pool.add_referrer(partner_account_id()));
+
+    harness.call(
+        user_account_id(),
+        11 * ONE_NEAR,
+        |stake_pool| stake_pool.deposit_for(user_account_id(), U128(10 * ONE_NEAR), None, Some(partner_account_id()))
+    );
+
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fungible_token.get_account_balance(&user_account_id()).unwrap().referrer_id, Some(partner_account_id()));
+}
+
+#[test]
+fn referral_fee_is_accrued_lazily_until_claim() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.stake_pool.fee_registry.reward_fee = Some(Fee { numerator: 1, denominator: 10 });
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.change_referral_fee(Some(Fee { numerator: 1, denominator: 10 })));
+    harness.call(owner_account_id(), ONE_NEAR, |stake_pool| stake_pool.add_referrer(partner_account_id()));
+    harness.call(
+        user_account_id(),
+        11 * ONE_NEAR,
+        |stake_pool| stake_pool.deposit(U128(10 * ONE_NEAR), None, Some(partner_account_id()))
+    );
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.increase_validator_stake(validator_account_id(), U128(10 * ONE_NEAR)));
+    harness.resolve("increase_validator_stake_callback", vec![empty_result()]);
+    harness.advance_epoch();
+    harness.synchronize_epoch(REWARDS_NEAR_AMOUNT);
+
+    // The update mints the referral fee, but does not credit the token account of the referrer.
+    let unclaimed_token_amount = harness.stake_pool.referral.unclaimed_token_amount;
+    assert!(unclaimed_token_amount > 0);
+    assert_eq!(harness.token_amount(&partner_account_id()), 0);
+    let referrer = harness.stake_pool.get_referrer(partner_account_id()).unwrap();
+    assert!(referrer.unclaimed_token_amount.0 > 0);
+    assert!(referrer.unclaimed_token_amount.0 <= unclaimed_token_amount);
+
+    harness.call(partner_account_id(), ONE_YOCTO, |stake_pool| stake_pool.claim_referral_fee());
+
+    assert_eq!(harness.token_amount(&partner_account_id()), referrer.unclaimed_token_amount.0);
+    assert_eq!(harness.stake_pool.referral.unclaimed_token_amount, unclaimed_token_amount - referrer.unclaimed_token_amount.0);
+    assert_eq!(harness.stake_pool.get_referrer(partner_account_id()).unwrap().unclaimed_token_amount.0, 0);
+}
+
+#[test]
+fn deposit_callback_stakes_on_preferred_validator() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.confirm_stake_distribution());
+
+    harness.deposit(10 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fungible_token.total_supply, 0);
+
+    harness.resolve("deposit_callback", vec![empty_result()]);
+
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 10 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fund.classic_unstaked_balance, 0);
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().last_classic_stake_increasing_epoch_height, Some(INITIAL_EPOCH_HEIGHT));
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+    assert!(harness.find_transfer(&user_account_id()).is_some());
+}
+
+#[test]
+fn deposit_callback_keeps_failed_stake_unstaked() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.confirm_stake_distribution());
+
+    harness.deposit(10 * ONE_NEAR);
+    harness.resolve("deposit_callback", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 0);
+    assert_eq!(harness.stake_pool.fund.classic_unstaked_balance, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.classic_near_amount, 0);
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fungible_token.total_supply, 10 * ONE_NEAR);
+}
+
+#[test]
+fn deposit_on_validator_callback_registers_investment() {
+    let mut harness = Harness::new();
+    harness.add_validator(true);
+    harness.call(owner_account_id(), ONE_NEAR, |stake_pool| stake_pool.add_investor(user_account_id()));
+
+    harness.call(user_account_id(), 11 * ONE_NEAR, |stake_pool| stake_pool.deposit_on_validator(U128(10 * ONE_NEAR), validator_account_id(), None));
+    harness.resolve("deposit_on_validator_callback", vec![empty_result()]);
+
+    assert_eq!(harness.stake_pool.fund.investment_staked_balance, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.investment_near_amount, 10 * ONE_NEAR);
+    let investor_investment = harness.stake_pool.validating.investor_investment_registry.get(&user_account_id()).unwrap();
+    assert_eq!(investor_investment.staked_balance, 10 * ONE_NEAR);
+    assert_eq!(investor_investment.distribution_registry.get(&validator_account_id()), Some(10 * ONE_NEAR));
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+}
+
+#[test]
+fn deposit_on_validator_callback_refunds_failed_deposit() {
+    let mut harness = Harness::new();
+    harness.add_validator(true);
+    harness.call(owner_account_id(), ONE_NEAR, |stake_pool| stake_pool.add_investor(user_account_id()));
+
+    harness.call(user_account_id(), 11 * ONE_NEAR, |stake_pool| stake_pool.deposit_on_validator(U128(10 * ONE_NEAR), validator_account_id(), None));
+    harness.resolve("deposit_on_validator_callback", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.stake_pool.fund.investment_staked_balance, 0);
+    assert_eq!(harness.validator().balance.investment_near_amount, 0);
+    assert_eq!(harness.token_amount(&user_account_id()), 0);
+    assert_eq!(harness.find_transfer(&user_account_id()), Some(11 * ONE_NEAR));
+}
+
+#[test]
+fn increase_validator_stake_callback_moves_unstaked_balance() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit(10 * ONE_NEAR);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.increase_validator_stake(validator_account_id(), U128(4 * ONE_NEAR)));
+    harness.resolve("increase_validator_stake_callback", vec![empty_result()]);
+
+    assert_eq!(harness.stake_pool.fund.classic_unstaked_balance, 6 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 4 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.classic_near_amount, 4 * ONE_NEAR);
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+}
+
+#[test]
+fn increase_validator_stake_callback_keeps_state_on_failure() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit(10 * ONE_NEAR);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.increase_validator_stake(validator_account_id(), U128(4 * ONE_NEAR)));
+    harness.resolve("increase_validator_stake_callback", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.stake_pool.fund.classic_unstaked_balance, 10 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 0);
+    assert_eq!(harness.validator().balance.classic_near_amount, 0);
+    assert_eq!(harness.validator().last_classic_stake_increasing_epoch_height, None);
+}
+
+#[test]
+fn requested_decrease_validator_stake_callbacks_request_unstake() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.delayed_withdraw(4 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount, 4 * ONE_NEAR);
+
+    harness.advance_to_right_epoch();
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.requested_decrease_validator_stake(validator_account_id(), U128(4 * ONE_NEAR), StakeDecreasingType::Classic));
+    harness.resolve("requested_decrease_validator_stake_callback_1", vec![near_amount_result(0)]);
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+
+    harness.resolve("requested_decrease_validator_stake_callback_2", vec![empty_result()]);
+
+    assert_eq!(harness.validator().balance.classic_near_amount, 6 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 4 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount, 0);
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 6 * ONE_NEAR);
+    assert_eq!(harness.token_amount(&user_account_id()), 6 * ONE_NEAR);
+}
+
+#[test]
+fn requested_decrease_validator_stake_callback_1_stops_on_failure() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.delayed_withdraw(4 * ONE_NEAR);
+
+    harness.advance_to_right_epoch();
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.requested_decrease_validator_stake(validator_account_id(), U128(4 * ONE_NEAR), StakeDecreasingType::Classic));
+    harness.resolve("requested_decrease_validator_stake_callback_1", vec![PromiseResult::Failed]);
+
+    assert!(!harness.is_scheduled("requested_decrease_validator_stake_callback_2"));
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 0);
+    assert_eq!(harness.stake_pool.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount, 4 * ONE_NEAR);
+}
+
+#[test]
+fn requested_decrease_validator_stake_callback_2_keeps_state_on_failure() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.delayed_withdraw(4 * ONE_NEAR);
+
+    harness.advance_to_right_epoch();
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.requested_decrease_validator_stake(validator_account_id(), U128(4 * ONE_NEAR), StakeDecreasingType::Classic));
+    harness.resolve("requested_decrease_validator_stake_callback_1", vec![near_amount_result(0)]);
+    harness.resolve("requested_decrease_validator_stake_callback_2", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 0);
+    assert_eq!(harness.stake_pool.fund.delayed_withdrawn_fund.needed_to_request_classic_near_amount, 4 * ONE_NEAR);
+}
+
+/// Requests unstake of the delayed withdrawal and synchronizes the epoch, so the unstaked balance can be taken
+/// in the next right epoch.
+fn request_unstake(harness: &mut Harness) {
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.delayed_withdraw(4 * ONE_NEAR);
+
+    harness.advance_to_right_epoch();
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.requested_decrease_validator_stake(validator_account_id(), U128(4 * ONE_NEAR), StakeDecreasingType::Classic));
+    harness.resolve("requested_decrease_validator_stake_callback_1", vec![near_amount_result(0)]);
+    harness.resolve("requested_decrease_validator_stake_callback_2", vec![empty_result()]);
+    harness.synchronize_epoch(0);
+
+    harness.advance_to_right_epoch();
+}
+
+#[test]
+fn take_unstaked_balance_callback_fills_delayed_withdrawn_fund() {
+    let mut harness = Harness::new();
+    request_unstake(&mut harness);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.take_unstaked_balance(validator_account_id()));
+    harness.resolve("take_unstaked_balance_callback", vec![empty_result()]);
+
+    assert_eq!(harness.stake_pool.fund.delayed_withdrawn_fund.balance, 4 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 0);
+    assert_eq!(harness.validator().balance.classic_near_amount, 6 * ONE_NEAR);
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 6 * ONE_NEAR);
+
+    harness.synchronize_epoch(0);
+    assert_eq!(harness.stake_pool.current_epoch_height, harness.epoch_height);
+}
+
+#[test]
+fn take_unstaked_balance_callback_keeps_state_on_failure() {
+    let mut harness = Harness::new();
+    request_unstake(&mut harness);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.take_unstaked_balance(validator_account_id()));
+    harness.resolve("take_unstaked_balance_callback", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.stake_pool.fund.delayed_withdrawn_fund.balance, 0);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 4 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.classic_near_amount, 6 * ONE_NEAR);
+}
+
+fn request_drain(harness: &mut Harness) {
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.start_draining_validator(validator_account_id()));
+
+    harness.advance_to_right_epoch();
+    harness.update_validator(0);
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.drain_validator(validator_account_id()));
+    assert!(harness.validator().is_drain_in_flight);
+}
+
+#[test]
+fn drain_validator_callback_moves_stake_to_drained() {
+    let mut harness = Harness::new();
+    request_drain(&mut harness);
+
+    harness.resolve("drain_validator_callback", vec![empty_result()]);
+
+    assert!(!harness.validator().is_drain_in_flight);
+    assert_eq!(harness.validator().balance.classic_near_amount, 0);
+    assert_eq!(harness.validator().balance.drained_near_amount, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 0);
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 10 * ONE_NEAR);
+}
+
+#[test]
+fn drain_validator_callback_keeps_state_on_failure() {
+    let mut harness = Harness::new();
+    request_drain(&mut harness);
+
+    harness.resolve("drain_validator_callback", vec![PromiseResult::Failed]);
+
+    assert!(!harness.validator().is_drain_in_flight);
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.drained_near_amount, 0);
+}
+
+#[test]
+fn drain_validator_callback_drains_only_remaining_classic_stake() {
+    let mut harness = Harness::new();
+    request_drain(&mut harness);
+
+    // The classic stake decreasing, that is requested before the draining, is received before the callback.
+    let mut validator = harness.validator();
+    validator.balance.classic_near_amount -= 4 * ONE_NEAR;
+    validator.balance.requested_to_withdrawal_near_amount += 4 * ONE_NEAR;
+    harness.stake_pool.validating.validator_registry.insert(&validator_account_id(), &validator);
+
+    harness.resolve("drain_validator_callback", vec![empty_result()]);
+
+    assert_eq!(harness.validator().balance.classic_near_amount, 0);
+    assert_eq!(harness.validator().balance.drained_near_amount, 6 * ONE_NEAR);
+    assert_eq!(harness.validator().balance.requested_to_withdrawal_near_amount, 4 * ONE_NEAR);
+}
+
+#[test]
+fn update_validator_callback_registers_rewards() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+
+    harness.advance_epoch();
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.update_validator(validator_account_id()));
+    harness.resolve("update_validator_callback", vec![near_amount_result(10 * ONE_NEAR + REWARDS_NEAR_AMOUNT)]);
+
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR + REWARDS_NEAR_AMOUNT);
+    assert_eq!(harness.validator().last_update_epoch_height, harness.epoch_height);
+    assert_eq!(harness.stake_pool.reward.previous_epoch_rewards_from_validators_near_amount, REWARDS_NEAR_AMOUNT);
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 10 * ONE_NEAR);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.update());
+
+    assert_eq!(harness.stake_pool.fund.classic_staked_balance, 10 * ONE_NEAR + REWARDS_NEAR_AMOUNT);
+    assert_eq!(harness.stake_pool.fungible_token.total_supply, 10 * ONE_NEAR);
+    assert_eq!(harness.token_amount(&user_account_id()), 10 * ONE_NEAR);
+    assert!(harness.stake_pool.internal_get_near_per_token() > ONE_NEAR);
+}
+
+#[test]
+fn update_validator_callback_flags_inactive_validator() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+    harness.validator_stake = 0;
+
+    for _ in 0..MAXIMUM_CONSECUTIVE_INACTIVE_EPOCH_QUANTITY {
+        harness.advance_epoch();
+        harness.synchronize_epoch(0);
+    }
+
+    assert!(harness.validator().is_flagged_as_inactive());
+    assert!(harness.stake_pool.validating.preffered_validator.is_none());
+}
+
+#[test]
+fn update_validator_callback_keeps_state_on_failure() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+
+    harness.advance_epoch();
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.update_validator(validator_account_id()));
+    harness.resolve("update_validator_callback", vec![PromiseResult::Failed]);
+
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+    assert_eq!(harness.validator().last_update_epoch_height, INITIAL_EPOCH_HEIGHT);
+    assert_eq!(harness.stake_pool.validating.quantity_of_validators_updated_in_current_epoch, 0);
+    assert_eq!(harness.stake_pool.reward.previous_epoch_rewards_from_validators_near_amount, 0);
+}
+
+#[test]
+fn reconcile_validator_callback_records_reconciliation() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.reconcile_validator(validator_account_id()));
+    harness.resolve("reconcile_validator_callback", vec![near_amount_result(10 * ONE_NEAR + REWARDS_NEAR_AMOUNT), near_amount_result(0)]);
+
+    let validator_reconciliation = harness.validator().last_reconciliation.unwrap();
+    assert_eq!(validator_reconciliation.staked_near_amount, 10 * ONE_NEAR + REWARDS_NEAR_AMOUNT);
+    assert_eq!(validator_reconciliation.recorded_staked_near_amount, 10 * ONE_NEAR);
+    assert_eq!(validator_reconciliation.unstaked_near_amount, 0);
+    assert_eq!(harness.validator().balance.classic_near_amount, 10 * ONE_NEAR);
+}
+
+#[test]
+fn reconcile_validator_callback_keeps_state_on_failure() {
+    let mut harness = Harness::new();
+    harness.add_validator(false);
+    harness.deposit_and_stake(10 * ONE_NEAR);
+
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.reconcile_validator(validator_account_id()));
+    harness.resolve("reconcile_validator_callback", vec![PromiseResult::Failed, near_amount_result(0)]);
+
+    assert!(harness.validator().last_reconciliation.is_none());
+}
+
+#[test]
+fn legacy_delayed_withdrawal_keeps_its_terms() {
+    let mut harness = Harness::new();
+    harness.stake_pool.fund.delayed_withdrawn_fund.legacy_delayed_withdrawal_registry.insert(
+        &user_account_id(),
+        &DelayedWithdrawalV1 {
+            near_amount: 10 * ONE_NEAR,
+            started_epoch_height: INITIAL_EPOCH_HEIGHT - 1
+        }
+    );
+
+    let delayed_withdrawal = harness.stake_pool.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&user_account_id()).unwrap();
+    assert_eq!(delayed_withdrawal.near_amount, 10 * ONE_NEAR);
+    assert_eq!(delayed_withdrawal.epoch_quantity_for_delayed_withdrawal, EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL);
+    assert!(!delayed_withdrawal.is_auto_claim);
+
+    harness.call(user_account_id(), ONE_NEAR, |stake_pool| stake_pool.enable_auto_claim());
+
+    let delayed_withdrawn_fund = &harness.stake_pool.fund.delayed_withdrawn_fund;
+    assert!(!delayed_withdrawn_fund.legacy_delayed_withdrawal_registry.contains_key(&user_account_id()));
+    let delayed_withdrawal = delayed_withdrawn_fund.delayed_withdrawal_registry.get(&user_account_id()).unwrap();
+    assert_eq!(delayed_withdrawal.started_epoch_height, INITIAL_EPOCH_HEIGHT - 1);
+    assert_eq!(delayed_withdrawal.epoch_quantity_for_delayed_withdrawal, EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL);
+    assert!(delayed_withdrawal.is_auto_claim);
+}
+
+#[test]
+fn removed_fee_beneficiary_returns_storage_staking_price_to_payer() {
+    let mut harness = Harness::new();
+    harness.stake_pool.fee_registry.reward_fee_split.beneficiaries[0].numerator = 100;
+    harness.stake_pool.fee_registry.reward_fee_split.denominator = 100;
+    let storage_staking_price = StakePool::calculate_storage_staking_price(harness.stake_pool.fee_registry.storage_usage_per_fee_beneficiary);
+    assert!(storage_staking_price > 0);
+
+    harness.call(owner_account_id(), ONE_NEAR, |stake_pool| stake_pool.add_fee_beneficiary(FeeType::Reward, partner_account_id(), 10));
+    assert_eq!(harness.stake_pool.fee_registry.reward_fee_split.beneficiaries[0].numerator, 90);
+
+    // The deposit is returned to the payer, not to the current manager.
+    harness.call(owner_account_id(), 0, |stake_pool| stake_pool.change_manager(user_account_id()));
+    harness.call(user_account_id(), 0, |stake_pool| stake_pool.remove_fee_beneficiary(FeeType::Reward, partner_account_id()));
+
+    assert_eq!(harness.stake_pool.fee_registry.reward_fee_split.beneficiaries[0].numerator, 100);
+    assert_eq!(harness.find_transfer(&owner_account_id()), Some(storage_staking_price));
+    assert_eq!(harness.find_transfer(&user_account_id()), None);
+}
+
+#[test]
+fn initialization_deposit_covers_epoch_history() {
+    let harness = Harness::new();
+    let epoch_history = &harness.stake_pool.epoch_history;
+
+    assert!(epoch_history.storage_usage_per_epoch_report > 0);
+    assert!(StakePool::calculate_storage_staking_price(epoch_history.get_storage_usage()) <= INITIALIZATION_DEPOSIT);
+    // The measurement does not occupy the position of the first report.
+    assert!(epoch_history.epoch_report_registry.get(&0).is_none());
+}
+
+#[test]
+fn apy_is_absent_when_it_does_not_fit_into_numerator() {
+    let mut harness = Harness::new();
+
+    let epoch_report = |epoch_height: EpochHeight, common_balance: Balance, total_supply: Balance| EpochReport {
+        epoch_height,
+        common_balance,
+        total_supply,
+        rewards_from_validators_near_amount: 0,
+        reward_fee_token_amount: 0,
+        deposited_near_amount: 0,
+        withdrawn_near_amount: 0
+    };
+    // The price of the token of the empty pool is equal to 1.
+    harness.stake_pool.epoch_history.add_epoch_report(&epoch_report(INITIAL_EPOCH_HEIGHT, 0, 0));
+    harness.stake_pool.epoch_history.add_epoch_report(&epoch_report(INITIAL_EPOCH_HEIGHT + 1, 1_000_000 * ONE_NEAR, 1));
+
+    assert!(harness.stake_pool.get_apy(1).is_none());
+}
+
+#[test]
+fn migrate_converts_state_of_previous_layout() {
+    let harness = Harness::new();
+    let storage_usage_per_account = harness.stake_pool.fungible_token.storage_usage_per_account;
+    let storage_usage_per_validator = harness.stake_pool.validating.storage_usage_per_validator;
+
+    // The state of the pool, that is deployed in the previous layout, is written to the empty storage.
+    near_sdk::mock::with_mocked_blockchain(|blockchain| blockchain.take_storage());
+    set_context(INITIAL_EPOCH_HEIGHT, ONE_NEAR, pool_account_id(), 0, vec![]);
+
+    let validator = || ValidatorV1 {
+        balance: ValidatorBalanceV1 {
+            classic_near_amount: 100 * ONE_NEAR,
+            investment_near_amount: 0,
+            requested_to_withdrawal_near_amount: ONE_NEAR
+        },
+        staking_contract_version: StakingContractVersion::Core,
+        is_only_for_investment: false,
+        last_update_epoch_height: INITIAL_EPOCH_HEIGHT,
+        last_classic_stake_increasing_epoch_height: Some(INITIAL_EPOCH_HEIGHT - 1)
+    };
+    // The initialization measured the storage usage on the registries of the same prefixes.
+    let mut account_registry: LookupMap<AccountId, AccountBalanceV1> = LookupMap::new(StorageKey::FungibleToken);
+    let account_balance = |token_amount: Balance| AccountBalanceV1 { token_amount, classic_near_amount: 1, investment_near_amount: 0 };
+    account_registry.insert(&get_account_id_with_maximum_length(), &account_balance(0));
+    account_registry.insert(&user_account_id(), &account_balance(100 * ONE_NEAR));
+    // The measurement was made on the separate empty registry, so its entry remains in the index of the keys.
+    let mut measurement_validator_registry: UnorderedMap<AccountId, ValidatorV1> = UnorderedMap::new(StorageKey::Validator);
+    measurement_validator_registry.insert(&get_account_id_with_maximum_length(), &validator());
+    let mut validator_registry: UnorderedMap<AccountId, ValidatorV1> = UnorderedMap::new(StorageKey::Validator);
+    validator_registry.insert(&validator_account_id(), &validator());
+    let mut delayed_withdrawal_registry: LookupMap<AccountId, DelayedWithdrawalV1> = LookupMap::new(StorageKey::DelayedWithdrawnFund);
+    delayed_withdrawal_registry.insert(
+        &user_account_id(),
+        &DelayedWithdrawalV1 {
+            near_amount: ONE_NEAR,
+            started_epoch_height: INITIAL_EPOCH_HEIGHT - 1
+        }
+    );
+
+    env::state_write(
+        &StakePoolV1 {
+            account_registry: AccountRegistryV1 {
+                owner_id: owner_account_id(),
+                manager_id: owner_account_id(),
+                self_fee_receiver_account_id: owner_account_id(),
+                partner_fee_receiver_account_id: partner_account_id()
+            },
+            fungible_token: FungibleTokenV1 {
+                total_supply: 100 * ONE_NEAR,
+                account_registry,
+                accounts_quantity: 1,
+                metadata: LazyOption::new(StorageKey::FungibleTokenMetadata, None),
+                storage_usage_per_account: 0
+            },
+            fund: FundV1 {
+                classic_unstaked_balance: 0,
+                classic_staked_balance: 100 * ONE_NEAR,
+                classic_liquidity_balance: 0,
+                investment_staked_balance: 0,
+                delayed_withdrawn_fund: DelayedWithdrawnFundV1 {
+                    delayed_withdrawal_registry,
+                    investment_withdrawal_registry: LookupMap::new(StorageKey::InvestmentWithdrawal),
+                    needed_to_request_classic_near_amount: ONE_NEAR,
+                    needed_to_request_investment_near_amount: 0,
+                    balance: 0,
+                    storage_usage_per_delayed_withdrawal: 0,
+                    storage_usage_per_investment_withdrawal: 0
+                },
+                is_distributed_on_validators_in_current_epoch: true
+            },
+            fee_registry: FeeRegistryV1 {
+                reward_fee: Some(
+                    SharedFeeV1 {
+                        self_fee: Fee { numerator: 1, denominator: 10 },
+                        partner_fee: Some(Fee { numerator: 1, denominator: 4 })
+                    }
+                ),
+                instant_withdraw_fee: None
+            },
+            validating: ValidatingV1 {
+                validator_registry,
+                investor_investment_registry: LookupMap::new(StorageKey::InvestorInvestment),
+                validators_quantity: 1,
+                preffered_validator: Some(validator_account_id()),
+                quantity_of_validators_updated_in_current_epoch: 0,
+                storage_usage_per_validator: 0,
+                storage_usage_per_investor_investment: 0,
+                storage_usage_per_distribution: 0
+            },
+            current_epoch_height: INITIAL_EPOCH_HEIGHT,
+            reward: Reward {
+                previous_epoch_rewards_from_validators_near_amount: 0,
+                total_rewards_from_validators_near_amount: 0
+            }
+        }
+    );
+
+    set_context(INITIAL_EPOCH_HEIGHT, ONE_NEAR, pool_account_id(), INITIALIZATION_DEPOSIT, vec![]);
+    let mut stake_pool = StakePool::migrate();
+
+    let reward_fee = stake_pool.fee_registry.reward_fee.as_ref().unwrap();
+    assert_eq!((reward_fee.numerator, reward_fee.denominator), (1, 10));
+    let reward_fee_split = &stake_pool.fee_registry.reward_fee_split;
+    reward_fee_split.assert_valid();
+    assert_eq!(reward_fee_split.split(100), vec![(owner_account_id(), 75), (partner_account_id(), 25)]);
+    assert!(stake_pool.fee_registry.instant_withdraw_fee.is_none());
+    assert_eq!(stake_pool.fee_registry.instant_withdraw_fee_split.split(100), vec![(owner_account_id(), 100)]);
+
+    let account_balance = stake_pool.fungible_token.get_account_balance(&user_account_id()).unwrap();
+    assert_eq!(account_balance.token_amount, 100 * ONE_NEAR);
+    assert!(account_balance.referrer_id.is_none());
+    stake_pool.fungible_token.insert_account_balance(&user_account_id(), &account_balance);
+    assert!(!stake_pool.fungible_token.legacy_account_registry.contains_key(&user_account_id()));
+    assert!(stake_pool.fungible_token.account_registry.contains_key(&user_account_id()));
+    assert_eq!(stake_pool.fungible_token.storage_usage_per_account, storage_usage_per_account);
+
+    assert_eq!(stake_pool.validating.validator_registry.len(), 1);
+    let validator = stake_pool.validating.validator_registry.get(&validator_account_id()).unwrap();
+    assert_eq!(validator.balance.classic_near_amount, 100 * ONE_NEAR);
+    assert_eq!(validator.balance.get_unstaked_balance(), ONE_NEAR);
+    assert_eq!(validator.last_classic_stake_increasing_epoch_height, Some(INITIAL_EPOCH_HEIGHT - 1));
+    assert!(!validator.is_draining);
+    assert_eq!(stake_pool.validating.storage_usage_per_validator, storage_usage_per_validator);
+
+    let delayed_withdrawal = stake_pool.fund.delayed_withdrawn_fund.get_delayed_withdrawal(&user_account_id()).unwrap();
+    assert_eq!(delayed_withdrawal.near_amount, ONE_NEAR);
+    assert_eq!(delayed_withdrawal.epoch_quantity_for_delayed_withdrawal, EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL);
+    assert_eq!(stake_pool.fund.pending_refund_fund.balance, 0);
+    assert_eq!(stake_pool.pool_config.epoch_quantity_for_delayed_withdrawal, EPOCH_QUANTITY_FOR_DELAYED_WITHDRAWAL);
+}
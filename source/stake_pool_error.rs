@@ -0,0 +1,393 @@
+use near_sdk::{env, AccountId, FunctionError};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::{self, Value};
+
+/// Errors of the stake pool. The panic message is a JSON object with the stable numeric 'code',
+/// the name of the error as 'kind', the human readable 'message' and the structured fields of the error.
+/// Do not change the codes of existing variants.
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "kind")]
+pub enum StakePoolError {
+    // Common errors.
+    ContractStateAlreadyInitialized,
+    ContractStateNotInitialized,
+    UnauthorizedManagementByOwnerOrManager,
+    UnauthorizedManagementByOwner,
+    UnauthorizedManagementByManager,
+    NotEnoughGas {
+        required_gas: u64,
+        provided_gas: u64
+    },
+    NotNaturalAttachedDeposit,
+    AttachedDepositLessThanMinimumDeposit {
+        required_near_amount: U128,
+        provided_near_amount: U128
+    },
+    InsufficientNearDeposit {
+        required_near_amount: U128,
+        provided_near_amount: U128
+    },
+    InsufficientNearAmount,
+    InsufficientTokenAmount,
+    EpochShouldBeSynchronized,
+    EpochShouldBeDesynchronized,
+    ChangingStateToSameState,
+    CalculationOverflow,
+    // Deposit and withdrawal errors.
+    NearAmountLessThanMinimumRequiredNearAmount {
+        required_near_amount: U128,
+        provided_near_amount: U128
+    },
+    TokenAmountLessThanMinimumExpectedTokenAmount {
+        expected_token_amount: U128,
+        token_amount: U128
+    },
+    NearAmountLessThanMinimumExpectedNearAmount {
+        expected_near_amount: U128,
+        near_amount: U128
+    },
+    TokenAmountExceededAvailableTokenBalance {
+        requested_token_amount: U128,
+        available_token_amount: U128
+    },
+    TokenAmountExceededAvailableUnstakedNearBalance,
+    TokenAmountExceededAvailableStakedNearBalance,
+    TokenAmountExceededAvailableToTransferTokenAmount,
+    TokenAmountExceededAvailableToInstantWithdrawTokenAmount,
+    TokenAmountExceededAvailableToDelayedWithdrawTokenAmount,
+    NearAmountExceededAvailableUnstakedNearBalance,
+    NearAmountExceededAvailableStakedNearBalance,
+    PoolDepositCapExceeded {
+        remaining_near_amount: U128,
+        near_amount: U128
+    },
+    AccountDepositCapExceeded {
+        remaining_near_amount: U128,
+        near_amount: U128
+    },
+    ValidatorShareCapExceeded {
+        remaining_near_amount: U128,
+        near_amount: U128
+    },
+    WrongEpochForWithdrawal,
+    TokenAccountNotRegistered {
+        account_id: AccountId
+    },
+    DelayedWithdrawalAccountNotRegistered {
+        account_id: AccountId
+    },
+    InvestmentWithdrawalAccountNotRegistered {
+        account_id: AccountId
+    },
+    AutoClaimAlreadyEnabled,
+    PendingRefundNotFound,
+    // Validator errors.
+    ValidatorAccountNotRegistered {
+        validator_account_id: AccountId
+    },
+    ValidatorAccountAlreadyRegistered {
+        validator_account_id: AccountId
+    },
+    ValidatorUsedOnlyForInvestment,
+    ValidatorFlaggedAsInactive,
+    ValidatorInDrainingState,
+    ValidatorNotInDrainingState,
+    ValidatorAlreadyUpdated,
+    ValidatorHasAvailableBalance,
+    ValidatorHasInvestmentBalance,
+    ValidatorClassicStakedBalanceNotZero,
+    PreferredValidatorOnlyForInvestment,
+    PreferredValidatorInDrainingState,
+    PreferredValidatorFlaggedAsInactive,
+    InsufficientStakedBalanceOnValidator,
+    InsufficientUnstakedBalanceOnValidator,
+    NearAmountExceededAvailableNearBalanceOnValidator,
+    NearAmountExceededAvailableInvestorNearBalanceOnValidator,
+    NearAmountMoreThanRequestedNearAmount,
+    NearAmountMoreThanRequestedNearAmountFromValidator,
+    NoInvestorStakeOnValidator,
+    SomeValidatorsNotUpdated,
+    SomeFundsNotUnstakedFromValidators,
+    FundAlreadyDistributed,
+    WrongEpochForRequestedDecreaseValidatorStake,
+    WrongEpochForTakeUnstakedBalance,
+    WrongEpochForDrainValidator,
+    ValidatorNotReconciled,
+    ValidatorReconciliationOutdated,
+    ValidatorBalanceChangedAfterReconciliation,
+    ValidatorBalanceHasNoDrift,
+    ValidatorBalanceDriftExceededMaximumCorrection,
+    ValidatorBalanceDriftExceededClassicStakedBalance,
+    MaximumNumberOfValidatorsReached,
+    ValidatorNotUpdated,
+    ValidatorDrainInFlight,
+    // Investor errors.
+    InvestorAccountNotRegistered {
+        investor_account_id: AccountId
+    },
+    InvestorAccountAlreadyRegistered {
+        investor_account_id: AccountId
+    },
+    // Fee errors.
+    FeeNotValid,
+    FeeSplitNotValid,
+    FeeBeneficiarySharesNotSumToOne,
+    FeeBeneficiaryDuplicated,
+    FeeBeneficiaryAlreadyRegistered,
+    FeeBeneficiaryNotRegistered,
+    FeeBeneficiaryShareNotValid,
+    FirstFeeBeneficiaryCanNotBeRemoved,
+    MaximumNumberOfFeeBeneficiariesReached,
+    InstantWithdrawFeeCurveNotValid,
+    // Referral errors.
+    ReferrerAccountNotRegistered {
+        referrer_account_id: AccountId
+    },
+    ReferrerAccountAlreadyRegistered {
+        referrer_account_id: AccountId
+    },
+    AccountCanNotBeReferrerOfItself,
+    ReferrerCanBeChosenOnlyByBeneficiary,
+    // Pool config errors.
+    PoolConfigEpochQuantitiesNotValid,
+    PoolConfigMinimumNumberOfTgasNotValid,
+    PoolConfigMinimumDepositAmountNotValid,
+    PendingPoolConfigNotFound,
+    // Invariant errors.
+    SomeInvariantsBroken,
+    // Errors, that can not occur if the contract is correct.
+    NonexecutableCode {
+        reason: String
+    },
+    CallbackExpectedResult,
+    CallbackExpectedTwoResults
+}
+
+impl StakePoolError {
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::ContractStateAlreadyInitialized => 1000,
+            Self::UnauthorizedManagementByOwnerOrManager => 1001,
+            Self::UnauthorizedManagementByOwner => 1002,
+            Self::UnauthorizedManagementByManager => 1003,
+            Self::NotEnoughGas { .. } => 1004,
+            Self::NotNaturalAttachedDeposit => 1005,
+            Self::AttachedDepositLessThanMinimumDeposit { .. } => 1006,
+            Self::InsufficientNearDeposit { .. } => 1007,
+            Self::InsufficientNearAmount => 1008,
+            Self::InsufficientTokenAmount => 1009,
+            Self::EpochShouldBeSynchronized => 1010,
+            Self::EpochShouldBeDesynchronized => 1011,
+            Self::ChangingStateToSameState => 1012,
+            Self::CalculationOverflow => 1013,
+            Self::ContractStateNotInitialized => 1014,
+            Self::NearAmountLessThanMinimumRequiredNearAmount { .. } => 2000,
+            Self::TokenAmountLessThanMinimumExpectedTokenAmount { .. } => 2001,
+            Self::NearAmountLessThanMinimumExpectedNearAmount { .. } => 2002,
+            Self::TokenAmountExceededAvailableTokenBalance { .. } => 2003,
+            Self::TokenAmountExceededAvailableUnstakedNearBalance => 2004,
+            Self::TokenAmountExceededAvailableStakedNearBalance => 2005,
+            Self::TokenAmountExceededAvailableToTransferTokenAmount => 2006,
+            Self::TokenAmountExceededAvailableToInstantWithdrawTokenAmount => 2007,
+            Self::TokenAmountExceededAvailableToDelayedWithdrawTokenAmount => 2008,
+            Self::NearAmountExceededAvailableUnstakedNearBalance => 2009,
+            Self::NearAmountExceededAvailableStakedNearBalance => 2010,
+            Self::PoolDepositCapExceeded { .. } => 2011,
+            Self::AccountDepositCapExceeded { .. } => 2012,
+            Self::ValidatorShareCapExceeded { .. } => 2013,
+            Self::WrongEpochForWithdrawal => 2014,
+            Self::TokenAccountNotRegistered { .. } => 2015,
+            Self::DelayedWithdrawalAccountNotRegistered { .. } => 2016,
+            Self::InvestmentWithdrawalAccountNotRegistered { .. } => 2017,
+            Self::AutoClaimAlreadyEnabled => 2018,
+            Self::PendingRefundNotFound => 2019,
+            Self::ValidatorAccountNotRegistered { .. } => 3000,
+            Self::ValidatorAccountAlreadyRegistered { .. } => 3001,
+            Self::ValidatorUsedOnlyForInvestment => 3002,
+            Self::ValidatorFlaggedAsInactive => 3003,
+            Self::ValidatorInDrainingState => 3004,
+            Self::ValidatorNotInDrainingState => 3005,
+            Self::ValidatorAlreadyUpdated => 3006,
+            Self::ValidatorHasAvailableBalance => 3007,
+            Self::ValidatorHasInvestmentBalance => 3008,
+            Self::ValidatorClassicStakedBalanceNotZero => 3009,
+            Self::PreferredValidatorOnlyForInvestment => 3010,
+            Self::PreferredValidatorInDrainingState => 3011,
+            Self::PreferredValidatorFlaggedAsInactive => 3012,
+            Self::InsufficientStakedBalanceOnValidator => 3013,
+            Self::InsufficientUnstakedBalanceOnValidator => 3014,
+            Self::NearAmountExceededAvailableNearBalanceOnValidator => 3015,
+            Self::NearAmountExceededAvailableInvestorNearBalanceOnValidator => 3016,
+            Self::NearAmountMoreThanRequestedNearAmount => 3017,
+            Self::NearAmountMoreThanRequestedNearAmountFromValidator => 3018,
+            Self::NoInvestorStakeOnValidator => 3019,
+            Self::SomeValidatorsNotUpdated => 3020,
+            Self::SomeFundsNotUnstakedFromValidators => 3021,
+            Self::FundAlreadyDistributed => 3022,
+            Self::WrongEpochForRequestedDecreaseValidatorStake => 3023,
+            Self::WrongEpochForTakeUnstakedBalance => 3024,
+            Self::WrongEpochForDrainValidator => 3025,
+            Self::ValidatorNotReconciled => 3026,
+            Self::ValidatorReconciliationOutdated => 3027,
+            Self::ValidatorBalanceChangedAfterReconciliation => 3028,
+            Self::ValidatorBalanceHasNoDrift => 3029,
+            Self::ValidatorBalanceDriftExceededMaximumCorrection => 3030,
+            Self::ValidatorBalanceDriftExceededClassicStakedBalance => 3031,
+            Self::MaximumNumberOfValidatorsReached => 3032,
+            Self::ValidatorNotUpdated => 3033,
+            Self::ValidatorDrainInFlight => 3034,
+            Self::InvestorAccountNotRegistered { .. } => 4000,
+            Self::InvestorAccountAlreadyRegistered { .. } => 4001,
+            Self::FeeNotValid => 5000,
+            Self::FeeSplitNotValid => 5001,
+            Self::FeeBeneficiarySharesNotSumToOne => 5002,
+            Self::FeeBeneficiaryDuplicated => 5003,
+            Self::FeeBeneficiaryAlreadyRegistered => 5004,
+            Self::FeeBeneficiaryNotRegistered => 5005,
+            Self::FeeBeneficiaryShareNotValid => 5006,
+            Self::FirstFeeBeneficiaryCanNotBeRemoved => 5007,
+            Self::MaximumNumberOfFeeBeneficiariesReached => 5008,
+            Self::InstantWithdrawFeeCurveNotValid => 5009,
+            Self::ReferrerAccountNotRegistered { .. } => 6000,
+            Self::ReferrerAccountAlreadyRegistered { .. } => 6001,
+            Self::AccountCanNotBeReferrerOfItself => 6002,
+            Self::ReferrerCanBeChosenOnlyByBeneficiary => 6004,
+            Self::PoolConfigEpochQuantitiesNotValid => 7000,
+            Self::PoolConfigMinimumNumberOfTgasNotValid => 7001,
+            Self::PoolConfigMinimumDepositAmountNotValid => 7002,
+            Self::PendingPoolConfigNotFound => 7003,
+            Self::SomeInvariantsBroken => 8000,
+            Self::NonexecutableCode { .. } => 9000,
+            Self::CallbackExpectedResult => 9001,
+            Self::CallbackExpectedTwoResults => 9002
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::ContractStateAlreadyInitialized => "Contract state is already initialize.",
+            Self::UnauthorizedManagementByOwnerOrManager => "Unauthorized management. Management must be carried out either by the owner or manager of the pool.",
+            Self::UnauthorizedManagementByOwner => "Unauthorized management. Management must be carried out either by the owner of the pool.",
+            Self::UnauthorizedManagementByManager => "Unauthorized management. Management must be carried out either by the manager of the pool.",
+            Self::NotEnoughGas { .. } => "Not enough Gas quantity.",
+            Self::NotNaturalAttachedDeposit => "Not natural attached deposit.",
+            Self::AttachedDepositLessThanMinimumDeposit { .. } => "Attached deposit less then minimum required deposit.",
+            Self::InsufficientNearDeposit { .. } => "Insufficient near deposit.",
+            Self::InsufficientNearAmount => "Insufficient near amount.",
+            Self::InsufficientTokenAmount => "Insufficient token amount.",
+            Self::EpochShouldBeSynchronized => "Epoch should be in synchronized state.",
+            Self::EpochShouldBeDesynchronized => "Epoch should be in desynchronized state.",
+            Self::ChangingStateToSameState => "Changing the state to the same state.",
+            Self::CalculationOverflow => "Calculation overflow.",
+            Self::ContractStateNotInitialized => "Contract state is not initialized.",
+            Self::NearAmountLessThanMinimumRequiredNearAmount { .. } => "Near amount less then minimum required near amount.",
+            Self::TokenAmountLessThanMinimumExpectedTokenAmount { .. } => "Token amount less then minimum expected token amount.",
+            Self::NearAmountLessThanMinimumExpectedNearAmount { .. } => "Near amount less then minimum expected near amount.",
+            Self::TokenAmountExceededAvailableTokenBalance { .. } => "Token amount exceeded the available token balance.",
+            Self::TokenAmountExceededAvailableUnstakedNearBalance => "Token amount exceeded the available unstaked near balance.",
+            Self::TokenAmountExceededAvailableStakedNearBalance => "Token amount exceeded the available staked near balance.",
+            Self::TokenAmountExceededAvailableToTransferTokenAmount => "Token amount exceeded the available to transfer token amount.",
+            Self::TokenAmountExceededAvailableToInstantWithdrawTokenAmount => "Token amount exceeded the available to instant withdraw token amount.",
+            Self::TokenAmountExceededAvailableToDelayedWithdrawTokenAmount => "Token amount exceeded the available to delayed withdraw token amount.",
+            Self::NearAmountExceededAvailableUnstakedNearBalance => "Near amount exceeded the available unstaked near balance.",
+            Self::NearAmountExceededAvailableStakedNearBalance => "Near amount exceeded the available staked near balance.",
+            Self::PoolDepositCapExceeded { .. } => "Near amount exceeded the pool deposit cap.",
+            Self::AccountDepositCapExceeded { .. } => "Near amount exceeded the account deposit cap.",
+            Self::ValidatorShareCapExceeded { .. } => "Near amount exceeded the validator share cap.",
+            Self::WrongEpochForWithdrawal => "Wrong epoch for withdrawal.",
+            Self::TokenAccountNotRegistered { .. } => "Token account is not registered.",
+            Self::DelayedWithdrawalAccountNotRegistered { .. } => "Delayed withdrawal account is not registered.",
+            Self::InvestmentWithdrawalAccountNotRegistered { .. } => "Investment withdrawal account is not registered yet.",
+            Self::AutoClaimAlreadyEnabled => "Auto claim is already enabled.",
+            Self::PendingRefundNotFound => "There is no pending refund.",
+            Self::ValidatorAccountNotRegistered { .. } => "Validator account is not registered yet.",
+            Self::ValidatorAccountAlreadyRegistered { .. } => "Validator account is already registered.",
+            Self::ValidatorUsedOnlyForInvestment => "Validator is used only for investment purpose.",
+            Self::ValidatorFlaggedAsInactive => "Validator is flagged as inactive.",
+            Self::ValidatorInDrainingState => "Validator is in draining state.",
+            Self::ValidatorNotInDrainingState => "Validator is not in draining state.",
+            Self::ValidatorAlreadyUpdated => "Validator is already updated.",
+            Self::ValidatorHasAvailableBalance => "Validator has an available balance.",
+            Self::ValidatorHasInvestmentBalance => "Validator has an investment balance.",
+            Self::ValidatorClassicStakedBalanceNotZero => "Validator classic staked balance is not equal to zero.",
+            Self::PreferredValidatorOnlyForInvestment => "Prefferred validator can not be only for investment.",
+            Self::PreferredValidatorInDrainingState => "Prefferred validator can not be in draining state.",
+            Self::PreferredValidatorFlaggedAsInactive => "Prefferred validator can not be flagged as inactive.",
+            Self::InsufficientStakedBalanceOnValidator => "Insufficient staked balance on validator.",
+            Self::InsufficientUnstakedBalanceOnValidator => "Insufficient unstaked balance on validator.",
+            Self::NearAmountExceededAvailableNearBalanceOnValidator => "Near amount exceeded the available near balance on validator.",
+            Self::NearAmountExceededAvailableInvestorNearBalanceOnValidator => "Near amount exceeded the available investor near balance on validator.",
+            Self::NearAmountMoreThanRequestedNearAmount => "Near amount is more than requested near amount.",
+            Self::NearAmountMoreThanRequestedNearAmountFromValidator => "Near amount is more than requested near amount from validator.",
+            Self::NoInvestorStakeOnValidator => "There is no investor stake on this validator.",
+            Self::SomeValidatorsNotUpdated => "Some validators are not updated.",
+            Self::SomeFundsNotUnstakedFromValidators => "Some funds are not unstaked from validators.",
+            Self::FundAlreadyDistributed => "Fund has already been distributed.",
+            Self::WrongEpochForRequestedDecreaseValidatorStake => "Epoch is not intended for a requested decrease validator stake request.",
+            Self::WrongEpochForTakeUnstakedBalance => "Epoch is not intended for a take unstaked balance.",
+            Self::WrongEpochForDrainValidator => "Epoch is not intended for a drain validator request.",
+            Self::ValidatorNotReconciled => "Validator is not reconciled yet.",
+            Self::ValidatorReconciliationOutdated => "Validator reconciliation is outdated.",
+            Self::ValidatorBalanceChangedAfterReconciliation => "Validator balance has changed after reconciliation.",
+            Self::ValidatorBalanceHasNoDrift => "Validator balance has no drift.",
+            Self::ValidatorBalanceDriftExceededMaximumCorrection => "Validator balance drift exceeded the maximum correction.",
+            Self::ValidatorBalanceDriftExceededClassicStakedBalance => "Validator balance drift exceeded the classic staked balance.",
+            Self::MaximumNumberOfValidatorsReached => "Maximum number of validators is reached.",
+            Self::ValidatorNotUpdated => "Validator is not updated in current epoch.",
+            Self::ValidatorDrainInFlight => "Validator is already being drained.",
+            Self::InvestorAccountNotRegistered { .. } => "Investor account is not registered yet.",
+            Self::InvestorAccountAlreadyRegistered { .. } => "Investor account is already registered.",
+            Self::FeeNotValid => "Fee is not valid.",
+            Self::FeeSplitNotValid => "Fee split is not valid.",
+            Self::FeeBeneficiarySharesNotSumToOne => "Shares of fee beneficiaries must sum to one.",
+            Self::FeeBeneficiaryDuplicated => "Fee beneficiary is duplicated.",
+            Self::FeeBeneficiaryAlreadyRegistered => "Fee beneficiary is already registered.",
+            Self::FeeBeneficiaryNotRegistered => "Fee beneficiary is not registered.",
+            Self::FeeBeneficiaryShareNotValid => "Fee beneficiary share is not valid.",
+            Self::FirstFeeBeneficiaryCanNotBeRemoved => "First fee beneficiary can not be removed.",
+            Self::MaximumNumberOfFeeBeneficiariesReached => "Maximum number of fee beneficiaries is reached.",
+            Self::InstantWithdrawFeeCurveNotValid => "Instant withdraw fee curve is not valid.",
+            Self::ReferrerAccountNotRegistered { .. } => "Referrer account is not registered yet.",
+            Self::ReferrerAccountAlreadyRegistered { .. } => "Referrer account is already registered.",
+            Self::AccountCanNotBeReferrerOfItself => "Account can not be the referrer of itself.",
+            Self::ReferrerCanBeChosenOnlyByBeneficiary => "Referrer can be chosen only by the beneficiary account itself.",
+            Self::PoolConfigEpochQuantitiesNotValid => "Epoch quantities of pool config are not valid.",
+            Self::PoolConfigMinimumNumberOfTgasNotValid => "Minimum number of TGas of pool config is not valid.",
+            Self::PoolConfigMinimumDepositAmountNotValid => "Minimum deposit amount of pool config is not valid.",
+            Self::PendingPoolConfigNotFound => "There is no pending pool config.",
+            Self::SomeInvariantsBroken => "Some invariants are broken.",
+            Self::NonexecutableCode { .. } => "Nonexecutable code.",
+            Self::CallbackExpectedResult => "Contract expected a result on the callback.",
+            Self::CallbackExpectedTwoResults => "Contract expected two results on the callback."
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut value = match serde_json::to_value(self) {
+            Ok(value_) => value_,
+            Err(_) => Value::Object(Default::default())
+        };
+
+        if let Value::Object(ref mut object) = value {
+            object.insert("code".to_string(), Value::from(self.code()));
+            object.insert("message".to_string(), Value::from(self.message()));
+        }
+
+        value.to_string()
+    }
+
+    /// Nonexecutable code. The object must exist in the contract state.
+    pub fn object_must_exist() -> Self {
+        Self::NonexecutableCode {
+            reason: "Object must exist.".to_string()
+        }
+    }
+}
+
+impl FunctionError for StakePoolError {
+    fn panic(&self) -> ! {
+        env::panic_str(self.to_json().as_str())
+    }
+}
@@ -4,6 +4,7 @@ use near_sdk::serde::{Deserialize, Serialize};
 /// Do not change the order of variants.
 /// The number of options must be less than or equal to 256 (1 byte).
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
 #[serde(crate = "near_sdk::serde")]
 pub enum StakingContractVersion {
     /// For https://github.com/near/core-contracts/tree/master/staking-pool contracts.
@@ -14,5 +14,15 @@ pub enum StorageKey {
     Distribution {
         investor_account_id: AccountId
     },
-    InvestmentWithdrawal
+    InvestmentWithdrawal,
+    EpochReport,
+    ValidatorPerformance {
+        validator_account_id: AccountId
+    },
+    Referrer,
+    AutoClaim,
+    PendingRefund,
+    DelayedWithdrawal,
+    StorageUsageMeasurement,
+    AccountBalance
 }
\ No newline at end of file
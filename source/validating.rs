@@ -2,10 +2,13 @@ use near_sdk::{env, StorageUsage, AccountId};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{UnorderedMap, LookupMap};
 use super::get_account_id_with_maximum_length;
+use super::MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS;
 use super::investor_investment::InvestorInvestment;
 use super::staking_contract_version::StakingContractVersion;
 use super::storage_key::StorageKey;
-use super::validator::Validator;
+use super::validator::{Validator, ValidatorV1};
+use super::validator_performance::ValidatorPerformance;
+use super::validator_reconciliation::ValidatorReconciliation;
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Validating {
@@ -40,16 +43,54 @@ impl Validating {
     }
 
     fn calculate_storage_usage_per_additional_validator() -> StorageUsage {
-        let mut validator_registry = Self::initialize_validator_registry();
+        Self::measure_storage_usage_per_additional_validator(&mut Self::initialize_validator_registry())
+    }
+
+    /// The registry stores the entry of the measurement made on initialization, so the validator is measured
+    /// in the empty registry of the same prefix length and is removed after that.
+    fn recalculate_storage_usage_per_additional_validator() -> StorageUsage {
+        let mut validator_registry: UnorderedMap<AccountId, Validator> = UnorderedMap::new(StorageKey::StorageUsageMeasurement);
+
+        let storage_usage = Self::measure_storage_usage_per_additional_validator(&mut validator_registry);
+
+        if let Some(mut validator) = validator_registry.remove(&get_account_id_with_maximum_length()) {
+            validator.clear_performance_registry();
+        }
+
+        storage_usage
+    }
 
+    fn measure_storage_usage_per_additional_validator(validator_registry: &mut UnorderedMap<AccountId, Validator>) -> StorageUsage {
         let initial_storage_usage = env::storage_usage();
 
         let account_id = get_account_id_with_maximum_length();
 
-        validator_registry.insert(
-            &account_id, &Validator::new(StakingContractVersion::Core, false)
+        let mut validator = Validator::new(account_id.clone(), StakingContractVersion::Core, false);
+        validator.last_reconciliation = Some(
+            ValidatorReconciliation {
+                epoch_height: 0,
+                staked_near_amount: 0,
+                unstaked_near_amount: 0,
+                recorded_staked_near_amount: 0,
+                recorded_unstaked_near_amount: 0
+            }
         );
 
+        // The storage for all performance records is reserved in advance.
+        for _ in 0..MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS {
+            validator.add_performance(
+                &ValidatorPerformance {
+                    epoch_height: 0,
+                    is_active: false,
+                    validator_stake: 0,
+                    near_amount: 0,
+                    rewards_near_amount: 0
+                }
+            );
+        }
+
+        validator_registry.insert(&account_id, &validator);
+
         env::storage_usage() - initial_storage_usage
     }
 
@@ -84,4 +125,41 @@ impl Validating {
     fn initialize_investor_investment_registry() -> LookupMap<AccountId, InvestorInvestment> {
         LookupMap::new(StorageKey::InvestorInvestment)
     }
-}
\ No newline at end of file
+}
+
+/// Layout of the validating, that was stored before the validator layout was changed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ValidatingV1 {
+    pub validator_registry: UnorderedMap<AccountId, ValidatorV1>,
+    pub investor_investment_registry: LookupMap<AccountId, InvestorInvestment>,
+    pub validators_quantity: u64,
+    pub preffered_validator: Option<AccountId>,
+    pub quantity_of_validators_updated_in_current_epoch: u64,
+    pub storage_usage_per_validator: StorageUsage,
+    pub storage_usage_per_investor_investment: StorageUsage,
+    pub storage_usage_per_distribution: StorageUsage
+}
+
+impl ValidatingV1 {
+    /// The validators are rewritten in the new layout, their quantity is small.
+    pub fn migrate(mut self) -> Validating {
+        let validators = self.validator_registry.to_vec();
+        self.validator_registry.clear();
+
+        let mut validator_registry = Validating::initialize_validator_registry();
+        for (validator_account_id, validator) in validators.into_iter() {
+            validator_registry.insert(&validator_account_id, &validator.migrate(validator_account_id.clone()));
+        }
+
+        Validating {
+            validator_registry,
+            investor_investment_registry: self.investor_investment_registry,
+            validators_quantity: self.validators_quantity,
+            preffered_validator: self.preffered_validator,
+            quantity_of_validators_updated_in_current_epoch: self.quantity_of_validators_updated_in_current_epoch,
+            storage_usage_per_validator: Validating::recalculate_storage_usage_per_additional_validator(),
+            storage_usage_per_investor_investment: self.storage_usage_per_investor_investment,
+            storage_usage_per_distribution: self.storage_usage_per_distribution
+        }
+    }
+}
@@ -1,7 +1,13 @@
-use near_sdk::{env, EpochHeight};
+use near_sdk::{env, AccountId, EpochHeight};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use super::MAXIMUM_CONSECUTIVE_INACTIVE_EPOCH_QUANTITY;
+use super::MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS;
 use super::staking_contract_version::StakingContractVersion;
-use super::validator_balance::ValidatorBalance;
+use super::storage_key::StorageKey;
+use super::validator_balance::{ValidatorBalance, ValidatorBalanceV1};
+use super::validator_performance::ValidatorPerformance;
+use super::validator_reconciliation::ValidatorReconciliation;
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Validator {
@@ -16,11 +22,28 @@ pub struct Validator {
     /// can be greater than zero.
     pub is_only_for_investment: bool,
     pub last_update_epoch_height: EpochHeight,
-    pub last_classic_stake_increasing_epoch_height: Option<EpochHeight>
+    pub last_classic_stake_increasing_epoch_height: Option<EpochHeight>,
+    pub last_reconciliation: Option<ValidatorReconciliation>,
+    /// Validator was in the active validator set of the network on last update.
+    pub is_active: bool,
+    pub consecutive_inactive_epoch_quantity: u64,
+    /// Validator is being removed. The pool does not stake on the validator and unstakes
+    /// all classic Near amount from it in the nearest epoch intended for unstaking.
+    pub is_draining: bool,
+    /// Unstaking of all classic Near amount is requested from the draining validator, and the callback
+    /// has not been received yet.
+    pub is_drain_in_flight: bool,
+    /// Storage.
+    /// Ring buffer of records created on each validator update.
+    /// u64 - position of the record in the ring buffer.
+    pub performance_registry: LookupMap<u64, ValidatorPerformance>,
+    /// Quantity of records created since validator registration.
+    pub performance_records_quantity: u64
 }
 
 impl Validator {
     pub fn new(
+        validator_account_id: AccountId,
         staking_contract_version: StakingContractVersion,
         is_only_for_investment: bool
     ) -> Self {
@@ -28,12 +51,93 @@ impl Validator {
             balance: ValidatorBalance {
                 classic_near_amount: 0,
                 investment_near_amount: 0,
-                requested_to_withdrawal_near_amount: 0
+                requested_to_withdrawal_near_amount: 0,
+                drained_near_amount: 0
             },
             staking_contract_version,
             is_only_for_investment,
             last_update_epoch_height: env::epoch_height(),
-            last_classic_stake_increasing_epoch_height: None
+            last_classic_stake_increasing_epoch_height: None,
+            last_reconciliation: None,
+            is_active: true,
+            consecutive_inactive_epoch_quantity: 0,
+            is_draining: false,
+            is_drain_in_flight: false,
+            performance_registry: Self::initialize_performance_registry(validator_account_id),
+            performance_records_quantity: 0
+        }
+    }
+
+    pub fn add_performance(&mut self, validator_performance: &ValidatorPerformance) {
+        if validator_performance.is_active {
+            self.consecutive_inactive_epoch_quantity = 0;
+        } else {
+            self.consecutive_inactive_epoch_quantity += 1;
+        }
+        self.is_active = validator_performance.is_active;
+
+        self.performance_registry.insert(
+            &(self.performance_records_quantity % MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS), validator_performance
+        );
+        self.performance_records_quantity += 1;
+    }
+
+    /// Index of the oldest record still stored in the ring buffer.
+    pub fn get_first_available_performance_index(&self) -> u64 {
+        self.performance_records_quantity.saturating_sub(MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS)
+    }
+
+    /// Index is the sequence number of the record since validator registration.
+    pub fn get_performance(&self, index: u64) -> Option<ValidatorPerformance> {
+        if index < self.get_first_available_performance_index() || index >= self.performance_records_quantity {
+            return None;
+        }
+
+        self.performance_registry.get(&(index % MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS))
+    }
+
+    pub fn clear_performance_registry(&mut self) {
+        for position in 0..self.performance_records_quantity.min(MAXIMUM_NUMBER_OF_VALIDATOR_PERFORMANCE_RECORDS) {
+            self.performance_registry.remove(&position);
+        }
+        self.performance_records_quantity = 0;
+    }
+
+    /// Validator is out of the active validator set of the network for too long.
+    pub fn is_flagged_as_inactive(&self) -> bool {
+        self.consecutive_inactive_epoch_quantity >= MAXIMUM_CONSECUTIVE_INACTIVE_EPOCH_QUANTITY
+    }
+
+    pub fn initialize_performance_registry(validator_account_id: AccountId) -> LookupMap<u64, ValidatorPerformance> {
+        LookupMap::new(StorageKey::ValidatorPerformance { validator_account_id })
+    }
+}
+
+/// Layout of the validator, that was stored before the reconciliation, the performance records and the draining.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ValidatorV1 {
+    pub balance: ValidatorBalanceV1,
+    pub staking_contract_version: StakingContractVersion,
+    pub is_only_for_investment: bool,
+    pub last_update_epoch_height: EpochHeight,
+    pub last_classic_stake_increasing_epoch_height: Option<EpochHeight>
+}
+
+impl ValidatorV1 {
+    pub fn migrate(self, validator_account_id: AccountId) -> Validator {
+        Validator {
+            balance: self.balance.migrate(),
+            staking_contract_version: self.staking_contract_version,
+            is_only_for_investment: self.is_only_for_investment,
+            last_update_epoch_height: self.last_update_epoch_height,
+            last_classic_stake_increasing_epoch_height: self.last_classic_stake_increasing_epoch_height,
+            last_reconciliation: None,
+            is_active: true,
+            consecutive_inactive_epoch_quantity: 0,
+            is_draining: false,
+            is_drain_in_flight: false,
+            performance_registry: Validator::initialize_performance_registry(validator_account_id),
+            performance_records_quantity: 0
         }
     }
-}
\ No newline at end of file
+}
@@ -8,11 +8,36 @@ pub struct ValidatorBalance {
     /// Investment part of valdator total Near amount.
     pub investment_near_amount: Balance,
     /// Requested to withdrawal Near amount.
-    pub requested_to_withdrawal_near_amount: Balance
+    pub requested_to_withdrawal_near_amount: Balance,
+    /// Near amount unstaked from the draining validator, that should be returned to the classic unstaked balance.
+    pub drained_near_amount: Balance
 }
 
 impl ValidatorBalance {
     pub fn get_balance(&self) -> Balance {
-        self.classic_near_amount + self.investment_near_amount + self.requested_to_withdrawal_near_amount
+        self.classic_near_amount + self.investment_near_amount + self.get_unstaked_balance()
+    }
+
+    pub fn get_unstaked_balance(&self) -> Balance {
+        self.requested_to_withdrawal_near_amount + self.drained_near_amount
     }
-}
\ No newline at end of file
+}
+
+/// Layout of the validator balance, that was stored before the validators could be drained.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ValidatorBalanceV1 {
+    pub classic_near_amount: Balance,
+    pub investment_near_amount: Balance,
+    pub requested_to_withdrawal_near_amount: Balance
+}
+
+impl ValidatorBalanceV1 {
+    pub fn migrate(self) -> ValidatorBalance {
+        ValidatorBalance {
+            classic_near_amount: self.classic_near_amount,
+            investment_near_amount: self.investment_near_amount,
+            requested_to_withdrawal_near_amount: self.requested_to_withdrawal_near_amount,
+            drained_near_amount: 0
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use near_sdk::{Balance, EpochHeight};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ValidatorPerformance {
+    pub epoch_height: EpochHeight,
+    /// Validator is in the active validator set of the network.
+    pub is_active: bool,
+    /// Stake of the validator in the network.
+    pub validator_stake: Balance,
+    /// Near amount registered on validator before receiving rewards.
+    pub near_amount: Balance,
+    /// Near amount received from validator as rewards.
+    pub rewards_near_amount: Balance
+}
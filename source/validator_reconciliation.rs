@@ -0,0 +1,27 @@
+use near_sdk::{Balance, EpochHeight};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Result of comparing the balance registered on the validator with the real balance
+/// of the pool on the validator contract.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ValidatorReconciliation {
+    pub epoch_height: EpochHeight,
+    /// Staked Near amount received from validator.
+    pub staked_near_amount: Balance,
+    /// Unstaked Near amount received from validator.
+    pub unstaked_near_amount: Balance,
+    /// Classic and investment Near amount registered on validator.
+    pub recorded_staked_near_amount: Balance,
+    /// Requested to withdrawal Near amount registered on validator.
+    pub recorded_unstaked_near_amount: Balance
+}
+
+impl ValidatorReconciliation {
+    pub fn get_near_amount(&self) -> Balance {
+        self.staked_near_amount + self.unstaked_near_amount
+    }
+
+    pub fn get_recorded_near_amount(&self) -> Balance {
+        self.recorded_staked_near_amount + self.recorded_unstaked_near_amount
+    }
+}
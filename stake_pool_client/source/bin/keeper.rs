@@ -0,0 +1,109 @@
+//! Passes the epoch pipeline of the stake pool and prints the submitted actions.
+//! Usage: keeper --rpc <RPC url> --pool <pool account id> --signer <signer account id> [--dry-run]
+//! [--retry-quantity <quantity>] [--retry-delay-seconds <seconds>] [--interval-seconds <seconds>]
+//! With the '--dry-run' the planned actions are printed without submitting. With the '--interval-seconds' the pipeline
+//! is passed repeatedly with the interval, otherwise once.
+//! Exits with the code 1 if the arguments are not valid, and with the code 2 if the pipeline can not be passed.
+
+use std::time::Duration;
+use std::{env, process, thread};
+use near_sdk::AccountId;
+use stake_pool_client::keeper::Keeper;
+use stake_pool_client::{CommandLineTransport, StakePoolClient};
+
+struct Arguments {
+    rpc_url: String,
+    pool_account_id: AccountId,
+    signer_account_id: AccountId,
+    keeper: Keeper,
+    interval: Option<Duration>
+}
+
+fn parse_account_id(value: &str) -> Result<AccountId, String> {
+    value.parse().map_err(|_| format!("Account id {} is not valid.", value))
+}
+
+fn parse_number(name: &str, value: &str) -> Result<u64, String> {
+    value.parse().map_err(|_| format!("Value {} of the {} is not valid.", value, name))
+}
+
+fn parse_arguments() -> Result<Arguments, String> {
+    let mut rpc_url: Option<String> = None;
+    let mut pool_account_id: Option<AccountId> = None;
+    let mut signer_account_id: Option<AccountId> = None;
+    let mut keeper = Keeper {
+        is_dry_run: false,
+        retry_quantity: 3,
+        retry_delay: Duration::from_secs(5)
+    };
+    let mut interval: Option<Duration> = None;
+
+    let mut arguments = env::args().skip(1);
+    while let Some(name) = arguments.next() {
+        if name == "--dry-run" {
+            keeper.is_dry_run = true;
+
+            continue;
+        }
+
+        let value = arguments.next()
+            .ok_or_else(|| format!("Value of the {} is absent.", name))?;
+        match name.as_str() {
+            "--rpc" => rpc_url = Some(value),
+            "--pool" => pool_account_id = Some(parse_account_id(&value)?),
+            "--signer" => signer_account_id = Some(parse_account_id(&value)?),
+            "--retry-quantity" => keeper.retry_quantity = parse_number(&name, &value)? as u32,
+            "--retry-delay-seconds" => keeper.retry_delay = Duration::from_secs(parse_number(&name, &value)?),
+            "--interval-seconds" => interval = Some(Duration::from_secs(parse_number(&name, &value)?)),
+            _ => return Err(format!("Argument {} is not supported.", name))
+        }
+    }
+
+    Ok(
+        Arguments {
+            rpc_url: rpc_url.ok_or("Argument --rpc is absent.")?,
+            pool_account_id: pool_account_id.ok_or("Argument --pool is absent.")?,
+            signer_account_id: signer_account_id.ok_or("Argument --signer is absent.")?,
+            keeper,
+            interval
+        }
+    )
+}
+
+fn main() {
+    let arguments = match parse_arguments() {
+        Ok(arguments_) => arguments_,
+        Err(error) => {
+            eprintln!("{}", error);
+
+            process::exit(1);
+        }
+    };
+
+    let mut client = StakePoolClient::new(
+        CommandLineTransport::new(arguments.rpc_url, arguments.signer_account_id),
+        arguments.pool_account_id
+    );
+
+    loop {
+        match arguments.keeper.run(&mut client) {
+            Ok(actions) => {
+                for action in actions {
+                    println!("{}", action);
+                }
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+
+                if arguments.interval.is_none() {
+                    process::exit(2);
+                }
+            }
+        }
+
+        match arguments.interval {
+            Some(interval) => thread::sleep(interval),
+            None => break
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+use near_sdk::serde::Deserialize;
+use near_sdk::serde_json;
+
+#[derive(Debug, PartialEq)]
+pub enum ClientError {
+    /// The stake pool has rejected the call with the error, the fields are the ones of the panic message of the contract.
+    Contract {
+        code: u32,
+        kind: String,
+        message: String
+    },
+    /// The failure is not the error of the stake pool.
+    Transport {
+        reason: String
+    },
+    /// The result of the method does not match the expected type.
+    Decoding {
+        method_name: String,
+        reason: String
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct ContractError {
+    code: u32,
+    kind: String,
+    message: String
+}
+
+impl ClientError {
+    /// The panic message of the stake pool is a JSON object, which can be wrapped by the transport into the description
+    /// of the execution failure.
+    pub(crate) fn from_failure(failure: String) -> Self {
+        let contract_error = match (failure.find('{'), failure.rfind('}')) {
+            (Some(start), Some(end)) if start < end => serde_json::from_str::<ContractError>(&failure[start..=end]).ok(),
+            _ => None
+        };
+
+        match contract_error {
+            Some(contract_error_) => Self::Contract {
+                code: contract_error_.code,
+                kind: contract_error_.kind,
+                message: contract_error_.message
+            },
+            None => Self::Transport { reason: failure }
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contract { code, kind, message } => write!(formatter, "Stake pool error {} {}: {}", code, kind, message),
+            Self::Transport { reason } => write!(formatter, "Transport error: {}", reason),
+            Self::Decoding { method_name, reason } => write!(formatter, "Result of the {} is not valid: {}", method_name, reason)
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
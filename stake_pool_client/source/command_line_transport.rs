@@ -0,0 +1,95 @@
+use std::process::Command;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::AccountId;
+use super::function_call::FunctionCall;
+use super::transport::Transport;
+
+/// Transport through the installed command line tools. The view-methods are sent to the JSON RPC with the 'curl',
+/// the transactions are signed and sent with the 'near call' of the NEAR CLI with the keys of the signer from its key store.
+pub struct CommandLineTransport {
+    pub rpc_url: String,
+    pub signer_account_id: AccountId
+}
+
+impl CommandLineTransport {
+    pub fn new(rpc_url: String, signer_account_id: AccountId) -> Self {
+        Self {
+            rpc_url,
+            signer_account_id
+        }
+    }
+
+    fn execute(command: &mut Command) -> Result<Vec<u8>, String> {
+        let output = command.output()
+            .map_err(|error| format!("Command can not be executed: {}.", error))?;
+
+        if !output.status.success() {
+            return Err(
+                format!(
+                    "Command is failed with {}: {}{}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr),
+                    String::from_utf8_lossy(&output.stdout)
+                )
+            );
+        }
+
+        Ok(output.stdout)
+    }
+}
+
+impl Transport for CommandLineTransport {
+    fn view(&mut self, contract_account_id: &AccountId, method_name: &str, arguments: Vec<u8>) -> Result<Vec<u8>, String> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "stake_pool_client",
+            "method": "query",
+            "params": {
+                "request_type": "call_function",
+                "finality": "final",
+                "account_id": contract_account_id,
+                "method_name": method_name,
+                "args_base64": Base64VecU8(arguments)
+            }
+        });
+
+        let response = Self::execute(
+            Command::new("curl")
+                .args(["--silent", "--show-error", "--header", "Content-Type: application/json", "--data"])
+                .arg(request.to_string())
+                .arg(&self.rpc_url)
+        )?;
+        let response: Value = serde_json::from_slice(&response)
+            .map_err(|error| format!("Response of the RPC is not valid: {}.", error))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(error.to_string());
+        }
+        if let Some(error) = response["result"].get("error") {
+            return Err(error.to_string());
+        }
+
+        serde_json::from_value(response["result"]["result"].clone())
+            .map_err(|error| format!("Result of the RPC is not valid: {}.", error))
+    }
+
+    /// The NEAR CLI prints the result of the method in the last line of the output.
+    fn call(&mut self, contract_account_id: &AccountId, function_call: FunctionCall) -> Result<Vec<u8>, String> {
+        let output = Self::execute(
+            Command::new("near")
+                .arg("call")
+                .arg(contract_account_id.as_str())
+                .arg(&function_call.method_name)
+                .arg(String::from_utf8_lossy(&function_call.arguments).as_ref())
+                .args(["--accountId", self.signer_account_id.as_str()])
+                .args(["--gas", &function_call.gas.0.to_string()])
+                .args(["--depositYocto", &function_call.deposit.to_string()])
+                .args(["--nodeUrl", &self.rpc_url])
+        )?;
+
+        let output = String::from_utf8_lossy(&output);
+
+        Ok(output.trim().lines().last().unwrap_or("").as_bytes().to_vec())
+    }
+}
@@ -0,0 +1,46 @@
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::{Balance, Gas, ONE_NEAR};
+
+/// Gas attached to the call by default. It is the maximum Gas amount of a transaction, so it covers the budget
+/// of any method with the minimum number of TGas of any pool config.
+pub const DEFAULT_GAS: Gas = Gas(300_000_000_000_000);
+/// Near amount attached by default to the calls, that reserve storage. It covers the storage staking price of any
+/// registry of the pool, the surplus is refunded by the pool.
+pub const STORAGE_STAKING_DEPOSIT: Balance = ONE_NEAR / 10;
+
+/// Near amount attached by default to the initialization. It covers the storage staking price of the epoch history,
+/// that is reserved once for all epoch reports.
+pub const INITIALIZATION_DEPOSIT: Balance = 2 * ONE_NEAR;
+
+/// Call of the stake pool method with the JSON arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCall {
+    pub method_name: String,
+    pub arguments: Vec<u8>,
+    pub gas: Gas,
+    pub deposit: Balance
+}
+
+impl FunctionCall {
+    pub(crate) fn new<T: Serialize>(method_name: &str, arguments: &T, deposit: Balance) -> Self {
+        Self {
+            method_name: method_name.to_string(),
+            arguments: serde_json::to_vec(arguments).unwrap(),
+            gas: DEFAULT_GAS,
+            deposit
+        }
+    }
+
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.gas = gas;
+
+        self
+    }
+
+    pub fn with_deposit(mut self, deposit: Balance) -> Self {
+        self.deposit = deposit;
+
+        self
+    }
+}
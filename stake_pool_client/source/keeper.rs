@@ -0,0 +1,331 @@
+//! Keeper of the epoch pipeline of the stake pool. The next actions are computed from the snapshot of the pool, so
+//! the keeper is idempotent: after each submitted action the snapshot is read again and the plan is computed again.
+//! An action, that is not confirmed because of the transport failure, is submitted again only if it is still planned.
+//!
+//! The order of the actions follows the assertions of the contract. While the pool is desynchronized with the network:
+//! 1. 'take_unstaked_balance' for each validator with the unstaked balance in the right epoch before its update;
+//! 2. 'update_validator' for each validator, that is not updated in the current epoch;
+//! 3. 'requested_decrease_validator_stake' for the Near amounts needed by the delayed withdrawals in the right epoch;
+//! 4. 'update'.
+//!
+//! After the synchronization:
+//! 5. 'increase_validator_stake' with the whole classic unstaked balance on the eligible validator with the least classic stake;
+//! 6. 'confirm_stake_distribution'.
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+use near_sdk::{AccountId, Balance};
+use stake_pool::data_transfer_object::epoch_height_registry::EpochHeightRegistry;
+use stake_pool::data_transfer_object::full::Full;
+use stake_pool::data_transfer_object::pool_config::PoolConfig;
+use stake_pool::data_transfer_object::requested_to_withdrawal_fund::RequestedToWithdrawalFund;
+use stake_pool::data_transfer_object::validator::Validator;
+use stake_pool::stake_decreasing_kind::StakeDecreasingType;
+use super::client_error::ClientError;
+use super::function_call::FunctionCall;
+use super::request;
+use super::stake_pool_client::StakePoolClient;
+use super::transport::Transport;
+
+/// Maximum quantity of actions submitted in one run. The epoch pipeline needs several actions for each validator.
+const MAXIMUM_ACTION_QUANTITY: usize = 1_000;
+/// Kinds of the stake pool errors, that mean that the state is changed by someone else between the snapshot and the action.
+/// The action is skipped and the plan is computed again.
+const CONCURRENT_CHANGE_ERROR_KINDS: [&str; 4] = [
+    "ValidatorAlreadyUpdated",
+    "FundAlreadyDistributed",
+    "EpochShouldBeSynchronized",
+    "EpochShouldBeDesynchronized"
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    TakeUnstakedBalance {
+        validator_account_id: AccountId
+    },
+    UpdateValidator {
+        validator_account_id: AccountId
+    },
+    RequestedDecreaseValidatorStake {
+        validator_account_id: AccountId,
+        near_amount: Balance,
+        stake_decreasing_type: StakeDecreasingType
+    },
+    Update,
+    IncreaseValidatorStake {
+        validator_account_id: AccountId,
+        near_amount: Balance
+    },
+    ConfirmStakeDistribution
+}
+
+impl Action {
+    pub fn to_function_call(&self) -> FunctionCall {
+        match self {
+            Self::TakeUnstakedBalance { validator_account_id } => request::take_unstaked_balance(validator_account_id.clone()),
+            Self::UpdateValidator { validator_account_id } => request::update_validator(validator_account_id.clone()),
+            Self::RequestedDecreaseValidatorStake { validator_account_id, near_amount, stake_decreasing_type } => {
+                request::requested_decrease_validator_stake(validator_account_id.clone(), *near_amount, stake_decreasing_type.clone())
+            }
+            Self::Update => request::update(),
+            Self::IncreaseValidatorStake { validator_account_id, near_amount } => {
+                request::increase_validator_stake(validator_account_id.clone(), *near_amount)
+            }
+            Self::ConfirmStakeDistribution => request::confirm_stake_distribution()
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TakeUnstakedBalance { validator_account_id } => write!(formatter, "take_unstaked_balance on @{}", validator_account_id),
+            Self::UpdateValidator { validator_account_id } => write!(formatter, "update_validator on @{}", validator_account_id),
+            Self::RequestedDecreaseValidatorStake { validator_account_id, near_amount, stake_decreasing_type } => write!(
+                formatter, "requested_decrease_validator_stake on @{} with {} yoctoNear in {:?} context", validator_account_id, near_amount, stake_decreasing_type
+            ),
+            Self::Update => write!(formatter, "update"),
+            Self::IncreaseValidatorStake { validator_account_id, near_amount } => write!(
+                formatter, "increase_validator_stake on @{} with {} yoctoNear", validator_account_id, near_amount
+            ),
+            Self::ConfirmStakeDistribution => write!(formatter, "confirm_stake_distribution")
+        }
+    }
+}
+
+/// State of the pool, that is needed to compute the next actions.
+pub struct Snapshot {
+    pub epoch_height_registry: EpochHeightRegistry,
+    pub pool_config: PoolConfig,
+    pub full: Full,
+    pub validators: Vec<Validator>,
+    pub requested_to_withdrawal_fund: RequestedToWithdrawalFund,
+    pub is_stake_distributed: bool
+}
+
+impl Snapshot {
+    pub fn read<T: Transport>(client: &mut StakePoolClient<T>) -> Result<Self, ClientError> {
+        Ok(
+            Self {
+                epoch_height_registry: client.get_current_epoch_height()?,
+                pool_config: client.get_pool_config()?,
+                full: client.get_full()?,
+                validators: client.get_validator_registry()?,
+                requested_to_withdrawal_fund: client.get_requested_to_withdrawal_fund()?,
+                is_stake_distributed: client.is_stake_distributed()?
+            }
+        )
+    }
+}
+
+/// Computes the actions, that are needed to pass the epoch pipeline from the state of the snapshot.
+/// The actions after the 'update' are computed after the synchronization, because they depend on its result.
+pub fn plan(snapshot: &Snapshot) -> Vec<Action> {
+    let network_epoch_height = snapshot.epoch_height_registry.network_epoch_height;
+
+    if snapshot.epoch_height_registry.pool_epoch_height == network_epoch_height {
+        return plan_distribution(snapshot);
+    }
+
+    let is_right_epoch = network_epoch_height % snapshot.pool_config.epoch_quantity_for_validator_unstake == 0;
+
+    let mut actions: Vec<Action> = vec![];
+    if is_right_epoch {
+        for validator in snapshot.validators.iter() {
+            if validator.unstaked_balance.0 > 0 && validator.last_update_epoch_height < network_epoch_height {
+                actions.push(Action::TakeUnstakedBalance { validator_account_id: validator.account_id.clone() });
+            }
+        }
+    }
+    for validator in snapshot.validators.iter() {
+        if validator.last_update_epoch_height < network_epoch_height {
+            actions.push(Action::UpdateValidator { validator_account_id: validator.account_id.clone() });
+        }
+    }
+    if is_right_epoch {
+        actions.extend(plan_stake_decreasing(snapshot));
+    }
+    actions.push(Action::Update);
+
+    actions
+}
+
+fn plan_stake_decreasing(snapshot: &Snapshot) -> Vec<Action> {
+    let mut actions: Vec<Action> = vec![];
+
+    // The classic Near amount is requested from the validators with the largest classic stake first.
+    // The classic stake of the draining validators is unstaked by the draining.
+    let mut validators: Vec<&Validator> = snapshot.validators.iter()
+        .filter(|validator| validator.classic_staked_balance.0 > 0 && !validator.is_draining)
+        .collect();
+    validators.sort_by_key(|validator| Reverse(validator.classic_staked_balance.0));
+
+    let mut classic_near_amount = snapshot.requested_to_withdrawal_fund.classic_near_amount.0;
+    for validator in validators {
+        if classic_near_amount == 0 {
+            break;
+        }
+
+        let near_amount = classic_near_amount.min(validator.classic_staked_balance.0);
+        classic_near_amount -= near_amount;
+
+        actions.push(
+            Action::RequestedDecreaseValidatorStake {
+                validator_account_id: validator.account_id.clone(),
+                near_amount,
+                stake_decreasing_type: StakeDecreasingType::Classic
+            }
+        );
+    }
+
+    // The investment Near amount is requested from the validator, on which it is withdrawn by the investors.
+    let mut investment_near_amount = snapshot.requested_to_withdrawal_fund.investment_near_amount.0;
+    for (validator_account_id, requested_near_amount) in snapshot.requested_to_withdrawal_fund.investment_withdrawal_registry.iter() {
+        let investment_staked_balance = match snapshot.validators.iter().find(|validator| validator.account_id == *validator_account_id) {
+            Some(validator) => validator.investment_staked_balance.0,
+            None => continue
+        };
+
+        let near_amount = investment_near_amount.min(requested_near_amount.0).min(investment_staked_balance);
+        if near_amount == 0 {
+            continue;
+        }
+        investment_near_amount -= near_amount;
+
+        actions.push(
+            Action::RequestedDecreaseValidatorStake {
+                validator_account_id: validator_account_id.clone(),
+                near_amount,
+                stake_decreasing_type: StakeDecreasingType::Investment
+            }
+        );
+    }
+
+    actions
+}
+
+fn plan_distribution(snapshot: &Snapshot) -> Vec<Action> {
+    if snapshot.is_stake_distributed {
+        return vec![];
+    }
+
+    let mut actions: Vec<Action> = vec![];
+
+    let classic_unstaked_balance = snapshot.full.fund.classic_unstaked_balance.0;
+    let validator = snapshot.validators.iter()
+        .filter(|validator| !validator.is_only_for_investment && !validator.is_flagged_as_inactive && !validator.is_draining)
+        .min_by_key(|validator| validator.classic_staked_balance.0);
+    if let (true, Some(validator_)) = (classic_unstaked_balance > 0, validator) {
+        actions.push(
+            Action::IncreaseValidatorStake {
+                validator_account_id: validator_.account_id.clone(),
+                near_amount: classic_unstaked_balance
+            }
+        );
+    }
+    actions.push(Action::ConfirmStakeDistribution);
+
+    actions
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KeeperError {
+    Client(ClientError),
+    /// The action is planned again after it has been accepted by the pool.
+    ActionHasNoEffect {
+        action: Action
+    },
+    ActionQuantityExceeded
+}
+
+impl fmt::Display for KeeperError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Client(error) => write!(formatter, "{}", error),
+            Self::ActionHasNoEffect { action } => write!(formatter, "Action {} has no effect on the pool.", action),
+            Self::ActionQuantityExceeded => write!(formatter, "Quantity of actions exceeded {}.", MAXIMUM_ACTION_QUANTITY)
+        }
+    }
+}
+
+impl std::error::Error for KeeperError {}
+
+impl From<ClientError> for KeeperError {
+    fn from(error: ClientError) -> Self {
+        Self::Client(error)
+    }
+}
+
+pub struct Keeper {
+    /// The actions are only planned and returned without submitting.
+    pub is_dry_run: bool,
+    /// Quantity of the consecutive transport failures after which the run is stopped.
+    pub retry_quantity: u32,
+    pub retry_delay: Duration
+}
+
+impl Keeper {
+    /// Passes the epoch pipeline and returns the accepted actions, or the planned actions in the dry-run mode.
+    pub fn run<T: Transport>(&self, client: &mut StakePoolClient<T>) -> Result<Vec<Action>, KeeperError> {
+        let mut accepted_actions: Vec<Action> = vec![];
+        let mut previous_action: Option<Action> = None;
+        let mut failure_quantity: u32 = 0;
+
+        for _ in 0..MAXIMUM_ACTION_QUANTITY {
+            let snapshot = match Snapshot::read(client) {
+                Ok(snapshot_) => snapshot_,
+                Err(error) => {
+                    self.register_failure(error, &mut failure_quantity)?;
+
+                    continue;
+                }
+            };
+
+            let actions = plan(&snapshot);
+            if self.is_dry_run {
+                return Ok(actions);
+            }
+
+            let action = match actions.into_iter().next() {
+                Some(action_) => action_,
+                None => return Ok(accepted_actions)
+            };
+            if previous_action.as_ref() == Some(&action) {
+                return Err(KeeperError::ActionHasNoEffect { action });
+            }
+
+            match client.call(action.to_function_call()) {
+                Ok(()) => {
+                    failure_quantity = 0;
+                    previous_action = Some(action.clone());
+                    accepted_actions.push(action);
+                }
+                Err(ClientError::Contract { ref kind, .. }) if CONCURRENT_CHANGE_ERROR_KINDS.contains(&kind.as_str()) => {
+                    failure_quantity = 0;
+                    previous_action = Some(action);
+                }
+                // The transaction can be executed despite the failure, so the action is submitted again only if it is still planned.
+                Err(error) => {
+                    previous_action = None;
+                    self.register_failure(error, &mut failure_quantity)?;
+                }
+            }
+        }
+
+        Err(KeeperError::ActionQuantityExceeded)
+    }
+
+    /// Only the transport failures are retried.
+    fn register_failure(&self, error: ClientError, failure_quantity: &mut u32) -> Result<(), KeeperError> {
+        if !matches!(error, ClientError::Transport { .. }) || *failure_quantity >= self.retry_quantity {
+            return Err(KeeperError::Client(error));
+        }
+
+        *failure_quantity += 1;
+        thread::sleep(self.retry_delay);
+
+        Ok(())
+    }
+}
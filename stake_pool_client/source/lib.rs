@@ -0,0 +1,19 @@
+//! Client of the stake pool contract. The arguments of the call-methods are built by the typed requests of the
+//! 'request' module with the Gas and the deposit, that pass the assertions of the contract, and the results of the
+//! view-methods are decoded into the data transfer objects of the contract. The network is accessed through the
+//! 'Transport', so the client can be used with any RPC library or with a local mock. The 'keeper' passes the epoch
+//! pipeline of the pool with the client.
+
+pub mod client_error;
+pub mod command_line_transport;
+pub mod function_call;
+pub mod keeper;
+pub mod request;
+pub mod stake_pool_client;
+pub mod transport;
+
+pub use client_error::ClientError;
+pub use command_line_transport::CommandLineTransport;
+pub use function_call::FunctionCall;
+pub use stake_pool_client::StakePoolClient;
+pub use transport::Transport;
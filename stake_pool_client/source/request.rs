@@ -0,0 +1,301 @@
+//! Requests of the call-methods of the stake pool. Each request attaches the default Gas and the deposit, that pass the
+//! assertions of the method: the exchangeable Near amount with the storage staking deposit for the deposits, the storage
+//! staking deposit for the methods, that reserve storage, the epoch history deposit for the initialization, one yoctoNear for the methods, that require the natural deposit,
+//! and nothing for the others. The defaults can be changed by 'FunctionCall::with_gas' and 'FunctionCall::with_deposit'.
+//! The 'ft_transfer_call' is not implemented by the stake pool, so it has no request.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde_json::json;
+use near_sdk::{AccountId, Balance, ONE_YOCTO};
+use stake_pool::data_transfer_object::fungible_token_metadata::FungibleTokenMetadata;
+use stake_pool::data_transfer_object::pool_config::PoolConfig;
+use stake_pool::fee::Fee;
+use stake_pool::fee_split::FeeSplit;
+use stake_pool::fee_type::FeeType;
+use stake_pool::instant_withdraw_fee_curve::InstantWithdrawFeeCurve;
+use stake_pool::stake_decreasing_kind::StakeDecreasingType;
+use stake_pool::staking_contract_version::StakingContractVersion;
+use super::function_call::{FunctionCall, INITIALIZATION_DEPOSIT, STORAGE_STAKING_DEPOSIT};
+
+fn to_json_balance(near_amount: Option<Balance>) -> Option<U128> {
+    near_amount.map(U128)
+}
+
+pub fn new(
+    fungible_token_metadata: FungibleTokenMetadata,
+    manager_id: Option<AccountId>,
+    reward_fee: Option<Fee>,
+    reward_fee_split: FeeSplit,
+    instant_withdraw_fee: Option<Fee>,
+    instant_withdraw_fee_split: FeeSplit,
+    pool_config: Option<PoolConfig>
+) -> FunctionCall {
+    FunctionCall::new(
+        "new",
+        &json!({
+            "fungible_token_metadata": fungible_token_metadata,
+            "manager_id": manager_id,
+            "reward_fee": reward_fee,
+            "reward_fee_split": reward_fee_split,
+            "instant_withdraw_fee": instant_withdraw_fee,
+            "instant_withdraw_fee_split": instant_withdraw_fee_split,
+            "pool_config": pool_config
+        }),
+        INITIALIZATION_DEPOSIT
+    )
+}
+
+pub fn deposit(near_amount: Balance, min_token_amount: Option<Balance>, referrer_id: Option<AccountId>) -> FunctionCall {
+    FunctionCall::new(
+        "deposit",
+        &json!({ "near_amount": U128(near_amount), "min_token_amount": to_json_balance(min_token_amount), "referrer_id": referrer_id }),
+        near_amount + STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn deposit_for(beneficiary_id: AccountId, near_amount: Balance, min_token_amount: Option<Balance>, referrer_id: Option<AccountId>) -> FunctionCall {
+    FunctionCall::new(
+        "deposit_for",
+        &json!({
+            "beneficiary_id": beneficiary_id,
+            "near_amount": U128(near_amount),
+            "min_token_amount": to_json_balance(min_token_amount),
+            "referrer_id": referrer_id
+        }),
+        near_amount + STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn deposit_on_validator(near_amount: Balance, validator_account_id: AccountId, referrer_id: Option<AccountId>) -> FunctionCall {
+    FunctionCall::new(
+        "deposit_on_validator",
+        &json!({ "near_amount": U128(near_amount), "validator_account_id": validator_account_id, "referrer_id": referrer_id }),
+        near_amount + STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn instant_withdraw(token_amount: Balance, min_near_amount: Option<Balance>) -> FunctionCall {
+    FunctionCall::new(
+        "instant_withdraw",
+        &json!({ "token_amount": U128(token_amount), "min_near_amount": to_json_balance(min_near_amount) }),
+        ONE_YOCTO
+    )
+}
+
+pub fn instant_withdraw_to(receiver_id: AccountId, token_amount: Balance, min_near_amount: Option<Balance>) -> FunctionCall {
+    FunctionCall::new(
+        "instant_withdraw_to",
+        &json!({ "receiver_id": receiver_id, "token_amount": U128(token_amount), "min_near_amount": to_json_balance(min_near_amount) }),
+        ONE_YOCTO
+    )
+}
+
+pub fn delayed_withdraw(token_amount: Balance, min_near_amount: Option<Balance>) -> FunctionCall {
+    FunctionCall::new(
+        "delayed_withdraw",
+        &json!({ "token_amount": U128(token_amount), "min_near_amount": to_json_balance(min_near_amount) }),
+        STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn delayed_withdraw_from_validator(near_amount: Balance, validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new(
+        "delayed_withdraw_from_validator",
+        &json!({ "near_amount": U128(near_amount), "validator_account_id": validator_account_id }),
+        STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn take_delayed_withdrawal() -> FunctionCall {
+    FunctionCall::new("take_delayed_withdrawal", &json!({}), ONE_YOCTO)
+}
+
+pub fn take_delayed_withdrawal_to(receiver_id: AccountId) -> FunctionCall {
+    FunctionCall::new("take_delayed_withdrawal_to", &json!({ "receiver_id": receiver_id }), ONE_YOCTO)
+}
+
+pub fn enable_auto_claim() -> FunctionCall {
+    FunctionCall::new("enable_auto_claim", &json!({}), STORAGE_STAKING_DEPOSIT)
+}
+
+pub fn process_matured_withdrawals(limit: u64) -> FunctionCall {
+    FunctionCall::new("process_matured_withdrawals", &json!({ "limit": limit }), 0)
+}
+
+pub fn claim_refund(receiver_id: Option<AccountId>) -> FunctionCall {
+    FunctionCall::new("claim_refund", &json!({ "receiver_id": receiver_id }), ONE_YOCTO)
+}
+
+pub fn increase_validator_stake(validator_account_id: AccountId, near_amount: Balance) -> FunctionCall {
+    FunctionCall::new(
+        "increase_validator_stake",
+        &json!({ "validator_account_id": validator_account_id, "near_amount": U128(near_amount) }),
+        0
+    )
+}
+
+pub fn requested_decrease_validator_stake(validator_account_id: AccountId, near_amount: Balance, stake_decreasing_type: StakeDecreasingType) -> FunctionCall {
+    FunctionCall::new(
+        "requested_decrease_validator_stake",
+        &json!({ "validator_account_id": validator_account_id, "near_amount": U128(near_amount), "stake_decreasing_type": stake_decreasing_type }),
+        0
+    )
+}
+
+pub fn take_unstaked_balance(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("take_unstaked_balance", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn update_validator(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("update_validator", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn reconcile_validator(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("reconcile_validator", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn apply_validator_correction(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("apply_validator_correction", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn update() -> FunctionCall {
+    FunctionCall::new("update", &json!({}), 0)
+}
+
+pub fn add_validator(
+    validator_account_id: AccountId,
+    staking_contract_version: StakingContractVersion,
+    is_only_for_investment: bool,
+    is_preferred: bool
+) -> FunctionCall {
+    FunctionCall::new(
+        "add_validator",
+        &json!({
+            "validator_account_id": validator_account_id,
+            "staking_contract_version": staking_contract_version,
+            "is_only_for_investment": is_only_for_investment,
+            "is_preferred": is_preferred
+        }),
+        STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn change_validator_investment_context(validator_account_id: AccountId, is_only_for_investment: bool) -> FunctionCall {
+    FunctionCall::new(
+        "change_validator_investment_context",
+        &json!({ "validator_account_id": validator_account_id, "is_only_for_investment": is_only_for_investment }),
+        0
+    )
+}
+
+pub fn change_preffered_validator(validator_account_id: Option<AccountId>) -> FunctionCall {
+    FunctionCall::new("change_preffered_validator", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn remove_validator(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("remove_validator", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn start_draining_validator(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("start_draining_validator", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn drain_validator(validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("drain_validator", &json!({ "validator_account_id": validator_account_id }), 0)
+}
+
+pub fn migrate_investment_to_classic(investor_account_id: AccountId, validator_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new(
+        "migrate_investment_to_classic",
+        &json!({ "investor_account_id": investor_account_id, "validator_account_id": validator_account_id }),
+        0
+    )
+}
+
+pub fn add_investor(investor_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("add_investor", &json!({ "investor_account_id": investor_account_id }), STORAGE_STAKING_DEPOSIT)
+}
+
+pub fn remove_investor(investor_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("remove_investor", &json!({ "investor_account_id": investor_account_id }), 0)
+}
+
+pub fn change_manager(manager_id: AccountId) -> FunctionCall {
+    FunctionCall::new("change_manager", &json!({ "manager_id": manager_id }), 0)
+}
+
+pub fn change_reward_fee(reward_fee: Option<Fee>) -> FunctionCall {
+    FunctionCall::new("change_reward_fee", &json!({ "reward_fee": reward_fee }), 0)
+}
+
+pub fn change_instant_withdraw_fee(instant_withdraw_fee: Option<Fee>) -> FunctionCall {
+    FunctionCall::new("change_instant_withdraw_fee", &json!({ "instant_withdraw_fee": instant_withdraw_fee }), 0)
+}
+
+pub fn change_instant_withdraw_fee_curve(instant_withdraw_fee_curve: Option<InstantWithdrawFeeCurve>) -> FunctionCall {
+    FunctionCall::new("change_instant_withdraw_fee_curve", &json!({ "instant_withdraw_fee_curve": instant_withdraw_fee_curve }), 0)
+}
+
+pub fn add_fee_beneficiary(fee_type: FeeType, beneficiary_account_id: AccountId, numerator: u64) -> FunctionCall {
+    FunctionCall::new(
+        "add_fee_beneficiary",
+        &json!({ "fee_type": fee_type, "beneficiary_account_id": beneficiary_account_id, "numerator": numerator }),
+        STORAGE_STAKING_DEPOSIT
+    )
+}
+
+pub fn remove_fee_beneficiary(fee_type: FeeType, beneficiary_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new(
+        "remove_fee_beneficiary",
+        &json!({ "fee_type": fee_type, "beneficiary_account_id": beneficiary_account_id }),
+        0
+    )
+}
+
+pub fn change_referral_fee(referral_fee: Option<Fee>) -> FunctionCall {
+    FunctionCall::new("change_referral_fee", &json!({ "referral_fee": referral_fee }), 0)
+}
+
+pub fn change_keeper_bounty(keeper_bounty: Option<Fee>) -> FunctionCall {
+    FunctionCall::new("change_keeper_bounty", &json!({ "keeper_bounty": keeper_bounty }), 0)
+}
+
+pub fn add_referrer(referrer_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("add_referrer", &json!({ "referrer_account_id": referrer_account_id }), STORAGE_STAKING_DEPOSIT)
+}
+
+pub fn remove_referrer(referrer_account_id: AccountId) -> FunctionCall {
+    FunctionCall::new("remove_referrer", &json!({ "referrer_account_id": referrer_account_id }), 0)
+}
+
+pub fn claim_referral_fee() -> FunctionCall {
+    FunctionCall::new("claim_referral_fee", &json!({}), ONE_YOCTO)
+}
+
+pub fn change_deposit_caps(pool_near_amount: Option<Balance>, validator_share: Option<Fee>, account_near_amount: Option<Balance>) -> FunctionCall {
+    FunctionCall::new(
+        "change_deposit_caps",
+        &json!({
+            "pool_near_amount": to_json_balance(pool_near_amount),
+            "validator_share": validator_share,
+            "account_near_amount": to_json_balance(account_near_amount)
+        }),
+        0
+    )
+}
+
+pub fn update_config(pool_config: PoolConfig) -> FunctionCall {
+    FunctionCall::new("update_config", &json!({ "pool_config": pool_config }), 0)
+}
+
+pub fn cancel_config_update() -> FunctionCall {
+    FunctionCall::new("cancel_config_update", &json!({}), 0)
+}
+
+pub fn confirm_stake_distribution() -> FunctionCall {
+    FunctionCall::new("confirm_stake_distribution", &json!({}), 0)
+}
+
+pub fn ft_transfer(receiver_id: AccountId, amount: Balance, memo: Option<String>) -> FunctionCall {
+    FunctionCall::new("ft_transfer", &json!({ "receiver_id": receiver_id, "amount": U128(amount), "memo": memo }), ONE_YOCTO)
+}
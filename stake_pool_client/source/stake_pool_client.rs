@@ -0,0 +1,246 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::{self, json};
+use near_sdk::{AccountId, Balance, EpochHeight};
+use stake_pool::data_transfer_object::account_balance::AccountBalance;
+use stake_pool::data_transfer_object::aggregated::Aggregated;
+use stake_pool::data_transfer_object::apy::Apy;
+use stake_pool::data_transfer_object::delayed_withdraw_preview::DelayedWithdrawPreview;
+use stake_pool::data_transfer_object::delayed_withdrawal_details::DelayedWithdrawalDetails;
+use stake_pool::data_transfer_object::deposit_capacity::DepositCapacity;
+use stake_pool::data_transfer_object::deposit_caps::DepositCaps;
+use stake_pool::data_transfer_object::deposit_preview::DepositPreview;
+use stake_pool::data_transfer_object::epoch_height_registry::EpochHeightRegistry;
+use stake_pool::data_transfer_object::epoch_report::EpochReport;
+use stake_pool::data_transfer_object::fee_registry_light::FeeRegistryLight;
+use stake_pool::data_transfer_object::full_for_account::FullForAccount;
+use stake_pool::data_transfer_object::full::Full;
+use stake_pool::data_transfer_object::fund::Fund;
+use stake_pool::data_transfer_object::instant_withdraw_preview::InstantWithdrawPreview;
+use stake_pool::data_transfer_object::invariant_report::InvariantReport;
+use stake_pool::data_transfer_object::investor_investment::InvestorInvestment;
+use stake_pool::data_transfer_object::pending_pool_config::PendingPoolConfig;
+use stake_pool::data_transfer_object::pool_config::PoolConfig;
+use stake_pool::data_transfer_object::referrer::Referrer;
+use stake_pool::data_transfer_object::requested_to_withdrawal_fund::RequestedToWithdrawalFund;
+use stake_pool::data_transfer_object::storage_staking_price::StorageStakingPrice;
+use stake_pool::data_transfer_object::storage_staking_requested_coverage::StorageStakingRequestedCoverage;
+use stake_pool::data_transfer_object::validator_performance::ValidatorPerformance;
+use stake_pool::data_transfer_object::validator_reconciliation::ValidatorReconciliation;
+use stake_pool::data_transfer_object::validator::Validator;
+use stake_pool::fee_registry::FeeRegistry;
+use super::client_error::ClientError;
+use super::function_call::FunctionCall;
+use super::transport::Transport;
+
+/// Client of the stake pool deployed on the account.
+pub struct StakePoolClient<T: Transport> {
+    transport: T,
+    contract_account_id: AccountId
+}
+
+impl<T: Transport> StakePoolClient<T> {
+    pub fn new(transport: T, contract_account_id: AccountId) -> Self {
+        Self {
+            transport,
+            contract_account_id
+        }
+    }
+
+    pub fn get_contract_account_id(&self) -> &AccountId {
+        &self.contract_account_id
+    }
+
+    pub fn get_transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Sends the request built by the 'request' module and discards the result.
+    pub fn call(&mut self, function_call: FunctionCall) -> Result<(), ClientError> {
+        self.transport.call(&self.contract_account_id, function_call)
+            .map(|_| ())
+            .map_err(ClientError::from_failure)
+    }
+
+    /// Sends the request built by the 'request' module and decodes the result.
+    pub fn call_with_result<R: DeserializeOwned>(&mut self, function_call: FunctionCall) -> Result<R, ClientError> {
+        let method_name = function_call.method_name.clone();
+
+        let result = self.transport.call(&self.contract_account_id, function_call)
+            .map_err(ClientError::from_failure)?;
+
+        Self::decode(&method_name, &result)
+    }
+
+    // View-methods:
+
+    pub fn get_delayed_withdrawal_details(&mut self, account_id: &AccountId) -> Result<Option<DelayedWithdrawalDetails>, ClientError> {
+        self.view("get_delayed_withdrawal_details", json!({ "account_id": account_id }))
+    }
+
+    pub fn get_auto_claim_queue_length(&mut self) -> Result<u64, ClientError> {
+        self.view("get_auto_claim_queue_length", json!({}))
+    }
+
+    pub fn get_pending_refund(&mut self, account_id: &AccountId) -> Result<Balance, ClientError> {
+        self.view::<U128>("get_pending_refund", json!({ "account_id": account_id })).map(|near_amount| near_amount.0)
+    }
+
+    pub fn get_account_balance(&mut self, account_id: &AccountId) -> Result<AccountBalance, ClientError> {
+        self.view("get_account_balance", json!({ "account_id": account_id }))
+    }
+
+    pub fn get_total_token_supply(&mut self) -> Result<Balance, ClientError> {
+        self.view::<U128>("get_total_token_supply", json!({})).map(|token_amount| token_amount.0)
+    }
+
+    pub fn get_minimum_deposit_amount(&mut self) -> Result<Balance, ClientError> {
+        self.view::<U128>("get_minimum_deposit_amount", json!({})).map(|near_amount| near_amount.0)
+    }
+
+    pub fn get_storage_staking_price(&mut self) -> Result<StorageStakingPrice, ClientError> {
+        self.view("get_storage_staking_price", json!({}))
+    }
+
+    pub fn get_storage_staking_requested_coverage(&mut self, account_id: &AccountId) -> Result<StorageStakingRequestedCoverage, ClientError> {
+        self.view("get_storage_staking_requested_coverage", json!({ "account_id": account_id }))
+    }
+
+    pub fn get_fund(&mut self) -> Result<Fund, ClientError> {
+        self.view("get_fund", json!({}))
+    }
+
+    pub fn get_fee_registry(&mut self) -> Result<FeeRegistry, ClientError> {
+        self.view("get_fee_registry", json!({}))
+    }
+
+    pub fn get_fee_registry_light(&mut self) -> Result<FeeRegistryLight, ClientError> {
+        self.view("get_fee_registry_light", json!({}))
+    }
+
+    pub fn get_current_epoch_height(&mut self) -> Result<EpochHeightRegistry, ClientError> {
+        self.view("get_current_epoch_height", json!({}))
+    }
+
+    pub fn is_stake_distributed(&mut self) -> Result<bool, ClientError> {
+        self.view("is_stake_distributed", json!({}))
+    }
+
+    pub fn get_investor_investment(&mut self, account_id: &AccountId) -> Result<Option<InvestorInvestment>, ClientError> {
+        self.view("get_investor_investment", json!({ "account_id": account_id }))
+    }
+
+    pub fn get_validator_registry(&mut self) -> Result<Vec<Validator>, ClientError> {
+        self.view("get_validator_registry", json!({}))
+    }
+
+    pub fn get_preffered_validator(&mut self) -> Result<Option<Validator>, ClientError> {
+        self.view("get_preffered_validator", json!({}))
+    }
+
+    pub fn get_aggregated(&mut self) -> Result<Aggregated, ClientError> {
+        self.view("get_aggregated", json!({}))
+    }
+
+    pub fn get_requested_to_withdrawal_fund(&mut self) -> Result<RequestedToWithdrawalFund, ClientError> {
+        self.view("get_requested_to_withdrawal_fund", json!({}))
+    }
+
+    pub fn get_full(&mut self) -> Result<Full, ClientError> {
+        self.view("get_full", json!({}))
+    }
+
+    pub fn get_full_for_account(&mut self, account_id: &AccountId) -> Result<FullForAccount, ClientError> {
+        self.view("get_full_for_account", json!({ "account_id": account_id }))
+    }
+
+    pub fn get_near_per_token(&mut self) -> Result<Balance, ClientError> {
+        self.view::<U128>("get_near_per_token", json!({})).map(|near_amount| near_amount.0)
+    }
+
+    pub fn preview_deposit(&mut self, near_amount: Balance) -> Result<DepositPreview, ClientError> {
+        self.view("preview_deposit", json!({ "near_amount": U128(near_amount) }))
+    }
+
+    pub fn preview_instant_withdraw(&mut self, token_amount: Balance) -> Result<InstantWithdrawPreview, ClientError> {
+        self.view("preview_instant_withdraw", json!({ "token_amount": U128(token_amount) }))
+    }
+
+    pub fn preview_delayed_withdraw(&mut self, token_amount: Balance) -> Result<DelayedWithdrawPreview, ClientError> {
+        self.view("preview_delayed_withdraw", json!({ "token_amount": U128(token_amount) }))
+    }
+
+    pub fn get_epoch_reports_quantity(&mut self) -> Result<u64, ClientError> {
+        self.view("get_epoch_reports_quantity", json!({}))
+    }
+
+    pub fn get_epoch_reports(&mut self, from_index: u64, limit: u64) -> Result<Vec<EpochReport>, ClientError> {
+        self.view("get_epoch_reports", json!({ "from_index": from_index, "limit": limit }))
+    }
+
+    pub fn get_apy(&mut self, epoch_quantity: EpochHeight) -> Result<Option<Apy>, ClientError> {
+        self.view("get_apy", json!({ "epoch_quantity": epoch_quantity }))
+    }
+
+    pub fn get_validator_reconciliation(&mut self, validator_account_id: &AccountId) -> Result<Option<ValidatorReconciliation>, ClientError> {
+        self.view("get_validator_reconciliation", json!({ "validator_account_id": validator_account_id }))
+    }
+
+    pub fn get_validator_performance(&mut self, validator_account_id: &AccountId, from_index: u64, limit: u64) -> Result<Vec<ValidatorPerformance>, ClientError> {
+        self.view("get_validator_performance", json!({ "validator_account_id": validator_account_id, "from_index": from_index, "limit": limit }))
+    }
+
+    pub fn get_pool_config(&mut self) -> Result<PoolConfig, ClientError> {
+        self.view("get_pool_config", json!({}))
+    }
+
+    pub fn get_pending_pool_config(&mut self) -> Result<Option<PendingPoolConfig>, ClientError> {
+        self.view("get_pending_pool_config", json!({}))
+    }
+
+    pub fn get_deposit_caps(&mut self) -> Result<DepositCaps, ClientError> {
+        self.view("get_deposit_caps", json!({}))
+    }
+
+    pub fn get_referrer(&mut self, referrer_account_id: &AccountId) -> Result<Option<Referrer>, ClientError> {
+        self.view("get_referrer", json!({ "referrer_account_id": referrer_account_id }))
+    }
+
+    pub fn get_referrer_registry(&mut self) -> Result<Vec<Referrer>, ClientError> {
+        self.view("get_referrer_registry", json!({}))
+    }
+
+    pub fn get_remaining_deposit_capacity(
+        &mut self,
+        account_id: Option<&AccountId>,
+        validator_account_id: Option<&AccountId>
+    ) -> Result<DepositCapacity, ClientError> {
+        self.view("get_remaining_deposit_capacity", json!({ "account_id": account_id, "validator_account_id": validator_account_id }))
+    }
+
+    pub fn check_invariants(&mut self, account_ids: Option<&[AccountId]>) -> Result<InvariantReport, ClientError> {
+        self.view("check_invariants", json!({ "account_ids": account_ids }))
+    }
+
+    pub fn ft_total_supply(&mut self) -> Result<Balance, ClientError> {
+        self.view::<U128>("ft_total_supply", json!({})).map(|token_amount| token_amount.0)
+    }
+
+    pub fn ft_balance_of(&mut self, account_id: &AccountId) -> Result<Balance, ClientError> {
+        self.view::<U128>("ft_balance_of", json!({ "account_id": account_id })).map(|token_amount| token_amount.0)
+    }
+
+    fn view<R: DeserializeOwned>(&mut self, method_name: &str, arguments: impl Serialize) -> Result<R, ClientError> {
+        let result = self.transport.view(&self.contract_account_id, method_name, serde_json::to_vec(&arguments).unwrap())
+            .map_err(ClientError::from_failure)?;
+
+        Self::decode(method_name, &result)
+    }
+
+    fn decode<R: DeserializeOwned>(method_name: &str, result: &[u8]) -> Result<R, ClientError> {
+        serde_json::from_slice(result).map_err(
+            |error| ClientError::Decoding { method_name: method_name.to_string(), reason: error.to_string() }
+        )
+    }
+}
@@ -0,0 +1,11 @@
+use near_sdk::AccountId;
+use super::function_call::FunctionCall;
+
+/// Access to the network. The implementation sends the JSON arguments as is and returns the JSON result of the method.
+/// The error is the failure message of the execution, the panic message of the stake pool is decoded by the client.
+pub trait Transport {
+    fn view(&mut self, contract_account_id: &AccountId, method_name: &str, arguments: Vec<u8>) -> Result<Vec<u8>, String>;
+
+    /// Signs and sends the transaction with the function call and returns the result after the execution of all the receipts.
+    fn call(&mut self, contract_account_id: &AccountId, function_call: FunctionCall) -> Result<Vec<u8>, String>;
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeSet;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::{AccountId, ONE_NEAR};
+use stake_pool::abi;
+use stake_pool::fee::Fee;
+use stake_pool::fee_type::FeeType;
+use stake_pool::stake_decreasing_kind::StakeDecreasingType;
+use stake_pool::staking_contract_version::StakingContractVersion;
+use stake_pool_client::{request, FunctionCall, StakePoolClient, Transport};
+
+/// Transport, that records the views and fails them.
+#[derive(Default)]
+struct RecordingTransport {
+    views: Vec<(String, Vec<u8>)>
+}
+
+impl Transport for RecordingTransport {
+    fn view(&mut self, _contract_account_id: &AccountId, method_name: &str, arguments: Vec<u8>) -> Result<Vec<u8>, String> {
+        self.views.push((method_name.to_string(), arguments));
+
+        Err("View is recorded.".to_string())
+    }
+
+    fn call(&mut self, _contract_account_id: &AccountId, _function_call: FunctionCall) -> Result<Vec<u8>, String> {
+        Err("Call is not expected.".to_string())
+    }
+}
+
+fn account_id() -> AccountId {
+    "account.near".parse().unwrap()
+}
+
+fn fee() -> Fee {
+    Fee { numerator: 1, denominator: 10 }
+}
+
+/// Asserts, that the method is declared in the ABI with the kind, and that the arguments are the declared parameters
+/// with all the parameters, that are not nullable.
+fn assert_matches_abi(abi: &Value, method_name: &str, is_view: bool, arguments: &[u8]) {
+    let function = abi["body"]["functions"].as_array().unwrap().iter()
+        .find(|function| function["name"] == method_name)
+        .unwrap_or_else(|| panic!("Method {} is not declared in the ABI.", method_name));
+    assert_eq!(function["kind"] == "view", is_view, "Kind of the {} does not match the ABI.", method_name);
+
+    let parameters: Vec<Value> = function["params"]["args"].as_array().cloned().unwrap_or_default();
+    let parameter_names: BTreeSet<&str> = parameters.iter().map(|parameter| parameter["name"].as_str().unwrap()).collect();
+    let required_parameter_names: BTreeSet<&str> = parameters.iter()
+        .filter(|parameter| !parameter["type_schema"].to_string().contains("\"null\""))
+        .map(|parameter| parameter["name"].as_str().unwrap())
+        .collect();
+
+    let arguments: Value = serde_json::from_slice(arguments).unwrap();
+    let argument_names: BTreeSet<&str> = arguments.as_object().unwrap().keys().map(String::as_str).collect();
+
+    assert!(argument_names.is_subset(&parameter_names), "Arguments of the {} are not declared in the ABI: {:?}.", method_name, argument_names);
+    assert!(required_parameter_names.is_subset(&argument_names), "Arguments of the {} are absent: {:?}.", method_name, required_parameter_names);
+}
+
+#[test]
+fn requests_match_abi() {
+    let abi = abi::generate_abi();
+
+    let function_calls = vec![
+        request::new(
+            serde_json::from_value(json!({ "name": "Staked Near", "symbol": "stNEAR", "icon": null, "reference": null, "reference_hash": null, "decimals": 24 })).unwrap(),
+            None,
+            Some(fee()),
+            serde_json::from_value(json!({ "beneficiaries": [{ "account_id": "account.near", "numerator": 1 }], "denominator": 1 })).unwrap(),
+            None,
+            serde_json::from_value(json!({ "beneficiaries": [{ "account_id": "account.near", "numerator": 1 }], "denominator": 1 })).unwrap(),
+            None
+        ),
+        request::deposit(ONE_NEAR, None, None),
+        request::deposit_for(account_id(), ONE_NEAR, None, None),
+        request::deposit_on_validator(ONE_NEAR, account_id(), None),
+        request::instant_withdraw(ONE_NEAR, None),
+        request::instant_withdraw_to(account_id(), ONE_NEAR, None),
+        request::delayed_withdraw(ONE_NEAR, None),
+        request::delayed_withdraw_from_validator(ONE_NEAR, account_id()),
+        request::take_delayed_withdrawal(),
+        request::take_delayed_withdrawal_to(account_id()),
+        request::enable_auto_claim(),
+        request::process_matured_withdrawals(10),
+        request::claim_refund(None),
+        request::increase_validator_stake(account_id(), ONE_NEAR),
+        request::requested_decrease_validator_stake(account_id(), ONE_NEAR, StakeDecreasingType::Classic),
+        request::take_unstaked_balance(account_id()),
+        request::update_validator(account_id()),
+        request::reconcile_validator(account_id()),
+        request::apply_validator_correction(account_id()),
+        request::update(),
+        request::add_validator(account_id(), StakingContractVersion::Core, false, false),
+        request::change_validator_investment_context(account_id(), true),
+        request::change_preffered_validator(None),
+        request::remove_validator(account_id()),
+        request::start_draining_validator(account_id()),
+        request::drain_validator(account_id()),
+        request::migrate_investment_to_classic(account_id(), account_id()),
+        request::add_investor(account_id()),
+        request::remove_investor(account_id()),
+        request::change_manager(account_id()),
+        request::change_reward_fee(None),
+        request::change_instant_withdraw_fee(None),
+        request::change_instant_withdraw_fee_curve(None),
+        request::add_fee_beneficiary(FeeType::Reward, account_id(), 1),
+        request::remove_fee_beneficiary(FeeType::InstantWithdraw, account_id()),
+        request::change_referral_fee(None),
+        request::change_keeper_bounty(None),
+        request::add_referrer(account_id()),
+        request::remove_referrer(account_id()),
+        request::claim_referral_fee(),
+        request::change_deposit_caps(None, None, None),
+        request::update_config(
+            serde_json::from_value(json!({
+                "epoch_quantity_for_delayed_withdrawal": 8,
+                "epoch_quantity_for_validator_unstake": 4,
+                "minimum_number_of_tgas": 300,
+                "minimum_deposit_amount": "1"
+            })).unwrap()
+        ),
+        request::cancel_config_update(),
+        request::confirm_stake_distribution()
+    ];
+    for function_call in function_calls.iter() {
+        assert_matches_abi(&abi, &function_call.method_name, false, &function_call.arguments);
+    }
+
+    // The pool declares the '_memo' instead of the 'memo' of the standard, so the memo is ignored.
+    let mut arguments: Value = serde_json::from_slice(&request::ft_transfer(account_id(), ONE_NEAR, None).arguments).unwrap();
+    arguments.as_object_mut().unwrap().remove("memo");
+    assert_matches_abi(&abi, "ft_transfer", false, &serde_json::to_vec(&arguments).unwrap());
+}
+
+#[test]
+fn views_match_abi() {
+    let abi = abi::generate_abi();
+    let mut client = StakePoolClient::new(RecordingTransport::default(), account_id());
+
+    // The results are not needed, only the requests are recorded.
+    let _ = client.get_delayed_withdrawal_details(&account_id());
+    let _ = client.get_auto_claim_queue_length();
+    let _ = client.get_pending_refund(&account_id());
+    let _ = client.get_account_balance(&account_id());
+    let _ = client.get_total_token_supply();
+    let _ = client.get_minimum_deposit_amount();
+    let _ = client.get_storage_staking_price();
+    let _ = client.get_storage_staking_requested_coverage(&account_id());
+    let _ = client.get_fund();
+    let _ = client.get_fee_registry();
+    let _ = client.get_fee_registry_light();
+    let _ = client.get_current_epoch_height();
+    let _ = client.is_stake_distributed();
+    let _ = client.get_investor_investment(&account_id());
+    let _ = client.get_validator_registry();
+    let _ = client.get_preffered_validator();
+    let _ = client.get_aggregated();
+    let _ = client.get_requested_to_withdrawal_fund();
+    let _ = client.get_full();
+    let _ = client.get_full_for_account(&account_id());
+    let _ = client.get_near_per_token();
+    let _ = client.preview_deposit(ONE_NEAR);
+    let _ = client.preview_instant_withdraw(ONE_NEAR);
+    let _ = client.preview_delayed_withdraw(ONE_NEAR);
+    let _ = client.get_epoch_reports_quantity();
+    let _ = client.get_epoch_reports(0, 10);
+    let _ = client.get_apy(10);
+    let _ = client.get_validator_reconciliation(&account_id());
+    let _ = client.get_validator_performance(&account_id(), 0, 10);
+    let _ = client.get_pool_config();
+    let _ = client.get_pending_pool_config();
+    let _ = client.get_deposit_caps();
+    let _ = client.get_referrer(&account_id());
+    let _ = client.get_referrer_registry();
+    let _ = client.get_remaining_deposit_capacity(Some(&account_id()), None);
+    let _ = client.check_invariants(None);
+    let _ = client.ft_total_supply();
+    let _ = client.ft_balance_of(&account_id());
+
+    let views = &client.get_transport_mut().views;
+    for (method_name, arguments) in views.iter() {
+        assert_matches_abi(&abi, method_name, true, arguments);
+    }
+
+    // Each view-method of the ABI has the view of the client.
+    let method_names: BTreeSet<&str> = views.iter().map(|(method_name, _)| method_name.as_str()).collect();
+    for function in abi["body"]["functions"].as_array().unwrap().iter().filter(|function| function["kind"] == "view") {
+        assert!(method_names.contains(function["name"].as_str().unwrap()), "View {} is absent in the client.", function["name"]);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{testing_env, AccountId, ONE_NEAR, ONE_YOCTO};
+use stake_pool::stake_decreasing_kind::StakeDecreasingType;
+use stake_pool::stake_pool::StakePool;
+use stake_pool_client::function_call::{DEFAULT_GAS, INITIALIZATION_DEPOSIT, STORAGE_STAKING_DEPOSIT};
+use stake_pool_client::{request, ClientError, FunctionCall, StakePoolClient, Transport};
+
+/// Transport, that records the requests and returns the prepared results by the method name.
+#[derive(Default)]
+struct MockTransport {
+    results: HashMap<String, Result<Vec<u8>, String>>,
+    views: Vec<(String, Value)>,
+    calls: Vec<FunctionCall>
+}
+
+impl MockTransport {
+    fn with_result(mut self, method_name: &str, result: Result<&str, &str>) -> Self {
+        self.results.insert(method_name.to_string(), result.map(|result_| result_.as_bytes().to_vec()).map_err(str::to_string));
+
+        self
+    }
+
+    fn get_result(&self, method_name: &str) -> Result<Vec<u8>, String> {
+        match self.results.get(method_name) {
+            Some(result) => result.clone(),
+            None => Err(format!("Method {} is not supported.", method_name))
+        }
+    }
+}
+
+impl Transport for MockTransport {
+    fn view(&mut self, _contract_account_id: &AccountId, method_name: &str, arguments: Vec<u8>) -> Result<Vec<u8>, String> {
+        self.views.push((method_name.to_string(), serde_json::from_slice(&arguments).unwrap()));
+
+        self.get_result(method_name)
+    }
+
+    fn call(&mut self, _contract_account_id: &AccountId, function_call: FunctionCall) -> Result<Vec<u8>, String> {
+        let result = self.get_result(&function_call.method_name);
+        self.calls.push(function_call);
+
+        result
+    }
+}
+
+fn pool_account_id() -> AccountId {
+    "pool.near".parse().unwrap()
+}
+
+fn user_account_id() -> AccountId {
+    "user.near".parse().unwrap()
+}
+
+fn validator_account_id() -> AccountId {
+    "validator.near".parse().unwrap()
+}
+
+fn get_arguments(function_call: &FunctionCall) -> Value {
+    serde_json::from_slice(&function_call.arguments).unwrap()
+}
+
+#[test]
+fn requests_attach_defaults_and_encode_arguments() {
+    let deposit = request::deposit(10 * ONE_NEAR, None, Some(user_account_id()));
+    assert_eq!(deposit.method_name, "deposit");
+    assert_eq!(get_arguments(&deposit), json!({ "near_amount": "10000000000000000000000000", "min_token_amount": null, "referrer_id": "user.near" }));
+    assert_eq!(deposit.gas, DEFAULT_GAS);
+    assert_eq!(deposit.deposit, 10 * ONE_NEAR + STORAGE_STAKING_DEPOSIT);
+
+    let instant_withdraw = request::instant_withdraw(ONE_NEAR, Some(ONE_NEAR / 2));
+    assert_eq!(get_arguments(&instant_withdraw), json!({ "token_amount": ONE_NEAR.to_string(), "min_near_amount": (ONE_NEAR / 2).to_string() }));
+    assert_eq!(instant_withdraw.deposit, ONE_YOCTO);
+
+    assert_eq!(request::delayed_withdraw(ONE_NEAR, None).deposit, STORAGE_STAKING_DEPOSIT);
+    assert_eq!(request::update().deposit, 0);
+
+    let requested_decrease_validator_stake = request::requested_decrease_validator_stake(validator_account_id(), ONE_NEAR, StakeDecreasingType::Investment);
+    assert_eq!(get_arguments(&requested_decrease_validator_stake)["stake_decreasing_type"], "Investment");
+
+    let update_validator = request::update_validator(validator_account_id())
+        .with_gas(DEFAULT_GAS.0.into())
+        .with_deposit(ONE_YOCTO);
+    assert_eq!(update_validator.deposit, ONE_YOCTO);
+}
+
+#[test]
+fn results_are_decoded() {
+    let transport = MockTransport::default()
+        .with_result("get_delayed_withdrawal_details", Ok(r#"{"epoch_quantity_to_take_delayed_withdrawal": 3, "near_amount": "5", "is_auto_claim": false}"#))
+        .with_result("ft_balance_of", Ok(r#""42""#))
+        .with_result("process_matured_withdrawals", Ok("7"))
+        .with_result("take_delayed_withdrawal", Ok(""));
+    let mut client = StakePoolClient::new(transport, pool_account_id());
+
+    let delayed_withdrawal_details = client.get_delayed_withdrawal_details(&user_account_id()).unwrap().unwrap();
+    assert_eq!(delayed_withdrawal_details.near_amount.0, 5);
+    assert_eq!(delayed_withdrawal_details.epoch_quantity_to_take_delayed_withdrawal, 3);
+    assert_eq!(client.ft_balance_of(&user_account_id()).unwrap(), 42);
+    assert_eq!(client.call_with_result::<u64>(request::process_matured_withdrawals(10)).unwrap(), 7);
+    client.call(request::take_delayed_withdrawal()).unwrap();
+
+    let transport = client.get_transport_mut();
+    assert_eq!(transport.views[0], ("get_delayed_withdrawal_details".to_string(), json!({ "account_id": "user.near" })));
+    assert_eq!(transport.calls.len(), 2);
+    assert_eq!(transport.calls[1], request::take_delayed_withdrawal());
+}
+
+#[test]
+fn failures_are_decoded() {
+    let transport = MockTransport::default()
+        .with_result(
+            "deposit",
+            Err(r#"Smart contract panicked: {"kind":"InsufficientNearDeposit","required_near_amount":"2","provided_near_amount":"1","code":1007,"message":"Insufficient Near deposit."}"#)
+        )
+        .with_result("get_fund", Ok(r#"{"classic_unstaked_balance": 1}"#));
+    let mut client = StakePoolClient::new(transport, pool_account_id());
+
+    assert_eq!(
+        client.call(request::deposit(ONE_NEAR, None, None)),
+        Err(
+            ClientError::Contract {
+                code: 1007,
+                kind: "InsufficientNearDeposit".to_string(),
+                message: "Insufficient Near deposit.".to_string()
+            }
+        )
+    );
+    assert!(matches!(client.get_fund(), Err(ClientError::Decoding { method_name, .. }) if method_name == "get_fund"));
+    assert!(matches!(client.get_full(), Err(ClientError::Transport { .. })));
+}
+
+#[test]
+fn storage_staking_deposit_covers_storage_staking_price() {
+    // The initialization panics, if the deposit does not cover the storage staking price of the epoch history.
+    testing_env!(
+        VMContextBuilder::new()
+            .current_account_id(pool_account_id())
+            .predecessor_account_id(user_account_id())
+            .attached_deposit(INITIALIZATION_DEPOSIT)
+            .build()
+    );
+
+    let stake_pool = StakePool::new(
+        serde_json::from_str(r#"{"name": "Staked Near", "symbol": "stNEAR", "icon": null, "reference": null, "reference_hash": null, "decimals": 24}"#).unwrap(),
+        None,
+        None,
+        serde_json::from_str(r#"{"beneficiaries": [{"account_id": "user.near", "numerator": 1}], "denominator": 1}"#).unwrap(),
+        None,
+        serde_json::from_str(r#"{"beneficiaries": [{"account_id": "user.near", "numerator": 1}], "denominator": 1}"#).unwrap(),
+        None
+    );
+
+    // Some methods reserve storage in several registries, so the deposit covers all of them together.
+    let storage_staking_price = serde_json::to_value(stake_pool.get_storage_staking_price()).unwrap();
+    let near_amount: u128 = storage_staking_price.as_object().unwrap().values()
+        .map(|near_amount| near_amount.as_str().unwrap().parse::<u128>().unwrap())
+        .sum();
+    assert!(near_amount <= STORAGE_STAKING_DEPOSIT, "{}", storage_staking_price);
+}
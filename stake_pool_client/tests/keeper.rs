@@ -0,0 +1,478 @@
+use std::time::Duration;
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::{AccountId, Balance, EpochHeight, ONE_NEAR};
+use stake_pool::data_transfer_object::epoch_height_registry::EpochHeightRegistry;
+use stake_pool::data_transfer_object::fee_registry_light::FeeRegistryLight;
+use stake_pool::data_transfer_object::full::Full;
+use stake_pool::data_transfer_object::fund::Fund;
+use stake_pool::data_transfer_object::pool_config::PoolConfig;
+use stake_pool::data_transfer_object::requested_to_withdrawal_fund::RequestedToWithdrawalFund;
+use stake_pool::data_transfer_object::storage_staking_price::StorageStakingPrice;
+use stake_pool::data_transfer_object::validator::Validator;
+use stake_pool::stake_decreasing_kind::StakeDecreasingType;
+use stake_pool_client::keeper::{Action, Keeper, KeeperError};
+use stake_pool_client::{FunctionCall, StakePoolClient, Transport};
+
+const EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE: EpochHeight = 4;
+
+struct StandInValidator {
+    account_id: AccountId,
+    unstaked_balance: Balance,
+    classic_staked_balance: Balance,
+    investment_staked_balance: Balance,
+    last_update_epoch_height: EpochHeight,
+    is_draining: bool
+}
+
+/// Transport, that keeps the state of the pool affected by the epoch pipeline and checks the same preconditions
+/// as the stake pool.
+struct StandInPool {
+    pool_epoch_height: EpochHeight,
+    network_epoch_height: EpochHeight,
+    validators: Vec<StandInValidator>,
+    classic_unstaked_balance: Balance,
+    requested_classic_near_amount: Balance,
+    requested_investment_near_amount: Balance,
+    investment_withdrawal_registry: Vec<(AccountId, Balance)>,
+    is_distributed: bool,
+    /// Method, which call fails in the transport once after the execution.
+    failing_method_name: Option<String>,
+    /// Method, which call is executed once by someone else before the call of the keeper.
+    concurrent_method_name: Option<String>,
+    /// Method, which call is accepted, but does not change the state.
+    ignored_method_name: Option<String>,
+    calls: Vec<String>
+}
+
+impl StandInPool {
+    /// The pool is desynchronized in the right epoch, the first validator has the unstaked balance.
+    fn new() -> Self {
+        Self {
+            pool_epoch_height: 7,
+            network_epoch_height: 8,
+            validators: vec![
+                StandInValidator {
+                    account_id: first_validator_account_id(),
+                    unstaked_balance: ONE_NEAR,
+                    classic_staked_balance: 10 * ONE_NEAR,
+                    investment_staked_balance: 0,
+                    last_update_epoch_height: 7,
+                    is_draining: false
+                },
+                StandInValidator {
+                    account_id: second_validator_account_id(),
+                    unstaked_balance: 0,
+                    classic_staked_balance: 20 * ONE_NEAR,
+                    investment_staked_balance: 5 * ONE_NEAR,
+                    last_update_epoch_height: 7,
+                    is_draining: false
+                }
+            ],
+            classic_unstaked_balance: 3 * ONE_NEAR,
+            requested_classic_near_amount: 25 * ONE_NEAR,
+            requested_investment_near_amount: 2 * ONE_NEAR,
+            investment_withdrawal_registry: vec![(second_validator_account_id(), 2 * ONE_NEAR)],
+            is_distributed: true,
+            failing_method_name: None,
+            concurrent_method_name: None,
+            ignored_method_name: None,
+            calls: vec![]
+        }
+    }
+
+    fn is_right_epoch(&self) -> bool {
+        self.network_epoch_height % EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE == 0
+    }
+
+    fn get_validator(&mut self, arguments: &Value) -> Result<&mut StandInValidator, String> {
+        let validator_account_id: AccountId = serde_json::from_value(arguments["validator_account_id"].clone()).unwrap();
+
+        self.validators.iter_mut()
+            .find(|validator| validator.account_id == validator_account_id)
+            .ok_or_else(|| error("ValidatorAccountNotRegistered"))
+    }
+
+    fn execute(&mut self, method_name: &str, arguments: &Value) -> Result<(), String> {
+        let is_synchronized = self.pool_epoch_height == self.network_epoch_height;
+        let is_right_epoch = self.is_right_epoch();
+        let network_epoch_height = self.network_epoch_height;
+
+        match method_name {
+            "take_unstaked_balance" => {
+                require(!is_synchronized, "EpochShouldBeDesynchronized")?;
+                require(is_right_epoch, "WrongEpochForTakeUnstakedBalance")?;
+
+                let validator = self.get_validator(arguments)?;
+                require(validator.last_update_epoch_height < network_epoch_height, "ValidatorAlreadyUpdated")?;
+                require(validator.unstaked_balance > 0, "InsufficientUnstakedBalanceOnValidator")?;
+
+                validator.unstaked_balance = 0;
+            }
+            "update_validator" => {
+                require(!is_synchronized, "EpochShouldBeDesynchronized")?;
+
+                let validator = self.get_validator(arguments)?;
+                require(validator.last_update_epoch_height < network_epoch_height, "ValidatorAlreadyUpdated")?;
+
+                validator.last_update_epoch_height = network_epoch_height;
+            }
+            "requested_decrease_validator_stake" => {
+                require(!is_synchronized, "EpochShouldBeDesynchronized")?;
+                require(is_right_epoch, "WrongEpochForRequestedDecreaseValidatorStake")?;
+
+                let near_amount: U128 = serde_json::from_value(arguments["near_amount"].clone()).unwrap();
+                let stake_decreasing_type: StakeDecreasingType = serde_json::from_value(arguments["stake_decreasing_type"].clone()).unwrap();
+                match stake_decreasing_type {
+                    StakeDecreasingType::Classic => {
+                        require(near_amount.0 <= self.requested_classic_near_amount, "NearAmountMoreThanRequestedNearAmountFromValidator")?;
+                        self.requested_classic_near_amount -= near_amount.0;
+
+                        let validator = self.get_validator(arguments)?;
+                        require(!validator.is_draining, "ValidatorInDrainingState")?;
+                        require(near_amount.0 <= validator.classic_staked_balance, "NearAmountExceededAvailableNearBalanceOnValidator")?;
+                        validator.classic_staked_balance -= near_amount.0;
+                        validator.unstaked_balance += near_amount.0;
+                    }
+                    StakeDecreasingType::Investment => {
+                        require(near_amount.0 <= self.requested_investment_near_amount, "NearAmountMoreThanRequestedNearAmountFromValidator")?;
+                        self.requested_investment_near_amount -= near_amount.0;
+
+                        let validator = self.get_validator(arguments)?;
+                        require(near_amount.0 <= validator.investment_staked_balance, "NearAmountExceededAvailableInvestorNearBalanceOnValidator")?;
+                        validator.investment_staked_balance -= near_amount.0;
+                        validator.unstaked_balance += near_amount.0;
+
+                        let validator_account_id = validator.account_id.clone();
+                        let (_, requested_near_amount) = self.investment_withdrawal_registry.iter_mut()
+                            .find(|(account_id, _)| *account_id == validator_account_id)
+                            .unwrap();
+                        *requested_near_amount -= near_amount.0;
+                    }
+                }
+            }
+            "update" => {
+                require(!is_synchronized, "EpochShouldBeDesynchronized")?;
+                require(
+                    self.validators.iter().all(|validator| validator.last_update_epoch_height == network_epoch_height),
+                    "SomeValidatorsNotUpdated"
+                )?;
+                require(
+                    !is_right_epoch || (self.requested_classic_near_amount == 0 && self.requested_investment_near_amount == 0),
+                    "SomeFundsNotUnstakedFromValidators"
+                )?;
+
+                self.pool_epoch_height = network_epoch_height;
+                self.is_distributed = false;
+            }
+            "increase_validator_stake" => {
+                require(is_synchronized, "EpochShouldBeSynchronized")?;
+
+                let near_amount: U128 = serde_json::from_value(arguments["near_amount"].clone()).unwrap();
+                require(near_amount.0 <= self.classic_unstaked_balance, "InsufficientNearAmount")?;
+                self.classic_unstaked_balance -= near_amount.0;
+
+                self.get_validator(arguments)?.classic_staked_balance += near_amount.0;
+            }
+            "confirm_stake_distribution" => {
+                require(is_synchronized, "EpochShouldBeSynchronized")?;
+                require(!self.is_distributed, "FundAlreadyDistributed")?;
+
+                self.is_distributed = true;
+            }
+            _ => return Err(format!("Method {} is not supported.", method_name))
+        }
+
+        Ok(())
+    }
+}
+
+fn error(kind: &str) -> String {
+    format!("Smart contract panicked: {}", json!({ "code": 1000, "kind": kind, "message": kind }))
+}
+
+fn require(condition: bool, kind: &str) -> Result<(), String> {
+    if !condition {
+        return Err(error(kind));
+    }
+
+    Ok(())
+}
+
+fn to_json(value: impl Serialize) -> Vec<u8> {
+    serde_json::to_vec(&value).unwrap()
+}
+
+impl Transport for StandInPool {
+    fn view(&mut self, _contract_account_id: &AccountId, method_name: &str, _arguments: Vec<u8>) -> Result<Vec<u8>, String> {
+        let requested_to_withdrawal_fund = RequestedToWithdrawalFund {
+            classic_near_amount: U128(self.requested_classic_near_amount),
+            investment_near_amount: U128(self.requested_investment_near_amount),
+            investment_withdrawal_registry: self.investment_withdrawal_registry.iter()
+                .map(|(account_id, near_amount)| (account_id.clone(), U128(*near_amount)))
+                .collect()
+        };
+
+        let result = match method_name {
+            "get_current_epoch_height" => to_json(
+                EpochHeightRegistry {
+                    pool_epoch_height: self.pool_epoch_height,
+                    network_epoch_height: self.network_epoch_height
+                }
+            ),
+            "get_pool_config" => to_json(
+                PoolConfig {
+                    epoch_quantity_for_delayed_withdrawal: 8,
+                    epoch_quantity_for_validator_unstake: EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE,
+                    minimum_number_of_tgas: 300,
+                    minimum_deposit_amount: U128(ONE_NEAR)
+                }
+            ),
+            "get_full" => {
+                let classic_staked_balance: Balance = self.validators.iter().map(|validator| validator.classic_staked_balance).sum();
+                let investment_staked_balance: Balance = self.validators.iter().map(|validator| validator.investment_staked_balance).sum();
+
+                to_json(
+                    Full {
+                        storage_staking_price: StorageStakingPrice {
+                            per_delayed_withdrawal_fund_delayed_withdrawal: U128(0),
+                            per_delayed_withdrawal_fund_investment_withdrawal: U128(0),
+                            per_delayed_withdrawal_fund_auto_claim: U128(0),
+                            per_fungible_token_account: U128(0),
+                            per_validating_node_validator: U128(0),
+                            per_validating_node_investor: U128(0),
+                            per_validating_node_distribution: U128(0)
+                        },
+                        fund: Fund {
+                            classic_unstaked_balance: U128(self.classic_unstaked_balance),
+                            classic_staked_balance: U128(classic_staked_balance),
+                            investment_staked_balance: U128(investment_staked_balance),
+                            common_staked_balance: U128(classic_staked_balance + investment_staked_balance),
+                            classic_remainder_balance: U128(0),
+                            common_balance: U128(self.classic_unstaked_balance + classic_staked_balance + investment_staked_balance),
+                            pending_refund_balance: U128(0)
+                        },
+                        total_token_supply: U128(0),
+                        requested_to_withdrawal_fund,
+                        fee_registry_light: FeeRegistryLight {
+                            reward_fee: None,
+                            instant_withdraw_fee: None
+                        },
+                        minimum_deposit_amount: U128(ONE_NEAR)
+                    }
+                )
+            }
+            "get_validator_registry" => to_json(
+                self.validators.iter()
+                    .map(|validator| Validator {
+                        account_id: validator.account_id.clone(),
+                        unstaked_balance: U128(validator.unstaked_balance),
+                        classic_staked_balance: U128(validator.classic_staked_balance),
+                        investment_staked_balance: U128(validator.investment_staked_balance),
+                        is_only_for_investment: false,
+                        last_update_epoch_height: validator.last_update_epoch_height,
+                        last_classic_stake_increasing_epoch_height: None,
+                        is_active: true,
+                        consecutive_inactive_epoch_quantity: 0,
+                        is_flagged_as_inactive: false,
+                        is_draining: validator.is_draining,
+                        drained_balance: U128(0)
+                    })
+                    .collect::<Vec<Validator>>()
+            ),
+            "get_requested_to_withdrawal_fund" => to_json(requested_to_withdrawal_fund),
+            "is_stake_distributed" => to_json(self.is_distributed),
+            _ => return Err(format!("Method {} is not supported.", method_name))
+        };
+
+        Ok(result)
+    }
+
+    fn call(&mut self, _contract_account_id: &AccountId, function_call: FunctionCall) -> Result<Vec<u8>, String> {
+        let method_name = function_call.method_name;
+        let arguments: Value = serde_json::from_slice(&function_call.arguments).unwrap();
+
+        if self.concurrent_method_name.as_ref() == Some(&method_name) {
+            self.concurrent_method_name = None;
+            self.execute(&method_name, &arguments)?;
+        }
+
+        self.calls.push(method_name.clone());
+
+        if self.ignored_method_name.as_ref() == Some(&method_name) {
+            return Ok(vec![]);
+        }
+
+        self.execute(&method_name, &arguments)?;
+
+        if self.failing_method_name.as_ref() == Some(&method_name) {
+            self.failing_method_name = None;
+
+            return Err("Transaction is not confirmed in time.".to_string());
+        }
+
+        Ok(vec![])
+    }
+}
+
+fn pool_account_id() -> AccountId {
+    "pool.near".parse().unwrap()
+}
+
+fn first_validator_account_id() -> AccountId {
+    "first_validator.near".parse().unwrap()
+}
+
+fn second_validator_account_id() -> AccountId {
+    "second_validator.near".parse().unwrap()
+}
+
+fn keeper(is_dry_run: bool) -> Keeper {
+    Keeper {
+        is_dry_run,
+        retry_quantity: 1,
+        retry_delay: Duration::ZERO
+    }
+}
+
+#[test]
+fn pipeline_is_passed_in_order() {
+    let mut client = StakePoolClient::new(StandInPool::new(), pool_account_id());
+
+    assert_eq!(
+        keeper(false).run(&mut client).unwrap(),
+        vec![
+            Action::TakeUnstakedBalance { validator_account_id: first_validator_account_id() },
+            Action::UpdateValidator { validator_account_id: first_validator_account_id() },
+            Action::UpdateValidator { validator_account_id: second_validator_account_id() },
+            Action::RequestedDecreaseValidatorStake {
+                validator_account_id: second_validator_account_id(),
+                near_amount: 20 * ONE_NEAR,
+                stake_decreasing_type: StakeDecreasingType::Classic
+            },
+            Action::RequestedDecreaseValidatorStake {
+                validator_account_id: first_validator_account_id(),
+                near_amount: 5 * ONE_NEAR,
+                stake_decreasing_type: StakeDecreasingType::Classic
+            },
+            Action::RequestedDecreaseValidatorStake {
+                validator_account_id: second_validator_account_id(),
+                near_amount: 2 * ONE_NEAR,
+                stake_decreasing_type: StakeDecreasingType::Investment
+            },
+            Action::Update,
+            Action::IncreaseValidatorStake {
+                validator_account_id: second_validator_account_id(),
+                near_amount: 3 * ONE_NEAR
+            },
+            Action::ConfirmStakeDistribution
+        ]
+    );
+
+    let pool = client.get_transport_mut();
+    assert_eq!(pool.pool_epoch_height, pool.network_epoch_height);
+    assert!(pool.is_distributed);
+    assert_eq!(pool.classic_unstaked_balance, 0);
+
+    // Nothing is left to do until the next epoch.
+    let call_quantity = pool.calls.len();
+    assert_eq!(keeper(false).run(&mut client).unwrap(), vec![]);
+    assert_eq!(client.get_transport_mut().calls.len(), call_quantity);
+
+    // The unstake is not requested outside of the right epoch.
+    client.get_transport_mut().network_epoch_height += 1;
+    client.get_transport_mut().requested_classic_near_amount = ONE_NEAR;
+    assert_eq!(
+        keeper(false).run(&mut client).unwrap(),
+        vec![
+            Action::UpdateValidator { validator_account_id: first_validator_account_id() },
+            Action::UpdateValidator { validator_account_id: second_validator_account_id() },
+            Action::Update,
+            Action::ConfirmStakeDistribution
+        ]
+    );
+}
+
+#[test]
+fn dry_run_submits_nothing() {
+    let mut client = StakePoolClient::new(StandInPool::new(), pool_account_id());
+
+    let actions = keeper(true).run(&mut client).unwrap();
+    assert_eq!(actions.len(), 7);
+    assert_eq!(actions.last(), Some(&Action::Update));
+    assert!(client.get_transport_mut().calls.is_empty());
+}
+
+#[test]
+fn classic_stake_is_not_requested_from_draining_validator() {
+    let mut pool = StandInPool::new();
+    pool.validators[1].is_draining = true;
+    pool.requested_classic_near_amount = 5 * ONE_NEAR;
+    let mut client = StakePoolClient::new(pool, pool_account_id());
+
+    let actions = keeper(true).run(&mut client).unwrap();
+    assert!(
+        actions.contains(
+            &Action::RequestedDecreaseValidatorStake {
+                validator_account_id: first_validator_account_id(),
+                near_amount: 5 * ONE_NEAR,
+                stake_decreasing_type: StakeDecreasingType::Classic
+            }
+        )
+    );
+    assert!(
+        !actions.iter().any(|action| matches!(
+            action,
+            Action::RequestedDecreaseValidatorStake { validator_account_id, stake_decreasing_type: StakeDecreasingType::Classic, .. }
+                if *validator_account_id == second_validator_account_id()
+        ))
+    );
+}
+
+#[test]
+fn executed_action_is_not_submitted_again_after_transport_failure() {
+    let mut pool = StandInPool::new();
+    pool.failing_method_name = Some("update".to_string());
+    let mut client = StakePoolClient::new(pool, pool_account_id());
+
+    let actions = keeper(false).run(&mut client).unwrap();
+    assert!(!actions.contains(&Action::Update));
+    assert_eq!(actions.last(), Some(&Action::ConfirmStakeDistribution));
+
+    let calls = &client.get_transport_mut().calls;
+    assert_eq!(calls.iter().filter(|method_name| *method_name == "update").count(), 1);
+}
+
+#[test]
+fn concurrent_change_is_tolerated() {
+    let mut pool = StandInPool::new();
+    pool.concurrent_method_name = Some("update_validator".to_string());
+    let mut client = StakePoolClient::new(pool, pool_account_id());
+
+    let actions = keeper(false).run(&mut client).unwrap();
+    assert!(!actions.contains(&Action::UpdateValidator { validator_account_id: first_validator_account_id() }));
+    assert!(actions.contains(&Action::UpdateValidator { validator_account_id: second_validator_account_id() }));
+    assert!(client.get_transport_mut().is_distributed);
+}
+
+#[test]
+fn action_without_effect_stops_run() {
+    let mut pool = StandInPool::new();
+    pool.ignored_method_name = Some("update".to_string());
+    let mut client = StakePoolClient::new(pool, pool_account_id());
+
+    assert_eq!(keeper(false).run(&mut client), Err(KeeperError::ActionHasNoEffect { action: Action::Update }));
+}
+
+#[test]
+fn contract_error_stops_run() {
+    let mut pool = StandInPool::new();
+    pool.requested_classic_near_amount = 100 * ONE_NEAR;
+    let mut client = StakePoolClient::new(pool, pool_account_id());
+
+    assert!(matches!(
+        keeper(false).run(&mut client),
+        Err(KeeperError::Client(stake_pool_client::ClientError::Contract { kind, .. })) if kind == "SomeFundsNotUnstakedFromValidators"
+    ));
+}
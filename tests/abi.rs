@@ -0,0 +1,178 @@
+#![cfg(feature = "abi")]
+
+mod support;
+
+use std::fs;
+use near_sdk::serde_json::{self, Map, Value};
+use near_sdk::ONE_NEAR;
+use stake_pool::abi;
+use stake_pool::stake_pool::StakePool;
+use support::executor::Executor;
+use support::scenario::*;
+
+const ABI_DIRECTORY: &str = "abi";
+
+fn run_scenario() -> Executor {
+    let mut executor = set_up();
+
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    deposit(&mut executor, &second_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+    synchronize_epoch(&mut executor);
+    delayed_withdraw_all(&mut executor, &first_user_account_id());
+
+    executor
+}
+
+fn get_result_schema<'a>(abi: &'a Value, function_name: &str) -> &'a Value {
+    match abi["body"]["functions"].as_array().unwrap().iter().find(|function| function["name"] == function_name) {
+        Some(function) => &function["result"]["type_schema"],
+        None => panic!("Function {} is not declared in the ABI.", function_name)
+    }
+}
+
+/// Returns the mismatches of the value with the subset of the JSON schema, that is generated for the types of the pool.
+fn check(value: &Value, schema: &Value, definitions: &Map<String, Value>, path: &str) -> Vec<String> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/definitions/");
+
+        return match definitions.get(name) {
+            Some(definition) => check(value, definition, definitions, path),
+            None => vec![format!("{}: definition {} is absent", path, name)]
+        };
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(schemas) = schema.get(key).and_then(Value::as_array) {
+            if schemas.iter().any(|schema_| check(value, schema_, definitions, path).is_empty()) {
+                return vec![];
+            }
+
+            return vec![format!("{}: {} matches none of the {}", path, value, key)];
+        }
+    }
+    if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+        return schemas.iter().flat_map(|schema_| check(value, schema_, definitions, path)).collect();
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        if values.contains(value) {
+            return vec![];
+        }
+
+        return vec![format!("{}: {} is not in the enum", path, value)];
+    }
+
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(type_) => vec![type_.as_str()],
+        Value::Array(types_) => types_.iter().filter_map(Value::as_str).collect(),
+        _ => return vec![]
+    };
+    let type_ = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object"
+    };
+    let is_type_matched = types.contains(&type_) || (type_ == "integer" && types.contains(&"number"));
+    if !is_type_matched {
+        return vec![format!("{}: {} is not {:?}", path, value, types)];
+    }
+
+    let mut mismatches: Vec<String> = vec![];
+    match value {
+        Value::Array(items) => match &schema["items"] {
+            Value::Array(item_schemas) => {
+                if items.len() != item_schemas.len() {
+                    mismatches.push(format!("{}: tuple has {} items instead of {}", path, items.len(), item_schemas.len()));
+                }
+                for (index, (item, item_schema)) in items.iter().zip(item_schemas.iter()).enumerate() {
+                    mismatches.extend(check(item, item_schema, definitions, &format!("{}[{}]", path, index)));
+                }
+            }
+            item_schema => {
+                for (index, item) in items.iter().enumerate() {
+                    mismatches.extend(check(item, item_schema, definitions, &format!("{}[{}]", path, index)));
+                }
+            }
+        },
+        Value::Object(object) => {
+            let properties = schema["properties"].as_object().cloned().unwrap_or_default();
+
+            for required in schema["required"].as_array().cloned().unwrap_or_default() {
+                if !object.contains_key(required.as_str().unwrap()) {
+                    mismatches.push(format!("{}: property {} is absent", path, required));
+                }
+            }
+            for (key, property) in object.iter() {
+                match properties.get(key) {
+                    Some(property_schema) => mismatches.extend(check(property, property_schema, definitions, &format!("{}.{}", path, key))),
+                    None => mismatches.push(format!("{}: property {} is not declared", path, key))
+                }
+            }
+        }
+        _ => {}
+    }
+
+    mismatches
+}
+
+#[test]
+fn abi_files_are_up_to_date() {
+    let abi = abi::generate_abi();
+
+    for (file_name, content) in [
+        (abi::ABI_FILE_NAME, format!("{}\n", serde_json::to_string_pretty(&abi).unwrap())),
+        (abi::TYPESCRIPT_FILE_NAME, abi::generate_typescript(&abi))
+    ] {
+        let path = format!("{}/{}", ABI_DIRECTORY, file_name);
+
+        assert!(
+            fs::read_to_string(&path).ok().as_ref() == Some(&content),
+            "File {} does not match the contract, it is regenerated by 'cargo run --features abi --bin abi'.", path
+        );
+    }
+}
+
+#[test]
+fn abi_has_entry_of_each_near_bindgen_block() {
+    let source = include_str!("../source/stake_pool.rs");
+
+    assert_eq!(source.matches("#[near_bindgen]\nimpl ").count(), abi::ABI_ENTRY_QUANTITY);
+}
+
+#[test]
+fn view_results_match_abi() {
+    let mut executor = run_scenario();
+
+    let results: Vec<(&str, Value)> = executor.view(&pool_account_id(), |stake_pool: &StakePool| {
+        vec![
+            ("get_full", serde_json::to_value(stake_pool.get_full()).unwrap()),
+            ("get_full_for_account", serde_json::to_value(stake_pool.get_full_for_account(first_user_account_id())).unwrap()),
+            ("get_account_balance", serde_json::to_value(stake_pool.get_account_balance(second_user_account_id())).unwrap()),
+            ("get_delayed_withdrawal_details", serde_json::to_value(stake_pool.get_delayed_withdrawal_details(first_user_account_id())).unwrap()),
+            ("get_validator_registry", serde_json::to_value(stake_pool.get_validator_registry()).unwrap()),
+            ("get_preffered_validator", serde_json::to_value(stake_pool.get_preffered_validator()).unwrap()),
+            ("get_fee_registry_light", serde_json::to_value(stake_pool.get_fee_registry_light()).unwrap()),
+            ("get_aggregated", serde_json::to_value(stake_pool.get_aggregated()).unwrap()),
+            ("get_current_epoch_height", serde_json::to_value(stake_pool.get_current_epoch_height()).unwrap()),
+            ("get_pool_config", serde_json::to_value(stake_pool.get_pool_config()).unwrap()),
+            ("get_deposit_caps", serde_json::to_value(stake_pool.get_deposit_caps()).unwrap()),
+            ("get_epoch_reports", serde_json::to_value(stake_pool.get_epoch_reports(0, 10)).unwrap()),
+            ("get_validator_performance", serde_json::to_value(stake_pool.get_validator_performance(validator_account_id(), 0, 10).unwrap()).unwrap()),
+            ("preview_deposit", serde_json::to_value(stake_pool.preview_deposit(ONE_NEAR.into()).unwrap()).unwrap()),
+            ("preview_delayed_withdraw", serde_json::to_value(stake_pool.preview_delayed_withdraw(ONE_NEAR.into()).unwrap()).unwrap()),
+            ("check_invariants", serde_json::to_value(stake_pool.check_invariants(None)).unwrap()),
+            ("get_total_token_supply", serde_json::to_value(stake_pool.get_total_token_supply()).unwrap())
+        ]
+    });
+
+    let abi = abi::generate_abi();
+    let definitions = abi["body"]["root_schema"]["definitions"].as_object().unwrap();
+    for (function_name, result) in results {
+        let mismatches = check(&result, get_result_schema(&abi, function_name), definitions, function_name);
+
+        assert!(mismatches.is_empty(), "{:#?}", mismatches);
+    }
+}
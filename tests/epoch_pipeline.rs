@@ -0,0 +1,126 @@
+mod support;
+
+use near_sdk::json_types::U128;
+use near_sdk::{Balance, EpochHeight, ONE_NEAR, ONE_YOCTO};
+use stake_pool::stake_pool::StakePool;
+use support::scenario::*;
+
+#[test]
+fn deposit_rewards_delayed_withdraw_and_claim() {
+    let mut executor = set_up();
+
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    deposit(&mut executor, &second_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+
+    let classic_staked_balance: Balance = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund().classic_staked_balance.into());
+    assert_eq!(classic_staked_balance, 200 * ONE_NEAR);
+    assert_eq!(get_mock_validator_total_balance(&mut executor), 200 * ONE_NEAR);
+
+    synchronize_epoch(&mut executor);
+
+    let common_balance: Balance = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund().common_balance.into());
+    assert_eq!(common_balance, 202 * ONE_NEAR);
+    assert_eq!(get_mock_validator_total_balance(&mut executor), common_balance);
+    // The reward fee is minted to the fee beneficiary.
+    assert!(get_token_amount(&mut executor, &owner_account_id()) > 0);
+
+    delayed_withdraw_all(&mut executor, &first_user_account_id());
+    let delayed_withdrawal_near_amount: Balance = executor.view(
+        &pool_account_id(),
+        |stake_pool: &StakePool| stake_pool.get_delayed_withdrawal_details(first_user_account_id()).unwrap().near_amount.into()
+    );
+    assert!(delayed_withdrawal_near_amount > 100 * ONE_NEAR);
+    assert_eq!(get_token_amount(&mut executor, &first_user_account_id()), 0);
+
+    // Unstake is requested in the next right epoch and the unstaked balance is taken 4 epochs later.
+    let mut unstake_epoch_height: Option<EpochHeight> = None;
+    let mut take_epoch_height: Option<EpochHeight> = None;
+    loop {
+        synchronize_epoch(&mut executor);
+
+        let unstaked_balance = get_validator_unstaked_balance(&mut executor);
+        if unstake_epoch_height.is_none() && unstaked_balance > 0 {
+            assert_eq!(unstaked_balance, delayed_withdrawal_near_amount);
+            unstake_epoch_height = Some(executor.get_epoch_height());
+        }
+        if unstake_epoch_height.is_some() && take_epoch_height.is_none() && unstaked_balance == 0 {
+            take_epoch_height = Some(executor.get_epoch_height());
+        }
+
+        let epoch_quantity_to_take_delayed_withdrawal = executor.view(
+            &pool_account_id(),
+            |stake_pool: &StakePool| stake_pool.get_delayed_withdrawal_details(first_user_account_id()).unwrap().epoch_quantity_to_take_delayed_withdrawal
+        );
+        if epoch_quantity_to_take_delayed_withdrawal == 0 {
+            break;
+        }
+    }
+    assert_eq!(take_epoch_height.unwrap() - unstake_epoch_height.unwrap(), EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE);
+
+    let first_user_balance = executor.get_balance(&first_user_account_id());
+    executor.call(
+        &first_user_account_id(),
+        &pool_account_id(),
+        ONE_YOCTO,
+        |stake_pool: &mut StakePool| stake_pool.take_delayed_withdrawal()
+    ).unwrap();
+    assert!(executor.get_balance(&first_user_account_id()) - first_user_balance >= delayed_withdrawal_near_amount);
+    assert_eq!(executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund().pending_refund_balance), U128(0));
+
+    // The remaining stake keeps receiving rewards.
+    let common_balance: Balance = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund().common_balance.into());
+    assert_eq!(get_mock_validator_total_balance(&mut executor), common_balance);
+    assert!(common_balance > 100 * ONE_NEAR);
+}
+
+#[test]
+fn deposit_after_distribution_is_staked_on_preferred_validator() {
+    let mut executor = set_up();
+
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+    // After the confirmed distribution the deposit is staked on the preferred validator in the same epoch.
+    deposit(&mut executor, &second_user_account_id(), 100 * ONE_NEAR);
+
+    let fund = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund());
+    assert_eq!(fund.classic_unstaked_balance, U128(0));
+    assert_eq!(fund.classic_staked_balance, U128(200 * ONE_NEAR));
+    assert_eq!(get_mock_validator_total_balance(&mut executor), 200 * ONE_NEAR);
+    assert_eq!(get_token_amount(&mut executor, &second_user_account_id()), get_token_amount(&mut executor, &first_user_account_id()));
+
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        0,
+        |stake_pool: &mut StakePool| stake_pool.reconcile_validator(validator_account_id())
+    ).unwrap();
+    let validator_reconciliation = executor.view(
+        &pool_account_id(),
+        |stake_pool: &StakePool| stake_pool.get_validator_reconciliation(validator_account_id()).unwrap().unwrap()
+    );
+    assert_eq!(validator_reconciliation.surplus_near_amount, U128(0));
+    assert_eq!(validator_reconciliation.shortfall_near_amount, U128(0));
+}
+
+#[test]
+fn deposit_exceeding_validator_share_stays_unstaked() {
+    let mut executor = set_up();
+
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        0,
+        |stake_pool: &mut StakePool| stake_pool.change_deposit_caps(None, Some(from_json(r#"{"numerator": 1, "denominator": 2}"#)), None)
+    ).unwrap();
+    // The preferred validator would own more than a half of the pool, so the deposit is not rejected, but is not staked.
+    deposit(&mut executor, &second_user_account_id(), 100 * ONE_NEAR);
+
+    let fund = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund());
+    assert_eq!(fund.classic_unstaked_balance, U128(100 * ONE_NEAR));
+    assert_eq!(fund.classic_staked_balance, U128(100 * ONE_NEAR));
+    assert_eq!(get_mock_validator_total_balance(&mut executor), 100 * ONE_NEAR);
+    assert_eq!(get_token_amount(&mut executor, &second_user_account_id()), get_token_amount(&mut executor, &first_user_account_id()));
+}
@@ -0,0 +1,412 @@
+mod support;
+
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{env, testing_env, AccountId, Balance, Gas, ONE_NEAR, ONE_YOCTO};
+use stake_pool::gas;
+use stake_pool::stake_pool::StakePool;
+use support::executor::{Executor, GasUsage};
+use support::scenario::*;
+
+/// Calls the method with the maximum prepaid Gas and asserts, that the transaction uses not more than the budget
+/// of the method, and that each receipt uses not more than its static Gas. Returns the Gas usages of the transaction
+/// and its receipts.
+fn call_within_budget<R>(
+    executor: &mut Executor,
+    signer_account_id: &AccountId,
+    attached_deposit: Balance,
+    budget: Gas,
+    method: impl FnOnce(&mut StakePool) -> R
+) -> Vec<GasUsage> {
+    executor.take_gas_usages();
+    executor.call(signer_account_id, &pool_account_id(), attached_deposit, method).unwrap();
+
+    let gas_usages = executor.take_gas_usages();
+    let (transaction, receipts) = gas_usages.split_first().unwrap();
+    assert!(
+        transaction.used_gas <= budget,
+        "Transaction uses {} Gas, that is more than the budget {}.", transaction.used_gas.0, budget.0
+    );
+    for receipt in receipts.iter() {
+        assert!(
+            receipt.used_gas <= receipt.prepaid_gas,
+            "Receipt {:?} on @{} uses {} Gas, that is more than its static Gas {}.",
+            receipt.function_name, receipt.account_id, receipt.used_gas.0, receipt.prepaid_gas.0
+        );
+    }
+
+    gas_usages
+}
+
+/// Asserts, that the Gas constant covers the measured Gas and exceeds it by less than the rounding step,
+/// so the constant is the measurement rounded up.
+fn assert_derived_from_measurement(name: &str, constant: Gas, measured_gas: Gas, rounding_step: Gas) {
+    assert!(
+        measured_gas <= constant && constant.0 - measured_gas.0 < rounding_step.0,
+        "{} is {} Gas, but it is not the measured {} Gas rounded up to {} Gas.", name, constant.0, measured_gas.0, rounding_step.0
+    );
+}
+
+/// Returns the maximum Gas of the own execution of the stake pool methods.
+fn get_maximum_execution_gas(gas_usages: &[GasUsage]) -> Gas {
+    gas_usages.iter()
+        .filter(|gas_usage| gas_usage.account_id == pool_account_id())
+        .map(|gas_usage| gas_usage.execution_gas)
+        .max()
+        .unwrap_or(Gas(0))
+}
+
+/// Returns the Gas of the own execution of the method, that is called with the maximum prepaid Gas.
+fn measure_execution_gas<R>(executor: &mut Executor, method: impl FnOnce(&mut StakePool) -> R) -> Gas {
+    executor.take_gas_usages();
+    executor.call(&owner_account_id(), &pool_account_id(), 0, method).unwrap();
+
+    executor.take_gas_usages()[0].execution_gas
+}
+
+/// Returns the Gas used by the creation of the promises.
+fn measure_used_gas(create_promises: impl FnOnce()) -> Gas {
+    let used_gas = env::used_gas();
+    create_promises();
+
+    Gas(env::used_gas().0 - used_gas.0)
+}
+
+/// Registers the quantity of validators and referrers in addition to the validator of the scenario, each referrer
+/// has the referred account. The validators are updated in the next epoch, so the pool is ready for the update.
+fn set_up_registries(validators_quantity: u64, referrers_quantity: u64) -> Executor {
+    let mut executor = set_up();
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        0,
+        |stake_pool: &mut StakePool| stake_pool.change_referral_fee(Some(from_json(r#"{"numerator": 1, "denominator": 10}"#)))
+    ).unwrap();
+
+    let mut validator_account_ids = vec![validator_account_id()];
+    for index in 0..validators_quantity {
+        let validator_account_id: AccountId = format!("validator{}.near", index).parse().unwrap();
+        executor.create_account(&validator_account_id, INITIAL_BALANCE);
+        executor.set_validator_stake(&validator_account_id, INITIAL_BALANCE);
+        executor.deploy::<mock_validator::MockValidator>(&validator_account_id);
+        executor.init(
+            &owner_account_id(),
+            &validator_account_id,
+            0,
+            || mock_validator::MockValidator::new(REWARD_NUMERATOR, REWARD_DENOMINATOR)
+        ).unwrap();
+        executor.call(
+            &owner_account_id(),
+            &pool_account_id(),
+            ONE_NEAR,
+            |stake_pool: &mut StakePool| stake_pool.add_validator(validator_account_id.clone(), from_json(r#""Core""#), false, false)
+        ).unwrap();
+        validator_account_ids.push(validator_account_id);
+    }
+    for index in 0..referrers_quantity {
+        let referrer_account_id: AccountId = format!("referrer{}.near", index).parse().unwrap();
+        executor.call(
+            &owner_account_id(),
+            &pool_account_id(),
+            ONE_NEAR,
+            |stake_pool: &mut StakePool| stake_pool.add_referrer(referrer_account_id.clone())
+        ).unwrap();
+        let user_account_id: AccountId = format!("user{}.near", index).parse().unwrap();
+        executor.create_account(&user_account_id, INITIAL_BALANCE);
+        executor.call(
+            &user_account_id,
+            &pool_account_id(),
+            11 * ONE_NEAR,
+            |stake_pool: &mut StakePool| stake_pool.deposit((10 * ONE_NEAR).into(), None, Some(referrer_account_id.clone()))
+        ).unwrap();
+    }
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+
+    executor.advance_epoch();
+    for validator_account_id in validator_account_ids.iter() {
+        executor.call(
+            &owner_account_id(),
+            &pool_account_id(),
+            0,
+            |stake_pool: &mut StakePool| stake_pool.update_validator(validator_account_id.clone())
+        ).unwrap();
+    }
+
+    executor
+}
+
+/// Enqueues the quantity of the delayed withdrawals with the auto claim and passes the epochs until they are matured.
+fn set_up_auto_claim_queue(withdrawals_quantity: u64) -> Executor {
+    let mut executor = set_up();
+
+    let mut user_account_ids = vec![];
+    for index in 0..withdrawals_quantity {
+        let user_account_id: AccountId = format!("user{}.near", index).parse().unwrap();
+        executor.create_account(&user_account_id, INITIAL_BALANCE);
+        deposit(&mut executor, &user_account_id, 10 * ONE_NEAR);
+        user_account_ids.push(user_account_id);
+    }
+    distribute(&mut executor);
+
+    for user_account_id in user_account_ids.iter() {
+        delayed_withdraw_all(&mut executor, user_account_id);
+        executor.call(user_account_id, &pool_account_id(), ONE_NEAR, |stake_pool: &mut StakePool| stake_pool.enable_auto_claim()).unwrap();
+    }
+    let last_user_account_id = user_account_ids.pop().unwrap();
+    while executor.view(
+        &pool_account_id(),
+        |stake_pool: &StakePool| stake_pool.get_delayed_withdrawal_details(last_user_account_id.clone()).unwrap().epoch_quantity_to_take_delayed_withdrawal
+    ) > 0 {
+        synchronize_epoch(&mut executor);
+    }
+
+    executor
+}
+
+#[test]
+fn methods_stay_within_budgets() {
+    let mut executor = set_up();
+    let mut gas_usages = vec![];
+
+    // Before the distribution the deposit is not staked.
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &first_user_account_id(),
+        101 * ONE_NEAR,
+        gas::DEPOSIT,
+        |stake_pool: &mut StakePool| stake_pool.deposit((100 * ONE_NEAR).into(), None, None)
+    ));
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &owner_account_id(),
+        0,
+        gas::INCREASE_VALIDATOR_STAKE,
+        |stake_pool: &mut StakePool| stake_pool.increase_validator_stake(validator_account_id(), (100 * ONE_NEAR).into())
+    ));
+    gas_usages.extend(call_within_budget(&mut executor, &owner_account_id(), 0, gas::COMMON, |stake_pool: &mut StakePool| stake_pool.confirm_stake_distribution()));
+    // After the distribution the deposit is staked on the preferred validator.
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &second_user_account_id(),
+        101 * ONE_NEAR,
+        gas::DEPOSIT,
+        |stake_pool: &mut StakePool| stake_pool.deposit((100 * ONE_NEAR).into(), None, None)
+    ));
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &owner_account_id(),
+        0,
+        gas::COMMON,
+        |stake_pool: &mut StakePool| stake_pool.change_preffered_validator(None)
+    ));
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &first_user_account_id(),
+        ONE_YOCTO,
+        gas::TRANSFER,
+        |stake_pool: &mut StakePool| stake_pool.ft_transfer(second_user_account_id(), ONE_NEAR.into(), None)
+    ));
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &owner_account_id(),
+        0,
+        gas::RECONCILE_VALIDATOR,
+        |stake_pool: &mut StakePool| stake_pool.reconcile_validator(validator_account_id())
+    ));
+
+    let token_amount = get_token_amount(&mut executor, &first_user_account_id());
+    gas_usages.extend(call_within_budget(
+        &mut executor,
+        &first_user_account_id(),
+        ONE_NEAR,
+        gas::TRANSFER,
+        |stake_pool: &mut StakePool| stake_pool.delayed_withdraw(token_amount.into(), None)
+    ));
+    gas_usages.extend(call_within_budget(&mut executor, &first_user_account_id(), ONE_NEAR, gas::TRANSFER, |stake_pool: &mut StakePool| stake_pool.enable_auto_claim()));
+
+    // The epoch pipeline is passed until the delayed withdrawal is sent automatically.
+    let mut is_unstake_requested = false;
+    while executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_delayed_withdrawal_details(first_user_account_id())).is_some() {
+        executor.advance_epoch();
+        let is_right_epoch = executor.get_epoch_height() % EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE == 0;
+
+        if is_right_epoch && get_validator_unstaked_balance(&mut executor) > 0 {
+            gas_usages.extend(call_within_budget(
+                &mut executor,
+                &owner_account_id(),
+                0,
+                gas::TAKE_UNSTAKED_BALANCE,
+                |stake_pool: &mut StakePool| stake_pool.take_unstaked_balance(validator_account_id())
+            ));
+        }
+        gas_usages.extend(call_within_budget(
+            &mut executor,
+            &owner_account_id(),
+            0,
+            gas::UPDATE_VALIDATOR,
+            |stake_pool: &mut StakePool| stake_pool.update_validator(validator_account_id())
+        ));
+
+        let needed_to_request_near_amount: Balance = executor.view(
+            &pool_account_id(),
+            |stake_pool: &StakePool| stake_pool.get_requested_to_withdrawal_fund().classic_near_amount.into()
+        );
+        if is_right_epoch && needed_to_request_near_amount > 0 {
+            gas_usages.extend(call_within_budget(
+                &mut executor,
+                &owner_account_id(),
+                0,
+                gas::REQUESTED_DECREASE_VALIDATOR_STAKE,
+                |stake_pool: &mut StakePool| stake_pool.requested_decrease_validator_stake(
+                    validator_account_id(),
+                    needed_to_request_near_amount.into(),
+                    from_json(r#""Classic""#)
+                )
+            ));
+            is_unstake_requested = true;
+        }
+        gas_usages.extend(call_within_budget(&mut executor, &owner_account_id(), 0, gas::update(1), |stake_pool: &mut StakePool| stake_pool.update()));
+
+        let auto_claim_queue_length = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_auto_claim_queue_length());
+        gas_usages.extend(call_within_budget(
+            &mut executor,
+            &second_user_account_id(),
+            0,
+            gas::process_matured_withdrawals(auto_claim_queue_length.min(10)),
+            |stake_pool: &mut StakePool| stake_pool.process_matured_withdrawals(10)
+        ));
+    }
+    assert!(is_unstake_requested);
+
+    assert_derived_from_measurement(
+        "Gas of the host functions of the own execution",
+        gas::EXECUTION_HOST_FUNCTIONS,
+        get_maximum_execution_gas(&gas_usages),
+        Gas::ONE_TERA
+    );
+}
+
+/// The methods are called with the prepaid Gas equal to their budgets, as the callers, that chain the methods inside
+/// their own transactions, do.
+#[test]
+fn methods_accept_their_budgets() {
+    let mut executor = set_up();
+
+    executor.set_prepaid_gas(gas::COMMON);
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        0,
+        |stake_pool: &mut StakePool| stake_pool.change_preffered_validator(None)
+    ).unwrap();
+
+    executor.set_prepaid_gas(gas::DEPOSIT);
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    assert_eq!(executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund().classic_unstaked_balance), (100 * ONE_NEAR).into());
+}
+
+/// The fees of the receipts are charged by the mocked blockchain on the creation of the promises.
+#[test]
+fn receipt_creations_are_derived_from_fees() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let validator_account_id = validator_account_id();
+    let pool_account_id = pool_account_id();
+
+    let function_call_receipt_creation_gas = measure_used_gas(|| {
+        env::promise_create(validator_account_id.clone(), "deposit_and_stake", &[], 0, Gas(0));
+    });
+    let validator_promise_index = env::promise_create(validator_account_id.clone(), "deposit_and_stake", &[], 0, Gas(0));
+    let callback_receipt_creation_gas = measure_used_gas(|| {
+        env::promise_then(validator_promise_index, pool_account_id.clone(), "deposit_callback", &[], 0, Gas(0));
+    });
+    let transfer_receipt_creation_gas = measure_used_gas(|| {
+        let promise_index = env::promise_batch_create(&first_user_account_id());
+        env::promise_batch_action_transfer(promise_index, ONE_NEAR);
+    });
+
+    assert_derived_from_measurement(
+        "Gas of the function call receipt creation",
+        gas::FUNCTION_CALL_RECEIPT_CREATION,
+        function_call_receipt_creation_gas,
+        Gas::ONE_TERA
+    );
+    assert_derived_from_measurement(
+        "Gas of the data receipt creation",
+        gas::DATA_RECEIPT_CREATION,
+        Gas(callback_receipt_creation_gas.0 - function_call_receipt_creation_gas.0),
+        Gas::ONE_TERA
+    );
+    assert_derived_from_measurement(
+        "Gas of the transfer receipt creation",
+        gas::TRANSFER_RECEIPT_CREATION,
+        transfer_receipt_creation_gas,
+        Gas::ONE_TERA
+    );
+}
+
+/// The update iterates over the validators, so its budget grows with the validator registry. The Gas of one iteration
+/// is the difference of the Gas of the updates with and without the registered quantity. The referral fee is accrued
+/// lazily, so the Gas of the update does not depend on the quantity of the referrers.
+#[test]
+fn update_stays_within_budget_of_registries() {
+    const QUANTITY: u64 = 10;
+
+    let base_execution_gas = measure_execution_gas(&mut set_up_registries(0, 0), |stake_pool: &mut StakePool| stake_pool.update());
+    assert!(base_execution_gas <= gas::EXECUTION_HOST_FUNCTIONS);
+
+    let validators_execution_gas = measure_execution_gas(&mut set_up_registries(QUANTITY, 0), |stake_pool: &mut StakePool| stake_pool.update());
+    assert_derived_from_measurement(
+        "Gas of the host functions of the validator invariant check",
+        gas::VALIDATOR_INVARIANT_CHECK_HOST_FUNCTIONS,
+        Gas((validators_execution_gas.0 - base_execution_gas.0) / QUANTITY),
+        Gas::ONE_TERA / 10
+    );
+
+    let referrers_execution_gas = measure_execution_gas(&mut set_up_registries(0, QUANTITY), |stake_pool: &mut StakePool| stake_pool.update());
+    assert!(
+        referrers_execution_gas.0.abs_diff(base_execution_gas.0) < Gas::ONE_TERA.0 / 100,
+        "The update with {} referrers used {} Gas instead of {} Gas.",
+        QUANTITY,
+        referrers_execution_gas.0,
+        base_execution_gas.0
+    );
+
+    let mut executor = set_up_registries(QUANTITY, QUANTITY);
+    call_within_budget(
+        &mut executor,
+        &owner_account_id(),
+        0,
+        gas::update(QUANTITY + 1),
+        |stake_pool: &mut StakePool| stake_pool.update()
+    );
+}
+
+/// The processing of the matured withdrawals iterates over the auto claim queue, so its budget grows with the examined
+/// elements. The Gas of one element is the difference of the Gas of the processings of the queues of different lengths.
+#[test]
+fn process_matured_withdrawals_stays_within_budget_of_queue() {
+    const QUANTITY: u64 = 10;
+
+    let base_execution_gas = measure_execution_gas(
+        &mut set_up_auto_claim_queue(1),
+        |stake_pool: &mut StakePool| stake_pool.process_matured_withdrawals(QUANTITY)
+    );
+    assert!(base_execution_gas <= gas::EXECUTION_HOST_FUNCTIONS);
+
+    let mut executor = set_up_auto_claim_queue(QUANTITY + 1);
+    let gas_usages = call_within_budget(
+        &mut executor,
+        &owner_account_id(),
+        0,
+        gas::process_matured_withdrawals(QUANTITY + 1),
+        |stake_pool: &mut StakePool| stake_pool.process_matured_withdrawals(QUANTITY + 1)
+    );
+    assert_derived_from_measurement(
+        "Gas of the host functions of the auto claim processing",
+        gas::AUTO_CLAIM_PROCESSING_HOST_FUNCTIONS,
+        Gas((gas_usages[0].execution_gas.0 - base_execution_gas.0) / QUANTITY),
+        Gas::ONE_TERA / 10
+    );
+}
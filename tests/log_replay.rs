@@ -0,0 +1,90 @@
+#![cfg(feature = "log_replay")]
+
+mod support;
+
+use near_contract_standards::fungible_token::core::FungibleTokenCore;
+use near_sdk::serde_json;
+use near_sdk::{AccountId, ONE_NEAR, ONE_YOCTO};
+use stake_pool::log_replay::Replay;
+use stake_pool::stake_pool::StakePool;
+use support::executor::Executor;
+use support::scenario::*;
+
+fn diff(executor: &mut Executor, replay: &Replay, account_ids: &[AccountId]) {
+    let full = serde_json::to_string(&executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_full())).unwrap();
+    let differences = replay.diff_full(&full).unwrap();
+    assert!(differences.is_empty(), "{}", serde_json::to_string(&differences).unwrap());
+
+    for account_id in account_ids.iter() {
+        let full_for_account = serde_json::to_string(
+            &executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_full_for_account(account_id.clone()))
+        ).unwrap();
+        let differences = replay.diff_full_for_account(account_id, &full_for_account).unwrap();
+        assert!(differences.is_empty(), "{}", serde_json::to_string(&differences).unwrap());
+    }
+}
+
+#[test]
+fn replayed_state_matches_snapshot() {
+    let mut executor = set_up();
+    let account_ids = [first_user_account_id(), second_user_account_id(), owner_account_id()];
+
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    deposit(&mut executor, &second_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+    synchronize_epoch(&mut executor);
+    executor.call(
+        &second_user_account_id(),
+        &pool_account_id(),
+        ONE_YOCTO,
+        |stake_pool: &mut StakePool| stake_pool.ft_transfer(first_user_account_id(), (10 * ONE_NEAR).into(), None)
+    ).unwrap();
+    deposit(&mut executor, &second_user_account_id(), 10 * ONE_NEAR);
+    executor.call(
+        &second_user_account_id(),
+        &pool_account_id(),
+        ONE_NEAR,
+        |stake_pool: &mut StakePool| stake_pool.instant_withdraw(ONE_NEAR.into(), None)
+    ).unwrap();
+    delayed_withdraw_all(&mut executor, &first_user_account_id());
+
+    let mut logs = executor.take_logs();
+    let replay = Replay::from_logs(&logs);
+    assert!(replay.discrepancies.is_empty(), "{}", serde_json::to_string(&replay.discrepancies).unwrap());
+    diff(&mut executor, &replay, &account_ids);
+
+    // The delayed withdrawal is requested from the validator, taken from it and then claimed.
+    loop {
+        synchronize_epoch(&mut executor);
+
+        let epoch_quantity_to_take_delayed_withdrawal = executor.view(
+            &pool_account_id(),
+            |stake_pool: &StakePool| stake_pool.get_delayed_withdrawal_details(first_user_account_id()).unwrap().epoch_quantity_to_take_delayed_withdrawal
+        );
+        if epoch_quantity_to_take_delayed_withdrawal == 0 {
+            break;
+        }
+    }
+    executor.call(
+        &first_user_account_id(),
+        &pool_account_id(),
+        ONE_YOCTO,
+        |stake_pool: &mut StakePool| stake_pool.take_delayed_withdrawal()
+    ).unwrap();
+
+    logs.extend(executor.take_logs());
+    let replay = Replay::from_logs(&logs);
+    assert!(replay.discrepancies.is_empty(), "{}", serde_json::to_string(&replay.discrepancies).unwrap());
+    assert!(replay.skipped_log_indexes.is_empty(), "{:?}", replay.skipped_log_indexes.iter().map(|index| &logs[*index]).collect::<Vec<_>>());
+    assert!(replay.delayed_withdrawn_fund.delayed_withdrawal_registry.is_empty());
+    assert_eq!(replay.validator_balances.len(), 1);
+    diff(&mut executor, &replay, &account_ids);
+}
+
+#[test]
+fn unknown_logs_are_skipped() {
+    let replay = Replay::from_logs(&["Unknown log.".to_string(), "Deposited to @pool.near in 100 epoch.".to_string()]);
+
+    assert_eq!(replay.skipped_log_indexes, vec![0, 1]);
+    assert!(replay.discrepancies.is_empty());
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "state_dump")]
+
+mod support;
+
+use std::collections::HashSet;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde_json::{self, json};
+use near_sdk::ONE_NEAR;
+use stake_pool::stake_pool::state_dump::{self, IssueKind, StateDump};
+use support::executor::Executor;
+use support::scenario::*;
+
+/// Prefix of the epoch reports is the index of the 'StorageKey::EpochReport' variant.
+const EPOCH_REPORT_TAG: u8 = 7;
+
+/// Exports the storage of the pool in the same form as the 'view_state' RPC method.
+fn view_state(storage: Vec<(Vec<u8>, Vec<u8>)>) -> String {
+    let values: Vec<_> = storage.into_iter()
+        .map(|(key, value)| json!({ "key": Base64VecU8(key), "value": Base64VecU8(value), "proof": [] }))
+        .collect();
+
+    json!({ "jsonrpc": "2.0", "id": "dontcare", "result": { "values": values, "proof": [], "block_height": 1, "block_hash": "" } }).to_string()
+}
+
+fn run_scenario() -> Executor {
+    let mut executor = set_up();
+
+    deposit(&mut executor, &first_user_account_id(), 100 * ONE_NEAR);
+    deposit(&mut executor, &second_user_account_id(), 100 * ONE_NEAR);
+    distribute(&mut executor);
+    synchronize_epoch(&mut executor);
+    delayed_withdraw_all(&mut executor, &first_user_account_id());
+
+    executor
+}
+
+/// Counts the entries, that are reachable from the contract root.
+fn count_entries(state_dump: &StateDump, storage_key: &str) -> usize {
+    let issue_keys = get_issue_keys(state_dump);
+
+    state_dump.entries.iter()
+        .filter(|entry| entry.storage_key == storage_key && !issue_keys.contains(&entry.key))
+        .count()
+}
+
+fn get_issue_keys(state_dump: &StateDump) -> HashSet<String> {
+    state_dump.issues.iter().map(|issue| issue.key.clone()).collect()
+}
+
+#[test]
+fn state_of_the_pool_is_decoded() {
+    let executor = run_scenario();
+
+    let state_dump = state_dump::decode_view_state(&view_state(executor.get_storage(&pool_account_id()))).unwrap();
+
+    // Only the entries of the storage usage measurement on initialization are orphaned.
+    let initial_state_dump = state_dump::decode(set_up().get_storage(&pool_account_id()));
+    assert!(initial_state_dump.issues.iter().all(|issue| issue.kind == IssueKind::Orphaned));
+    assert_eq!(get_issue_keys(&state_dump), get_issue_keys(&initial_state_dump), "{}", serde_json::to_string(&state_dump.issues).unwrap());
+    let root = state_dump.root.as_ref().unwrap();
+    assert_eq!(root["account_registry"]["owner_id"], owner_account_id().to_string());
+    assert_eq!(root["validating"]["validators_quantity"], 1);
+    // The account of the first user is removed after withdrawing all the tokens, the fee beneficiary has received the reward fee.
+    assert_eq!(count_entries(&state_dump, "AccountBalance"), 2);
+    assert_eq!(count_entries(&state_dump, "FungibleTokenMetadata"), 1);
+    assert_eq!(count_entries(&state_dump, "DelayedWithdrawal"), 1);
+    // Key index, key and value of the validator registry.
+    assert_eq!(count_entries(&state_dump, "Validator"), 3);
+    assert!(count_entries(&state_dump, "EpochReport") > 0);
+    assert!(count_entries(&state_dump, "ValidatorPerformance") > 0);
+
+    assert!(
+        state_dump.entries.iter()
+            .any(|entry| entry.storage_key == "DelayedWithdrawal" && entry.key_fields["account_id"] == first_user_account_id().to_string())
+    );
+}
+
+#[test]
+fn orphaned_and_undecodable_keys_are_flagged() {
+    let executor = run_scenario();
+    let mut storage = executor.get_storage(&pool_account_id());
+    let initial_issue_keys = get_issue_keys(&state_dump::decode(storage.clone()));
+
+    let epoch_report_value = storage.iter()
+        .find(|(key, _)| key[0] == EPOCH_REPORT_TAG)
+        .map(|(_, value)| value.clone())
+        .unwrap();
+    // Epoch report out of the ring buffer is not reachable from the contract root.
+    let mut orphaned_key = vec![EPOCH_REPORT_TAG];
+    orphaned_key.extend_from_slice(&700u64.to_le_bytes());
+    storage.push((orphaned_key, epoch_report_value));
+    // Key without the matching storage key.
+    storage.push((vec![255, 1, 2, 3], vec![0]));
+    // Key of the epoch report with the truncated position.
+    storage.push((vec![EPOCH_REPORT_TAG, 1], vec![0]));
+
+    let state_dump = state_dump::decode(storage);
+
+    assert!(state_dump.root.is_some());
+    let new_issues: Vec<_> = state_dump.issues.iter()
+        .filter(|issue| !initial_issue_keys.contains(&issue.key))
+        .collect();
+    assert_eq!(new_issues.len(), 3);
+    assert_eq!(new_issues.iter().filter(|issue| issue.kind == IssueKind::Orphaned).count(), 1);
+    assert_eq!(new_issues.iter().filter(|issue| issue.kind == IssueKind::Undecodable).count(), 2);
+}
+
+#[test]
+fn absent_root_is_flagged() {
+    let state_dump = state_dump::decode(vec![]);
+
+    assert!(state_dump.root.is_none());
+    assert_eq!(state_dump.issues.len(), 1);
+    assert_eq!(state_dump.issues[0].kind, IssueKind::Undecodable);
+}
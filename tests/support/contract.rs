@@ -0,0 +1,289 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json;
+use near_sdk::{AccountId, Balance, EpochHeight};
+use mock_validator::MockValidator;
+use stake_pool::cross_contract_call::classic_validator::ClassicValidator;
+use stake_pool::stake_decreasing_kind::StakeDecreasingType;
+use stake_pool::stake_pool::StakePool;
+
+/// Contract, that can be called by the receipts of the executor.
+pub trait Contract: BorshDeserialize + BorshSerialize {
+    /// Executes the function with the JSON arguments and returns the JSON result.
+    fn dispatch(&mut self, function_name: &str, arguments: &[u8]) -> Vec<u8>;
+}
+
+fn deserialize<T: DeserializeOwned>(arguments: &[u8]) -> T {
+    match serde_json::from_slice(arguments) {
+        Ok(arguments_) => arguments_,
+        Err(error) => panic!("Arguments are not valid: {}.", error)
+    }
+}
+
+fn serialize<T: Serialize>(result: &T) -> Vec<u8> {
+    serde_json::to_vec(result).unwrap()
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct TransferNearAmountCallbackArguments {
+    receiver_account_id: AccountId,
+    refund_account_id: AccountId,
+    near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct DepositCallbackArguments {
+    predecessor_account_id: AccountId,
+    beneficiary_account_id: AccountId,
+    validator_account_id: AccountId,
+    attached_deposit: Balance,
+    near_amount: Balance,
+    refundable_near_amount: Balance,
+    token_amount: Balance,
+    near_remainder: Balance,
+    current_epoch_height: EpochHeight,
+    storage_staking_price_per_additional_account: Balance,
+    referrer_id: Option<AccountId>
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct DepositOnValidatorCallbackArguments {
+    predecessor_account_id: AccountId,
+    validator_account_id: AccountId,
+    near_amount: Balance,
+    attached_deposit: Balance,
+    refundable_near_amount: Balance,
+    token_amount: Balance,
+    near_remainder: Balance,
+    storage_staking_price_per_additional_accounts: Balance,
+    referrer_id: Option<AccountId>
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct IncreaseValidatorStakeCallbackArguments {
+    validator_account_id: AccountId,
+    near_amount: Balance,
+    current_epoch_height: EpochHeight
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct RequestedDecreaseValidatorStakeCallbackArguments {
+    validator_account_id: AccountId,
+    near_amount: Balance,
+    stake_decreasing_type: StakeDecreasingType,
+    refundable_near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct TakeUnstakedBalanceCallbackArguments {
+    validator_account_id: AccountId,
+    requested_to_withdrawal_near_amount: Balance,
+    drained_near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct DrainValidatorCallbackArguments {
+    validator_account_id: AccountId,
+    near_amount: Balance
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct ValidatorCallbackArguments {
+    validator_account_id: AccountId,
+    current_epoch_height: EpochHeight
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct AmountArguments {
+    amount: U128
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct AccountIdArguments {
+    account_id: AccountId
+}
+
+/// The stake pool receives only the callbacks of its own promises.
+impl Contract for StakePool {
+    fn dispatch(&mut self, function_name: &str, arguments: &[u8]) -> Vec<u8> {
+        match function_name {
+            "transfer_near_amount_callback" => {
+                let arguments: TransferNearAmountCallbackArguments = deserialize(arguments);
+
+                self.transfer_near_amount_callback(arguments.receiver_account_id, arguments.refund_account_id, arguments.near_amount);
+
+                vec![]
+            }
+            "deposit_callback" => {
+                let arguments: DepositCallbackArguments = deserialize(arguments);
+
+                self.deposit_callback(
+                    arguments.predecessor_account_id,
+                    arguments.beneficiary_account_id,
+                    arguments.validator_account_id,
+                    arguments.attached_deposit,
+                    arguments.near_amount,
+                    arguments.refundable_near_amount,
+                    arguments.token_amount,
+                    arguments.near_remainder,
+                    arguments.current_epoch_height,
+                    arguments.storage_staking_price_per_additional_account,
+                    arguments.referrer_id
+                );
+
+                vec![]
+            }
+            "deposit_on_validator_callback" => {
+                let arguments: DepositOnValidatorCallbackArguments = deserialize(arguments);
+
+                serialize(
+                    &self.deposit_on_validator_callback(
+                        arguments.predecessor_account_id,
+                        arguments.validator_account_id,
+                        arguments.near_amount,
+                        arguments.attached_deposit,
+                        arguments.refundable_near_amount,
+                        arguments.token_amount,
+                        arguments.near_remainder,
+                        arguments.storage_staking_price_per_additional_accounts,
+                        arguments.referrer_id
+                    )
+                )
+            }
+            "increase_validator_stake_callback" => {
+                let arguments: IncreaseValidatorStakeCallbackArguments = deserialize(arguments);
+
+                serialize(&self.increase_validator_stake_callback(arguments.validator_account_id, arguments.near_amount, arguments.current_epoch_height))
+            }
+            "requested_decrease_validator_stake_callback_1" => {
+                let arguments: RequestedDecreaseValidatorStakeCallbackArguments = deserialize(arguments);
+
+                drop(
+                    self.requested_decrease_validator_stake_callback_1(
+                        arguments.validator_account_id,
+                        arguments.near_amount,
+                        arguments.stake_decreasing_type,
+                        arguments.refundable_near_amount
+                    )
+                );
+
+                vec![]
+            }
+            "requested_decrease_validator_stake_callback_2" => {
+                let arguments: RequestedDecreaseValidatorStakeCallbackArguments = deserialize(arguments);
+
+                serialize(
+                    &self.requested_decrease_validator_stake_callback_2(
+                        arguments.validator_account_id,
+                        arguments.near_amount,
+                        arguments.stake_decreasing_type,
+                        arguments.refundable_near_amount
+                    )
+                )
+            }
+            "take_unstaked_balance_callback" => {
+                let arguments: TakeUnstakedBalanceCallbackArguments = deserialize(arguments);
+
+                serialize(
+                    &self.take_unstaked_balance_callback(
+                        arguments.validator_account_id,
+                        arguments.requested_to_withdrawal_near_amount,
+                        arguments.drained_near_amount
+                    )
+                )
+            }
+            "drain_validator_callback" => {
+                let arguments: DrainValidatorCallbackArguments = deserialize(arguments);
+
+                serialize(&self.drain_validator_callback(arguments.validator_account_id, arguments.near_amount))
+            }
+            "update_validator_callback" => {
+                let arguments: ValidatorCallbackArguments = deserialize(arguments);
+
+                serialize(&self.update_validator_callback(arguments.validator_account_id, arguments.current_epoch_height))
+            }
+            "reconcile_validator_callback" => {
+                let arguments: ValidatorCallbackArguments = deserialize(arguments);
+
+                serialize(&self.reconcile_validator_callback(arguments.validator_account_id, arguments.current_epoch_height))
+            }
+            _ => panic!("Function {} is not supported.", function_name)
+        }
+    }
+}
+
+impl Contract for MockValidator {
+    fn dispatch(&mut self, function_name: &str, arguments: &[u8]) -> Vec<u8> {
+        match function_name {
+            "deposit" => {
+                self.deposit();
+
+                vec![]
+            }
+            "deposit_and_stake" => {
+                self.deposit_and_stake();
+
+                vec![]
+            }
+            "withdraw" => {
+                let arguments: AmountArguments = deserialize(arguments);
+
+                self.withdraw(arguments.amount);
+
+                vec![]
+            }
+            "withdraw_all" => {
+                self.withdraw_all();
+
+                vec![]
+            }
+            "stake" => {
+                let arguments: AmountArguments = deserialize(arguments);
+
+                self.stake(arguments.amount);
+
+                vec![]
+            }
+            "unstake" => {
+                let arguments: AmountArguments = deserialize(arguments);
+
+                self.unstake(arguments.amount);
+
+                vec![]
+            }
+            "unstake_all" => {
+                self.unstake_all();
+
+                vec![]
+            }
+            "get_account_staked_balance" => {
+                let arguments: AccountIdArguments = deserialize(arguments);
+
+                serialize(&self.get_account_staked_balance(arguments.account_id))
+            }
+            "get_account_unstaked_balance" => {
+                let arguments: AccountIdArguments = deserialize(arguments);
+
+                serialize(&self.get_account_unstaked_balance(arguments.account_id))
+            }
+            "get_account_total_balance" => {
+                let arguments: AccountIdArguments = deserialize(arguments);
+
+                serialize(&self.get_account_total_balance(arguments.account_id))
+            }
+            _ => panic!("Function {} is not supported.", function_name)
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::mock::{self, MockedBlockchain, Receipt, VmAction};
+use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+use near_sdk::{env, AccountId, Balance, EpochHeight, Gas, PromiseResult, RuntimeFeesConfig, StorageUsage, VMConfig};
+use super::contract::Contract;
+
+/// Gas attached to the transaction by default.
+const PREPAID_GAS: Gas = Gas(300_000_000_000_000);
+
+/// Executes the function of the contract with the JSON arguments and returns the JSON result.
+type Dispatcher = fn(&str, &[u8]) -> Vec<u8>;
+
+/// Gas of the executed transaction or receipt. Only the Gas of the host functions is counted by the mocked blockchain,
+/// the static Gas of the created promises included.
+#[derive(Debug)]
+pub struct GasUsage {
+    pub account_id: AccountId,
+    /// Name of the function of the receipt, it is absent for the transaction.
+    pub function_name: Option<String>,
+    pub prepaid_gas: Gas,
+    pub used_gas: Gas,
+    /// Gas of the own execution, that is the used Gas without the static Gas and the fees of the created receipts.
+    pub execution_gas: Gas
+}
+
+struct Account {
+    balance: Balance,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    storage_usage: StorageUsage,
+    dispatcher: Option<Dispatcher>
+}
+
+impl Account {
+    fn new(balance: Balance) -> Self {
+        Self {
+            balance,
+            storage: HashMap::new(),
+            storage_usage: 0,
+            dispatcher: None
+        }
+    }
+}
+
+/// In-process blockchain, that executes transactions and all the receipts created by them on the mocked blockchain.
+/// Each account has its own storage and balance. Receipts are executed in the order of creation. A function call
+/// to the account that created the receipts is a callback, that receives the results of the receipts created before it.
+/// A function call receipt is prepaid with its static Gas, so the Gas exceeding aborts the test.
+/// Only the Rust panics are rolled back: the host panics of the mocked blockchain can not unwind and abort the test.
+pub struct Executor {
+    epoch_height: EpochHeight,
+    accounts: HashMap<AccountId, Account>,
+    validator_stakes: HashMap<String, Balance>,
+    prepaid_gas: Gas,
+    logs: Vec<String>,
+    gas_usages: Vec<GasUsage>
+}
+
+impl Executor {
+    pub fn new(epoch_height: EpochHeight) -> Self {
+        Self {
+            epoch_height,
+            accounts: HashMap::new(),
+            validator_stakes: HashMap::new(),
+            prepaid_gas: PREPAID_GAS,
+            logs: vec![],
+            gas_usages: vec![]
+        }
+    }
+
+    pub fn create_account(&mut self, account_id: &AccountId, balance: Balance) {
+        self.accounts.insert(account_id.clone(), Account::new(balance));
+    }
+
+    pub fn deploy<T: Contract>(&mut self, account_id: &AccountId) {
+        self.get_account_mut(account_id).dispatcher = Some(dispatch::<T>);
+    }
+
+    pub fn set_validator_stake(&mut self, account_id: &AccountId, stake: Balance) {
+        self.validator_stakes.insert(account_id.to_string(), stake);
+    }
+
+    /// Sets the Gas attached to the next transactions.
+    pub fn set_prepaid_gas(&mut self, prepaid_gas: Gas) {
+        self.prepaid_gas = prepaid_gas;
+    }
+
+    pub fn get_epoch_height(&self) -> EpochHeight {
+        self.epoch_height
+    }
+
+    pub fn advance_epoch(&mut self) {
+        self.epoch_height += 1;
+    }
+
+    /// Returns the key/value pairs of the account storage in the same form as the 'view_state' RPC method.
+    pub fn get_storage(&self, account_id: &AccountId) -> Vec<(Vec<u8>, Vec<u8>)> {
+        match self.accounts.get(account_id) {
+            Some(account) => account.storage.iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => vec![]
+        }
+    }
+
+    pub fn get_balance(&self, account_id: &AccountId) -> Balance {
+        match self.accounts.get(account_id) {
+            Some(account) => account.balance,
+            None => 0
+        }
+    }
+
+    pub fn take_logs(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.logs)
+    }
+
+    /// Returns the Gas usages of the transactions and the receipts in the order of execution.
+    pub fn take_gas_usages(&mut self) -> Vec<GasUsage> {
+        std::mem::take(&mut self.gas_usages)
+    }
+
+    /// Initializes the contract state with the result of the init-method.
+    pub fn init<T: Contract>(
+        &mut self,
+        signer_account_id: &AccountId,
+        receiver_account_id: &AccountId,
+        attached_deposit: Balance,
+        method: impl FnOnce() -> T
+    ) -> Result<(), String> {
+        self.transact(
+            signer_account_id,
+            receiver_account_id,
+            attached_deposit,
+            || {
+                env::state_write(&method());
+
+                vec![]
+            }
+        )
+    }
+
+    /// Executes the call-method as a transaction and all the receipts created by it.
+    pub fn call<T: Contract, R>(
+        &mut self,
+        signer_account_id: &AccountId,
+        receiver_account_id: &AccountId,
+        attached_deposit: Balance,
+        method: impl FnOnce(&mut T) -> R
+    ) -> Result<(), String> {
+        self.transact(
+            signer_account_id,
+            receiver_account_id,
+            attached_deposit,
+            || {
+                let mut contract = read_state::<T>();
+                drop(method(&mut contract));
+                env::state_write(&contract);
+
+                vec![]
+            }
+        )
+    }
+
+    /// Executes the view-method without changing the state.
+    pub fn view<T: Contract, R>(&mut self, receiver_account_id: &AccountId, method: impl FnOnce(&T) -> R) -> R {
+        self.set_context(receiver_account_id, receiver_account_id, 0, PREPAID_GAS, vec![]);
+
+        method(&read_state::<T>())
+    }
+
+    fn transact(
+        &mut self,
+        signer_account_id: &AccountId,
+        receiver_account_id: &AccountId,
+        attached_deposit: Balance,
+        function: impl FnOnce() -> Vec<u8>
+    ) -> Result<(), String> {
+        let signer_account = self.get_account_mut(signer_account_id);
+        if signer_account.balance < attached_deposit {
+            panic!("Signer @{} has not enough balance for the attached deposit.", signer_account_id);
+        }
+        signer_account.balance -= attached_deposit;
+
+        let prepaid_gas = self.prepaid_gas;
+
+        match self.execute(signer_account_id, receiver_account_id, attached_deposit, prepaid_gas, None, vec![], function) {
+            (PromiseResult::Failed, Some(error)) => Err(error),
+            _ => Ok(())
+        }
+    }
+
+    /// Executes the function on the receiver account and all the receipts created by it. The state changes
+    /// of the failed function are discarded and the attached deposit is returned to the predecessor.
+    #[allow(clippy::too_many_arguments)]
+    fn execute(
+        &mut self,
+        predecessor_account_id: &AccountId,
+        receiver_account_id: &AccountId,
+        attached_deposit: Balance,
+        prepaid_gas: Gas,
+        function_name: Option<String>,
+        promise_results: Vec<PromiseResult>,
+        function: impl FnOnce() -> Vec<u8>
+    ) -> (PromiseResult, Option<String>) {
+        self.get_account_mut(receiver_account_id).balance += attached_deposit;
+
+        self.set_context(predecessor_account_id, receiver_account_id, attached_deposit, prepaid_gas, promise_results);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(function));
+
+        let receipts = get_created_receipts();
+        let used_gas = env::used_gas();
+        self.gas_usages.push(
+            GasUsage {
+                account_id: receiver_account_id.clone(),
+                function_name,
+                prepaid_gas,
+                used_gas,
+                execution_gas: Gas(used_gas.0 - get_receipts_gas(receiver_account_id, &receipts).0)
+            }
+        );
+
+        match result {
+            Ok(result) => {
+                self.logs.extend(get_logs());
+
+                let receiver_account = self.get_account_mut(receiver_account_id);
+                receiver_account.balance = env::account_balance();
+                receiver_account.storage_usage = env::storage_usage();
+                receiver_account.storage = mock::with_mocked_blockchain(|mocked_blockchain| mocked_blockchain.take_storage());
+
+                self.execute_receipts(receiver_account_id, receipts);
+
+                (PromiseResult::Successful(result), None)
+            }
+            Err(error) => {
+                self.get_account_mut(receiver_account_id).balance -= attached_deposit;
+                self.get_account_mut(predecessor_account_id).balance += attached_deposit;
+
+                let message = match error.downcast::<String>() {
+                    Ok(message_) => *message_,
+                    Err(error_) => match error_.downcast::<&str>() {
+                        Ok(message_) => message_.to_string(),
+                        Err(_) => "Unknown error.".to_string()
+                    }
+                };
+
+                (PromiseResult::Failed, Some(message))
+            }
+        }
+    }
+
+    fn execute_receipts(&mut self, predecessor_account_id: &AccountId, receipts: Vec<Receipt>) {
+        let mut promise_results: Vec<PromiseResult> = vec![];
+
+        for receipt in receipts {
+            if receipt.receiver_id == *predecessor_account_id {
+                let promise_results_ = std::mem::take(&mut promise_results);
+
+                self.execute_receipt(predecessor_account_id, receipt, promise_results_);
+            } else {
+                promise_results.push(self.execute_receipt(predecessor_account_id, receipt, vec![]));
+            }
+        }
+    }
+
+    fn execute_receipt(&mut self, predecessor_account_id: &AccountId, receipt: Receipt, mut promise_results: Vec<PromiseResult>) -> PromiseResult {
+        let mut promise_result = PromiseResult::Successful(vec![]);
+
+        for action in receipt.actions {
+            match action {
+                VmAction::Transfer { deposit } => {
+                    self.get_account_mut(&receipt.receiver_id).balance += deposit;
+                }
+                VmAction::FunctionCall { function_name, args, gas, deposit } => {
+                    let dispatcher = match self.get_account_mut(&receipt.receiver_id).dispatcher {
+                        Some(dispatcher_) => dispatcher_,
+                        None => panic!("Contract is not deployed on @{}.", receipt.receiver_id)
+                    };
+
+                    let (promise_result_, _) = self.execute(
+                        predecessor_account_id,
+                        &receipt.receiver_id,
+                        deposit,
+                        gas,
+                        Some(function_name.clone()),
+                        std::mem::take(&mut promise_results),
+                        || dispatcher(function_name.as_str(), args.as_slice())
+                    );
+
+                    promise_result = promise_result_;
+                }
+                _ => panic!("Action is not supported.")
+            }
+        }
+
+        promise_result
+    }
+
+    fn set_context(
+        &mut self,
+        predecessor_account_id: &AccountId,
+        receiver_account_id: &AccountId,
+        attached_deposit: Balance,
+        prepaid_gas: Gas,
+        promise_results: Vec<PromiseResult>
+    ) {
+        let epoch_height = self.epoch_height;
+        let validator_stakes = self.validator_stakes.clone();
+
+        let receiver_account = self.get_account_mut(receiver_account_id);
+
+        env::set_blockchain_interface(
+            MockedBlockchain::new(
+                VMContextBuilder::new()
+                    .current_account_id(receiver_account_id.clone())
+                    .predecessor_account_id(predecessor_account_id.clone())
+                    .signer_account_id(predecessor_account_id.clone())
+                    .attached_deposit(attached_deposit)
+                    .account_balance(receiver_account.balance)
+                    .storage_usage(receiver_account.storage_usage)
+                    .epoch_height(epoch_height)
+                    .prepaid_gas(prepaid_gas)
+                    .build(),
+                VMConfig::test(),
+                RuntimeFeesConfig::test(),
+                promise_results,
+                receiver_account.storage.clone(),
+                validator_stakes,
+                None
+            )
+        );
+    }
+
+    fn get_account_mut(&mut self, account_id: &AccountId) -> &mut Account {
+        self.accounts.entry(account_id.clone()).or_insert_with(|| Account::new(0))
+    }
+}
+
+/// Returns the static Gas and the fees of the receipts created on the account. The fees are charged as by the mocked
+/// blockchain: the sending and the execution of each receipt and action, and a data receipt for each receipt, that
+/// the callback depends on.
+fn get_receipts_gas(current_account_id: &AccountId, receipts: &[Receipt]) -> Gas {
+    let fees = RuntimeFeesConfig::test();
+    let receipt_fee = &fees.action_receipt_creation_config;
+    let data_receipt_fee = &fees.data_receipt_creation_config.base_cost;
+    let function_call_fee = &fees.action_creation_config.function_call_cost;
+    let function_call_byte_fee = &fees.action_creation_config.function_call_cost_per_byte;
+    let transfer_fee = &fees.action_creation_config.transfer_cost;
+
+    let mut gas: u64 = 0;
+    let mut dependencies_quantity: u64 = 0;
+    for receipt in receipts.iter() {
+        gas += receipt_fee.send_not_sir + receipt_fee.execution;
+
+        if receipt.receiver_id == *current_account_id {
+            gas += dependencies_quantity * (data_receipt_fee.send_not_sir + data_receipt_fee.execution);
+            dependencies_quantity = 0;
+        } else {
+            dependencies_quantity += 1;
+        }
+
+        for action in receipt.actions.iter() {
+            match action {
+                VmAction::Transfer { .. } => {
+                    gas += transfer_fee.send_not_sir + transfer_fee.execution;
+                }
+                VmAction::FunctionCall { function_name, args, gas: static_gas, .. } => {
+                    let bytes_quantity = (function_name.len() + args.len()) as u64;
+
+                    gas += function_call_fee.send_not_sir + function_call_fee.execution
+                        + bytes_quantity * (function_call_byte_fee.send_not_sir + function_call_byte_fee.execution)
+                        + static_gas.0;
+                }
+                _ => panic!("Action is not supported.")
+            }
+        }
+    }
+
+    Gas(gas)
+}
+
+fn read_state<T: BorshDeserialize>() -> T {
+    match env::state_read::<T>() {
+        Some(contract) => contract,
+        None => panic!("Contract state is not initialized.")
+    }
+}
+
+/// Executes the function as the exported method of the contract: the state is read before the call
+/// and written after it.
+fn dispatch<T: Contract + BorshSerialize>(function_name: &str, arguments: &[u8]) -> Vec<u8> {
+    let mut contract = read_state::<T>();
+    let result = contract.dispatch(function_name, arguments);
+    env::state_write(&contract);
+
+    result
+}
@@ -0,0 +1,6 @@
+// Each test binary compiles the whole support, but uses only a part of it.
+#![allow(dead_code)]
+
+pub mod contract;
+pub mod executor;
+pub mod scenario;
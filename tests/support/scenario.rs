@@ -0,0 +1,180 @@
+use mock_validator::MockValidator;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde_json;
+use near_sdk::{AccountId, Balance, EpochHeight, ONE_NEAR};
+use stake_pool::cross_contract_call::classic_validator::ClassicValidator;
+use stake_pool::stake_pool::StakePool;
+use super::executor::Executor;
+
+pub const INITIAL_EPOCH_HEIGHT: EpochHeight = 100;
+pub const EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE: EpochHeight = 4;
+pub const INITIAL_BALANCE: Balance = 1_000 * ONE_NEAR;
+// Covers the storage staking price of the epoch history.
+pub const INITIALIZATION_DEPOSIT: Balance = 2 * ONE_NEAR;
+/// Reward of the validator is 1 percent of the staked balance for each epoch.
+pub const REWARD_NUMERATOR: u64 = 1;
+pub const REWARD_DENOMINATOR: u64 = 100;
+
+pub fn pool_account_id() -> AccountId {
+    "pool.near".parse().unwrap()
+}
+
+pub fn owner_account_id() -> AccountId {
+    "owner.near".parse().unwrap()
+}
+
+pub fn validator_account_id() -> AccountId {
+    "validator.near".parse().unwrap()
+}
+
+pub fn first_user_account_id() -> AccountId {
+    "first_user.near".parse().unwrap()
+}
+
+pub fn second_user_account_id() -> AccountId {
+    "second_user.near".parse().unwrap()
+}
+
+/// Arguments of the stake pool are passed as JSON, as the clients do.
+pub fn from_json<T: DeserializeOwned>(json: &str) -> T {
+    serde_json::from_str(json).unwrap()
+}
+
+pub fn set_up() -> Executor {
+    let mut executor = Executor::new(INITIAL_EPOCH_HEIGHT);
+    executor.create_account(&owner_account_id(), INITIAL_BALANCE);
+    executor.create_account(&first_user_account_id(), INITIAL_BALANCE);
+    executor.create_account(&second_user_account_id(), INITIAL_BALANCE);
+    // Balance of the validator covers the rewards.
+    executor.create_account(&validator_account_id(), INITIAL_BALANCE);
+    executor.create_account(&pool_account_id(), 10 * ONE_NEAR);
+    executor.set_validator_stake(&validator_account_id(), INITIAL_BALANCE);
+
+    executor.deploy::<MockValidator>(&validator_account_id());
+    executor.init(
+        &owner_account_id(),
+        &validator_account_id(),
+        0,
+        || MockValidator::new(REWARD_NUMERATOR, REWARD_DENOMINATOR)
+    ).unwrap();
+
+    executor.deploy::<StakePool>(&pool_account_id());
+    executor.init(
+        &owner_account_id(),
+        &pool_account_id(),
+        INITIALIZATION_DEPOSIT,
+        || StakePool::new(
+            from_json(r#"{"name": "Staked Near", "symbol": "stNEAR", "icon": null, "reference": null, "reference_hash": null, "decimals": 24}"#),
+            None,
+            from_json(r#"{"numerator": 10, "denominator": 100}"#),
+            from_json(r#"{"beneficiaries": [{"account_id": "owner.near", "numerator": 1}], "denominator": 1}"#),
+            None,
+            from_json(r#"{"beneficiaries": [{"account_id": "owner.near", "numerator": 1}], "denominator": 1}"#),
+            None
+        )
+    ).unwrap();
+
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        ONE_NEAR,
+        |stake_pool: &mut StakePool| stake_pool.add_validator(validator_account_id(), from_json(r#""Core""#), false, true)
+    ).unwrap();
+
+    executor
+}
+
+pub fn deposit(executor: &mut Executor, account_id: &AccountId, near_amount: Balance) {
+    executor.call(
+        account_id,
+        &pool_account_id(),
+        near_amount + ONE_NEAR,
+        |stake_pool: &mut StakePool| stake_pool.deposit(near_amount.into(), None, None)
+    ).unwrap();
+}
+
+pub fn delayed_withdraw_all(executor: &mut Executor, account_id: &AccountId) {
+    let token_amount = get_token_amount(executor, account_id);
+
+    executor.call(
+        account_id,
+        &pool_account_id(),
+        ONE_NEAR,
+        |stake_pool: &mut StakePool| stake_pool.delayed_withdraw(token_amount.into(), None)
+    ).unwrap();
+}
+
+/// Stakes the whole unstaked balance of the pool on the validator.
+pub fn distribute(executor: &mut Executor) {
+    let classic_unstaked_balance: Balance = executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_fund().classic_unstaked_balance.into());
+
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        0,
+        |stake_pool: &mut StakePool| stake_pool.increase_validator_stake(validator_account_id(), classic_unstaked_balance.into())
+    ).unwrap();
+    executor.call(&owner_account_id(), &pool_account_id(), 0, |stake_pool: &mut StakePool| stake_pool.confirm_stake_distribution()).unwrap();
+}
+
+/// Runs the epoch pipeline: takes the unstaked balance, updates the validator, requests unstake for the delayed
+/// withdrawals and updates the pool.
+pub fn synchronize_epoch(executor: &mut Executor) {
+    executor.advance_epoch();
+    let is_right_epoch = executor.get_epoch_height() % EPOCH_QUANTITY_FOR_VALIDATOR_UNSTAKE == 0;
+
+    let unstaked_balance = get_validator_unstaked_balance(executor);
+    if is_right_epoch && unstaked_balance > 0 {
+        executor.call(
+            &owner_account_id(),
+            &pool_account_id(),
+            0,
+            |stake_pool: &mut StakePool| stake_pool.take_unstaked_balance(validator_account_id())
+        ).unwrap();
+    }
+
+    executor.call(
+        &owner_account_id(),
+        &pool_account_id(),
+        0,
+        |stake_pool: &mut StakePool| stake_pool.update_validator(validator_account_id())
+    ).unwrap();
+
+    let needed_to_request_near_amount: Balance = executor.view(
+        &pool_account_id(),
+        |stake_pool: &StakePool| stake_pool.get_requested_to_withdrawal_fund().classic_near_amount.into()
+    );
+    if is_right_epoch && needed_to_request_near_amount > 0 {
+        executor.call(
+            &owner_account_id(),
+            &pool_account_id(),
+            0,
+            |stake_pool: &mut StakePool| stake_pool.requested_decrease_validator_stake(
+                validator_account_id(),
+                needed_to_request_near_amount.into(),
+                from_json(r#""Classic""#)
+            )
+        ).unwrap();
+    }
+
+    executor.call(&owner_account_id(), &pool_account_id(), 0, |stake_pool: &mut StakePool| stake_pool.update()).unwrap();
+    assert!(executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.check_invariants(None).is_valid));
+}
+
+pub fn get_token_amount(executor: &mut Executor, account_id: &AccountId) -> Balance {
+    executor.view(
+        &pool_account_id(),
+        |stake_pool: &StakePool| match stake_pool.get_account_balance(account_id.clone()).base_account_balance {
+            Some(base_account_balance) => base_account_balance.token_balance.into(),
+            None => 0
+        }
+    )
+}
+
+pub fn get_validator_unstaked_balance(executor: &mut Executor) -> Balance {
+    executor.view(&pool_account_id(), |stake_pool: &StakePool| stake_pool.get_validator_registry().remove(0).unstaked_balance.into())
+}
+
+pub fn get_mock_validator_total_balance(executor: &mut Executor) -> Balance {
+    executor.view(&validator_account_id(), |mock_validator: &MockValidator| mock_validator.get_account_total_balance(pool_account_id()).into())
+}